@@ -0,0 +1,159 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+use clap::Parser;
+
+use super::AllwallCommand;
+use crate::{
+    cli::error::CliError,
+    config::{AppConfig, Config, load_config},
+    engine::Engine,
+    prelude::*,
+    sources::SourceKind,
+};
+
+/// Time a source's render loop for a number of frames and print the frame times
+///
+/// Draws into an offscreen texture through a headless GPU context, like `render`,
+/// so no Wayland session or running daemon is needed. Each frame is timed from the
+/// source update until the GPU has finished drawing it.
+///
+/// # Examples
+///
+/// ```bash
+/// allwall bench --source smoke --frames 600
+/// allwall bench --source media --path ~/Videos/loop.mp4 --width 3840 --height 2160
+/// ```
+#[derive(Parser, Debug)]
+#[command()]
+pub struct Bench {
+    /// Number of frames to time
+    #[arg(short, long, default_value_t = 600)]
+    pub frames: usize,
+
+    /// Width of the offscreen target in pixels
+    #[arg(long, default_value_t = 1920)]
+    pub width: u32,
+
+    /// Height of the offscreen target in pixels
+    #[arg(long, default_value_t = 1080)]
+    pub height: u32,
+
+    /// Source type: media (images/videos), smoke, grass, color, or gradient
+    #[arg(short, long, value_enum, default_value_t = SourceKind::Media)]
+    pub source: SourceKind,
+
+    /// Path to image/video file or directory, instead of the first scene's path
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Explicit config file, taken from the global `--config` flag
+    #[arg(skip)]
+    pub config: Option<PathBuf>,
+}
+
+impl AllwallCommand for Bench {
+    async fn execute(&self) -> Result<()> {
+        let config = match load_config(self.config.as_deref()) {
+            Err(e) if self.config.is_none() => {
+                warn!("Using default config: {e}");
+                Config::default()
+            },
+            result => result?,
+        };
+
+        if matches!(self.source, SourceKind::Media) && self.path.is_none() && config.scenes.is_empty() {
+            return Err(CliError::MediaPathRequired.into());
+        }
+
+        let config = AppConfig::from_config(config)?.merge_cli(self.path.clone(), None, None, None, None);
+        let times = Engine::bench(config, self.source, (self.width, self.height), self.frames)?;
+
+        match BenchStats::from_times(&times) {
+            Some(stats) => info!("{} at {}x{}: {stats}", self.source, self.width, self.height),
+            None => warn!("No frames timed"),
+        }
+        Ok(())
+    }
+}
+
+/// What `bench` reports for a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BenchStats {
+    frames: usize,
+    mean: Duration,
+    /// Frame time that 99% of frames came in at or under
+    p99: Duration,
+}
+
+impl BenchStats {
+    /// Stats over `times`, or `None` when no frames were timed
+    fn from_times(times: &[Duration]) -> Option<Self> {
+        let frames = times.len();
+        let count = u32::try_from(frames).ok().filter(|&n| n > 0)?;
+        let mean = times.iter().sum::<Duration>() / count;
+
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable();
+        // Nearest rank, so the slowest frame counts as the p99 of fewer than 100
+        let rank = (frames * 99).div_ceil(100);
+        let p99 = sorted[rank.saturating_sub(1)];
+
+        Some(Self { frames, mean, p99 })
+    }
+}
+
+impl fmt::Display for BenchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "{} frames, mean {:.2} ms, p99 {:.2} ms",
+            self.frames,
+            ms(self.mean),
+            ms(self.p99)
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn millis(values: impl IntoIterator<Item = u64>) -> Vec<Duration> {
+        values.into_iter().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_no_frames_has_no_stats() {
+        assert_eq!(BenchStats::from_times(&[]), None);
+    }
+
+    #[test]
+    fn test_mean_and_p99() {
+        // 1..=100 ms in reverse, so the input order doesn't matter
+        let stats = BenchStats::from_times(&millis((1..=100).rev())).unwrap();
+        assert_eq!(stats.frames, 100);
+        assert_eq!(stats.mean, Duration::from_micros(50_500));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+    }
+
+    #[test]
+    fn test_p99_of_few_frames_is_the_slowest() {
+        let stats = BenchStats::from_times(&millis([4, 20, 6])).unwrap();
+        assert_eq!(stats.mean, Duration::from_millis(10));
+        assert_eq!(stats.p99, Duration::from_millis(20));
+
+        let single = BenchStats::from_times(&millis([7])).unwrap();
+        assert_eq!(
+            (single.mean, single.p99),
+            (Duration::from_millis(7), Duration::from_millis(7))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let stats = BenchStats::from_times(&millis([10, 20])).unwrap();
+        assert_eq!(stats.to_string(), "2 frames, mean 15.00 ms, p99 20.00 ms");
+    }
+}
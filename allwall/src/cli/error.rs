@@ -1,8 +1,5 @@
 #[derive(thiserror::Error, Debug)]
 pub enum CliError {
-    #[error("Daemon is already running - only one instance allowed")]
-    DaemonRunning,
-
     #[error("Daemon is not running - start with 'allwall run'")]
     DaemonNotRunning,
 
@@ -25,9 +22,23 @@ pub enum CliError {
     #[error("IPC error: {0}")]
     Ipc(String),
 
+    #[error("Daemon sent an unexpected response")]
+    UnexpectedResponse,
+
     #[error("Failed to generate shell completions for {shell}")]
     Completions { shell: String },
 
-    #[error("--path is required for media source. Use --path <PATH>")]
+    #[error(
+        "The media source needs a wallpaper: pass --path <PATH>, add a scene with a path to the config, or pick another --source"
+    )]
     MediaPathRequired,
+
+    #[error("Path does not exist: {0}")]
+    PathNotFound(std::path::PathBuf),
+
+    #[error("Configuration has {0} error(s)")]
+    InvalidConfig(usize),
+
+    #[error("Config file {0} already exists, pass --force to replace it")]
+    ConfigExists(std::path::PathBuf),
 }
@@ -0,0 +1,305 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use serde::Serialize;
+
+use super::AllwallCommand;
+use crate::{
+    cli::error::CliError,
+    config::{GeneralConfig, SmokeConfig, default_config_path},
+    engine::SceneConfig,
+    prelude::*,
+    transitions::TransitionConfig,
+};
+
+#[derive(Parser, Debug)]
+#[command()]
+pub struct GenerateConfig {
+    /// Replace an existing config file
+    #[arg(long)]
+    pub force: bool,
+
+    /// File to write, taken from the global `--config` flag
+    #[arg(skip)]
+    pub config: Option<PathBuf>,
+}
+
+impl AllwallCommand for GenerateConfig {
+    async fn execute(&self) -> Result<()> {
+        let path = match &self.config {
+            Some(path) => path.clone(),
+            None => default_config_path()?,
+        };
+
+        write_starter_config(&path, self.force)?;
+        info!("Wrote {}", path.display());
+
+        Ok(())
+    }
+}
+
+fn write_starter_config(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(CliError::ConfigExists(path.to_path_buf()).into());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, starter_config()?)?;
+
+    Ok(())
+}
+
+/// Formats a default as a TOML value
+///
+/// serde_json writes `f32`s with their shortest round-tripping digits, and its output for
+/// scalars and flat arrays is also valid TOML.
+fn value<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string(value)?.replace(',', ", "))
+}
+
+/// A commented config holding the current defaults, so it can't drift from the code
+pub fn starter_config() -> Result<String> {
+    let general = GeneralConfig::default();
+    let transition = TransitionConfig::default();
+    let scene = SceneConfig::default();
+    let smoke = SmokeConfig::default();
+
+    Ok(f!(
+        r#"# Allwall configuration
+# Every value below is the default; edit or delete lines as you like.
+# Run `allwall validate` to check the file after changing it.
+
+[general]
+# Target framerate while anything on screen is moving
+fps = {fps}
+# Framerate once a still image has settled, until the next transition starts
+idle_fps = {idle_fps}
+# Reopen the last shown wallpaper after a restart instead of picking a new one
+restore_last = {restore_last}
+# Let smoke and grass follow the cursor. Clicks on the desktop then hit the wallpaper.
+interactive = {interactive}
+# Pause rotation and drop to idle_fps while a fullscreen or maximized window hides
+# the wallpaper (needs wlr-foreign-toplevel-management, e.g. Sway or Hyprland)
+pause_on_fullscreen = {pause_on_fullscreen}
+# Connection attempts at startup while the compositor isn't ready yet.
+# The wait between attempts doubles, starting at connect_delay_ms.
+connect_attempts = {connect_attempts}
+connect_delay_ms = {connect_delay_ms}
+# Listen for `allwall next`, `set` and other commands; turn off where sockets can't be bound
+ipc = {ipc}
+# Fade the first wallpaper in at startup; false shows it straight away
+startup_transition = {startup_transition}
+# GPU to render on: auto, low-power, high-performance, pci:VENDOR:DEVICE or name:TEXT
+gpu = {gpu}
+# Render at 10 bits per channel when the display supports it (experimental)
+hdr = {hdr}
+# auto-vsync, auto-no-vsync, fifo, mailbox or immediate; unsupported modes fall back to fifo
+present_mode = {present_mode}
+# Opacity of the whole wallpaper (0.0 - 1.0), for layering over whatever is below it
+opacity = {opacity}
+
+[transition]
+# fade, dip, wipe, pixelate, circle-top-left, circle-top-right, circle-bottom-left,
+# circle-bottom-right, circle-center, circle-random, circle-mouse or random
+type = {transition_type}
+# Types random picks from, a different one each rotation
+random_types = {random_types}
+# Length of each transition in seconds
+duration = {duration}
+# Seconds between rotations, 0 to never rotate
+interval = {interval}
+# Randomize each rotation within interval ± interval_jitter seconds
+interval_jitter = {interval_jitter}
+# Randomize each transition's length within duration ± duration_jitter_ms milliseconds
+duration_jitter_ms = {duration_jitter_ms}
+
+[transition.circle]
+# Edge softness, 0.0 for a hard edge
+feather = {circle_feather}
+# Where circle-* transitions without a corner in their name start from
+origin = {circle_origin}
+
+[transition.fade]
+# blend crossfades both, overlay-out fades the old wallpaper away over the new one,
+# overlay-in fades the new one in over the old; only translucent images look different
+mode = {fade_mode}
+
+[transition.dip]
+# Color dip passes through between wallpapers, as sRGB (0.0 - 1.0)
+color = {dip_color}
+
+[transition.wipe]
+# Direction the edge travels in degrees: 0 = left to right, 90 = bottom to top
+angle = {wipe_angle}
+# Edge softness, 0.0 for a hard edge
+feather = {wipe_feather}
+
+# Scenes assign wallpapers to monitors. Uncomment one and point it at your
+# wallpapers, or pass --path to `allwall run` instead.
+# [[scene]]
+# path = "/path/to/wallpapers"
+# Which outputs to cover: "*", a name like "DP-1", or a list of names and globs
+# monitors = {monitors}
+# clone mirrors one picture everywhere, independent rotates each monitor separately
+# layout = {layout}
+# With layout = "independent": "even" staggers the monitors' rotations evenly across
+# the interval, "random" starts each at a random point, "none" rotates them together
+# independent_offset = {independent_offset}
+# cover crops to fill the monitor, contain letterboxes the whole picture, smart picks
+# cover unless it would crop away more than smart_max_crop of the picture
+# fit = {fit}
+# smart_max_crop = {smart_max_crop}
+# Filter used when scaling: linear (smooth) or nearest (crisp pixel art)
+# scaling = {scaling}
+# Gaussian blur radius in pixels for still images (0 = off)
+# blur = {blur}
+# Slowly zoom and drift across still images over each rotation interval
+# ken_burns = {ken_burns}
+# "random", or "album" to show every file once before reshuffling
+# order = {order}
+# Also pick up media from subdirectories of path
+# recursive = {recursive}
+# RGBA color shown while the directory has no media
+# fallback_color = {fallback_color}
+
+# Switch media scenes to another path at a local time of day, e.g. day and night
+# folders. Each entry holds until the next one; scene picks one [[scene]] by
+# position from 0, or every media scene when left out.
+# [[schedule]]
+# at = "07:00"
+# path = "/path/to/wallpapers/day"
+# [[schedule]]
+# at = "19:00"
+# path = "/path/to/wallpapers/night"
+
+# Smoke source settings (used with --source smoke)
+[smoke]
+# continuous, burst, audio (needs the audio feature), or fixed to rise from
+# emission_point alone like a chimney
+emission_mode = {emission_mode}
+# How much smoke each emitter adds per frame
+emission_intensity = {emission_intensity}
+# sRGB colors (0.0 - 1.0), as color pickers show them
+background_color = {background_color}
+smoke_color = {smoke_color}
+# Shade from one color in thin wisps to another in dense smoke; each defaults to smoke_color
+# smoke_color_low = [0.3, 0.3, 0.4]
+# smoke_color_high = [0.95, 0.9, 0.85]
+# How long the cursor keeps stirring the smoke after it stops, 0.0 - 1.0
+mouse_inertia = {mouse_inertia}
+# Cursor moves shorter than this fraction of the screen don't stir the smoke (0.0 = off)
+mouse_deadzone = {mouse_deadzone}
+# Simulation grid size: a power of two from 128 (fast) to 1024 (fine detail)
+simulation_resolution = {simulation_resolution}
+# Fixed emitter position (0.0 - 1.0, y pointing down) and radius as a fraction of the width
+emission_point = {emission_point}
+emission_radius = {emission_radius}
+"#,
+        fps = value(&general.fps)?,
+        idle_fps = value(&general.idle_fps)?,
+        restore_last = value(&general.restore_last)?,
+        interactive = value(&general.interactive)?,
+        pause_on_fullscreen = value(&general.pause_on_fullscreen)?,
+        connect_attempts = value(&general.connect_attempts)?,
+        connect_delay_ms = value(&general.connect_delay_ms)?,
+        ipc = value(&general.ipc)?,
+        startup_transition = value(&general.startup_transition)?,
+        gpu = value(&general.gpu)?,
+        hdr = value(&general.hdr)?,
+        present_mode = value(&general.present_mode)?,
+        opacity = value(&general.opacity)?,
+        transition_type = value(&transition.transition_type())?,
+        duration = value(&transition.duration)?,
+        interval = value(&transition.interval)?,
+        interval_jitter = value(&transition.interval_jitter)?,
+        duration_jitter_ms = value(&transition.duration_jitter_ms)?,
+        random_types = value(&transition.random_types)?,
+        circle_feather = value(&transition.circle.feather)?,
+        circle_origin = value(&transition.circle.origin)?,
+        fade_mode = value(&transition.fade.mode)?,
+        dip_color = value(&transition.dip.color)?,
+        wipe_angle = value(&transition.wipe.angle)?,
+        wipe_feather = value(&transition.wipe.feather)?,
+        monitors = value(&scene.monitors)?,
+        layout = value(&scene.layout)?,
+        independent_offset = value(&scene.independent_offset)?,
+        fit = value(&scene.fit)?,
+        smart_max_crop = value(&scene.smart_max_crop)?,
+        scaling = value(&scene.scaling)?,
+        blur = value(&scene.blur)?,
+        ken_burns = value(&scene.ken_burns)?,
+        order = value(&scene.order)?,
+        recursive = value(&scene.recursive)?,
+        fallback_color = value(&scene.fallback_color)?,
+        emission_mode = value(&smoke.emission_mode)?,
+        emission_intensity = value(&smoke.emission_intensity)?,
+        background_color = value(&smoke.background_color)?,
+        smoke_color = value(&smoke.smoke_color)?,
+        mouse_inertia = value(&smoke.mouse_inertia)?,
+        mouse_deadzone = value(&smoke.mouse_deadzone)?,
+        simulation_resolution = value(&smoke.simulation_resolution)?,
+        emission_point = value(&smoke.emission_point)?,
+        emission_radius = value(&smoke.emission_radius)?,
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, utils::scratch::ScratchDir};
+
+    #[test]
+    fn test_starter_config_parses_back_to_the_defaults() {
+        let config: Config = toml::from_str(&starter_config().unwrap()).unwrap();
+        let general = GeneralConfig::default();
+        let transition = TransitionConfig::default();
+        let smoke = SmokeConfig::default();
+
+        assert_eq!(config.general.fps, general.fps);
+        assert_eq!(config.general.idle_fps, general.idle_fps);
+        assert_eq!(config.general.connect_delay_ms, general.connect_delay_ms);
+        assert_eq!(config.transition.transition_type(), transition.transition_type());
+        assert_eq!(config.transition.interval, transition.interval);
+        assert_eq!(config.transition.wipe.feather, transition.wipe.feather);
+        assert_eq!(config.transition.dip.color, transition.dip.color);
+        assert_eq!(config.smoke.mouse_inertia, smoke.mouse_inertia);
+        assert_eq!(config.smoke.emission_point, smoke.emission_point);
+        assert_eq!(config.smoke.simulation_resolution, smoke.simulation_resolution);
+        assert!(config.scenes.is_empty());
+    }
+
+    #[test]
+    fn test_commented_scene_parses_once_uncommented() {
+        let starter = starter_config().unwrap();
+        let scene: String = starter
+            .lines()
+            .skip_while(|line| *line != "# [[scene]]")
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.starts_with("[[") || line.split_once(" = ").is_some_and(|(key, _)| !key.contains(' ')))
+            .map(|line| f!("{line}\n"))
+            .collect();
+
+        let config: Config = toml::from_str(&scene).unwrap();
+        assert_eq!(config.scenes.len(), 1);
+    }
+
+    #[test]
+    fn test_refuses_to_overwrite_without_force() {
+        let dir = ScratchDir::new("generate-overwrite");
+        let path = dir.join("allwall/config.toml");
+
+        write_starter_config(&path, false).unwrap();
+        std::fs::write(&path, "# mine").unwrap();
+
+        let err = write_starter_config(&path, false).unwrap_err();
+        assert!(matches!(err, Error::Cli(CliError::ConfigExists(_))));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# mine");
+
+        write_starter_config(&path, true).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("# Allwall configuration"));
+    }
+}
@@ -1,10 +1,12 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use crate::{
     cli::{
         AllwallCommand,
         error::CliError,
-        ipc::protocol::{Request, Response, send_request},
+        ipc::protocol::{MAX_FPS, Request, Response, clamp_fps, send_request},
     },
     prelude::*,
 };
@@ -12,17 +14,56 @@ use crate::{
 #[derive(Parser, Debug)]
 #[command(name = "fps")]
 pub struct Fps {
-    /// Target framerate
+    /// Target framerate, values above 240 are capped
+    #[arg(value_parser = parse_fps)]
     pub value: u32,
+
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+fn parse_fps(value: &str) -> std::result::Result<u32, String> {
+    let fps: u32 = value.parse().map_err(|e| f!("{e}"))?;
+    clamp_fps(fps).ok_or("FPS must be greater than 0")?;
+    Ok(fps)
+}
+
+impl Fps {
+    /// The requested framerate within the daemon's limits
+    ///
+    /// Capped here rather than in the parser, which runs before logging is set up.
+    fn capped(&self) -> u32 {
+        let capped = clamp_fps(self.value).unwrap_or(MAX_FPS);
+        if capped != self.value {
+            warn!("FPS capped at {MAX_FPS}");
+        }
+        capped
+    }
 }
 
 impl AllwallCommand for Fps {
     async fn execute(&self) -> Result<()> {
-        let response = send_request(&Request::SetFps(self.value))?;
+        let response = send_request(self.socket.as_deref(), &Request::SetFps(self.capped()))?;
 
         match response {
             Response::Ok => Ok(()),
             Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_parser_clamps_and_rejects_zero() {
+        assert_eq!(Fps::try_parse_from(["fps", "60"]).unwrap().capped(), 60);
+        assert_eq!(Fps::try_parse_from(["fps", "100000"]).unwrap().capped(), MAX_FPS);
+        assert!(Fps::try_parse_from(["fps", "0"]).is_err());
+        assert!(Fps::try_parse_from(["fps", "fast"]).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{
+        AllwallCommand,
+        error::CliError,
+        ipc::protocol::{Request, Response, send_request},
+    },
+    prelude::*,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "goto")]
+pub struct Goto {
+    /// File to show, by name within the current scene's directory or by absolute path
+    pub file: PathBuf,
+
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+impl AllwallCommand for Goto {
+    async fn execute(&self) -> Result<()> {
+        // Names are looked up in the scene's directory by the daemon, not the working directory
+        let response = send_request(self.socket.as_deref(), &Request::Goto(self.file.clone()))?;
+
+        match response {
+            Response::Ok => Ok(()),
+            Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
+        }
+    }
+}
@@ -1,12 +1,22 @@
 mod fps;
+mod goto;
 mod next;
+mod overlay;
 mod prev;
 pub mod protocol;
+mod quit;
+mod set;
+mod timing;
 
 use clap::Subcommand;
 pub use fps::Fps;
+pub use goto::Goto;
 pub use next::Next;
+pub use overlay::Overlay;
 pub use prev::Prev;
+pub use quit::Quit;
+pub use set::Set;
+pub use timing::Timing;
 
 #[derive(Subcommand, Debug)]
 pub enum IpcCommand {
@@ -18,4 +28,21 @@ pub enum IpcCommand {
 
     /// Set the target framerate
     Fps(Fps),
+
+    /// Display a different image, video, or directory
+    #[command(visible_alias = "swap")]
+    Set(Set),
+
+    /// Transition to a file in the current scene's directory
+    Goto(Goto),
+
+    /// Show time until the next rotation and transition progress
+    Timing(Timing),
+
+    /// Toggle the frame-time overlay
+    Overlay(Overlay),
+
+    /// Stop the running daemon
+    #[command(visible_alias = "kill")]
+    Quit(Quit),
 }
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use crate::{
@@ -11,15 +13,29 @@ use crate::{
 
 #[derive(Parser, Debug)]
 #[command(name = "next")]
-pub struct Next;
+pub struct Next {
+    /// Only change the wallpaper on this monitor, such as `DP-1`
+    #[arg(long)]
+    pub monitor: Option<String>,
+
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
 
 impl AllwallCommand for Next {
     async fn execute(&self) -> Result<()> {
-        let response = send_request(&Request::Next)?;
+        let response = send_request(
+            self.socket.as_deref(),
+            &Request::Next {
+                monitor: self.monitor.clone(),
+            },
+        )?;
 
         match response {
             Response::Ok => Ok(()),
             Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
         }
     }
 }
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{
+        AllwallCommand,
+        error::CliError,
+        ipc::protocol::{Request, Response, send_request},
+    },
+    prelude::*,
+};
+
+/// Show or hide the frame-time overlay in the top-left corner of each output
+#[derive(Parser, Debug)]
+#[command(name = "overlay")]
+pub struct Overlay {
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+impl AllwallCommand for Overlay {
+    async fn execute(&self) -> Result<()> {
+        let response = send_request(self.socket.as_deref(), &Request::ToggleOverlay)?;
+
+        match response {
+            Response::Ok => Ok(()),
+            Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
+        }
+    }
+}
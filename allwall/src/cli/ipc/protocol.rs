@@ -1,8 +1,8 @@
 use std::{
-    env,
+    env, fs,
     io::{self, Read, Write},
-    os::unix::net::{SocketAddr, UnixStream},
-    path::PathBuf,
+    os::unix::net::{SocketAddr, UnixListener, UnixStream},
+    path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
@@ -11,17 +11,49 @@ use crate::{cli::error::CliError, prelude::*};
 
 const SOCKET_NAME: &str = "allwall.sock";
 
+/// Framerate range accepted over IPC, keeping the frame timer finite and the CPU out of a spin
+pub const MIN_FPS: u32 = 1;
+pub const MAX_FPS: u32 = 240;
+
+/// `fps` pulled into `MIN_FPS..=MAX_FPS`, or `None` for zero, which has no frame interval
+pub fn clamp_fps(fps: u32) -> Option<u32> {
+    (fps != 0).then(|| fps.clamp(MIN_FPS, MAX_FPS))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Request {
-    Next,
-    Prev,
+    /// Advance the wallpaper, on just the named monitor's scene and source when given
+    Next {
+        monitor: Option<String>,
+    },
+    /// Step back to the previous wallpaper, on just the named monitor when given
+    Prev {
+        monitor: Option<String>,
+    },
     SetFps(u32),
+    SetPath(PathBuf),
+    /// Transition to a file in the current scene's directory, by bare file name or path
+    Goto(PathBuf),
+    Timing,
+    /// Show or hide the frame-time overlay
+    ToggleOverlay,
+    /// Liveness check, answered with [`Response::Pong`]
+    Ping,
+    /// Shut the daemon down once the response is sent
+    Quit,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Response {
     Ok,
+    Pong,
     Error(String),
+    /// Seconds until the next rotation and progress of the running transition (0.0 - 1.0),
+    /// each `None` when there isn't one
+    Timing {
+        rotation_remaining_secs: Option<f32>,
+        transition_progress: Option<f32>,
+    },
 }
 
 impl Response {
@@ -30,6 +62,7 @@ impl Response {
     }
 }
 
+/// Default IPC socket, used unless `--socket` names another
 pub fn socket_path() -> PathBuf {
     let xdg = xdg::BaseDirectories::with_prefix("allwall");
     xdg.get_runtime_directory().map(|p| p.join(SOCKET_NAME)).unwrap_or_else(|_| {
@@ -38,22 +71,49 @@ pub fn socket_path() -> PathBuf {
     })
 }
 
+/// `explicit` when given, so daemons started with different `--socket` values don't collide
+pub fn resolve_socket_path(explicit: Option<&Path>) -> PathBuf {
+    explicit.map(Path::to_path_buf).unwrap_or_else(socket_path)
+}
+
 pub fn socket_addr() -> Result<SocketAddr> {
     SocketAddr::from_pathname(socket_path()).map_err(|_| CliError::SocketAddrCreate.into())
 }
 
-pub fn is_daemon_running() -> bool {
-    let path = socket_path();
-    if !path.exists() {
-        return false;
+pub fn is_daemon_running(socket: Option<&Path>) -> bool {
+    ping(&resolve_socket_path(socket))
+}
+
+/// Whether a daemon answers a [`Request::Ping`] on the socket at `path`
+fn ping(path: &Path) -> bool {
+    path.exists() && matches!(send_request_to(path, &Request::Ping), Ok(Response::Pong))
+}
+
+/// Binds the IPC socket at `path`, replacing one left behind by an unclean shutdown
+///
+/// Returns `None` when a live daemon already owns the socket.
+pub fn bind_socket(path: &Path) -> Result<Option<UnixListener>> {
+    match UnixListener::bind(path) {
+        Ok(listener) => Ok(Some(listener)),
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+            if ping(path) {
+                return Ok(None);
+            }
+
+            info!("Removing stale socket at {}", path.display());
+            fs::remove_file(path)?;
+            Ok(Some(UnixListener::bind(path)?))
+        },
+        Err(e) => Err(e.into()),
     }
+}
 
-    UnixStream::connect(&path).is_ok()
+pub fn send_request(socket: Option<&Path>, request: &Request) -> Result<Response> {
+    send_request_to(&resolve_socket_path(socket), request)
 }
 
-pub fn send_request(request: &Request) -> Result<Response> {
-    let path = socket_path();
-    let mut stream = UnixStream::connect(&path).map_err(|e| match e.kind() {
+fn send_request_to(path: &Path, request: &Request) -> Result<Response> {
+    let mut stream = UnixStream::connect(path).map_err(|e| match e.kind() {
         io::ErrorKind::ConnectionRefused => Error::DaemonNotRunning,
         _ => Error::from(e),
     })?;
@@ -78,21 +138,40 @@ pub fn send_request(request: &Request) -> Result<Response> {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::utils::scratch::ScratchDir;
 
     #[test]
     fn test_request_serialize_next() {
-        let request = Request::Next;
+        let request = Request::Next { monitor: None };
         let encoded = bincode::serialize(&request).unwrap();
         let decoded: Request = bincode::deserialize(&encoded).unwrap();
-        assert!(matches!(decoded, Request::Next));
+        assert!(matches!(decoded, Request::Next { monitor: None }));
+    }
+
+    #[test]
+    fn test_request_serialize_next_for_monitor() {
+        let request = Request::Next {
+            monitor: Some("DP-1".to_string()),
+        };
+        let encoded = bincode::serialize(&request).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        match decoded {
+            Request::Next { monitor } => assert_eq!(monitor.as_deref(), Some("DP-1")),
+            _ => panic!("Expected Next variant"),
+        }
     }
 
     #[test]
     fn test_request_serialize_prev() {
-        let request = Request::Prev;
+        let request = Request::Prev {
+            monitor: Some("HDMI-A-1".to_string()),
+        };
         let encoded = bincode::serialize(&request).unwrap();
         let decoded: Request = bincode::deserialize(&encoded).unwrap();
-        assert!(matches!(decoded, Request::Prev));
+        match decoded {
+            Request::Prev { monitor } => assert_eq!(monitor.as_deref(), Some("HDMI-A-1")),
+            _ => panic!("Expected Prev variant"),
+        }
     }
 
     #[test]
@@ -106,6 +185,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_request_serialize_set_path() {
+        let request = Request::SetPath(PathBuf::from("/home/user/wallpapers"));
+        let encoded = bincode::serialize(&request).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        match decoded {
+            Request::SetPath(path) => assert_eq!(path, PathBuf::from("/home/user/wallpapers")),
+            _ => panic!("Expected SetPath variant"),
+        }
+    }
+
+    #[test]
+    fn test_request_goto_serialization() {
+        let request = Request::Goto(PathBuf::from("sunset.jpg"));
+        let encoded = bincode::serialize(&request).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        match decoded {
+            Request::Goto(path) => assert_eq!(path, PathBuf::from("sunset.jpg")),
+            _ => panic!("Expected Goto variant"),
+        }
+    }
+
     #[test]
     fn test_response_ok_is_ok() {
         let response = Response::Ok;
@@ -133,13 +234,112 @@ mod tests {
         let decoded: Response = bincode::deserialize(&encoded).unwrap();
         match decoded {
             Response::Error(msg) => assert_eq!(msg, "daemon error"),
-            Response::Ok => panic!("Expected Error variant"),
+            _ => panic!("Expected Error variant"),
         }
     }
 
+    #[test]
+    fn test_request_serialize_timing() {
+        let encoded = bincode::serialize(&Request::Timing).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        assert!(matches!(decoded, Request::Timing));
+    }
+
+    #[test]
+    fn test_response_serialize_timing() {
+        let response = Response::Timing {
+            rotation_remaining_secs: Some(7.5),
+            transition_progress: None,
+        };
+        let encoded = bincode::serialize(&response).unwrap();
+        let decoded: Response = bincode::deserialize(&encoded).unwrap();
+        match decoded {
+            Response::Timing {
+                rotation_remaining_secs,
+                transition_progress,
+            } => {
+                assert_eq!(rotation_remaining_secs, Some(7.5));
+                assert_eq!(transition_progress, None);
+            },
+            _ => panic!("Expected Timing variant"),
+        }
+    }
+
+    #[test]
+    fn test_request_serialize_toggle_overlay() {
+        let encoded = bincode::serialize(&Request::ToggleOverlay).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        assert!(matches!(decoded, Request::ToggleOverlay));
+    }
+
+    #[test]
+    fn test_request_serialize_quit() {
+        let encoded = bincode::serialize(&Request::Quit).unwrap();
+        let decoded: Request = bincode::deserialize(&encoded).unwrap();
+        assert!(matches!(decoded, Request::Quit));
+    }
+
+    #[test]
+    fn test_ping_pong_serialize() {
+        let encoded = bincode::serialize(&Request::Ping).unwrap();
+        assert!(matches!(bincode::deserialize(&encoded).unwrap(), Request::Ping));
+
+        let encoded = bincode::serialize(&Response::Pong).unwrap();
+        assert!(matches!(bincode::deserialize(&encoded).unwrap(), Response::Pong));
+    }
+
+    #[test]
+    fn test_bind_socket_replaces_stale_socket() {
+        let dir = ScratchDir::new("ipc-stale");
+        let path = dir.join("allwall.sock");
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let listener = bind_socket(&path).unwrap();
+
+        assert!(listener.is_some());
+    }
+
+    #[test]
+    fn test_bind_socket_defers_to_live_daemon() {
+        let dir = ScratchDir::new("ipc-live");
+        let path = dir.join("allwall.sock");
+        let daemon = UnixListener::bind(&path).unwrap();
+        let responder = std::thread::spawn(move || {
+            let (mut stream, _) = daemon.accept().unwrap();
+            let mut len_buf = [0u8; 4];
+            stream.read_exact(&mut len_buf).unwrap();
+            let mut req_buf = vec![0u8; u32::from_le_bytes(len_buf) as usize];
+            stream.read_exact(&mut req_buf).unwrap();
+            assert!(matches!(bincode::deserialize(&req_buf).unwrap(), Request::Ping));
+
+            let encoded = bincode::serialize(&Response::Pong).unwrap();
+            stream.write_all(&(encoded.len() as u32).to_le_bytes()).unwrap();
+            stream.write_all(&encoded).unwrap();
+        });
+
+        assert!(bind_socket(&path).unwrap().is_none());
+        responder.join().unwrap();
+    }
+
+    #[test]
+    fn test_clamp_fps() {
+        assert_eq!(clamp_fps(0), None);
+        assert_eq!(clamp_fps(1), Some(1));
+        assert_eq!(clamp_fps(240), Some(240));
+        assert_eq!(clamp_fps(100_000), Some(MAX_FPS));
+    }
+
     #[test]
     fn test_socket_path_contains_socket_name() {
         let path = socket_path();
         assert!(path.to_string_lossy().contains("allwall.sock"));
     }
+
+    #[test]
+    fn test_resolve_socket_path_prefers_override() {
+        let explicit = Path::new("/tmp/allwall-second.sock");
+        assert_eq!(resolve_socket_path(Some(explicit)), explicit);
+        assert_eq!(resolve_socket_path(None), socket_path());
+    }
 }
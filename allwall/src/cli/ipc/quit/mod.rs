@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{
+        AllwallCommand,
+        error::CliError,
+        ipc::protocol::{Request, Response, send_request},
+    },
+    prelude::*,
+};
+
+/// Stop the running daemon, removing its IPC socket
+#[derive(Parser, Debug)]
+#[command(name = "quit")]
+pub struct Quit {
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+impl AllwallCommand for Quit {
+    async fn execute(&self) -> Result<()> {
+        let response = send_request(self.socket.as_deref(), &Request::Quit)?;
+
+        match response {
+            Response::Ok => Ok(()),
+            Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{
+        AllwallCommand,
+        error::CliError,
+        ipc::protocol::{Request, Response, send_request},
+    },
+    prelude::*,
+    sources::media::remote,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "set")]
+pub struct Set {
+    /// Image, video, or directory of media to display
+    pub path: PathBuf,
+
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+impl AllwallCommand for Set {
+    async fn execute(&self) -> Result<()> {
+        // The daemon has its own working directory, so relative paths must be resolved here
+        let path = if remote::is_remote(&self.path) {
+            self.path.clone()
+        } else {
+            self.path
+                .canonicalize()
+                .map_err(|_| CliError::PathNotFound(self.path.clone()))?
+        };
+
+        let response = send_request(self.socket.as_deref(), &Request::SetPath(path))?;
+
+        match response {
+            Response::Ok => Ok(()),
+            Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Timing { .. } | Response::Pong => Err(CliError::UnexpectedResponse.into()),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{
+        AllwallCommand,
+        error::CliError,
+        ipc::protocol::{Request, Response, send_request},
+    },
+    prelude::*,
+};
+
+/// Print the time until the next rotation and the progress of any running transition
+#[derive(Parser, Debug)]
+#[command(name = "timing")]
+pub struct Timing {
+    /// Daemon socket, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
+}
+
+impl AllwallCommand for Timing {
+    async fn execute(&self) -> Result<()> {
+        let response = send_request(self.socket.as_deref(), &Request::Timing)?;
+
+        match response {
+            Response::Timing {
+                rotation_remaining_secs,
+                transition_progress,
+            } => {
+                info!("{}", format_timing(rotation_remaining_secs, transition_progress));
+                Ok(())
+            },
+            Response::Error(msg) => Err(CliError::Ipc(msg).into()),
+            Response::Ok | Response::Pong => Err(CliError::UnexpectedResponse.into()),
+        }
+    }
+}
+
+fn format_timing(rotation_remaining_secs: Option<f32>, transition_progress: Option<f32>) -> String {
+    let rotation = match rotation_remaining_secs {
+        Some(secs) => f!("{secs:.1}s"),
+        None => "none".to_string(),
+    };
+    let transition = match transition_progress {
+        Some(progress) => f!("{:.0}%", progress * 100.0),
+        None => "none".to_string(),
+    };
+    f!("next rotation: {rotation}\ntransition: {transition}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timing_with_values() {
+        assert_eq!(
+            format_timing(Some(7.3), Some(0.42)),
+            "next rotation: 7.3s\ntransition: 42%"
+        );
+    }
+
+    #[test]
+    fn test_format_timing_without_values() {
+        assert_eq!(format_timing(None, None), "next rotation: none\ntransition: none");
+    }
+}
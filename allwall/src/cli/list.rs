@@ -0,0 +1,98 @@
+use clap::{Parser, ValueEnum};
+
+use super::AllwallCommand;
+use crate::{
+    engine::scene::{Fit, Layout},
+    prelude::*,
+    sources::SourceKind,
+    transitions::TransitionType,
+};
+
+/// Print the names a config option accepts, one per line, then exit
+///
+/// Meant for scripts and completion generators, so nothing but the names is printed.
+#[derive(Parser, Debug)]
+#[command()]
+pub struct List {
+    /// Which option's values to print
+    #[arg(value_enum)]
+    what: ListKind,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[clap(rename_all = "kebab-case")]
+enum ListKind {
+    /// Transition `type`s
+    Transitions,
+    /// Scene `fit`s
+    Fits,
+    /// Scene `layout`s
+    Layouts,
+    /// Scene `type`s
+    Sources,
+}
+
+impl ListKind {
+    fn names(self) -> Vec<String> {
+        match self {
+            ListKind::Transitions => names::<TransitionType>(),
+            ListKind::Fits => names::<Fit>(),
+            ListKind::Layouts => names::<Layout>(),
+            ListKind::Sources => names::<SourceKind>(),
+        }
+    }
+}
+
+/// The kebab-case name of every variant, as config files and the CLI spell them
+fn names<T: ValueEnum>() -> Vec<String> {
+    T::value_variants()
+        .iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+impl AllwallCommand for List {
+    async fn execute(&self) -> Result<()> {
+        for name in self.what.names() {
+            info!("{name}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions_cover_every_type() {
+        let names = ListKind::Transitions.names();
+        assert!(names.contains(&"fade".to_string()));
+        for circle in [
+            "circle-top-left",
+            "circle-top-right",
+            "circle-bottom-left",
+            "circle-bottom-right",
+            "circle-center",
+            "circle-random",
+            "circle-mouse",
+        ] {
+            assert!(names.contains(&circle.to_string()), "missing {circle}");
+        }
+
+        // Matches the names config files use for every variant
+        let config_names: Vec<_> = TransitionType::value_variants()
+            .iter()
+            .map(|t| t.as_kebab_case_str().to_string())
+            .collect();
+        assert_eq!(names, config_names);
+    }
+
+    #[test]
+    fn test_other_lists() {
+        assert!(ListKind::Fits.names().contains(&"cover".to_string()));
+        assert_eq!(ListKind::Layouts.names(), ["clone", "independent", "span"]);
+        assert!(ListKind::Sources.names().contains(&"media".to_string()));
+    }
+}
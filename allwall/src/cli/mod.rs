@@ -4,15 +4,27 @@ pub trait AllwallCommand {
     async fn execute(&self) -> Result<()>;
 }
 
+mod bench;
 pub mod completions;
 pub mod error;
+mod generate_config;
 pub mod ipc;
+mod list;
+mod monitors;
+mod render;
 mod run;
+mod validate;
 mod version;
 
+pub use bench::Bench;
 pub use completions::Completions;
-pub use ipc::{Fps, Next, Prev};
+pub use generate_config::GenerateConfig;
+pub use ipc::{Fps, Goto, Next, Overlay, Prev, Quit, Set, Timing};
+pub use list::List;
+pub use monitors::ListMonitors;
+pub use render::Render;
 pub use run::Run;
+pub use validate::Validate;
 pub use version::Version;
 
 #[derive(clap::Subcommand, Debug)]
@@ -34,4 +46,42 @@ pub enum Commands {
 
     /// Set the target framerate
     Fps(Fps),
+
+    /// Display a different image, video, or directory
+    #[command(visible_alias = "swap")]
+    Set(Set),
+
+    /// Transition to a file in the current scene's directory
+    Goto(Goto),
+
+    /// Show time until the next rotation and transition progress
+    Timing(Timing),
+
+    /// Toggle the frame-time overlay
+    Overlay(Overlay),
+
+    /// Stop the running daemon
+    #[command(visible_alias = "kill")]
+    Quit(Quit),
+
+    /// Check the config file for errors without starting the daemon
+    #[command(visible_alias = "dry-run")]
+    Validate(Validate),
+
+    /// Render a single frame to an image file and exit
+    #[command(visible_alias = "once")]
+    Render(Render),
+
+    /// Time a source's frames in a headless context and print the mean and p99
+    Bench(Bench),
+
+    /// List connected outputs and their geometry
+    #[command(visible_alias = "monitors")]
+    ListMonitors(ListMonitors),
+
+    /// Write a commented starter config holding the default settings
+    GenerateConfig(GenerateConfig),
+
+    /// Print the accepted values of a config option, one per line
+    List(List),
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use clap::Parser;
+use smithay_client_toolkit::{
+    delegate_output, delegate_registry,
+    output::{OutputHandler, OutputInfo, OutputState},
+    reexports::client::{Connection, QueueHandle, globals::registry_queue_init, protocol::wl_output},
+    registry::{ProvidesRegistryState, RegistryState},
+};
+
+use super::AllwallCommand;
+use crate::{engine::error::EngineError, prelude::*};
+
+/// Print connected outputs and their geometry, then exit
+///
+/// Output names are what `monitors = [...]` in a scene matches against.
+/// Only talks to the compositor, so no GPU context or running daemon is needed.
+#[derive(Parser, Debug)]
+#[command()]
+pub struct ListMonitors;
+
+impl AllwallCommand for ListMonitors {
+    async fn execute(&self) -> Result<()> {
+        let conn = Connection::connect_to_env().map_err(|e| EngineError::WaylandConnect(e.to_string()))?;
+        let (globals, mut queue) =
+            registry_queue_init::<OutputLister>(&conn).map_err(|e| EngineError::WaylandRegistry(e.to_string()))?;
+        let qh = queue.handle();
+
+        let mut lister = OutputLister {
+            registry_state: RegistryState::new(&globals),
+            output_state: OutputState::new(&globals, &qh),
+        };
+
+        // The first roundtrip binds each output, the second delivers its properties
+        for _ in 0..2 {
+            queue
+                .roundtrip(&mut lister)
+                .map_err(|e| EngineError::WaylandRegistry(e.to_string()))?;
+        }
+
+        let mut monitors: Vec<_> = lister
+            .output_state
+            .outputs()
+            .filter_map(|output| lister.output_state.info(&output))
+            .map(|info| MonitorSummary::from(&info))
+            .collect();
+        monitors.sort_by_key(|m| m.position);
+
+        if monitors.is_empty() {
+            warn!("No outputs reported by the compositor");
+        }
+        for monitor in &monitors {
+            info!("{monitor}");
+        }
+
+        Ok(())
+    }
+}
+
+/// What `list-monitors` prints for one output
+#[derive(Debug, Clone, PartialEq)]
+struct MonitorSummary {
+    name: String,
+    make: String,
+    model: String,
+    size: Option<(i32, i32)>,
+    position: (i32, i32),
+    scale: i32,
+}
+
+impl From<&OutputInfo> for MonitorSummary {
+    fn from(info: &OutputInfo) -> Self {
+        Self {
+            name: info.name.clone().unwrap_or_else(|| "unknown".to_string()),
+            make: info.make.clone(),
+            model: info.model.clone(),
+            size: info.logical_size,
+            position: info.logical_position.unwrap_or(info.location),
+            scale: info.scale_factor,
+        }
+    }
+}
+
+impl fmt::Display for MonitorSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        writeln!(f, "  make/model: {} {}", self.make, self.model)?;
+        match self.size {
+            Some((width, height)) => writeln!(f, "  size: {width}x{height}")?,
+            None => writeln!(f, "  size: unknown")?,
+        }
+        writeln!(f, "  position: {},{}", self.position.0, self.position.1)?;
+        write!(f, "  scale: {}", self.scale)
+    }
+}
+
+/// Just enough Wayland state to enumerate outputs
+struct OutputLister {
+    registry_state: RegistryState,
+    output_state: OutputState,
+}
+
+impl OutputHandler for OutputLister {
+    fn output_state(&mut self) -> &mut OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+
+    fn update_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+
+    fn output_destroyed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+}
+
+impl ProvidesRegistryState for OutputLister {
+    fn registry(&mut self) -> &mut RegistryState {
+        &mut self.registry_state
+    }
+
+    fn runtime_add_global(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _name: u32,
+        _interface: &str,
+        _version: u32,
+    ) {
+    }
+
+    fn runtime_remove_global(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _name: u32, _interface: &str) {}
+}
+
+delegate_output!(OutputLister);
+delegate_registry!(OutputLister);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_summary_display() {
+        let monitor = MonitorSummary {
+            name: "DP-1".to_string(),
+            make: "Dell Inc.".to_string(),
+            model: "U2720Q".to_string(),
+            size: Some((1920, 1080)),
+            position: (2560, 0),
+            scale: 2,
+        };
+
+        assert_eq!(
+            monitor.to_string(),
+            "DP-1\n  make/model: Dell Inc. U2720Q\n  size: 1920x1080\n  position: 2560,0\n  scale: 2"
+        );
+    }
+
+    #[test]
+    fn test_monitor_summary_display_unknown_size() {
+        let monitor = MonitorSummary {
+            name: "HDMI-A-1".to_string(),
+            make: String::new(),
+            model: String::new(),
+            size: None,
+            position: (0, 0),
+            scale: 1,
+        };
+
+        assert!(monitor.to_string().contains("  size: unknown\n"));
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use super::AllwallCommand;
+use crate::{
+    cli::error::CliError,
+    config::{AppConfig, Config, load_config},
+    engine::Engine,
+    prelude::*,
+    sources::SourceKind,
+};
+
+/// Render a single frame to an image file and exit
+///
+/// Uses a headless GPU context, so no Wayland session or running daemon is needed.
+/// Handy for wallpaper picker thumbnails.
+///
+/// # Examples
+///
+/// ```bash
+/// allwall render --source media --path ~/Pictures/wallpapers -o preview.png
+/// allwall render --source smoke --width 640 --height 360 -o smoke.png
+/// ```
+#[derive(Parser, Debug)]
+#[command()]
+pub struct Render {
+    /// Image file to write, format chosen from the extension
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Width of the rendered image in pixels
+    #[arg(long, default_value_t = 1920)]
+    pub width: u32,
+
+    /// Height of the rendered image in pixels
+    #[arg(long, default_value_t = 1080)]
+    pub height: u32,
+
+    /// Source type: media (images/videos), smoke, grass, color, or gradient
+    #[arg(short, long, value_enum, default_value_t = SourceKind::Media)]
+    pub source: SourceKind,
+
+    /// Path to image/video file or directory, instead of the first scene's path
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Explicit config file, taken from the global `--config` flag
+    #[arg(skip)]
+    pub config: Option<PathBuf>,
+}
+
+impl AllwallCommand for Render {
+    async fn execute(&self) -> Result<()> {
+        let config = match load_config(self.config.as_deref()) {
+            Err(e) if self.config.is_none() => {
+                warn!("Using default config: {e}");
+                Config::default()
+            },
+            result => result?,
+        };
+
+        if matches!(self.source, SourceKind::Media) && self.path.is_none() && config.scenes.is_empty() {
+            return Err(CliError::MediaPathRequired.into());
+        }
+
+        let config = AppConfig::from_config(config)?.merge_cli(self.path.clone(), None, None, None, None);
+        let image = Engine::render_once(config, self.source, (self.width, self.height))?;
+        image.save(&self.output)?;
+
+        info!(
+            "Rendered {}x{} frame to {}",
+            self.width,
+            self.height,
+            self.output.display()
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use std::{fs, path::PathBuf};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
 
 use clap::Parser;
 
@@ -6,9 +9,9 @@ use super::AllwallCommand;
 use crate::{
     cli::{
         error::CliError,
-        ipc::protocol::{is_daemon_running, socket_path},
+        ipc::protocol::{is_daemon_running, resolve_socket_path},
     },
-    config::{AppConfig, load_config},
+    config::{AppConfig, Config, load_config},
     engine::Engine,
     prelude::*,
     sources::SourceKind,
@@ -22,8 +25,10 @@ pub struct Run {
     #[arg(long)]
     pub path: Option<PathBuf>,
 
-    /// Source type: media (images/videos), smoke, or grass
-    #[arg(short, long)]
+    /// Source type: media (images/videos), smoke, grass, color, or gradient
+    ///
+    /// Media needs a path, from `--path` or a configured scene.
+    #[arg(short, long, value_enum, default_value_t = SourceKind::Media)]
     pub source: SourceKind,
 
     /// Duration of transitions between images (in seconds)
@@ -34,42 +39,123 @@ pub struct Run {
     #[arg(short, long)]
     pub transition_interval: Option<u64>,
 
-    /// Transition animation type: fade, circle-top-left, circle-top-right, circle-bottom-left, circle-bottom-right, circle-center, circle-random
+    /// Transition animation type: fade, dip, wipe, pixelate, circle-top-left, circle-top-right, circle-bottom-left, circle-bottom-right, circle-center, circle-random, circle-mouse, random
     #[arg(long)]
     pub transition_type: Option<TransitionType>,
 
     /// Target framerate
     #[arg(long)]
     pub fps: Option<u32>,
+
+    /// Print the effective configuration, after CLI overrides, as JSON and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Run without the IPC socket, so `next`, `set` and the other commands can't reach this daemon
+    #[arg(long)]
+    pub no_ipc: bool,
+
+    /// Read WGSL from this directory instead of the built-in copies and rebuild sources when it changes
+    ///
+    /// Point it at `allwall/src`, or any tree holding the shader files under their usual names.
+    #[cfg(feature = "dev")]
+    #[arg(long, value_name = "DIR")]
+    pub watch_shaders: Option<PathBuf>,
+
+    /// Explicit config file, taken from the global `--config` flag
+    #[arg(skip)]
+    pub config: Option<PathBuf>,
+
+    /// Socket to listen on, taken from the global `--socket` flag
+    #[arg(skip)]
+    pub socket: Option<PathBuf>,
 }
 
 impl AllwallCommand for Run {
     async fn execute(&self) -> Result<()> {
-        if is_daemon_running() {
-            return Err(CliError::DaemonRunning.into());
+        // Checked again when the engine binds the socket, but bailing here skips GPU setup
+        if !self.print_config && is_daemon_running(self.socket.as_deref()) {
+            warn!("allwall already running");
+            return Ok(());
         }
 
-        let socket_path = socket_path();
-        if socket_path.exists() {
-            info!("Removing stale socket at {}", socket_path.display());
-            fs::remove_file(&socket_path)?;
+        let config = match load_config(self.config.as_deref()) {
+            Err(e) if self.config.is_none() => {
+                warn!("Using default config: {e}");
+                Config::default()
+            },
+            result => result?,
+        };
+
+        if !self.print_config
+            && matches!(self.source, SourceKind::Media)
+            && self.path.is_none()
+            && config.scenes.is_empty()
+        {
+            return Err(CliError::MediaPathRequired.into());
         }
 
-        let config = load_config().unwrap_or_default();
+        let mut app_config = AppConfig::from_config(config)?.merge_cli(
+            self.path.clone(),
+            self.transition_duration,
+            self.transition_interval,
+            self.transition_type,
+            self.fps,
+        );
+        if self.no_ipc {
+            app_config.general.ipc = false;
+        }
 
-        if matches!(self.source, SourceKind::Media) && self.path.is_none() && config.scenes.is_empty() {
-            return Err(CliError::MediaPathRequired.into());
+        if self.print_config {
+            // The config itself is the output, so it goes to stdout rather than the log
+            writeln!(io::stdout().lock(), "{}", app_config.to_json()?)?;
+            return Ok(());
         }
 
-        Engine::run(
-            AppConfig::from_config(config)?.merge_cli(
-                self.path.clone(),
-                self.transition_duration,
-                self.transition_interval,
-                self.transition_type,
-                self.fps,
-            ),
-            self.source,
-        )
+        #[cfg(feature = "dev")]
+        if let Some(dir) = &self.watch_shaders {
+            crate::engine::graphics::shader_store::watch(dir)?;
+        }
+
+        let socket = app_config.general.ipc.then(|| resolve_socket_path(self.socket.as_deref()));
+        Engine::run(app_config, self.source, socket)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_defaults_to_media() {
+        assert_eq!(Run::try_parse_from(["run"]).unwrap().source, SourceKind::Media);
+    }
+
+    #[test]
+    fn test_source_parses_every_kind() {
+        for (arg, kind) in [
+            ("media", SourceKind::Media),
+            ("smoke", SourceKind::Smoke),
+            ("grass", SourceKind::Grass),
+            ("color", SourceKind::Color),
+            ("gradient", SourceKind::Gradient),
+        ] {
+            assert_eq!(Run::try_parse_from(["run", "--source", arg]).unwrap().source, kind);
+            assert_eq!(Run::try_parse_from(["run", "-s", arg]).unwrap().source, kind);
+        }
+        assert!(Run::try_parse_from(["run", "--source", "fire"]).is_err());
+    }
+
+    #[test]
+    fn test_print_config_flag() {
+        assert!(!Run::try_parse_from(["run"]).unwrap().print_config);
+        assert!(Run::try_parse_from(["run", "--print-config"]).unwrap().print_config);
+    }
+
+    #[test]
+    fn test_no_ipc_flag() {
+        assert!(!Run::try_parse_from(["run"]).unwrap().no_ipc);
+        assert!(Run::try_parse_from(["run", "--no-ipc"]).unwrap().no_ipc);
     }
 }
@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+
+use super::AllwallCommand;
+use crate::{
+    cli::error::CliError,
+    config::{AppConfig, Config, default_config_path, load_config_from},
+    engine::Layout,
+    prelude::*,
+    sources::media::{is_supported_media, media_files, remote},
+};
+
+#[derive(Parser, Debug)]
+#[command()]
+pub struct Validate {
+    /// Config file to check, taken from the global `--config` flag
+    #[arg(skip)]
+    pub config: Option<PathBuf>,
+}
+
+impl AllwallCommand for Validate {
+    async fn execute(&self) -> Result<()> {
+        let path = match &self.config {
+            Some(path) => path.clone(),
+            None => default_config_path()?,
+        };
+
+        let report = validate_file(&path, self.config.is_some());
+        report.print(&path);
+
+        if report.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::InvalidConfig(report.errors.len()).into())
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl Report {
+    fn print(&self, path: &Path) {
+        info!("Checking {}", path.display());
+        for warning in &self.warnings {
+            warn!("{warning}");
+        }
+        for error in &self.errors {
+            error!("{error}");
+        }
+        info!("{} error(s), {} warning(s)", self.errors.len(), self.warnings.len());
+    }
+}
+
+/// A missing file only counts as an error when it was asked for explicitly, since the
+/// daemon falls back to defaults without one
+fn validate_file(path: &Path, required: bool) -> Report {
+    if !path.exists() {
+        let message = f!("No config file at {}", path.display());
+        let mut report = Report::default();
+        if required {
+            report.errors.push(message);
+        } else {
+            report.warnings.push(f!("{message}, defaults will be used"));
+        }
+        return report;
+    }
+
+    match load_config_from(path) {
+        Ok(config) => validate_config(config),
+        Err(e) => Report {
+            errors: vec![e.to_string()],
+            warnings: Vec::new(),
+        },
+    }
+}
+
+fn validate_config(config: Config) -> Report {
+    let mut report = Report::default();
+    let scenes = config.scenes.clone();
+
+    if let Err(e) = AppConfig::from_config(config) {
+        report.errors.push(e.to_string());
+    }
+
+    if scenes.is_empty() {
+        report
+            .warnings
+            .push("No scenes configured, media sources will need --path".to_string());
+    }
+
+    for (i, scene) in scenes.iter().enumerate() {
+        let label = f!("Scene {i}");
+        let mapped = scene.monitors.mapped_paths();
+
+        if mapped.is_empty() || scene.path.is_some() {
+            check_scene_path(&label, scene.path.as_deref(), scene.recursive, &mut report);
+        }
+
+        for (handle, path) in mapped {
+            check_scene_path(
+                &f!("{label} monitor {}", handle.name()),
+                Some(path),
+                scene.recursive,
+                &mut report,
+            );
+        }
+
+        if !mapped.is_empty() && scene.layout != Layout::Independent {
+            report.warnings.push(f!(
+                "{label} maps monitors to paths, but only the independent layout uses them"
+            ));
+        }
+    }
+
+    report
+}
+
+fn check_scene_path(label: &str, path: Option<&Path>, recursive: bool, report: &mut Report) {
+    let Some(path) = path else {
+        report.warnings.push(f!("{label} has no path, media sources will need --path"));
+        return;
+    };
+
+    if remote::is_remote(path) {
+        if !cfg!(feature = "net") {
+            report.warnings.push(f!(
+                "{label}: {} needs the `net` feature, only cached copies will show",
+                path.display()
+            ));
+        }
+        return;
+    }
+
+    if !path.exists() {
+        report.errors.push(f!("{label}: {} does not exist", path.display()));
+        return;
+    }
+
+    if path.is_file() {
+        if !is_supported_media(path) {
+            report
+                .errors
+                .push(f!("{label}: {} is not a supported image or video", path.display()));
+        }
+        return;
+    }
+
+    match path.read_dir() {
+        Ok(_) => {
+            let has_media = media_files(path, recursive).is_ok_and(|files| !files.is_empty());
+            if !has_media {
+                report
+                    .errors
+                    .push(f!("{label}: {} contains no supported images or videos", path.display()));
+            }
+        },
+        Err(e) => report.errors.push(f!("{label}: failed to read {}: {e}", path.display())),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        engine::{
+            DEFAULT_FALLBACK_COLOR, DEFAULT_LETTERBOX_BLUR, DEFAULT_SMART_MAX_CROP, MonitorsSpec, SceneConfig,
+            scene::MonitorHandle,
+        },
+        utils::scratch::ScratchDir,
+    };
+
+    fn make_scene(path: Option<PathBuf>, monitors: MonitorsSpec) -> SceneConfig {
+        SceneConfig {
+            path,
+            layout: Default::default(),
+            independent_offset: Default::default(),
+            fit: Default::default(),
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
+            monitors,
+            transition: None,
+        }
+    }
+
+    #[test]
+    fn test_default_config_only_warns() {
+        let report = validate_config(Config::default());
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_scene_path_is_error() {
+        let config = Config {
+            scenes: vec![make_scene(
+                Some(PathBuf::from("/nonexistent/allwall/wallpapers")),
+                MonitorsSpec::Any,
+            )],
+            ..Default::default()
+        };
+
+        let report = validate_config(config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("does not exist"));
+    }
+
+    #[test]
+    fn test_directory_without_media_is_error() {
+        let dir = ScratchDir::new("validate-empty");
+        fs::write(dir.join("notes.txt"), "not a wallpaper").unwrap();
+
+        let mut report = Report::default();
+        check_scene_path("Scene 0", Some(&dir), false, &mut report);
+        assert_eq!(report.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_directory_with_media_is_ok() {
+        let dir = ScratchDir::new("validate-media");
+        fs::write(dir.join("wallpaper.png"), b"").unwrap();
+
+        let mut report = Report::default();
+        check_scene_path("Scene 0", Some(&dir), false, &mut report);
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_nested_media_needs_recursive() {
+        let dir = ScratchDir::new("validate-nested");
+        fs::create_dir_all(dir.join("nature")).unwrap();
+        fs::write(dir.join("nature/wallpaper.png"), b"").unwrap();
+
+        let mut report = Report::default();
+        check_scene_path("Scene 0", Some(&dir), false, &mut report);
+        assert_eq!(report.errors.len(), 1);
+
+        let mut report = Report::default();
+        check_scene_path("Scene 0", Some(&dir), true, &mut report);
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn test_url_path_is_not_checked_on_disk() {
+        let mut report = Report::default();
+        check_scene_path(
+            "Scene 0",
+            Some(Path::new("https://example.com/wall.jpg")),
+            false,
+            &mut report,
+        );
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), usize::from(!cfg!(feature = "net")));
+    }
+
+    #[test]
+    fn test_overlapping_monitors_is_error() {
+        let dp1 = || MonitorsSpec::Specific(vec![MonitorHandle::new("DP-1".to_string())]);
+        let config = Config {
+            scenes: vec![make_scene(None, dp1()), make_scene(None, dp1())],
+            ..Default::default()
+        };
+
+        let report = validate_config(config);
+        assert_eq!(report.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_mapped_path_is_error() {
+        let dir = ScratchDir::new("validate-mapped");
+        fs::write(dir.join("wallpaper.png"), b"").unwrap();
+
+        let mut scene = make_scene(
+            None,
+            MonitorsSpec::Mapped(vec![
+                (MonitorHandle::new("DP-1".to_string()), dir.clone()),
+                (
+                    MonitorHandle::new("HDMI-A-1".to_string()),
+                    PathBuf::from("/nonexistent/allwall/wallpapers"),
+                ),
+            ]),
+        );
+        scene.layout = Layout::Independent;
+        let config = Config {
+            scenes: vec![scene],
+            ..Default::default()
+        };
+
+        let report = validate_config(config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("HDMI-A-1"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_mapped_paths_outside_independent_layout_warn() {
+        let config = Config {
+            scenes: vec![make_scene(
+                Some(PathBuf::from("/nonexistent/allwall/wallpapers")),
+                MonitorsSpec::Mapped(vec![(MonitorHandle::new("DP-1".to_string()), PathBuf::from("/a"))]),
+            )],
+            ..Default::default()
+        };
+
+        let report = validate_config(config);
+        assert!(report.warnings.iter().any(|w| w.contains("independent")));
+    }
+
+    #[test]
+    fn test_missing_explicit_config_is_error() {
+        let path = Path::new("/nonexistent/allwall/config.toml");
+        assert_eq!(validate_file(path, true).errors.len(), 1);
+        assert!(validate_file(path, false).errors.is_empty());
+    }
+}
@@ -14,6 +14,9 @@ pub enum ConfigError {
         source: toml::de::Error,
     },
 
+    #[error("Config file {0} does not exist")]
+    NotFound(std::path::PathBuf),
+
     #[error("Failed to determine XDG config directory: {0}")]
     XdgBaseDir(String),
 
@@ -25,4 +28,49 @@ pub enum ConfigError {
 
     #[error("Invalid GPU selection '{value}': {reason}")]
     InvalidGpu { value: String, reason: String },
+
+    #[error(
+        "Invalid smoke simulation_resolution {0}: expected a power of two from {min} to {max}",
+        min = crate::config::source::MIN_SIMULATION_RESOLUTION,
+        max = crate::config::source::MAX_SIMULATION_RESOLUTION
+    )]
+    InvalidSimulationResolution(u32),
+
+    #[error("Invalid smoke mouse_deadzone {0}: expected a fraction of the surface from 0.0 to 1.0")]
+    InvalidMouseDeadzone(f32),
+
+    #[error(
+        "Invalid video playback_rate {0}: expected a speed from {min} to {max}",
+        min = crate::config::source::MIN_PLAYBACK_RATE,
+        max = crate::config::source::MAX_PLAYBACK_RATE
+    )]
+    InvalidPlaybackRate(f64),
+
+    #[error("Invalid grass msaa {0}: expected one of {counts:?}", counts = crate::config::source::MSAA_SAMPLE_COUNTS)]
+    InvalidMsaa(u32),
+
+    #[error(
+        "Invalid color_adjust {field} {value}: brightness, contrast and saturation can't be negative, and temperature must be from {min} to {max}",
+        min = crate::config::source::MIN_TEMPERATURE_SHIFT,
+        max = crate::config::source::MAX_TEMPERATURE_SHIFT
+    )]
+    InvalidColorAdjust { field: &'static str, value: f32 },
+
+    #[error("Invalid scene smart_max_crop {0}: expected a share of the image from 0.0 to 1.0")]
+    InvalidSmartMaxCrop(f32),
+
+    #[error("Invalid scene {field} {value}: expected a blur radius in pixels of 0.0 or more")]
+    InvalidBlur { field: &'static str, value: f32 },
+
+    #[error("Transition type random needs at least one other type in random_types")]
+    EmptyRandomTransitions,
+
+    #[error("Schedule entry at {at} switches scene {scene}, but only scenes 0 to {last} exist", last = scenes - 1)]
+    UnknownScheduleScene { at: String, scene: usize, scenes: usize },
+
+    #[error("The {kind} source does not support the {transition} transition")]
+    UnsupportedSourceTransition {
+        kind: crate::sources::SourceKind,
+        transition: crate::transitions::TransitionType,
+    },
 }
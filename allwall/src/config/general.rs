@@ -1,14 +1,17 @@
 use std::{
     borrow::Cow::{self, Borrowed},
     result::Result,
+    time::Duration,
 };
 
 #[cfg(feature = "generate")]
 use schemars::{JsonSchema, json_schema};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "generate")]
 use serde_nixos::{self, NixosType};
 
+use crate::prelude::f;
+
 /// GPU selection strategy for rendering
 ///
 /// Determines which GPU to use for wallpaper rendering.
@@ -23,12 +26,12 @@ pub enum GpuSelection {
     #[default]
     Auto,
 
-    /// Force use of integrated graphics
+    /// Force use of integrated graphics (alias: `low-power`)
     ///
     /// Useful for power saving on laptops with hybrid graphics.
     Integrated,
 
-    /// Force use of dedicated graphics
+    /// Force use of dedicated graphics (alias: `high-performance`)
     ///
     /// Required for some multi-monitor setups or when integrated
     /// graphics don't support required features.
@@ -39,6 +42,11 @@ pub enum GpuSelection {
     /// Use `lspci -nn` to find the vendor and device IDs.
     /// Format: `pci:VENDOR:DEVICE` (hexadecimal, e.g., `pci:10de:1b80`)
     Pci { vendor: u16, device: u16 },
+
+    /// Select a GPU whose adapter name contains the given text (case-insensitive)
+    ///
+    /// Format: `name:TEXT` (e.g., `name:radeon`)
+    Name(String),
 }
 
 impl<'de> Deserialize<'de> for GpuSelection {
@@ -48,9 +56,15 @@ impl<'de> Deserialize<'de> for GpuSelection {
     {
         match String::deserialize(deserializer)?.to_lowercase().as_str() {
             "auto" => Ok(GpuSelection::Auto),
-            "integrated" => Ok(GpuSelection::Integrated),
-            "dedicated" => Ok(GpuSelection::Dedicated),
+            "integrated" | "low-power" => Ok(GpuSelection::Integrated),
+            "dedicated" | "high-performance" => Ok(GpuSelection::Dedicated),
             s => {
+                if let Some(name) = s.strip_prefix("name:")
+                    && !name.is_empty()
+                {
+                    return Ok(GpuSelection::Name(name.to_string()));
+                }
+
                 if let Some(pci_str) = s.strip_prefix("pci:") {
                     let parts: Vec<&str> = pci_str.split(':').collect();
                     if parts.len() == 2 {
@@ -62,7 +76,7 @@ impl<'de> Deserialize<'de> for GpuSelection {
                     }
                 }
                 Err(serde::de::Error::custom(format!(
-                    "Invalid gpu value: {}. Expected 'auto', 'integrated', 'dedicated', or 'pci:vendor:device'",
+                    "Invalid gpu value: {}. Expected 'auto', 'integrated', 'dedicated', 'low-power', 'high-performance', 'pci:vendor:device', or 'name:text'",
                     s
                 )))
             },
@@ -70,6 +84,21 @@ impl<'de> Deserialize<'de> for GpuSelection {
     }
 }
 
+impl Serialize for GpuSelection {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            GpuSelection::Auto => serializer.serialize_str("auto"),
+            GpuSelection::Integrated => serializer.serialize_str("integrated"),
+            GpuSelection::Dedicated => serializer.serialize_str("dedicated"),
+            GpuSelection::Pci { vendor, device } => serializer.serialize_str(&f!("pci:{vendor:04x}:{device:04x}")),
+            GpuSelection::Name(name) => serializer.serialize_str(&f!("name:{name}")),
+        }
+    }
+}
+
 #[cfg(feature = "generate")]
 impl JsonSchema for GpuSelection {
     fn schema_name() -> Cow<'static, str> {
@@ -83,14 +112,53 @@ impl JsonSchema for GpuSelection {
     fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
         json_schema!({
             "type": "string",
-            "pattern": "auto|integrated|dedicated|pci:[0-9a-fA-F]{4}:[0-9a-fA-F]{4}",
-            "description": "GPU selection strategy. Use 'auto', 'integrated', 'dedicated', or 'pci:VENDOR:DEVICE' (hex IDs, e.g., 'pci:10de:1b80')"
+            "pattern": "auto|integrated|dedicated|low-power|high-performance|pci:[0-9a-fA-F]{4}:[0-9a-fA-F]{4}|name:.+",
+            "description": "GPU selection strategy. Use 'auto', 'integrated' (or 'low-power'), 'dedicated' (or 'high-performance'), 'pci:VENDOR:DEVICE' (hex IDs, e.g., 'pci:10de:1b80'), or 'name:TEXT' to match the adapter name"
         })
     }
 }
 
+/// What is drawn under each frame, for checking transparency and letterboxing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum DebugBackground {
+    /// Frames are drawn as usual
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    None,
+
+    /// A grey checkerboard shows through wherever a frame is transparent
+    Checker,
+}
+
+/// How finished frames are handed to the compositor
+///
+/// Modes the surface doesn't support fall back to `fifo`, which every surface offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum PresentModeConfig {
+    /// Vsync, queueing frames until the display refreshes
+    Fifo,
+
+    /// Vsync, replacing a waiting frame with a newer one for lower latency
+    Mailbox,
+
+    /// No vsync, presenting straight away and possibly tearing
+    Immediate,
+
+    /// Vsync through the best of `fifo-relaxed` and `fifo` the surface offers
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    AutoVsync,
+
+    /// No vsync through the best of `immediate`, `mailbox` and `fifo` the surface offers
+    AutoNoVsync,
+}
+
 /// General engine configuration options
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, NixosType))]
 pub struct GeneralConfig {
     /// Target framerate for wallpaper rendering
@@ -102,16 +170,131 @@ pub struct GeneralConfig {
     #[cfg_attr(feature = "generate", nixos(default = "30"))]
     pub fps: u32,
 
+    /// Framerate used while nothing on screen is changing
+    ///
+    /// Once a still image or solid color has finished its transition, rendering
+    /// drops to this rate until the next transition starts.
+    #[serde(default = "default_idle_fps")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_idle_fps"))]
+    #[cfg_attr(feature = "generate", nixos(default = "1"))]
+    pub idle_fps: u32,
+
+    /// Reopen the last shown wallpaper after a restart
+    ///
+    /// Each scene's current media is recorded in `$XDG_STATE_HOME/allwall/state.json`
+    /// whenever it changes and on shutdown.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "false"))]
+    pub restore_last: bool,
+
+    /// Let the wallpaper receive pointer input
+    ///
+    /// Smoke and grass react to the cursor when enabled. Clicks on the desktop
+    /// then land on the wallpaper instead of passing through to the surface below.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "false"))]
+    pub interactive: bool,
+
+    /// Stop rotating and drop to `idle_fps` while fullscreen or maximized windows hide a scene
+    ///
+    /// Only the focused window is considered, as windows on hidden workspaces look the same
+    /// to allwall as visible ones. Needs a compositor with `wlr-foreign-toplevel-management`
+    /// (Sway, Hyprland, river, labwc, ...). Elsewhere the setting has no effect.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "false"))]
+    pub pause_on_fullscreen: bool,
+
+    /// How many times to try connecting to the compositor at startup
+    ///
+    /// Useful when allwall starts slightly before the Wayland socket exists.
+    /// The wait between attempts doubles each time, starting at `connect_delay_ms`, so
+    /// the default 6 attempts wait 100ms, 200ms, 400ms, 800ms and 1.6s.
+    #[serde(default = "default_connect_attempts")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_connect_attempts"))]
+    #[cfg_attr(feature = "generate", nixos(default = "6"))]
+    pub connect_attempts: u32,
+
+    /// Wait in milliseconds before the first connection retry
+    #[serde(default = "default_connect_delay_ms")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_connect_delay_ms"))]
+    #[cfg_attr(feature = "generate", nixos(default = "100"))]
+    pub connect_delay_ms: u64,
+
+    /// Listen on a Unix socket for `allwall next`, `set` and the other control commands
+    ///
+    /// Turn off where binding a socket fails or isn't wanted, such as in some sandboxes.
+    /// The wallpaper still runs, but can only be changed by restarting it.
+    #[serde(default = "default_ipc")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_ipc"))]
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    pub ipc: bool,
+
+    /// Play the scene's transition when the first wallpaper appears, fading it in from black
+    ///
+    /// Turn off to show the first wallpaper straight away. Later rotations still transition.
+    #[serde(default = "default_startup_transition")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_startup_transition"))]
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    pub startup_transition: bool,
+
     /// GPU selection strategy
     #[serde(default)]
     pub gpu: GpuSelection,
+
+    /// Render at 10 bits per channel or in half float when the output supports it (experimental)
+    ///
+    /// Falls back to the usual 8-bit sRGB format on displays and compositors that
+    /// don't offer a deeper one.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "false"))]
+    pub hdr: bool,
+
+    /// How frames are presented: `auto-vsync`, `auto-no-vsync`, `fifo`, `mailbox` or `immediate`
+    ///
+    /// `mailbox` can cut latency on some compositors, and the no-vsync modes help when
+    /// benchmarking. Falls back to `fifo` when the surface doesn't offer the mode.
+    #[serde(default)]
+    pub present_mode: PresentModeConfig,
+
+    /// Opacity of the whole wallpaper (0.0 - 1.0), letting what's under the layer show through
+    ///
+    /// Multiplies every finished frame, so allwall can sit over another wallpaper or under
+    /// a translucent window. Needs a compositor that blends the surface; values outside
+    /// the range are clamped.
+    #[serde(default = "default_opacity")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_opacity"))]
+    #[cfg_attr(feature = "generate", nixos(default = "1.0"))]
+    pub opacity: f32,
+
+    /// Pattern drawn under every frame, for debugging transparent images and letterbox bars
+    #[serde(default)]
+    pub debug_background: DebugBackground,
+
+    /// Width of one checkerboard cell in pixels, used when `debug_background` is `checker`
+    #[serde(default = "default_checker_size")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_checker_size"))]
+    #[cfg_attr(feature = "generate", nixos(default = "16"))]
+    pub checker_size: u32,
 }
 
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
             fps: default_fps(),
+            idle_fps: default_idle_fps(),
+            restore_last: false,
+            interactive: false,
+            pause_on_fullscreen: false,
+            connect_attempts: default_connect_attempts(),
+            connect_delay_ms: default_connect_delay_ms(),
+            ipc: default_ipc(),
+            startup_transition: default_startup_transition(),
             gpu: GpuSelection::Auto,
+            hdr: false,
+            present_mode: PresentModeConfig::AutoVsync,
+            opacity: default_opacity(),
+            debug_background: DebugBackground::None,
+            checker_size: default_checker_size(),
         }
     }
 }
@@ -120,6 +303,54 @@ fn default_fps() -> u32 {
     30
 }
 
+fn default_idle_fps() -> u32 {
+    1
+}
+
+fn default_connect_attempts() -> u32 {
+    6
+}
+
+fn default_connect_delay_ms() -> u64 {
+    100
+}
+
+fn default_ipc() -> bool {
+    true
+}
+
+fn default_startup_transition() -> bool {
+    true
+}
+
+fn default_checker_size() -> u32 {
+    16
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+impl GeneralConfig {
+    pub fn connect_delay(&self) -> Duration {
+        Duration::from_millis(self.connect_delay_ms)
+    }
+
+    /// `opacity` clamped to 0.0 - 1.0, with NaN treated as fully opaque
+    pub fn opacity(&self) -> f32 {
+        if self.opacity.is_nan() {
+            1.0
+        } else {
+            self.opacity.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Checkerboard cell size when the checker debug background is enabled
+    pub fn checker_size(&self) -> Option<u32> {
+        (self.debug_background == DebugBackground::Checker).then_some(self.checker_size.max(1))
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -148,6 +379,26 @@ mod tests {
         assert_eq!(config.gpu, GpuSelection::Dedicated);
     }
 
+    #[test]
+    fn test_gpu_selection_power_aliases() {
+        let config: GpuConfig = toml::from_str(r#"gpu = "low-power""#).unwrap();
+        assert_eq!(config.gpu, GpuSelection::Integrated);
+        let config: GpuConfig = toml::from_str(r#"gpu = "high-performance""#).unwrap();
+        assert_eq!(config.gpu, GpuSelection::Dedicated);
+    }
+
+    #[test]
+    fn test_gpu_selection_name() {
+        let config: GpuConfig = toml::from_str(r#"gpu = "name:Radeon RX""#).unwrap();
+        assert_eq!(config.gpu, GpuSelection::Name("radeon rx".to_string()));
+    }
+
+    #[test]
+    fn test_gpu_selection_empty_name() {
+        let result = toml::from_str::<GpuConfig>(r#"gpu = "name:""#);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_gpu_selection_pci_uppercase() {
         let config: GpuConfig = toml::from_str(r#"gpu = "pci:10de:1B80""#).unwrap();
@@ -184,11 +435,61 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_gpu_selection_serialize_round_trip() {
+        for gpu in [
+            GpuSelection::Auto,
+            GpuSelection::Integrated,
+            GpuSelection::Dedicated,
+            GpuSelection::Pci {
+                vendor: 0x10de,
+                device: 0x1b80,
+            },
+            GpuSelection::Name("radeon".to_string()),
+        ] {
+            let json = serde_json::to_string(&gpu).unwrap();
+            assert_eq!(serde_json::from_str::<GpuSelection>(&json).unwrap(), gpu);
+        }
+        assert_eq!(
+            serde_json::to_string(&GpuSelection::Pci {
+                vendor: 0x10de,
+                device: 0x1b80
+            })
+            .unwrap(),
+            r#""pci:10de:1b80""#
+        );
+    }
+
     #[test]
     fn test_general_config_defaults() {
         let config = GeneralConfig::default();
         assert_eq!(config.fps, 30);
+        assert_eq!(config.idle_fps, 1);
+        assert!(!config.restore_last);
+        assert!(!config.interactive);
+        assert!(config.startup_transition);
+        assert_eq!(config.connect_attempts, 6);
+        assert_eq!(config.connect_delay(), Duration::from_millis(100));
         assert_eq!(config.gpu, GpuSelection::Auto);
+        assert!(!config.hdr);
+        assert_eq!(config.debug_background, DebugBackground::None);
+        assert_eq!(config.checker_size(), None);
+    }
+
+    #[test]
+    fn test_general_config_checker_background() {
+        let config: GeneralConfig = toml::from_str(
+            r#"
+            debug_background = "checker"
+            checker_size = 32
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.debug_background, DebugBackground::Checker);
+        assert_eq!(config.checker_size(), Some(32));
+
+        let config: GeneralConfig = toml::from_str(r#"debug_background = "checker""#).unwrap();
+        assert_eq!(config.checker_size(), Some(16));
     }
 
     #[test]
@@ -197,4 +498,32 @@ mod tests {
         assert_eq!(config.fps, 60);
         assert_eq!(config.gpu, GpuSelection::Auto);
     }
+
+    #[test]
+    fn test_present_mode_deserialize() {
+        #[derive(serde::Deserialize)]
+        struct PresentConfig {
+            present_mode: PresentModeConfig,
+        }
+
+        let config: PresentConfig = toml::from_str(r#"present_mode = "mailbox""#).unwrap();
+        assert_eq!(config.present_mode, PresentModeConfig::Mailbox);
+        let config: PresentConfig = toml::from_str(r#"present_mode = "auto-no-vsync""#).unwrap();
+        assert_eq!(config.present_mode, PresentModeConfig::AutoNoVsync);
+        assert!(toml::from_str::<PresentConfig>(r#"present_mode = "vsync""#).is_err());
+        assert_eq!(GeneralConfig::default().present_mode, PresentModeConfig::AutoVsync);
+    }
+
+    #[test]
+    fn test_opacity_clamped_to_unit_range() {
+        let with_opacity = |opacity| GeneralConfig {
+            opacity,
+            ..Default::default()
+        };
+        assert_eq!(GeneralConfig::default().opacity(), 1.0);
+        assert_eq!(with_opacity(0.75).opacity(), 0.75);
+        assert_eq!(with_opacity(1.5).opacity(), 1.0);
+        assert_eq!(with_opacity(-0.2).opacity(), 0.0);
+        assert_eq!(with_opacity(f32::NAN).opacity(), 1.0);
+    }
 }
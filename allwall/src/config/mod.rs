@@ -1,18 +1,31 @@
 pub mod error;
 mod general;
+mod schedule;
 mod source;
 
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-pub use general::{GeneralConfig, GpuSelection};
+pub use general::{GeneralConfig, GpuSelection, PresentModeConfig};
+pub use schedule::{ScheduleEntry, TimeOfDay, active_entry, until_next};
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
-pub use source::{EmissionMode, SmokeConfig};
+use serde::{Deserialize, Serialize};
+pub use source::{
+    ColorAdjustConfig, ColorConfig, EmissionMode, GradientConfig, GrassConfig, HwDecode, SmokeConfig, SourceConfigs,
+    StillConfig, VideoConfig,
+};
 
+use self::error::ConfigError;
 use crate::{
-    engine::{Fit, Layout, MonitorsSpec, SceneConfig},
+    engine::{
+        DEFAULT_FALLBACK_COLOR, DEFAULT_LETTERBOX_BLUR, DEFAULT_SMART_MAX_CROP, Fit, Layout, LetterboxStyle,
+        MonitorsSpec, OffsetMode, Placement, RotationOrder, Scaling, SceneConfig, Upscale, scene::MonitorHandle,
+    },
     prelude::*,
+    sources::media::remote,
     transitions::{TransitionType, config::TransitionConfig},
 };
 
@@ -27,6 +40,8 @@ use crate::{
 /// ```toml
 /// [general]
 /// fps = 30
+/// idle_fps = 1
+/// restore_last = true
 /// gpu = "auto"
 ///
 /// [transition]
@@ -38,10 +53,12 @@ use crate::{
 /// path = "wallpapers/nature"
 /// layout = "clone"
 /// fit = "cover"
+/// scaling = "linear"
+/// upscale = "linear"
 /// monitors = "*"
 /// ```
 #[derive(Debug, Default, Deserialize)]
-#[cfg_attr(feature = "generate", derive(JsonSchema))]
+#[cfg_attr(feature = "generate", derive(JsonSchema, Serialize))]
 pub struct Config {
     /// General engine settings
     #[serde(default)]
@@ -61,13 +78,84 @@ pub struct Config {
     /// Smoke source configuration
     #[serde(default)]
     pub smoke: SmokeConfig,
+
+    /// Grass source configuration
+    #[serde(default)]
+    pub grass: GrassConfig,
+
+    /// Solid color source configuration
+    #[serde(default)]
+    pub color: ColorConfig,
+
+    /// Gradient source configuration
+    #[serde(default)]
+    pub gradient: GradientConfig,
+
+    /// Video playback configuration
+    #[serde(default)]
+    pub video: VideoConfig,
+
+    /// Still image configuration
+    #[serde(default)]
+    pub still: StillConfig,
+
+    /// Color grading for images and videos
+    #[serde(default)]
+    pub color_adjust: ColorAdjustConfig,
+
+    /// Times of day media scenes switch to another path, such as day and night folders
+    #[serde(default, rename = "schedule")]
+    pub schedule: Vec<ScheduleEntry>,
+}
+
+impl Config {
+    /// Resolves relative scene and schedule paths against `config_dir`, the directory holding the config file
+    ///
+    /// URLs parse as relative paths too, so they're skipped to stay fetchable.
+    pub fn resolve_relative_paths(&mut self, config_dir: &Path) {
+        for scene in &mut self.scenes {
+            scene.resolve_relative_paths(config_dir);
+        }
+        for entry in &mut self.schedule {
+            if entry.path.is_relative() && !remote::is_remote(&entry.path) {
+                entry.path = config_dir.join(&entry.path);
+            }
+        }
+    }
+
+    /// The defaults plus one scene, written out as TOML for docs and the NixOS module
+    #[cfg(feature = "generate")]
+    pub fn example_toml() -> Result<String> {
+        let example = Self {
+            scenes: vec![SceneConfig {
+                path: Some(PathBuf::from("/path/to/wallpapers")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        toml::to_string(&example).map_err(|e| Error::Generic(f!("Failed to serialize example config: {e}")))
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MergedSceneConfig {
     pub path: Option<PathBuf>,
     pub layout: Layout,
+    pub independent_offset: OffsetMode,
     pub fit: Fit,
+    pub smart_max_crop: f32,
+    pub scaling: Scaling,
+    pub blur: f32,
+    pub letterbox: LetterboxStyle,
+    pub letterbox_blur: f32,
+    pub upscale: Upscale,
+    pub mipmaps: bool,
+    pub ken_burns: bool,
+    pub fallback_color: [f32; 4],
+    pub order: RotationOrder,
+    pub recursive: bool,
+    pub fps: Option<u32>,
+    pub placement: Placement,
     pub monitors: MonitorsSpec,
     pub transition: TransitionConfig,
 }
@@ -77,24 +165,62 @@ impl MergedSceneConfig {
         Self {
             path: scene.path.clone(),
             layout: scene.layout,
+            independent_offset: scene.independent_offset,
             fit: scene.fit,
+            smart_max_crop: scene.smart_max_crop,
+            scaling: scene.scaling,
+            blur: scene.blur,
+            letterbox: scene.letterbox,
+            letterbox_blur: scene.letterbox_blur,
+            upscale: scene.upscale,
+            mipmaps: scene.mipmaps,
+            ken_burns: scene.ken_burns,
+            fallback_color: scene.fallback_color,
+            order: scene.order,
+            recursive: scene.recursive,
+            fps: scene.fps,
+            placement: scene.placement.clone(),
             monitors: scene.monitors.clone(),
             transition: global_transition.merge(scene.transition.as_ref()),
         }
     }
 }
 
-#[derive(Debug)]
+/// Effective configuration after scene merging and CLI overrides
+///
+/// Serializes with the same keys as [`Config`], so `run --print-config`
+/// output reads like the config file that would produce it.
+#[derive(Debug, Serialize)]
 pub struct AppConfig {
     pub general: GeneralConfig,
     pub transition: TransitionConfig,
+    #[serde(rename = "scene")]
     pub scenes: Vec<MergedSceneConfig>,
     pub smoke: SmokeConfig,
+    pub grass: GrassConfig,
+    pub color: ColorConfig,
+    pub gradient: GradientConfig,
+    pub video: VideoConfig,
+    pub still: StillConfig,
+    pub color_adjust: ColorAdjustConfig,
+    pub schedule: Vec<ScheduleEntry>,
 }
 
 impl AppConfig {
     pub fn from_config(config: Config) -> Result<Self> {
         validate_monitor_overlaps(&config.scenes)?;
+        for scene in &config.scenes {
+            scene.validate()?;
+        }
+        config.smoke.validate()?;
+        config.grass.validate()?;
+        config.video.validate()?;
+        config.color_adjust.validate()?;
+        config.transition.validate()?;
+        for transition in config.scenes.iter().filter_map(|scene| scene.transition.as_ref()) {
+            transition.validate()?;
+        }
+        validate_schedule(&config.schedule, config.scenes.len())?;
 
         let scenes: Vec<MergedSceneConfig> = config
             .scenes
@@ -107,9 +233,32 @@ impl AppConfig {
             transition: config.transition,
             scenes,
             smoke: config.smoke,
+            grass: config.grass,
+            color: config.color,
+            gradient: config.gradient,
+            video: config.video,
+            still: config.still,
+            color_adjust: config.color_adjust,
+            schedule: config.schedule,
         })
     }
 
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn source_configs(&self) -> SourceConfigs {
+        SourceConfigs {
+            smoke: self.smoke.clone(),
+            grass: self.grass.clone(),
+            color: self.color.clone(),
+            gradient: self.gradient.clone(),
+            video: self.video.clone(),
+            still: self.still.clone(),
+            color_adjust: self.color_adjust,
+        }
+    }
+
     pub fn merge_cli(
         mut self,
         path: Option<PathBuf>,
@@ -125,7 +274,21 @@ impl AppConfig {
                 self.scenes.push(MergedSceneConfig {
                     path: Some(p),
                     layout: Default::default(),
+                    independent_offset: Default::default(),
                     fit: Default::default(),
+                    smart_max_crop: DEFAULT_SMART_MAX_CROP,
+                    scaling: Default::default(),
+                    blur: 0.0,
+                    letterbox: Default::default(),
+                    letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+                    upscale: Default::default(),
+                    mipmaps: false,
+                    ken_burns: false,
+                    fallback_color: DEFAULT_FALLBACK_COLOR,
+                    order: Default::default(),
+                    recursive: false,
+                    fps: None,
+                    placement: Default::default(),
                     monitors: MonitorsSpec::Any,
                     transition: self.transition.clone(),
                 });
@@ -147,9 +310,9 @@ impl AppConfig {
         }
 
         if let Some(t) = transition_type {
-            self.transition.r#type = t;
+            self.transition.r#type = Some(t);
             for scene in &mut self.scenes {
-                scene.transition.r#type = t;
+                scene.transition.r#type = Some(t);
             }
         }
 
@@ -162,29 +325,34 @@ impl AppConfig {
 }
 
 fn validate_monitor_overlaps(scenes: &[SceneConfig]) -> Result<()> {
-    let mut claimed: HashSet<String> = HashSet::new();
+    let mut claimed: Vec<&MonitorHandle> = Vec::new();
 
     for scene in scenes {
-        match &scene.monitors {
-            MonitorsSpec::Any => {
+        match scene.monitors.monitors() {
+            None => {
                 if !claimed.is_empty() {
-                    let monitors: Vec<&String> = claimed.iter().collect();
+                    let monitors: Vec<&str> = claimed.iter().map(|h| h.name()).collect();
                     return Err(Error::Generic(format!(
                         "Monitors {:?} are claimed by other scenes, but this scene uses 'any' which conflicts",
                         monitors
                     )));
                 }
             },
-            MonitorsSpec::Specific(handles) => {
+            Some(handles) => {
                 for handle in handles {
-                    let name = handle.name();
-                    if claimed.contains(name) {
-                        return Err(Error::Generic(format!(
-                            "Monitor '{}' is claimed by multiple scenes",
-                            name
-                        )));
+                    for other in claimed.iter().filter(|other| other.overlaps(handle)) {
+                        if !handle.is_pattern() && !other.is_pattern() {
+                            return Err(Error::Generic(format!(
+                                "Monitor '{}' is claimed by multiple scenes",
+                                handle
+                            )));
+                        }
+                        warn!(
+                            "Monitor patterns '{}' and '{}' can match the same output, the first listed scene takes it",
+                            other, handle
+                        );
                     }
-                    claimed.insert(name.to_string());
+                    claimed.push(handle);
                 }
             },
         }
@@ -193,11 +361,38 @@ fn validate_monitor_overlaps(scenes: &[SceneConfig]) -> Result<()> {
     Ok(())
 }
 
-pub fn load_config() -> Result<Config> {
-    let xdg_dirs = xdg::BaseDirectories::new();
-    let config_path = xdg_dirs.place_config_file("allwall/config.toml");
+/// Every `scene` a schedule entry names has to exist, counting the default scene made
+/// when none are configured
+fn validate_schedule(schedule: &[ScheduleEntry], scenes: usize) -> Result<()> {
+    let scenes = scenes.max(1);
+    match schedule.iter().find(|entry| entry.scene.is_some_and(|scene| scene >= scenes)) {
+        Some(entry) => Err(ConfigError::UnknownScheduleScene {
+            at: entry.at.to_string(),
+            scene: entry.scene.unwrap_or_default(),
+            scenes,
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Location of the user's config file, which may not exist yet
+pub fn default_config_path() -> std::io::Result<PathBuf> {
+    xdg::BaseDirectories::new().place_config_file("allwall/config.toml")
+}
+
+/// Reads `explicit` when given, otherwise `$XDG_CONFIG_HOME/allwall/config.toml`
+///
+/// A missing default file means defaults, but a missing explicit one is an error.
+pub fn load_config(explicit: Option<&Path>) -> Result<Config> {
+    if let Some(path) = explicit {
+        if !path.exists() {
+            return Err(ConfigError::NotFound(path.to_path_buf()).into());
+        }
+        return load_config_from(path);
+    }
 
-    let config_path = match config_path {
+    let config_path = match default_config_path() {
         Ok(path) => {
             if !path.exists() {
                 return Ok(Config::default());
@@ -211,12 +406,20 @@ pub fn load_config() -> Result<Config> {
         },
     };
 
-    let config_content = fs::read_to_string(&config_path)
+    load_config_from(&config_path)
+}
+
+pub fn load_config_from(config_path: &Path) -> Result<Config> {
+    let config_content = fs::read_to_string(config_path)
         .map_err(|e| Error::Generic(f!("Failed to read config file at {}: {}", config_path.display(), e)))?;
 
-    let config: Config = toml::from_str(&config_content)
+    let mut config: Config = toml::from_str(&config_content)
         .map_err(|e| Error::Generic(f!("Failed to parse config file at {}: {}", config_path.display(), e)))?;
 
+    if let Some(config_dir) = config_path.parent() {
+        config.resolve_relative_paths(config_dir);
+    }
+
     Ok(config)
 }
 
@@ -228,12 +431,27 @@ mod tests {
     use super::*;
     use crate::engine::scene::MonitorHandle;
     use crate::transitions::TransitionType;
+    use crate::utils::scratch::ScratchDir;
 
     fn make_scene(monitors: MonitorsSpec) -> SceneConfig {
         SceneConfig {
             path: None,
             layout: Default::default(),
+            independent_offset: Default::default(),
             fit: Default::default(),
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
             monitors,
             transition: None,
         }
@@ -258,6 +476,36 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_mapped_monitor_overlap_error() {
+        let scenes = vec![
+            make_scene(MonitorsSpec::Mapped(vec![
+                (MonitorHandle::new("DP-1".to_string()), PathBuf::from("/a")),
+                (MonitorHandle::new("HDMI-1".to_string()), PathBuf::from("/b")),
+            ])),
+            make_scene(MonitorsSpec::Specific(vec![MonitorHandle::new("HDMI-1".to_string())])),
+        ];
+        assert!(validate_monitor_overlaps(&scenes).is_err());
+    }
+
+    #[test]
+    fn test_validate_monitor_pattern_overlap_allowed() {
+        let scenes = vec![
+            make_scene(MonitorsSpec::Specific(vec![MonitorHandle::new("DP-*".to_string())])),
+            make_scene(MonitorsSpec::Specific(vec![MonitorHandle::new("DP-1".to_string())])),
+        ];
+        assert!(validate_monitor_overlaps(&scenes).is_ok());
+    }
+
+    #[test]
+    fn test_validate_monitor_pattern_with_any_error() {
+        let scenes = vec![
+            make_scene(MonitorsSpec::Specific(vec![MonitorHandle::new("DP-*".to_string())])),
+            make_scene(MonitorsSpec::Any),
+        ];
+        assert!(validate_monitor_overlaps(&scenes).is_err());
+    }
+
     #[test]
     fn test_validate_monitor_any_alone_ok() {
         let scenes = vec![make_scene(MonitorsSpec::Any)];
@@ -280,7 +528,21 @@ mod tests {
         let scene = SceneConfig {
             path: Some(PathBuf::from("/test/path")),
             layout: crate::engine::Layout::Independent,
+            independent_offset: Default::default(),
             fit: crate::engine::Fit::Cover,
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
             monitors: MonitorsSpec::Specific(vec![MonitorHandle::new("DP-1".to_string())]),
             transition: None,
         };
@@ -290,7 +552,7 @@ mod tests {
         assert_eq!(merged.path, Some(PathBuf::from("/test/path")));
         assert_eq!(merged.layout, crate::engine::Layout::Independent);
         assert_eq!(merged.fit, crate::engine::Fit::Cover);
-        assert_eq!(merged.transition.r#type, TransitionType::Fade);
+        assert_eq!(merged.transition.transition_type(), TransitionType::Fade);
     }
 
     #[test]
@@ -298,12 +560,26 @@ mod tests {
         let mut global = TransitionConfig::default();
         global.duration = 5;
         global.interval = 30;
-        global.r#type = TransitionType::CircleCenter;
+        global.r#type = Some(TransitionType::CircleCenter);
 
         let scene = SceneConfig {
             path: None,
             layout: Default::default(),
+            independent_offset: Default::default(),
             fit: Default::default(),
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
             monitors: MonitorsSpec::Any,
             transition: None,
         };
@@ -312,22 +588,43 @@ mod tests {
 
         assert_eq!(merged.transition.duration, 5);
         assert_eq!(merged.transition.interval, 30);
-        assert_eq!(merged.transition.r#type, TransitionType::CircleCenter);
+        assert_eq!(merged.transition.transition_type(), TransitionType::CircleCenter);
     }
 
     #[test]
     fn test_merged_scene_config_scene_transition_overrides() {
         let global = TransitionConfig::default();
         let scene_transition = TransitionConfig {
-            r#type: TransitionType::CircleTopLeft,
+            r#type: Some(TransitionType::CircleTopLeft),
             duration: 3,
             interval: 15,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: Default::default(),
+            fade: Default::default(),
+            dip: Default::default(),
+            wipe: Default::default(),
+            per_source: Default::default(),
+            random_types: TransitionType::CONCRETE.to_vec(),
         };
         let scene = SceneConfig {
             path: None,
             layout: Default::default(),
+            independent_offset: Default::default(),
             fit: Default::default(),
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
             monitors: MonitorsSpec::Any,
             transition: Some(scene_transition),
         };
@@ -336,7 +633,42 @@ mod tests {
 
         assert_eq!(merged.transition.duration, 3);
         assert_eq!(merged.transition.interval, 15);
-        assert_eq!(merged.transition.r#type, TransitionType::CircleTopLeft);
+        assert_eq!(merged.transition.transition_type(), TransitionType::CircleTopLeft);
+    }
+
+    #[test]
+    fn test_schedule_scene_must_exist() {
+        let config: Config = toml::from_str(
+            r#"
+            [[scene]]
+            path = "/wallpapers"
+            layout = "clone"
+
+            [[schedule]]
+            at = "19:00"
+            path = "/wallpapers/night"
+            scene = 1
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            AppConfig::from_config(config),
+            Err(Error::Config(ConfigError::UnknownScheduleScene {
+                scene: 1,
+                scenes: 1,
+                ..
+            }))
+        ));
+
+        let config: Config = toml::from_str("[[schedule]]\nat = \"19:00\"\npath = \"/night\"\nscene = 0").unwrap();
+        assert_eq!(AppConfig::from_config(config).unwrap().schedule.len(), 1);
+    }
+
+    #[test]
+    fn test_schedule_paths_resolve_from_config_dir() {
+        let mut config: Config = toml::from_str("[[schedule]]\nat = \"07:00\"\npath = \"day\"").unwrap();
+        config.resolve_relative_paths(Path::new("/home/user/.config/allwall"));
+        assert_eq!(config.schedule[0].path, PathBuf::from("/home/user/.config/allwall/day"));
     }
 
     #[test]
@@ -346,12 +678,70 @@ mod tests {
             transition: TransitionConfig::default(),
             scenes: vec![],
             smoke: SmokeConfig::default(),
+            grass: GrassConfig::default(),
+            color: ColorConfig::default(),
+            gradient: GradientConfig::default(),
+            video: VideoConfig::default(),
+            still: StillConfig::default(),
+            color_adjust: ColorAdjustConfig::default(),
+            schedule: vec![],
         };
 
         let app_config = AppConfig::from_config(config).unwrap();
         assert!(app_config.scenes.is_empty());
     }
 
+    #[test]
+    fn test_app_config_json_round_trip() {
+        let config: Config = toml::from_str(
+            r#"
+            [general]
+            fps = 45
+
+            [transition]
+            type = "wipe"
+            duration = 2
+
+            [transition.per_source]
+            smoke = "fade"
+
+            [[scene]]
+            path = "/srv/wallpapers"
+            layout = "independent"
+            fit = "contain"
+            letterbox = { color = [0.0, 0.0, 0.0, 1.0] }
+            monitors = { "DP-1" = "/srv/portrait" }
+
+            [[scene]]
+            path = "/srv/other"
+            layout = "clone"
+            fps = 24
+            monitors = ["HDMI-A-1"]
+            transition = { type = "dip" }
+            "#,
+        )
+        .unwrap();
+        let app_config = AppConfig::from_config(config)
+            .unwrap()
+            .merge_cli(None, None, Some(30), None, None);
+
+        let json = app_config.to_json().unwrap();
+        let reparsed = AppConfig::from_config(serde_json::from_str(&json).unwrap()).unwrap();
+
+        assert_eq!(reparsed.to_json().unwrap(), json);
+        assert_eq!(reparsed.general.fps, 45);
+        assert_eq!(reparsed.scenes.len(), 2);
+        assert_eq!(reparsed.scenes[0].transition.transition_type(), TransitionType::Wipe);
+        assert_eq!(reparsed.scenes[0].transition.interval, 30);
+        assert_eq!(reparsed.scenes[1].transition.transition_type(), TransitionType::Dip);
+        assert_eq!(reparsed.scenes[1].fps, Some(24));
+        assert_eq!(
+            reparsed.scenes[0].monitors.path_for("DP-1"),
+            Some(Path::new("/srv/portrait"))
+        );
+        assert_eq!(reparsed.scenes[1].monitors.names(), vec!["HDMI-A-1"]);
+    }
+
     #[test]
     fn test_app_config_merge_cli_path() {
         let config = Config::default();
@@ -396,4 +786,109 @@ mod tests {
         assert_eq!(config.transition.interval, 10);
         assert!(config.scenes.is_empty());
     }
+
+    #[test]
+    fn test_resolve_relative_scene_path() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[scene]]
+            path = "wallpapers/nature"
+            "#,
+        )
+        .unwrap();
+
+        let config_path = Path::new("/home/u/.config/allwall/config.toml");
+        config.resolve_relative_paths(config_path.parent().unwrap());
+
+        assert_eq!(
+            config.scenes[0].path,
+            Some(PathBuf::from("/home/u/.config/allwall/wallpapers/nature"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_keeps_absolute_paths() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[scene]]
+            path = "/srv/wallpapers"
+            layout = "independent"
+            monitors = { "DP-1" = "portrait", "HDMI-A-1" = "/srv/wide" }
+            "#,
+        )
+        .unwrap();
+
+        config.resolve_relative_paths(Path::new("/home/u/.config/allwall"));
+
+        let scene = &config.scenes[0];
+        assert_eq!(scene.path, Some(PathBuf::from("/srv/wallpapers")));
+        assert_eq!(
+            scene.monitors.path_for("DP-1"),
+            Some(Path::new("/home/u/.config/allwall/portrait"))
+        );
+        assert_eq!(scene.monitors.path_for("HDMI-A-1"), Some(Path::new("/srv/wide")));
+    }
+
+    #[test]
+    fn test_load_config_keeps_url_paths() {
+        let dir = ScratchDir::new("config-url");
+        let path = dir.join("config.toml");
+        fs::write(
+            &path,
+            r#"
+            [[scene]]
+            path = "https://example.com/wall.jpg"
+            layout = "independent"
+            monitors = { "DP-1" = "http://example.com/portrait.png" }
+
+            [[schedule]]
+            at = "19:00"
+            path = "https://example.com/night.jpg"
+            "#,
+        )
+        .unwrap();
+
+        let config = load_config_from(&path).unwrap();
+
+        let scene = &config.scenes[0];
+        assert_eq!(scene.path, Some(PathBuf::from("https://example.com/wall.jpg")));
+        assert_eq!(
+            scene.monitors.path_for("DP-1"),
+            Some(Path::new("http://example.com/portrait.png"))
+        );
+        assert_eq!(config.schedule[0].path, PathBuf::from("https://example.com/night.jpg"));
+    }
+
+    #[test]
+    fn test_load_config_prefers_explicit_path() {
+        let dir = ScratchDir::new("config-explicit");
+        let path = dir.join("other.toml");
+        fs::write(&path, "[[scene]]\npath = \"/srv/explicit\"\nlayout = \"clone\"\n").unwrap();
+
+        let config = load_config(Some(&path)).unwrap();
+
+        assert_eq!(config.scenes.len(), 1);
+        assert_eq!(config.scenes[0].path, Some(PathBuf::from("/srv/explicit")));
+    }
+
+    #[test]
+    fn test_load_config_missing_explicit_path_is_error() {
+        let result = load_config(Some(Path::new("/nonexistent/allwall/config.toml")));
+        assert!(matches!(result, Err(Error::Config(ConfigError::NotFound(_)))));
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_example_toml_reparses_to_same_config() {
+        let example = Config::example_toml().unwrap();
+        let parsed: Config = toml::from_str(&example).unwrap();
+
+        // Not every nested config is PartialEq, so compare the parsed config by its serialized form
+        assert_eq!(
+            toml::Value::try_from(&parsed).unwrap(),
+            toml::from_str::<toml::Value>(&example).unwrap()
+        );
+        assert_eq!(parsed.scenes.len(), 1);
+        assert_eq!(parsed.scenes[0].path, Some(PathBuf::from("/path/to/wallpapers")));
+    }
 }
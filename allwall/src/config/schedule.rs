@@ -0,0 +1,213 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+use chrono::{Local, Timelike};
+#[cfg(feature = "generate")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::f;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// Local wall-clock time, as seconds after midnight
+///
+/// Written `"HH:MM"` in the config, 24-hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay(u32);
+
+impl TimeOfDay {
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Self(hour * 3600 + minute * 60))
+    }
+
+    /// The current local time, to the second
+    pub fn now() -> Self {
+        Self(Local::now().num_seconds_from_midnight())
+    }
+
+    /// How long after `self` the clock next reads `later`, a full day when they're equal
+    fn until(self, later: Self) -> Duration {
+        let seconds = (later.0 + SECONDS_PER_DAY - self.0) % SECONDS_PER_DAY;
+        Duration::from_secs(u64::from(if seconds == 0 { SECONDS_PER_DAY } else { seconds }))
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || f!("invalid time '{value}', expected HH:MM from 00:00 to 23:59");
+        let (hour, minute) = value.split_once(':').ok_or_else(invalid)?;
+        let hour = hour.parse().map_err(|_| invalid())?;
+        let minute = minute.parse().map_err(|_| invalid())?;
+        Self::new(hour, minute).ok_or_else(invalid)
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 3600, self.0 / 60 % 60)
+    }
+}
+
+/// Switches a scene to another path at a time of day, until the next entry takes over
+///
+/// # Example
+///
+/// ```toml
+/// [[schedule]]
+/// at = "07:00"
+/// path = "wallpapers/day"
+///
+/// [[schedule]]
+/// at = "19:00"
+/// path = "wallpapers/night"
+/// scene = 0
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct ScheduleEntry {
+    /// Local time the entry starts, `"HH:MM"`
+    #[cfg_attr(feature = "generate", schemars(with = "String"))]
+    pub at: TimeOfDay,
+
+    /// Image, video, or directory to switch to. Relative paths are resolved from the config file location.
+    pub path: PathBuf,
+
+    /// Position of the `[[scene]]` to switch, counting from 0, or every media scene when omitted
+    #[serde(default)]
+    pub scene: Option<usize>,
+}
+
+impl ScheduleEntry {
+    pub fn applies_to(&self, scene: usize) -> bool {
+        self.scene.is_none_or(|index| index == scene)
+    }
+}
+
+/// The entry in effect at `now`: the latest one already started today, or failing that
+/// the last one of the day, still running since yesterday
+pub fn active_entry<'a>(
+    entries: impl IntoIterator<Item = &'a ScheduleEntry>,
+    now: TimeOfDay,
+) -> Option<&'a ScheduleEntry> {
+    let mut latest = None;
+    let mut latest_started = None;
+    for entry in entries {
+        if latest.is_none_or(|e: &ScheduleEntry| entry.at > e.at) {
+            latest = Some(entry);
+        }
+        if entry.at <= now && latest_started.is_none_or(|e: &ScheduleEntry| entry.at > e.at) {
+            latest_started = Some(entry);
+        }
+    }
+    latest_started.or(latest)
+}
+
+/// Time from `now` until the next entry starts, or `None` without entries
+pub fn until_next(entries: &[ScheduleEntry], now: TimeOfDay) -> Option<Duration> {
+    entries.iter().map(|entry| now.until(entry.at)).min()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> TimeOfDay {
+        TimeOfDay::new(hour, minute).unwrap()
+    }
+
+    fn entry(time: &str, path: &str, scene: Option<usize>) -> ScheduleEntry {
+        ScheduleEntry {
+            at: TimeOfDay::try_from(time.to_string()).unwrap(),
+            path: PathBuf::from(path),
+            scene,
+        }
+    }
+
+    fn day_night() -> Vec<ScheduleEntry> {
+        vec![entry("19:00", "night", None), entry("07:00", "day", None)]
+    }
+
+    #[test]
+    fn test_time_of_day_parse() {
+        assert_eq!(TimeOfDay::try_from("18:00".to_string()), Ok(at(18, 0)));
+        assert_eq!(TimeOfDay::try_from("7:05".to_string()), Ok(at(7, 5)));
+        assert_eq!(at(7, 5).to_string(), "07:05");
+
+        for invalid in ["24:00", "12:60", "noon", "12", "12:3a"] {
+            assert!(TimeOfDay::try_from(invalid.to_string()).is_err(), "{invalid} parsed");
+        }
+    }
+
+    #[test]
+    fn test_active_entry_latest_started_today() {
+        let entries = day_night();
+        assert_eq!(active_entry(&entries, at(7, 0)).unwrap().path, PathBuf::from("day"));
+        assert_eq!(active_entry(&entries, at(12, 30)).unwrap().path, PathBuf::from("day"));
+        assert_eq!(active_entry(&entries, at(19, 0)).unwrap().path, PathBuf::from("night"));
+        assert_eq!(active_entry(&entries, at(23, 59)).unwrap().path, PathBuf::from("night"));
+    }
+
+    #[test]
+    fn test_active_entry_wraps_past_midnight() {
+        let entries = day_night();
+        assert_eq!(active_entry(&entries, at(0, 0)).unwrap().path, PathBuf::from("night"));
+        assert_eq!(active_entry(&entries, at(6, 59)).unwrap().path, PathBuf::from("night"));
+    }
+
+    #[test]
+    fn test_active_entry_per_scene() {
+        let entries = vec![entry("07:00", "day", None), entry("19:00", "night", Some(1))];
+        let for_scene = |scene| move |e: &&ScheduleEntry| e.applies_to(scene);
+
+        let scene_0 = active_entry(entries.iter().filter(for_scene(0)), at(20, 0));
+        assert_eq!(scene_0.unwrap().path, PathBuf::from("day"));
+        let scene_1 = active_entry(entries.iter().filter(for_scene(1)), at(20, 0));
+        assert_eq!(scene_1.unwrap().path, PathBuf::from("night"));
+        assert!(active_entry(&[], at(20, 0)).is_none());
+    }
+
+    #[test]
+    fn test_until_next_boundary() {
+        let entries = day_night();
+        assert_eq!(until_next(&entries, at(18, 0)), Some(Duration::from_secs(3600)));
+        assert_eq!(until_next(&entries, at(23, 0)), Some(Duration::from_secs(8 * 3600)));
+        // Fired right on a boundary, so the next one is the other entry
+        assert_eq!(until_next(&entries, at(19, 0)), Some(Duration::from_secs(12 * 3600)));
+        assert_eq!(
+            until_next(&entries[..1], at(19, 0)),
+            Some(Duration::from_secs(u64::from(SECONDS_PER_DAY)))
+        );
+        assert_eq!(until_next(&[], at(19, 0)), None);
+    }
+
+    #[test]
+    fn test_schedule_deserialize() {
+        #[derive(Deserialize)]
+        struct Schedule {
+            schedule: Vec<ScheduleEntry>,
+        }
+
+        let parsed: Schedule = toml::from_str(
+            r#"
+            [[schedule]]
+            at = "18:00"
+            path = "night"
+            scene = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.schedule, vec![entry("18:00", "night", Some(1))]);
+
+        assert!(toml::from_str::<Schedule>("[[schedule]]\nat = \"25:00\"\npath = \"x\"").is_err());
+    }
+}
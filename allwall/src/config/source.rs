@@ -1,8 +1,25 @@
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+use super::error::ConfigError;
+
+/// Coarsest smoke grid allowed, below which the fluid turns blocky
+pub const MIN_SIMULATION_RESOLUTION: u32 = 128;
+/// Finest smoke grid allowed, above which the pressure solve gets too slow for most GPUs
+pub const MAX_SIMULATION_RESOLUTION: u32 = 1024;
+/// Slowest video playback speed allowed
+pub const MIN_PLAYBACK_RATE: f64 = 0.25;
+/// Fastest video playback speed allowed
+pub const MAX_PLAYBACK_RATE: f64 = 4.0;
+/// Warmest white point shift allowed, taking daylight down to 1000K candlelight
+pub const MIN_TEMPERATURE_SHIFT: f32 = -5500.0;
+/// Coolest white point shift allowed, taking daylight up to a 10000K blue sky
+pub const MAX_TEMPERATURE_SHIFT: f32 = 3500.0;
+/// MSAA sample counts the grass source accepts
+pub const MSAA_SAMPLE_COUNTS: [u32; 3] = [1, 2, 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 #[derive(Default)]
@@ -10,9 +27,13 @@ pub enum EmissionMode {
     #[default]
     Continuous,
     Burst,
+    /// Pulse with the loudness of the system audio (requires the `audio` feature)
+    Audio,
+    /// Rise from `emission_point` alone, like a chimney, ignoring the cursor
+    Fixed,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema))]
 pub struct SmokeConfig {
     #[serde(default)]
@@ -21,11 +42,47 @@ pub struct SmokeConfig {
     #[serde(default = "default_emission_intensity")]
     pub emission_intensity: f32,
 
+    /// Color behind the smoke, as sRGB (0.0 - 1.0)
     #[serde(default = "default_background_color")]
     pub background_color: [f32; 3],
 
+    /// Color of the smoke, as sRGB (0.0 - 1.0), used for both ends of its gradient
+    /// unless `smoke_color_low` or `smoke_color_high` set their own
     #[serde(default = "default_smoke_color")]
     pub smoke_color: [f32; 3],
+
+    /// Color of thin wisps of smoke, as sRGB (0.0 - 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smoke_color_low: Option<[f32; 3]>,
+
+    /// Color of dense smoke, as sRGB (0.0 - 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smoke_color_high: Option<[f32; 3]>,
+
+    /// Fraction of the cursor's velocity kept each frame after it stops (0 = no momentum)
+    #[serde(default = "default_mouse_inertia")]
+    pub mouse_inertia: f32,
+
+    /// Distance the cursor must move, as a fraction of the surface, before it stirs the smoke
+    ///
+    /// Smaller movements count as the cursor standing still, hiding pointer jitter. 0 reacts to every move.
+    #[serde(default = "default_mouse_deadzone")]
+    pub mouse_deadzone: f32,
+
+    /// Width and height of the fluid simulation grid in cells
+    ///
+    /// Must be a power of two from 128 to 1024. Lower is faster, higher gives finer smoke.
+    #[serde(default = "default_simulation_resolution")]
+    pub simulation_resolution: u32,
+
+    /// Where the `fixed` emission mode emits from, in surface coordinates from 0.0 to 1.0
+    /// with y pointing down, so `[0.5, 0.95]` sits at the bottom center
+    #[serde(default = "default_emission_point")]
+    pub emission_point: [f32; 2],
+
+    /// Radius of the `fixed` emitter as a fraction of the surface width
+    #[serde(default = "default_emission_radius")]
+    pub emission_radius: f32,
 }
 
 impl Default for SmokeConfig {
@@ -35,10 +92,40 @@ impl Default for SmokeConfig {
             emission_intensity: default_emission_intensity(),
             background_color: default_background_color(),
             smoke_color: default_smoke_color(),
+            smoke_color_low: None,
+            smoke_color_high: None,
+            mouse_inertia: default_mouse_inertia(),
+            mouse_deadzone: default_mouse_deadzone(),
+            simulation_resolution: default_simulation_resolution(),
+            emission_point: default_emission_point(),
+            emission_radius: default_emission_radius(),
         }
     }
 }
 
+impl SmokeConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let resolution = self.simulation_resolution;
+        if !resolution.is_power_of_two()
+            || !(MIN_SIMULATION_RESOLUTION..=MAX_SIMULATION_RESOLUTION).contains(&resolution)
+        {
+            return Err(ConfigError::InvalidSimulationResolution(resolution));
+        }
+        if !(0.0..=1.0).contains(&self.mouse_deadzone) {
+            return Err(ConfigError::InvalidMouseDeadzone(self.mouse_deadzone));
+        }
+        Ok(())
+    }
+
+    /// Colors of thin and dense smoke, falling back to `smoke_color` for either left unset
+    pub fn smoke_gradient(&self) -> [[f32; 3]; 2] {
+        [
+            self.smoke_color_low.unwrap_or(self.smoke_color),
+            self.smoke_color_high.unwrap_or(self.smoke_color),
+        ]
+    }
+}
+
 fn default_emission_intensity() -> f32 {
     1.0
 }
@@ -51,6 +138,356 @@ fn default_smoke_color() -> [f32; 3] {
     [0.75, 0.75, 0.75]
 }
 
+fn default_mouse_inertia() -> f32 {
+    0.9
+}
+
+fn default_mouse_deadzone() -> f32 {
+    0.0
+}
+
+fn default_simulation_resolution() -> u32 {
+    512
+}
+
+fn default_emission_point() -> [f32; 2] {
+    [0.5, 0.95]
+}
+
+fn default_emission_radius() -> f32 {
+    0.05
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct GrassConfig {
+    #[serde(default = "default_wind_strength")]
+    pub wind_strength: f32,
+
+    #[serde(default = "default_wind_direction_degrees")]
+    pub wind_direction_degrees: f32,
+
+    #[serde(default = "default_blade_height_percent")]
+    pub blade_height_percent: f32,
+
+    #[serde(default = "default_blade_spacing")]
+    pub blade_spacing: f32,
+
+    /// Base blade color as sRGB (0.0 - 1.0)
+    #[serde(default = "default_blade_color")]
+    pub blade_color: [f32; 3],
+
+    /// Seed for blade placement and the wind pattern; the same seed always gives the same layout
+    #[serde(default = "default_grass_seed")]
+    pub seed: u64,
+
+    /// Samples per pixel for smoothing blade edges: 1 (off), 2 or 4
+    ///
+    /// Counts the GPU can't render with fall back to 1.
+    #[serde(default = "default_msaa")]
+    pub msaa: u32,
+}
+
+impl GrassConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !MSAA_SAMPLE_COUNTS.contains(&self.msaa) {
+            return Err(ConfigError::InvalidMsaa(self.msaa));
+        }
+        Ok(())
+    }
+
+    /// Unit vector the wind blows towards, with 0 degrees pointing right and 90 pointing up
+    pub fn wind_direction(&self) -> [f32; 2] {
+        let radians = self.wind_direction_degrees.to_radians();
+        [radians.cos(), radians.sin()]
+    }
+}
+
+impl Default for GrassConfig {
+    fn default() -> Self {
+        Self {
+            wind_strength: default_wind_strength(),
+            wind_direction_degrees: default_wind_direction_degrees(),
+            blade_height_percent: default_blade_height_percent(),
+            blade_spacing: default_blade_spacing(),
+            blade_color: default_blade_color(),
+            seed: default_grass_seed(),
+            msaa: default_msaa(),
+        }
+    }
+}
+
+fn default_wind_strength() -> f32 {
+    0.03
+}
+
+fn default_wind_direction_degrees() -> f32 {
+    30.0
+}
+
+fn default_blade_height_percent() -> f32 {
+    0.085
+}
+
+fn default_blade_spacing() -> f32 {
+    3.5
+}
+
+fn default_blade_color() -> [f32; 3] {
+    [0.2, 0.5, 0.15]
+}
+
+fn default_grass_seed() -> u64 {
+    42
+}
+
+fn default_msaa() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct ColorConfig {
+    /// Fill color, as sRGB (0.0 - 1.0)
+    #[serde(default = "default_color")]
+    pub color: [f32; 3],
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self { color: default_color() }
+    }
+}
+
+fn default_color() -> [f32; 3] {
+    [0.0, 0.0, 0.0]
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct GradientConfig {
+    /// Color at the start of the gradient, as sRGB (0.0 - 1.0)
+    #[serde(default = "default_gradient_start")]
+    pub start: [f32; 3],
+
+    /// Color at the end of the gradient, as sRGB (0.0 - 1.0)
+    #[serde(default = "default_gradient_end")]
+    pub end: [f32; 3],
+
+    /// Direction of the gradient in degrees, with 0 running left to right and 90 bottom to top
+    #[serde(default = "default_gradient_angle")]
+    pub angle: f32,
+}
+
+impl GradientConfig {
+    /// Unit vector from the start color towards the end color, in screen space with y pointing down
+    pub fn direction(&self) -> [f32; 2] {
+        let radians = self.angle.to_radians();
+        [radians.cos(), -radians.sin()]
+    }
+}
+
+impl Default for GradientConfig {
+    fn default() -> Self {
+        Self {
+            start: default_gradient_start(),
+            end: default_gradient_end(),
+            angle: default_gradient_angle(),
+        }
+    }
+}
+
+fn default_gradient_start() -> [f32; 3] {
+    [0.0, 0.0, 0.0]
+}
+
+fn default_gradient_end() -> [f32; 3] {
+    [0.1, 0.1, 0.2]
+}
+
+fn default_gradient_angle() -> f32 {
+    90.0
+}
+
+/// Which hardware video decoder to ask GStreamer for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum HwDecode {
+    /// Let `decodebin` pick, which may be a software decoder
+    #[default]
+    Off,
+    /// VAAPI if installed, then NVDEC
+    Auto,
+    /// `vaapidecodebin` from gstreamer-vaapi, for Intel and AMD GPUs
+    Vaapi,
+    /// `nvh264dec` from the nvcodec plugin, for H.264 on NVIDIA GPUs
+    Nvdec,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct VideoConfig {
+    /// Speed videos play at, from 0.25 (quarter speed) to 4.0 (four times as fast)
+    #[serde(default = "default_playback_rate")]
+    pub playback_rate: f64,
+
+    /// Frames in a row without a new video frame before the pipeline is rebuilt (0 = never)
+    ///
+    /// Rendering pulls one frame per tick, so 150 is about five seconds at 30 fps. A video
+    /// that stays stuck after a few rebuilds is skipped for the next file in its directory.
+    #[serde(default = "default_stall_frames")]
+    pub stall_frames: u32,
+
+    /// Start videos over when they end, or hold their last frame as a still when `false`
+    #[serde(default = "default_loop_playback")]
+    pub loop_playback: bool,
+
+    /// Show each frame when its timestamp comes due instead of pulling one per rendered frame
+    ///
+    /// Keeps a 24 fps video from juddering at 60 fps. Turn off to get the old one-per-render pulling.
+    #[serde(default = "default_pace_frames")]
+    pub pace_frames: bool,
+
+    /// Decode on the GPU, `"auto"`, `"vaapi"` or `"nvdec"`, or `"off"` to let GStreamer choose
+    ///
+    /// Falls back to software decoding when the decoder isn't installed or can't play the file.
+    #[serde(default)]
+    pub hw_decode: HwDecode,
+}
+
+impl Default for VideoConfig {
+    fn default() -> Self {
+        Self {
+            playback_rate: default_playback_rate(),
+            stall_frames: default_stall_frames(),
+            loop_playback: default_loop_playback(),
+            pace_frames: default_pace_frames(),
+            hw_decode: HwDecode::Off,
+        }
+    }
+}
+
+impl VideoConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(MIN_PLAYBACK_RATE..=MAX_PLAYBACK_RATE).contains(&self.playback_rate) {
+            return Err(ConfigError::InvalidPlaybackRate(self.playback_rate));
+        }
+        Ok(())
+    }
+}
+
+fn default_playback_rate() -> f64 {
+    1.0
+}
+
+fn default_stall_frames() -> u32 {
+    150
+}
+
+fn default_loop_playback() -> bool {
+    true
+}
+
+fn default_pace_frames() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct StillConfig {
+    /// Recently shown images kept on the GPU, so going back and forth skips decoding them again
+    ///
+    /// Each entry holds a full texture, around 33MB for a 4K image. 0 decodes every time.
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+}
+
+impl Default for StillConfig {
+    fn default() -> Self {
+        Self {
+            cache_size: default_cache_size(),
+        }
+    }
+}
+
+fn default_cache_size() -> usize {
+    8
+}
+
+/// Color grading applied to every image and video as it's drawn
+///
+/// The defaults leave colors untouched. Transitions blend the unadjusted pictures.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct ColorAdjustConfig {
+    /// Multiplier on every channel (1.0 = unchanged, 0.5 = half as bright)
+    #[serde(default = "default_unit")]
+    pub brightness: f32,
+
+    /// Spread around mid-gray (1.0 = unchanged, below 1.0 flattens, above 1.0 punches up)
+    #[serde(default = "default_unit")]
+    pub contrast: f32,
+
+    /// Color intensity (1.0 = unchanged, 0.0 = grayscale)
+    #[serde(default = "default_unit")]
+    pub saturation: f32,
+
+    /// White point shift in Kelvin from 6500K daylight, negative for warmer tones
+    ///
+    /// -2500 gives a warm 4000K look for night use. Ranges from -5500 to 3500, 0 = unchanged.
+    #[serde(default)]
+    pub temperature: f32,
+}
+
+impl Default for ColorAdjustConfig {
+    fn default() -> Self {
+        Self {
+            brightness: default_unit(),
+            contrast: default_unit(),
+            saturation: default_unit(),
+            temperature: 0.0,
+        }
+    }
+}
+
+impl ColorAdjustConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (field, value) in [
+            ("brightness", self.brightness),
+            ("contrast", self.contrast),
+            ("saturation", self.saturation),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(ConfigError::InvalidColorAdjust { field, value });
+            }
+        }
+        if !(MIN_TEMPERATURE_SHIFT..=MAX_TEMPERATURE_SHIFT).contains(&self.temperature) {
+            return Err(ConfigError::InvalidColorAdjust {
+                field: "temperature",
+                value: self.temperature,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn default_unit() -> f32 {
+    1.0
+}
+
+/// Settings for every source kind, handed to each scene so it can build whichever source is active
+#[derive(Debug, Clone, Default)]
+pub struct SourceConfigs {
+    pub smoke: SmokeConfig,
+    pub grass: GrassConfig,
+    pub color: ColorConfig,
+    pub gradient: GradientConfig,
+    pub video: VideoConfig,
+    pub still: StillConfig,
+    pub color_adjust: ColorAdjustConfig,
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -68,6 +505,34 @@ mod tests {
         assert_eq!(config.emission_mode, EmissionMode::Burst);
     }
 
+    #[test]
+    fn test_emission_mode_deserialize_audio() {
+        let config: SmokeConfig = toml::from_str(r#"emission_mode = "audio""#).unwrap();
+        assert_eq!(config.emission_mode, EmissionMode::Audio);
+    }
+
+    #[test]
+    fn test_emission_mode_deserialize_fixed() {
+        let config: SmokeConfig = toml::from_str(
+            r#"
+            emission_mode = "fixed"
+            emission_point = [0.25, 0.9]
+            emission_radius = 0.1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.emission_mode, EmissionMode::Fixed);
+        assert_eq!(config.emission_point, [0.25, 0.9]);
+        assert_eq!(config.emission_radius, 0.1);
+    }
+
+    #[test]
+    fn test_emission_point_default_is_bottom_center() {
+        let config = SmokeConfig::default();
+        assert_eq!(config.emission_point, [0.5, 0.95]);
+        assert!(config.emission_radius > 0.0);
+    }
+
     #[test]
     fn test_emission_mode_default() {
         assert_eq!(EmissionMode::default(), EmissionMode::Continuous);
@@ -80,6 +545,60 @@ mod tests {
         assert!((config.emission_intensity - 1.0).abs() < f32::EPSILON);
         assert_eq!(config.background_color, [0.0, 0.0, 0.0]);
         assert_eq!(config.smoke_color, [0.75, 0.75, 0.75]);
+        assert!((config.mouse_inertia - 0.9).abs() < f32::EPSILON);
+        assert_eq!(config.mouse_deadzone, 0.0);
+        assert_eq!(config.simulation_resolution, 512);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_smoke_simulation_resolution_accepts_powers_of_two_in_range() {
+        for resolution in [128, 256, 512, 1024] {
+            let config = SmokeConfig {
+                simulation_resolution: resolution,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok(), "{resolution} should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_smoke_simulation_resolution_rejects_non_power_of_two() {
+        for resolution in [0, 300, 500, 768] {
+            let config = SmokeConfig {
+                simulation_resolution: resolution,
+                ..Default::default()
+            };
+            assert!(
+                matches!(config.validate(), Err(ConfigError::InvalidSimulationResolution(r)) if r == resolution),
+                "{resolution} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_smoke_simulation_resolution_rejects_out_of_range() {
+        for resolution in [64, 2048] {
+            let config = SmokeConfig {
+                simulation_resolution: resolution,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{resolution} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_smoke_mouse_deadzone_rejects_out_of_range() {
+        for deadzone in [-0.1, 1.5, f32::NAN] {
+            let config = SmokeConfig {
+                mouse_deadzone: deadzone,
+                ..Default::default()
+            };
+            assert!(
+                matches!(config.validate(), Err(ConfigError::InvalidMouseDeadzone(_))),
+                "{deadzone} should be rejected"
+            );
+        }
     }
 
     #[test]
@@ -98,4 +617,264 @@ mod tests {
         assert_eq!(config.background_color, [0.1, 0.2, 0.3]);
         assert_eq!(config.smoke_color, [0.5, 0.6, 0.7]);
     }
+
+    #[test]
+    fn test_smoke_color_sets_both_gradient_ends() {
+        let config: SmokeConfig = toml::from_str("smoke_color = [0.5, 0.6, 0.7]").unwrap();
+        assert_eq!(config.smoke_gradient(), [[0.5, 0.6, 0.7], [0.5, 0.6, 0.7]]);
+        assert_eq!(SmokeConfig::default().smoke_gradient(), [[0.75, 0.75, 0.75]; 2]);
+    }
+
+    #[test]
+    fn test_smoke_gradient_ends_override_smoke_color() {
+        let config: SmokeConfig = toml::from_str(
+            r#"
+            smoke_color = [0.5, 0.5, 0.5]
+            smoke_color_low = [0.2, 0.1, 0.3]
+            smoke_color_high = [1.0, 0.9, 0.8]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.smoke_gradient(), [[0.2, 0.1, 0.3], [1.0, 0.9, 0.8]]);
+
+        let high_only: SmokeConfig = toml::from_str("smoke_color_high = [1.0, 1.0, 1.0]").unwrap();
+        assert_eq!(high_only.smoke_gradient(), [[0.75, 0.75, 0.75], [1.0, 1.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_grass_config_defaults() {
+        let config = GrassConfig::default();
+        assert!((config.wind_strength - 0.03).abs() < f32::EPSILON);
+        assert!((config.wind_direction_degrees - 30.0).abs() < f32::EPSILON);
+        assert!((config.blade_height_percent - 0.085).abs() < f32::EPSILON);
+        assert!((config.blade_spacing - 3.5).abs() < f32::EPSILON);
+        assert_eq!(config.blade_color, [0.2, 0.5, 0.15]);
+        assert_eq!(config.seed, 42);
+        assert_eq!(config.msaa, 1);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_grass_msaa_accepts_supported_counts() {
+        for msaa in MSAA_SAMPLE_COUNTS {
+            let config = GrassConfig {
+                msaa,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok(), "{msaa} should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_grass_msaa_rejects_other_counts() {
+        for msaa in [0, 3, 8, 16] {
+            let config = GrassConfig {
+                msaa,
+                ..Default::default()
+            };
+            assert!(
+                matches!(config.validate(), Err(ConfigError::InvalidMsaa(m)) if m == msaa),
+                "{msaa} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_grass_config_custom_values() {
+        let config: GrassConfig = toml::from_str(
+            r#"
+            wind_strength = 0.1
+            wind_direction_degrees = 180.0
+            blade_height_percent = 0.2
+            blade_spacing = 8.0
+            blade_color = [0.3, 0.4, 0.1]
+            seed = 1234
+            "#,
+        )
+        .unwrap();
+        assert!((config.wind_strength - 0.1).abs() < f32::EPSILON);
+        assert!((config.wind_direction_degrees - 180.0).abs() < f32::EPSILON);
+        assert!((config.blade_height_percent - 0.2).abs() < f32::EPSILON);
+        assert!((config.blade_spacing - 8.0).abs() < f32::EPSILON);
+        assert_eq!(config.blade_color, [0.3, 0.4, 0.1]);
+        assert_eq!(config.seed, 1234);
+    }
+
+    #[test]
+    fn test_color_config_default() {
+        let config = ColorConfig::default();
+        assert_eq!(config.color, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_color_config_deserialize() {
+        let config: ColorConfig = toml::from_str(r#"color = [0.2, 0.4, 0.6]"#).unwrap();
+        assert_eq!(config.color, [0.2, 0.4, 0.6]);
+    }
+
+    #[test]
+    fn test_color_config_rejects_short_array() {
+        let result: std::result::Result<ColorConfig, _> = toml::from_str(r#"color = [0.2, 0.4]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_gradient_config_defaults() {
+        let config = GradientConfig::default();
+        assert_eq!(config.start, [0.0, 0.0, 0.0]);
+        assert_eq!(config.end, [0.1, 0.1, 0.2]);
+        assert!((config.angle - 90.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_gradient_config_deserialize() {
+        let config: GradientConfig = toml::from_str(
+            r#"
+            start = [1.0, 0.5, 0.0]
+            end = [0.0, 0.0, 0.5]
+            angle = 45.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.start, [1.0, 0.5, 0.0]);
+        assert_eq!(config.end, [0.0, 0.0, 0.5]);
+        assert!((config.angle - 45.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_gradient_config_integer_angle() {
+        let config: GradientConfig = toml::from_str("angle = 180").unwrap();
+        assert!((config.angle - 180.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_gradient_direction() {
+        let config = GradientConfig {
+            angle: 0.0,
+            ..Default::default()
+        };
+        let [x, y] = config.direction();
+        assert!((x - 1.0).abs() < 1e-6);
+        assert!(y.abs() < 1e-6);
+
+        let config = GradientConfig {
+            angle: 90.0,
+            ..Default::default()
+        };
+        let [x, y] = config.direction();
+        assert!(x.abs() < 1e-6);
+        assert!((y + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_grass_wind_direction() {
+        let mut config = GrassConfig {
+            wind_direction_degrees: 0.0,
+            ..Default::default()
+        };
+        let [x, y] = config.wind_direction();
+        assert!((x - 1.0).abs() < 1e-6);
+        assert!(y.abs() < 1e-6);
+
+        config.wind_direction_degrees = 90.0;
+        let [x, y] = config.wind_direction();
+        assert!(x.abs() < 1e-6);
+        assert!((y - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_video_config_default() {
+        let config = VideoConfig::default();
+        assert!((config.playback_rate - 1.0).abs() < f64::EPSILON);
+        assert_eq!(config.stall_frames, 150);
+        assert!(config.loop_playback);
+        assert!(config.pace_frames);
+        assert_eq!(config.hw_decode, HwDecode::Off);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_video_playback_rate_accepts_band() {
+        for playback_rate in [MIN_PLAYBACK_RATE, 0.5, 1.0, 2.0, MAX_PLAYBACK_RATE] {
+            let config = VideoConfig {
+                playback_rate,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok(), "{playback_rate} should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_video_playback_rate_rejects_out_of_band() {
+        for playback_rate in [0.0, -1.0, 0.1, 4.5, f64::NAN] {
+            let config = VideoConfig {
+                playback_rate,
+                ..Default::default()
+            };
+            assert!(
+                matches!(config.validate(), Err(ConfigError::InvalidPlaybackRate(_))),
+                "{playback_rate} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_video_config_deserialize() {
+        let config: VideoConfig = toml::from_str(r#"loop_playback = false"#).unwrap();
+        assert!(!config.loop_playback);
+        assert!((config.playback_rate - 1.0).abs() < f64::EPSILON);
+
+        let config: VideoConfig = toml::from_str(r#"playback_rate = 0.5"#).unwrap();
+        assert!((config.playback_rate - 0.5).abs() < f64::EPSILON);
+
+        let config: VideoConfig = toml::from_str(r#"hw_decode = "vaapi""#).unwrap();
+        assert_eq!(config.hw_decode, HwDecode::Vaapi);
+        assert!(toml::from_str::<VideoConfig>(r#"hw_decode = "quicksync""#).is_err());
+    }
+
+    #[test]
+    fn test_color_adjust_default_is_neutral() {
+        let config: ColorAdjustConfig = toml::from_str("").unwrap();
+        assert_eq!(config, ColorAdjustConfig::default());
+        assert_eq!(
+            (
+                config.brightness,
+                config.contrast,
+                config.saturation,
+                config.temperature
+            ),
+            (1.0, 1.0, 1.0, 0.0)
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_color_adjust_rejects_negative_factors() {
+        let config: ColorAdjustConfig = toml::from_str("saturation = -0.5").unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidColorAdjust {
+                field: "saturation",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_color_adjust_temperature_range() {
+        for temperature in [MIN_TEMPERATURE_SHIFT, -2500.0, 0.0, MAX_TEMPERATURE_SHIFT] {
+            let config = ColorAdjustConfig {
+                temperature,
+                ..Default::default()
+            };
+            assert!(config.validate().is_ok(), "{temperature} should be accepted");
+        }
+        for temperature in [-6000.0, 4000.0, f32::NAN] {
+            let config = ColorAdjustConfig {
+                temperature,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{temperature} should be rejected");
+        }
+    }
 }
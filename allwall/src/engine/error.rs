@@ -1,3 +1,5 @@
+use crate::prelude::f;
+
 #[derive(thiserror::Error, Debug)]
 pub enum EngineError {
     #[error("Failed to connect to Wayland: {0}")]
@@ -9,8 +11,9 @@ pub enum EngineError {
     #[error("Compositor not available - ensure a Wayland compositor is running")]
     NoCompositor,
 
-    #[error("Layer shell protocol not available")]
-    NoLayerShell,
+    /// `desktop` is `$XDG_CURRENT_DESKTOP`, named in the message when set
+    #[error("{}", no_layer_shell_message(desktop.as_deref()))]
+    NoLayerShell { desktop: Option<String> },
 
     #[error("Event loop creation failed: {0}")]
     EventLoopCreate(String),
@@ -39,6 +42,9 @@ pub enum EngineError {
 
     #[error("No scenes configured")]
     NoScenes,
+
+    #[error("Failed to watch shaders: {0}")]
+    ShaderWatch(String),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -64,6 +70,47 @@ pub enum ContextError {
     #[error("No SRGB surface format available")]
     NoSrgbFormat,
 
+    #[error("Reading back the offscreen frame failed: {0}")]
+    Readback(String),
+
     #[error(transparent)]
     Wgpu(#[from] wgpu::Error),
 }
+
+/// Explains a missing `wlr-layer-shell` global and what to do about it
+fn no_layer_shell_message(desktop: Option<&str>) -> String {
+    let compositor = match desktop.filter(|desktop| !desktop.is_empty()) {
+        Some(desktop) => f!("The compositor ({desktop})"),
+        None => "The compositor".to_string(),
+    };
+    f!(
+        "{compositor} does not support the wlr-layer-shell protocol, which allwall needs to place \
+         wallpapers behind windows. Compositors that support it include Sway, Hyprland, river, niri, \
+         labwc, Wayfire and KDE Plasma. GNOME (Mutter) does not; set the wallpaper through GNOME's own \
+         settings there, or run allwall under one of the compositors above."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_layer_shell_message_names_desktop() {
+        let message = EngineError::NoLayerShell {
+            desktop: Some("GNOME".to_string()),
+        }
+        .to_string();
+        assert!(message.starts_with("The compositor (GNOME) does not support the wlr-layer-shell protocol"));
+        assert!(message.contains("Sway, Hyprland"));
+        assert!(message.contains("GNOME (Mutter) does not"));
+    }
+
+    #[test]
+    fn test_no_layer_shell_message_without_desktop() {
+        for desktop in [None, Some(String::new())] {
+            let message = EngineError::NoLayerShell { desktop }.to_string();
+            assert!(message.starts_with("The compositor does not support"), "{message}");
+        }
+    }
+}
@@ -2,16 +2,26 @@ use std::{
     io::{Read, Write},
     net::Shutdown,
     os::unix::net::UnixStream,
+    path::PathBuf,
     time::Duration,
 };
 
 use crate::{
-    cli::ipc::protocol::{Request, Response},
-    engine::Engine,
+    cli::ipc::protocol::{Request, Response, clamp_fps},
+    engine::{Engine, FrameStats, scene::Scene},
     prelude::{f, info},
-    sources::SourceKind,
+    sources::{SourceKind, media::remote},
 };
 
+fn write_response(stream: &mut UnixStream, response: &Response) -> crate::prelude::Result<()> {
+    let encoded = bincode::serialize(response)?;
+    stream.write_all(&(encoded.len() as u32).to_le_bytes())?;
+    stream.write_all(&encoded)?;
+    stream.flush()?;
+    let _ = stream.shutdown(Shutdown::Both);
+    Ok(())
+}
+
 impl Engine {
     pub fn handle_ipc_client(&mut self, mut stream: UnixStream) -> crate::prelude::Result<()> {
         stream.set_read_timeout(Some(Duration::from_secs(5)))?;
@@ -25,61 +35,152 @@ impl Engine {
         stream.read_exact(&mut req_buf)?;
 
         let request: Request = bincode::deserialize(&req_buf)?;
+        let read_only = matches!(request, Request::Timing | Request::Ping);
+        let quit = matches!(request, Request::Quit);
         let response = self.handle_ipc_request(request);
+        if !read_only {
+            self.save_state();
+            self.wake();
+        }
 
-        let encoded = bincode::serialize(&response)?;
-        stream.write_all(&(encoded.len() as u32).to_le_bytes())?;
-        stream.write_all(&encoded)?;
-        stream.flush()?;
-        let _ = stream.shutdown(Shutdown::Both);
-
-        Ok(())
+        // Quit even if the client hung up before reading the response
+        let written = write_response(&mut stream, &response);
+        if quit {
+            self.quit();
+        }
+        written
     }
 
     fn handle_ipc_request(&mut self, request: Request) -> Response {
         match request {
-            Request::Next => self.handle_next(),
-            Request::Prev => self.handle_prev(),
+            Request::Next { monitor } => self.handle_next(monitor.as_deref()),
+            Request::Prev { monitor } => self.handle_prev(monitor.as_deref()),
             Request::SetFps(fps) => self.handle_set_fps(fps),
+            Request::SetPath(path) => self.handle_set_path(path),
+            Request::Goto(path) => self.handle_goto(path),
+            Request::Timing => self.handle_timing(),
+            Request::ToggleOverlay => self.handle_toggle_overlay(),
+            Request::Ping => Response::Pong,
+            Request::Quit => Response::Ok,
+        }
+    }
+
+    fn handle_timing(&self) -> Response {
+        let Some(scene) = self.scenes.first() else {
+            return Response::Error("No scenes available".to_string());
+        };
+
+        Response::Timing {
+            rotation_remaining_secs: scene.rotation_remaining().map(|d| d.as_secs_f32()),
+            transition_progress: scene.transition_progress(),
+        }
+    }
+
+    fn handle_toggle_overlay(&mut self) -> Response {
+        self.show_overlay = !self.show_overlay;
+        info!(
+            "Frame-time overlay {}",
+            if self.show_overlay { "shown" } else { "hidden" }
+        );
+
+        if self.show_overlay {
+            self.frame_stats = FrameStats::default();
+        } else {
+            for scene in &mut self.scenes {
+                scene.clear_overlay();
+            }
         }
+        Response::Ok
     }
 
-    fn handle_next(&mut self) -> Response {
+    /// Scene showing `monitor`, or the first scene when no monitor is named
+    fn target_scene(&mut self, monitor: Option<&str>) -> Result<&mut Scene, Response> {
+        match monitor {
+            Some(name) => self
+                .scenes
+                .iter_mut()
+                .find(|scene| scene.has_monitor(name))
+                .ok_or_else(|| Response::Error(f!("No scene is showing monitor {name}"))),
+            None => self
+                .scenes
+                .first_mut()
+                .ok_or_else(|| Response::Error("No scenes available".to_string())),
+        }
+    }
+
+    fn handle_next(&mut self, monitor: Option<&str>) -> Response {
         if !matches!(self.source_kind, SourceKind::Media) {
             return Response::Error("Next command only works with media source".to_string());
         }
 
-        if let Some(scene) = self.scenes.first_mut() {
-            match scene.advance_source() {
+        match self.target_scene(monitor) {
+            Ok(scene) => match scene.advance_source(monitor) {
                 Ok(()) => Response::Ok,
                 Err(e) => Response::Error(f!("Failed to load next image: {e}")),
-            }
-        } else {
-            Response::Error("No scenes available".to_string())
+            },
+            Err(response) => response,
         }
     }
 
-    fn handle_prev(&mut self) -> Response {
+    fn handle_prev(&mut self, monitor: Option<&str>) -> Response {
         if !matches!(self.source_kind, SourceKind::Media) {
             return Response::Error("Prev command only works with media source".to_string());
         }
 
-        if let Some(scene) = self.scenes.first_mut() {
-            match scene.advance_source() {
+        match self.target_scene(monitor) {
+            Ok(scene) => match scene.previous_source(monitor) {
                 Ok(()) => Response::Ok,
                 Err(e) => Response::Error(f!("Failed to load previous image: {e}")),
+            },
+            Err(response) => response,
+        }
+    }
+
+    fn handle_set_fps(&mut self, fps: u32) -> Response {
+        let Some(clamped) = clamp_fps(fps) else {
+            return Response::Error("FPS must be greater than 0".to_string());
+        };
+        self.fps = clamped as f32;
+        info!("FPS set to {} (requested {})", clamped, fps);
+        Response::Ok
+    }
+
+    fn handle_set_path(&mut self, path: PathBuf) -> Response {
+        if !matches!(self.source_kind, SourceKind::Media) {
+            return Response::Error("Set command only works with media source".to_string());
+        }
+
+        if !remote::is_remote(&path) && !path.exists() {
+            return Response::Error(f!("Path does not exist: {}", path.display()));
+        }
+
+        if let Some(scene) = self.scenes.first_mut() {
+            match scene.set_path(path.clone()) {
+                Ok(()) => {
+                    info!("Wallpaper path set to {}", path.display());
+                    Response::Ok
+                },
+                Err(e) => Response::Error(f!("Failed to load {}: {e}", path.display())),
             }
         } else {
             Response::Error("No scenes available".to_string())
         }
     }
 
-    fn handle_set_fps(&mut self, fps: u32) -> Response {
-        if fps == 0 {
-            return Response::Error("FPS must be greater than 0".to_string());
+    fn handle_goto(&mut self, path: PathBuf) -> Response {
+        if !matches!(self.source_kind, SourceKind::Media) {
+            return Response::Error("Goto command only works with media source".to_string());
+        }
+
+        match self.target_scene(None) {
+            Ok(scene) => match scene.goto_media(&path) {
+                Ok(()) => {
+                    info!("Jumped to {}", path.display());
+                    Response::Ok
+                },
+                Err(e) => Response::Error(f!("Failed to load {}: {e}", path.display())),
+            },
+            Err(response) => response,
         }
-        self.fps = fps as f32;
-        info!("FPS set to {}", fps);
-        Response::Ok
     }
 }
@@ -0,0 +1,127 @@
+use wgpu::{AdapterInfo, DeviceType, PowerPreference};
+
+use crate::{config::GpuSelection, engine::error::ContextError, prelude::*};
+
+/// Picks the adapter described by `selection`, falling back to wgpu's own choice when no
+/// adapter matches
+pub(super) async fn select_adapter(instance: &wgpu::Instance, selection: &GpuSelection) -> Result<wgpu::Adapter> {
+    let matched = match selection {
+        GpuSelection::Auto => None,
+        _ => {
+            let found = instance
+                .enumerate_adapters(wgpu::Backends::PRIMARY)
+                .into_iter()
+                .find(|adapter| matches_selection(selection, &adapter.get_info()));
+            if found.is_none() {
+                warn!(
+                    "No GPU matching {:?} found, falling back to automatic selection",
+                    selection
+                );
+            }
+            found
+        },
+    };
+
+    let adapter = match matched {
+        Some(adapter) => adapter,
+        None => instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: power_preference(selection),
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or(ContextError::NoAdapter)?,
+    };
+
+    let info = adapter.get_info();
+    info!(
+        "Using GPU: {} ({:?}, {:?}, pci:{:04x}:{:04x})",
+        info.name, info.device_type, info.backend, info.vendor, info.device
+    );
+
+    Ok(adapter)
+}
+
+fn power_preference(selection: &GpuSelection) -> PowerPreference {
+    match selection {
+        GpuSelection::Integrated => PowerPreference::LowPower,
+        _ => PowerPreference::HighPerformance,
+    }
+}
+
+fn matches_selection(selection: &GpuSelection, info: &AdapterInfo) -> bool {
+    match selection {
+        GpuSelection::Auto => true,
+        GpuSelection::Integrated => info.device_type == DeviceType::IntegratedGpu,
+        GpuSelection::Dedicated => info.device_type == DeviceType::DiscreteGpu,
+        GpuSelection::Pci { vendor, device } => info.vendor == u32::from(*vendor) && info.device == u32::from(*device),
+        GpuSelection::Name(name) => info.name.to_lowercase().contains(&name.to_lowercase()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn adapter_info(name: &str, vendor: u32, device: u32, device_type: DeviceType) -> AdapterInfo {
+        AdapterInfo {
+            name: name.to_string(),
+            vendor,
+            device,
+            device_type,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend: wgpu::Backend::Vulkan,
+        }
+    }
+
+    #[test]
+    fn test_name_matches_case_insensitive_substring() {
+        let info = adapter_info(
+            "AMD Radeon RX 7900 XTX (RADV NAVI31)",
+            0x1002,
+            0x744c,
+            DeviceType::DiscreteGpu,
+        );
+        assert!(matches_selection(
+            &GpuSelection::Name("radeon rx 7900".to_string()),
+            &info
+        ));
+        assert!(!matches_selection(&GpuSelection::Name("nvidia".to_string()), &info));
+    }
+
+    #[test]
+    fn test_pci_matches_vendor_and_device() {
+        let info = adapter_info("NVIDIA GeForce GTX 1080", 0x10de, 0x1b80, DeviceType::DiscreteGpu);
+        assert!(matches_selection(
+            &GpuSelection::Pci {
+                vendor: 0x10de,
+                device: 0x1b80
+            },
+            &info
+        ));
+        assert!(!matches_selection(
+            &GpuSelection::Pci {
+                vendor: 0x10de,
+                device: 0x1b81
+            },
+            &info
+        ));
+    }
+
+    #[test]
+    fn test_device_type_matches() {
+        let integrated = adapter_info("Intel(R) UHD Graphics 620", 0x8086, 0x5917, DeviceType::IntegratedGpu);
+        assert!(matches_selection(&GpuSelection::Integrated, &integrated));
+        assert!(!matches_selection(&GpuSelection::Dedicated, &integrated));
+        assert!(matches_selection(&GpuSelection::Auto, &integrated));
+    }
+
+    #[test]
+    fn test_power_preference() {
+        assert_eq!(power_preference(&GpuSelection::Integrated), PowerPreference::LowPower);
+        assert_eq!(power_preference(&GpuSelection::Auto), PowerPreference::HighPerformance);
+    }
+}
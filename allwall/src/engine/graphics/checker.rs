@@ -0,0 +1,152 @@
+use std::iter::once;
+
+use bytemuck::{Pod, Zeroable};
+
+use super::Context;
+use crate::{
+    prelude::*,
+    sources::{INDICES, create_index_buffer, create_pipeline, create_uniform_binds, create_vertex_buffer},
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct CheckerUniforms {
+    cells: [f32; 2],
+    _padding: [f32; 2],
+}
+
+/// Cells across and down a surface of `size` pixels, which the shader scales UVs by
+fn cell_count(size: (u32, u32), cell_size: u32) -> [f32; 2] {
+    let cell_size = cell_size.max(1) as f32;
+    [size.0 as f32 / cell_size, size.1 as f32 / cell_size]
+}
+
+/// Grey checkerboard drawn under each frame when `debug_background = "checker"`
+///
+/// Passes that draw into the frame load it instead of clearing, see [`Context::clear_op`],
+/// so anything transparent or uncovered shows the grid.
+pub struct Checker {
+    cell_size: u32,
+
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Checker {
+    pub fn new(ctx: &Context, cell_size: u32) -> Result<Self> {
+        let vertex_buffer = create_vertex_buffer(ctx);
+        let index_buffer = create_index_buffer(ctx);
+
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<CheckerUniforms>() as u64, ctx);
+
+        let render_pipeline = create_pipeline(
+            ctx,
+            &[&uniform_bind_group_layout],
+            &ctx.device().create_shader_module(crate::wgsl!("./shaders/checker.wgsl")),
+            ctx.config(),
+        )?;
+
+        Ok(Self {
+            cell_size,
+            vertex_buffer,
+            index_buffer,
+            uniform_buffer,
+            uniform_bind_group,
+            render_pipeline,
+        })
+    }
+
+    /// Clears `target` and fills it with the checkerboard
+    pub fn draw(&self, ctx: &Context, target: &wgpu::TextureView) {
+        let config = ctx.config();
+        let uniforms = CheckerUniforms {
+            cells: cell_count((config.width, config.height), self.cell_size),
+            _padding: [0.0; 2],
+        };
+        ctx.queue()
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = ctx.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("checker_background"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        ctx.queue().submit(once(encoder.finish()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirrors `fs_main` in `checker.wgsl`
+    fn checker_cell(uv: [f32; 2], size: (u32, u32), cell_size: u32) -> (u32, u32) {
+        let cells = cell_count(size, cell_size);
+        ((uv[0] * cells[0]).floor() as u32, (uv[1] * cells[1]).floor() as u32)
+    }
+
+    fn is_dark_cell(uv: [f32; 2], size: (u32, u32), cell_size: u32) -> bool {
+        let (x, y) = checker_cell(uv, size, cell_size);
+        (x + y) % 2 == 1
+    }
+
+    #[test]
+    fn test_checker_uniforms_size() {
+        assert_eq!(std::mem::size_of::<CheckerUniforms>(), std::mem::size_of::<[f32; 4]>());
+    }
+
+    #[test]
+    fn test_cell_count_ignores_zero_cell_size() {
+        assert_eq!(cell_count((1920, 1080), 16), [120.0, 67.5]);
+        assert_eq!(cell_count((64, 32), 0), [64.0, 32.0]);
+    }
+
+    #[test]
+    fn test_checker_cell_from_uv() {
+        assert_eq!(checker_cell([0.0, 0.0], (1920, 1080), 16), (0, 0));
+        assert_eq!(checker_cell([0.5, 0.5], (1920, 1080), 16), (60, 33));
+        assert_eq!(checker_cell([0.999, 0.999], (1920, 1080), 16), (119, 67));
+        assert_eq!(checker_cell([0.25, 0.5], (100, 100), 10), (2, 5));
+    }
+
+    #[test]
+    fn test_neighbouring_cells_alternate() {
+        let size = (160, 160);
+        assert!(!is_dark_cell([0.0, 0.0], size, 16));
+        assert!(is_dark_cell([0.15, 0.0], size, 16));
+        assert!(is_dark_cell([0.0, 0.15], size, 16));
+        assert!(!is_dark_cell([0.15, 0.15], size, 16));
+    }
+
+    #[test]
+    fn test_larger_cells_cover_more_pixels() {
+        let size = (1000, 1000);
+        assert_eq!(checker_cell([0.05, 0.05], size, 64), (0, 0));
+        assert_eq!(checker_cell([0.05, 0.05], size, 16), (3, 3));
+    }
+}
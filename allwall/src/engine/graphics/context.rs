@@ -1,4 +1,4 @@
-use std::{ffi::c_void, ptr::NonNull};
+use std::{cell::Cell, ffi::c_void, ptr::NonNull, sync::Arc};
 
 use client::{Connection, Proxy};
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle};
@@ -6,39 +6,98 @@ use smithay_client_toolkit::{
     reexports::client,
     shell::{WaylandSurface, wlr_layer::LayerSurface},
 };
-use wgpu::{CompositeAlphaMode, Device, PresentMode, Queue, Surface, SurfaceConfiguration, TextureUsages};
+use wgpu::{
+    CompositeAlphaMode, Device, PresentMode, Queue, Surface, SurfaceConfiguration, TextureFormat, TextureUsages,
+};
+
+use crate::{
+    config::{GeneralConfig, PresentModeConfig},
+    engine::error::ContextError,
+    prelude::*,
+    sources::types::UvRect,
+};
+
+use super::{
+    adapter::select_adapter,
+    checker::Checker,
+    offscreen::{Frame, FrameTarget, OFFSCREEN_FORMAT, create_offscreen_texture, read_rgba},
+    opacity::Opacity,
+    overlay::Overlay,
+};
+
+/// Deep surface formats tried in order when HDR output is enabled
+const HDR_SURFACE_FORMATS: [TextureFormat; 2] = [TextureFormat::Rgb10a2Unorm, TextureFormat::Rgba16Float];
+
+/// Picks the surface format from those the surface `supported`, preferring a deep
+/// format when `hdr` is set and otherwise the first sRGB one
+///
+/// Deep formats don't encode to sRGB on write, so media drawn to them is sampled through
+/// [`create_sampled_view`](super::create_sampled_view) to keep it from coming out too dark.
+fn select_surface_format(supported: &[TextureFormat], hdr: bool, fallback: TextureFormat) -> TextureFormat {
+    if hdr && let Some(format) = HDR_SURFACE_FORMATS.iter().find(|format| supported.contains(format)) {
+        return *format;
+    }
 
-use crate::{engine::error::ContextError, prelude::*};
+    supported.iter().find(|format| format.is_srgb()).copied().unwrap_or(fallback)
+}
+
+/// The `requested` present mode when the surface `supported` it, and `Fifo` otherwise
+///
+/// The auto modes are always accepted, since wgpu resolves them to a supported mode itself.
+fn select_present_mode(requested: PresentModeConfig, supported: &[PresentMode]) -> PresentMode {
+    let mode = match requested {
+        PresentModeConfig::Fifo => PresentMode::Fifo,
+        PresentModeConfig::Mailbox => PresentMode::Mailbox,
+        PresentModeConfig::Immediate => PresentMode::Immediate,
+        PresentModeConfig::AutoVsync => return PresentMode::AutoVsync,
+        PresentModeConfig::AutoNoVsync => return PresentMode::AutoNoVsync,
+    };
+
+    if supported.contains(&mode) {
+        mode
+    } else {
+        PresentMode::Fifo
+    }
+}
+
+/// How the compositor blends the surface with what's under it: opaque unless `translucent`,
+/// then the first mode in the surface's `supported` list that honours premultiplied alpha
+fn select_alpha_mode(translucent: bool, supported: &[CompositeAlphaMode]) -> Option<CompositeAlphaMode> {
+    if !translucent {
+        return Some(CompositeAlphaMode::Opaque);
+    }
+    [CompositeAlphaMode::PreMultiplied, CompositeAlphaMode::Inherit]
+        .into_iter()
+        .find(|mode| supported.contains(mode))
+}
 
 pub struct GpuContext {
     device: Device,
     queue: Queue,
     adapter: wgpu::Adapter,
     instance: wgpu::Instance,
-    surface_format: wgpu::TextureFormat,
+    surface_format: TextureFormat,
+    hdr: bool,
+    present_mode: PresentModeConfig,
+    opacity: f32,
+    checker_size: Option<u32>,
 }
 
 impl GpuContext {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(config: &GeneralConfig) -> Result<Self> {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::PRIMARY,
             ..Default::default()
         });
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                compatible_surface: None,
-                force_fallback_adapter: false,
-                ..Default::default()
-            })
-            .await
-            .ok_or(ContextError::NoAdapter)?;
+        let adapter = select_adapter(&instance, &config.gpu).await?;
 
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: None,
-                    required_features: wgpu::Features::empty(),
+                    // Lets sources use every MSAA sample count the adapter offers, not just 1 and 4
+                    required_features: adapter.features() & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                     required_limits: wgpu::Limits {
                         max_texture_dimension_2d: 16384,
                         ..Default::default()
@@ -48,8 +107,10 @@ impl GpuContext {
             )
             .await
             .map_err(|e| ContextError::DeviceCreate(e.to_string()))?;
+        // wgpu panics on errors no error scope caught, which would take the whole daemon down
+        device.on_uncaptured_error(Box::new(|e| error!("Uncaptured GPU error: {e}")));
 
-        let surface_format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let surface_format = TextureFormat::Bgra8UnormSrgb;
 
         Ok(Self {
             device,
@@ -57,6 +118,10 @@ impl GpuContext {
             adapter,
             instance,
             surface_format,
+            hdr: config.hdr,
+            present_mode: config.present_mode,
+            opacity: config.opacity(),
+            checker_size: config.checker_size(),
         })
     }
 
@@ -76,14 +141,57 @@ impl GpuContext {
         &self.instance
     }
 
-    pub fn surface_format(&self) -> wgpu::TextureFormat {
+    /// What the device can do with `format`, such as its supported MSAA sample counts
+    pub fn format_flags(&self, format: TextureFormat) -> wgpu::TextureFormatFeatureFlags {
+        let features = self.device.features();
+        if features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            self.adapter.get_texture_format_features(format).flags
+        } else {
+            format.guaranteed_format_features(features).flags
+        }
+    }
+
+    pub fn surface_format(&self) -> TextureFormat {
         self.surface_format
     }
+
+    /// Whether surfaces should use a deep color format when one is available
+    pub fn hdr(&self) -> bool {
+        self.hdr
+    }
+
+    /// Present mode asked for in the config, before checking what each surface supports
+    pub fn present_mode(&self) -> PresentModeConfig {
+        self.present_mode
+    }
+
+    /// Multiplier on every frame's colors and alpha, 1.0 for a fully opaque wallpaper
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Whether frames are drawn below full opacity for the compositor to blend
+    pub fn translucent(&self) -> bool {
+        self.opacity < 1.0
+    }
+
+    /// Cell size of the checkerboard drawn under every frame, `None` unless enabled for debugging
+    pub fn checker_size(&self) -> Option<u32> {
+        self.checker_size
+    }
+}
+
+/// Where a [`RenderSurface`] presents its frames
+enum Target {
+    Window(Surface<'static>),
+    Offscreen(Arc<wgpu::Texture>),
 }
 
 pub struct RenderSurface {
-    surface: Surface<'static>,
+    target: Target,
     config: SurfaceConfiguration,
+    /// Set when a lost surface had to be reconfigured, until the scene takes it
+    reconfigured: Cell<bool>,
 }
 
 impl RenderSurface {
@@ -106,39 +214,131 @@ impl RenderSurface {
 
         let surface_caps = surface.get_capabilities(gpu.adapter());
         debug!("Supported present modes: {:?}", surface_caps.present_modes);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .find(|f| f.is_srgb())
-            .copied()
-            .unwrap_or(gpu.surface_format());
+        debug!("Supported surface formats: {:?}", surface_caps.formats);
+        let surface_format = select_surface_format(&surface_caps.formats, gpu.hdr(), gpu.surface_format());
 
         debug!("Surface format: {:?}", surface_format);
 
+        let present_mode = select_present_mode(gpu.present_mode(), &surface_caps.present_modes);
+        if present_mode == PresentMode::Fifo && gpu.present_mode() != PresentModeConfig::Fifo {
+            warn!(
+                "Present mode {:?} is not supported by this surface, using Fifo",
+                gpu.present_mode()
+            );
+        }
+        info!("Present mode: {:?}", present_mode);
+
+        let alpha_mode = select_alpha_mode(gpu.translucent(), &surface_caps.alpha_modes).unwrap_or_else(|| {
+            warn!("Surface can't blend with what's under it, drawing at full opacity");
+            CompositeAlphaMode::Opaque
+        });
+
         let config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.0.max(1),
             height: size.1.max(1),
+            present_mode,
+            alpha_mode,
+            view_formats: Vec::new(),
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(gpu.device(), &config);
+
+        Ok(Self {
+            target: Target::Window(surface),
+            config,
+            reconfigured: Cell::new(false),
+        })
+    }
+
+    /// A render target with no window behind it, for rendering previews to a file
+    pub fn offscreen(gpu: &GpuContext, size: (u32, u32)) -> Self {
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            format: OFFSCREEN_FORMAT,
+            width: size.0.max(1),
+            height: size.1.max(1),
             present_mode: PresentMode::AutoVsync,
             alpha_mode: CompositeAlphaMode::Opaque,
             view_formats: Vec::new(),
             desired_maximum_frame_latency: 2,
         };
-        surface.configure(gpu.device(), &config);
+        let texture = create_offscreen_texture(gpu.device(), config.width, config.height);
 
-        Ok(Self { surface, config })
+        Self {
+            target: Target::Offscreen(Arc::new(texture)),
+            config,
+            reconfigured: Cell::new(false),
+        }
     }
 
     pub fn resize(&mut self, device: &Device, dimensions: (u32, u32)) {
         let (width, height) = dimensions;
         self.config.width = width.max(1);
         self.config.height = height.max(1);
-        self.surface.configure(device, &self.config);
+        match &mut self.target {
+            Target::Window(surface) => surface.configure(device, &self.config),
+            Target::Offscreen(texture) => {
+                *texture = Arc::new(create_offscreen_texture(device, self.config.width, self.config.height));
+            },
+        }
     }
 
-    pub fn surface(&self) -> &Surface<'static> {
-        &self.surface
+    /// The Wayland surface, or `None` when rendering offscreen
+    pub fn surface(&self) -> Option<&Surface<'static>> {
+        match &self.target {
+            Target::Window(surface) => Some(surface),
+            Target::Offscreen(_) => None,
+        }
+    }
+
+    /// Texture to draw the next frame into, or `None` if the surface can't provide one
+    ///
+    /// A lost or outdated surface, as after DPMS off/on or a compositor restart, is
+    /// reconfigured with the stored configuration and acquired once more.
+    pub fn acquire(&self, device: &Device) -> Option<FrameTarget> {
+        let surface = match &self.target {
+            Target::Window(surface) => surface,
+            Target::Offscreen(texture) => return Some(FrameTarget::Offscreen(texture.clone())),
+        };
+
+        let err = match surface.get_current_texture() {
+            Ok(texture) => return Some(FrameTarget::Surface(texture)),
+            Err(e) => e,
+        };
+
+        if !should_reconfigure(&err) {
+            error!("Could not get texture from surface: {err}");
+            return None;
+        }
+
+        warn!("Surface {err}, reconfiguring");
+        surface.configure(device, &self.config);
+        self.reconfigured.set(true);
+
+        match surface.get_current_texture() {
+            Ok(texture) => Some(FrameTarget::Surface(texture)),
+            Err(e) => {
+                error!("Could not get texture from surface after reconfiguring: {e}");
+                None
+            },
+        }
+    }
+
+    /// Whether the surface was reconfigured after being lost since the last call
+    pub fn take_reconfigured(&self) -> bool {
+        self.reconfigured.take()
+    }
+
+    /// Copies the last offscreen frame back to the CPU
+    pub fn read_pixels(&self, gpu: &GpuContext) -> Result<image::RgbaImage> {
+        match &self.target {
+            Target::Offscreen(texture) => read_rgba(gpu, texture),
+            Target::Window(_) => {
+                Err(ContextError::Readback("only offscreen targets can be read back".to_string()).into())
+            },
+        }
     }
 
     pub fn config(&self) -> &SurfaceConfiguration {
@@ -161,6 +361,13 @@ impl RenderSurface {
 pub struct Context {
     gpu: std::sync::Arc<GpuContext>,
     surface: RenderSurface,
+    uv_rect: UvRect,
+    scale_factor: i32,
+    overlay: Option<Overlay>,
+    background: Option<Checker>,
+    opacity: Option<Opacity>,
+    /// Whether what's on screen is out of date regardless of the source, such as after a resize
+    needs_frame: Cell<bool>,
 }
 
 impl RenderSurface {
@@ -168,8 +375,8 @@ impl RenderSurface {
         &self.config
     }
 
-    pub fn surface_ref(&self) -> &Surface<'static> {
-        &self.surface
+    pub fn surface_ref(&self) -> Option<&Surface<'static>> {
+        self.surface()
     }
 }
 
@@ -191,7 +398,7 @@ impl<'a> ContextRef<'a> {
         &self.gpu.queue
     }
 
-    pub fn surface(&self) -> &'a Surface<'static> {
+    pub fn surface(&self) -> Option<&'a Surface<'static>> {
         self.surface.surface()
     }
 
@@ -209,17 +416,47 @@ impl<'a> ContextRef<'a> {
 }
 
 impl Context {
-    pub async fn new(conn: &Connection, layer: &LayerSurface, size: (u32, u32)) -> Result<Self> {
-        let gpu = GpuContext::new().await?;
+    pub async fn new(
+        conn: &Connection,
+        layer: &LayerSurface,
+        size: (u32, u32),
+        config: &GeneralConfig,
+    ) -> Result<Self> {
+        let gpu = GpuContext::new(config).await?;
         let surface = RenderSurface::new(&gpu, conn, layer, size)?;
-        Ok(Self {
-            gpu: std::sync::Arc::new(gpu),
-            surface,
-        })
+        Ok(Self::from_parts(std::sync::Arc::new(gpu), surface))
+    }
+
+    /// A context that renders into an offscreen texture of `size` instead of a Wayland surface
+    pub fn headless(gpu: Arc<GpuContext>, size: (u32, u32)) -> Self {
+        let surface = RenderSurface::offscreen(&gpu, size);
+        Self::from_parts(gpu, surface)
     }
 
     pub fn from_parts(gpu: std::sync::Arc<GpuContext>, surface: RenderSurface) -> Self {
-        Self { gpu, surface }
+        let mut ctx = Self {
+            gpu,
+            surface,
+            uv_rect: UvRect::FULL,
+            scale_factor: 1,
+            overlay: None,
+            background: None,
+            opacity: None,
+            needs_frame: Cell::new(true),
+        };
+        ctx.background = ctx.gpu.checker_size().and_then(|size| {
+            Checker::new(&ctx, size)
+                .inspect_err(|e| warn!("Drawing no checker background: {e}"))
+                .ok()
+        });
+        // Surfaces left opaque would only darken, not show what's under them
+        let blended = ctx.config().alpha_mode != CompositeAlphaMode::Opaque;
+        ctx.opacity = blended.then(|| ctx.gpu.opacity()).and_then(|opacity| {
+            Opacity::new(&ctx, opacity)
+                .inspect_err(|e| warn!("Drawing at full opacity: {e}"))
+                .ok()
+        });
+        ctx
     }
 
     pub fn gpu_arc(&self) -> std::sync::Arc<GpuContext> {
@@ -232,12 +469,59 @@ impl Context {
 
     pub fn resize(&mut self, dimensions: (u32, u32)) {
         self.surface.resize(&self.gpu.device, dimensions);
+        self.needs_frame.set(true);
+    }
+
+    /// Whether this surface has to be drawn again even if the source hasn't changed
+    ///
+    /// True until the first frame is acquired, and again after a resize or a change
+    /// to the span region or overlay.
+    pub fn needs_frame(&self) -> bool {
+        self.needs_frame.get()
+    }
+
+    /// Buffer scale of the output this context draws to, 2 on a typical HiDPI monitor
+    pub fn scale_factor(&self) -> i32 {
+        self.scale_factor
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: i32) {
+        self.scale_factor = scale_factor.max(1);
+    }
+
+    /// Surface size in physical pixels
+    pub fn size(&self) -> (u32, u32) {
+        self.surface.size()
+    }
+
+    /// Surface size in logical pixels, the buffer size divided by the scale factor
+    pub fn logical_size(&self) -> (u32, u32) {
+        let (width, height) = self.surface.size();
+        let scale = self.scale_factor as u32;
+        (width / scale, height / scale)
     }
 
     pub fn surface_aspect_ratio(&self) -> f32 {
         self.surface.aspect_ratio()
     }
 
+    /// Region of the source content this surface displays, [`UvRect::FULL`] unless spanning
+    pub fn uv_rect(&self) -> UvRect {
+        self.uv_rect
+    }
+
+    pub fn set_uv_rect(&mut self, uv_rect: UvRect) {
+        if self.uv_rect != uv_rect {
+            self.needs_frame.set(true);
+        }
+        self.uv_rect = uv_rect;
+    }
+
+    /// Aspect ratio of the whole content area, which is the span canvas when this surface shows a slice of it
+    pub fn content_aspect_ratio(&self) -> f32 {
+        self.surface_aspect_ratio() * self.uv_rect.height / self.uv_rect.width
+    }
+
     pub fn device(&self) -> &Device {
         &self.gpu.device
     }
@@ -246,10 +530,62 @@ impl Context {
         &self.gpu.queue
     }
 
-    pub fn surface(&self) -> &Surface<'static> {
+    pub fn surface(&self) -> Option<&Surface<'static>> {
         self.surface.surface()
     }
 
+    /// Texture to draw the next frame into, from the surface or the offscreen target
+    ///
+    /// Errors are logged here, so callers can skip the frame on `None`. With a debug
+    /// background enabled the frame already holds the checkerboard.
+    pub fn acquire_frame(&self) -> Option<Frame<'_>> {
+        let target = self.surface.acquire(&self.gpu.device)?;
+        self.needs_frame.set(false);
+        let frame = Frame::new(target, &self.gpu, self.opacity.as_ref(), self.overlay.as_ref());
+        if let Some(background) = &self.background {
+            background.draw(self, &frame.view());
+        }
+        Some(frame)
+    }
+
+    /// Whether the surface came back from being lost since the last call, as it does
+    /// when the monitor wakes from DPMS sleep
+    pub fn take_resumed(&self) -> bool {
+        self.surface.take_reconfigured()
+    }
+
+    /// Load op for a pass drawing straight into an acquired frame
+    ///
+    /// Clears to `color` as usual, but keeps the debug background when one is drawn.
+    pub fn clear_op(&self, color: wgpu::Color) -> wgpu::LoadOp<wgpu::Color> {
+        match self.background {
+            Some(_) => wgpu::LoadOp::Load,
+            None => wgpu::LoadOp::Clear(color),
+        }
+    }
+
+    /// Shows `text` over every frame presented from now on, creating the overlay on first use
+    pub fn set_overlay_text(&mut self, text: &str) {
+        let size = self.surface.size();
+        let overlay = self
+            .overlay
+            .get_or_insert_with(|| Overlay::new(&self.gpu, self.surface.config().format));
+        overlay.set_text(&self.gpu.queue, text, size);
+        self.needs_frame.set(true);
+    }
+
+    /// Drops the overlay so frames are presented untouched again
+    pub fn clear_overlay(&mut self) {
+        if self.overlay.take().is_some() {
+            self.needs_frame.set(true);
+        }
+    }
+
+    /// Copies the last rendered frame back to the CPU, for headless contexts
+    pub fn read_pixels(&self) -> Result<image::RgbaImage> {
+        self.surface.read_pixels(&self.gpu)
+    }
+
     pub fn config(&self) -> &SurfaceConfiguration {
         self.surface.config()
     }
@@ -266,3 +602,109 @@ impl Context {
         &mut self.surface
     }
 }
+
+/// Whether a surface error clears up by reconfiguring the surface
+fn should_reconfigure(err: &wgpu::SurfaceError) -> bool {
+    matches!(err, wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_reconfigure_lost_and_outdated() {
+        assert!(should_reconfigure(&wgpu::SurfaceError::Lost));
+        assert!(should_reconfigure(&wgpu::SurfaceError::Outdated));
+    }
+
+    #[test]
+    fn test_select_surface_format_prefers_deep_format_for_hdr() {
+        let supported = [
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::Bgra8UnormSrgb,
+            TextureFormat::Rgba16Float,
+            TextureFormat::Rgb10a2Unorm,
+        ];
+        assert_eq!(
+            select_surface_format(&supported, true, TextureFormat::Bgra8UnormSrgb),
+            TextureFormat::Rgb10a2Unorm
+        );
+
+        let supported = [TextureFormat::Bgra8UnormSrgb, TextureFormat::Rgba16Float];
+        assert_eq!(
+            select_surface_format(&supported, true, TextureFormat::Bgra8UnormSrgb),
+            TextureFormat::Rgba16Float
+        );
+    }
+
+    #[test]
+    fn test_select_present_mode_supported_or_fifo() {
+        let supported = [PresentMode::Fifo, PresentMode::Mailbox];
+        assert_eq!(
+            select_present_mode(PresentModeConfig::Mailbox, &supported),
+            PresentMode::Mailbox
+        );
+        assert_eq!(
+            select_present_mode(PresentModeConfig::Fifo, &supported),
+            PresentMode::Fifo
+        );
+        assert_eq!(
+            select_present_mode(PresentModeConfig::Immediate, &supported),
+            PresentMode::Fifo
+        );
+        assert_eq!(select_present_mode(PresentModeConfig::Mailbox, &[]), PresentMode::Fifo);
+    }
+
+    #[test]
+    fn test_select_present_mode_keeps_auto_modes() {
+        let supported = [PresentMode::Fifo];
+        assert_eq!(
+            select_present_mode(PresentModeConfig::AutoVsync, &supported),
+            PresentMode::AutoVsync
+        );
+        assert_eq!(
+            select_present_mode(PresentModeConfig::AutoNoVsync, &supported),
+            PresentMode::AutoNoVsync
+        );
+    }
+
+    #[test]
+    fn test_select_alpha_mode_only_blends_when_translucent() {
+        let supported = [CompositeAlphaMode::Opaque, CompositeAlphaMode::PreMultiplied];
+        assert_eq!(select_alpha_mode(false, &supported), Some(CompositeAlphaMode::Opaque));
+        assert_eq!(
+            select_alpha_mode(true, &supported),
+            Some(CompositeAlphaMode::PreMultiplied)
+        );
+        assert_eq!(select_alpha_mode(true, &[CompositeAlphaMode::Opaque]), None);
+    }
+
+    #[test]
+    fn test_select_surface_format_falls_back_to_srgb() {
+        let supported = [TextureFormat::Bgra8Unorm, TextureFormat::Rgba8UnormSrgb];
+        assert_eq!(
+            select_surface_format(&supported, true, TextureFormat::Bgra8UnormSrgb),
+            TextureFormat::Rgba8UnormSrgb
+        );
+    }
+
+    #[test]
+    fn test_select_surface_format_ignores_deep_formats_without_hdr() {
+        let supported = [TextureFormat::Rgb10a2Unorm, TextureFormat::Bgra8UnormSrgb];
+        assert_eq!(
+            select_surface_format(&supported, false, TextureFormat::Bgra8Unorm),
+            TextureFormat::Bgra8UnormSrgb
+        );
+        assert_eq!(
+            select_surface_format(&[], false, TextureFormat::Bgra8UnormSrgb),
+            TextureFormat::Bgra8UnormSrgb
+        );
+    }
+
+    #[test]
+    fn test_should_not_reconfigure_timeout_or_oom() {
+        assert!(!should_reconfigure(&wgpu::SurfaceError::Timeout));
+        assert!(!should_reconfigure(&wgpu::SurfaceError::OutOfMemory));
+    }
+}
@@ -57,60 +57,26 @@ impl DmabufFrame {
 
 /// Import a DMA-BUF frame into WGPU textures
 ///
-/// This is a placeholder that creates empty textures. Full implementation requires:
+/// Not implemented yet, so this always errs rather than handing back uninitialised
+/// textures. A full implementation requires:
 /// 1. Using wgpu-hal to access the raw Vulkan device
 /// 2. Creating VkDeviceMemory from the DMA-BUF FDs using Vulkan external memory extensions
 /// 3. Creating VkImages bound to that memory
 /// 4. Converting back to wgpu Textures via hal device interface
 ///
-/// The vulkan_ext module contains the Vulkan-level code needed for this integration.
-///
 /// # Errors
 ///
-/// Returns an error if the frame format is invalid.
-pub fn import_dmabuf_frame(device: &wgpu::Device, frame: &DmabufFrame) -> Result<(wgpu::Texture, wgpu::Texture)> {
+/// Returns an error if the frame format is invalid, and otherwise because the import is missing.
+pub fn import_dmabuf_frame(_device: &wgpu::Device, frame: &DmabufFrame) -> Result<(wgpu::Texture, wgpu::Texture)> {
     if !frame.is_valid_nv() {
         return Err(Error::Generic(
             "DMA-BUF frame must have exactly 2 planes (Y and UV) for NV12/NV21 format".to_string(),
         ));
     }
 
-    debug!(
-        "DMA-BUF import requested for {}x{} frame (Tier 1 CUDA→DMA-BUF integration in progress)",
-        frame.width, frame.height
-    );
-
-    // Create temporary textures as placeholders for now
-    // TODO: Replace with actual Vulkan external memory import when HAL integration is complete
-    let y_tex = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("dmabuf_y_plane_placeholder"),
-        size: wgpu::Extent3d {
-            width: frame.width,
-            height: frame.height,
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::R8Unorm,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[],
-    });
-
-    let uv_tex = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("dmabuf_uv_plane_placeholder"),
-        size: wgpu::Extent3d {
-            width: frame.width / 2,
-            height: frame.height / 2,
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rg8Unorm,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[],
-    });
-
-    Ok((y_tex, uv_tex))
+    Err(Error::Generic(f!(
+        "Cannot import {}x{} DMA-BUF frame: Vulkan external memory import is not implemented",
+        frame.width,
+        frame.height
+    )))
 }
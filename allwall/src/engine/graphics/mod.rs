@@ -14,10 +14,24 @@
 
 #![allow(unsafe_code)]
 
+mod adapter;
+mod checker;
 mod context;
+#[cfg(feature = "dmabuf")]
 mod dmabuf;
+mod mipmap;
+mod offscreen;
+mod opacity;
+mod overlay;
+mod shader;
+#[cfg(feature = "dev")]
+pub mod shader_store;
 mod texture;
 
 pub use context::{Context, ContextRef, GpuContext, RenderSurface};
+#[cfg(feature = "dmabuf")]
 pub use dmabuf::{DmabufFormat, DmabufFrame, DmabufPlane, import_dmabuf_frame};
-pub use texture::Texture;
+pub use offscreen::Frame;
+pub use overlay::{FrameStats, Overlay};
+pub use shader::{shader_module, validated};
+pub use texture::{Texture, create_sampled_view};
@@ -0,0 +1,170 @@
+use std::{iter::once, sync::Arc};
+
+use crate::{engine::error::ContextError, prelude::*};
+
+use super::{GpuContext, opacity::Opacity, overlay::Overlay};
+
+/// Format of headless render targets, chosen so readback bytes are already RGBA
+pub const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Where a frame's pixels live, a Wayland surface or an offscreen target
+pub enum FrameTarget {
+    Surface(wgpu::SurfaceTexture),
+    Offscreen(Arc<wgpu::Texture>),
+}
+
+/// A texture to draw one frame into, with the opacity and overlay passes to finish it on present
+pub struct Frame<'a> {
+    target: FrameTarget,
+    gpu: &'a GpuContext,
+    opacity: Option<&'a Opacity>,
+    overlay: Option<&'a Overlay>,
+}
+
+impl<'a> Frame<'a> {
+    pub(super) fn new(
+        target: FrameTarget,
+        gpu: &'a GpuContext,
+        opacity: Option<&'a Opacity>,
+        overlay: Option<&'a Overlay>,
+    ) -> Self {
+        Self {
+            target,
+            gpu,
+            opacity,
+            overlay,
+        }
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        match &self.target {
+            FrameTarget::Surface(surface) => &surface.texture,
+            FrameTarget::Offscreen(texture) => texture,
+        }
+    }
+
+    pub fn view(&self) -> wgpu::TextureView {
+        self.texture().create_view(&Default::default())
+    }
+
+    /// Shows the frame on screen; offscreen frames stay in their texture until read back
+    pub fn present(self) {
+        if let Some(opacity) = self.opacity {
+            opacity.draw(self.gpu, &self.view());
+        }
+        // Drawn after the opacity pass so the frame-time text stays readable
+        if let Some(overlay) = self.overlay {
+            overlay.draw(self.gpu, &self.view());
+        }
+        if let FrameTarget::Surface(surface) = self.target {
+            surface.present();
+        }
+    }
+}
+
+pub(super) fn create_offscreen_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen_target"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: OFFSCREEN_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+/// Row stride for copying a `width`-pixel RGBA row into a buffer, padded to wgpu's alignment
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Drops the per-row padding from a texture-to-buffer copy
+fn unpad_rows(data: &[u8], width: u32, height: u32, padded_bytes_per_row: u32) -> Vec<u8> {
+    let row_len = (width * 4) as usize;
+    data.chunks(padded_bytes_per_row as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[..row_len])
+        .copied()
+        .collect()
+}
+
+/// Copies an [`OFFSCREEN_FORMAT`] texture back to the CPU, blocking until the GPU is done
+pub fn read_rgba(gpu: &GpuContext, texture: &wgpu::Texture) -> Result<image::RgbaImage> {
+    let device = gpu.device();
+    let (width, height) = (texture.width(), texture.height());
+    let bytes_per_row = padded_bytes_per_row(width);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("offscreen_readback"),
+        size: u64::from(bytes_per_row) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+    gpu.queue().submit(once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+
+    rx.recv()
+        .map_err(|e| ContextError::Readback(e.to_string()))?
+        .map_err(|e| ContextError::Readback(e.to_string()))?;
+
+    let pixels = unpad_rows(&slice.get_mapped_range(), width, height, bytes_per_row);
+    buffer.unmap();
+
+    image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| ContextError::Readback(f!("expected {}x{} RGBA pixels", width, height)).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_bytes_per_row_aligns() {
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(1920) % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT, 0);
+    }
+
+    #[test]
+    fn test_unpad_rows_strips_padding() {
+        let padded = padded_bytes_per_row(2) as usize;
+        let mut data = vec![0xff; padded * 2];
+        data[..8].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data[padded..padded + 8].copy_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]);
+
+        let pixels = unpad_rows(&data, 2, 2, padded as u32);
+        assert_eq!(pixels, (1..=16).collect::<Vec<u8>>());
+    }
+}
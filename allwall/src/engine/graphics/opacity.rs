@@ -0,0 +1,84 @@
+use std::iter::once;
+
+use super::{Context, GpuContext};
+use crate::{
+    prelude::*,
+    sources::{INDICES, create_blended_pipeline, create_index_buffer, create_vertex_buffer},
+};
+
+/// Keeps none of the fragment and scales the frame underneath by the blend constant,
+/// alpha included, so premultiplied colors stay premultiplied
+const SCALE_DESTINATION: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::Zero,
+    dst_factor: wgpu::BlendFactor::Constant,
+    operation: wgpu::BlendOperation::Add,
+};
+
+/// Final pass multiplying every finished frame by `opacity` from the general config
+///
+/// Only created below full opacity, on surfaces the compositor blends with what's under them.
+pub struct Opacity {
+    opacity: f32,
+
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Opacity {
+    pub fn new(ctx: &Context, opacity: f32) -> Result<Self> {
+        let render_pipeline = create_blended_pipeline(
+            ctx,
+            &[],
+            &ctx.device().create_shader_module(crate::wgsl!("./shaders/opacity.wgsl")),
+            ctx.config(),
+            wgpu::BlendState {
+                color: SCALE_DESTINATION,
+                alpha: SCALE_DESTINATION,
+            },
+        )?;
+
+        Ok(Self {
+            opacity,
+            vertex_buffer: create_vertex_buffer(ctx),
+            index_buffer: create_index_buffer(ctx),
+            render_pipeline,
+        })
+    }
+
+    /// Scales everything already drawn into `target` by the opacity
+    pub fn draw(&self, gpu: &GpuContext, target: &wgpu::TextureView) {
+        let opacity = f64::from(self.opacity);
+        let mut encoder = gpu.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("opacity"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_blend_constant(wgpu::Color {
+                r: opacity,
+                g: opacity,
+                b: opacity,
+                a: opacity,
+            });
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        gpu.queue().submit(once(encoder.finish()));
+    }
+}
@@ -0,0 +1,451 @@
+use std::{collections::VecDeque, iter::once, time::Duration};
+
+use bytemuck::{Pod, Zeroable};
+
+use super::GpuContext;
+use crate::prelude::*;
+
+/// Frames averaged by [`FrameStats`], about two seconds at 60 fps
+const STATS_WINDOW: usize = 120;
+
+/// Rolling average over the most recent frame durations
+#[derive(Debug)]
+pub struct FrameStats {
+    samples: VecDeque<Duration>,
+    total: Duration,
+    capacity: usize,
+}
+
+impl FrameStats {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            total: Duration::ZERO,
+            capacity,
+        }
+    }
+
+    /// Records one frame, dropping the oldest once the window is full
+    pub fn push(&mut self, frame: Duration) {
+        if self.samples.len() == self.capacity
+            && let Some(oldest) = self.samples.pop_front()
+        {
+            self.total -= oldest;
+        }
+        self.samples.push_back(frame);
+        self.total += frame;
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.samples.len()).ok().filter(|&n| n > 0)?;
+        Some(self.total / count)
+    }
+
+    pub fn fps(&self) -> Option<f32> {
+        self.average()
+            .filter(|average| !average.is_zero())
+            .map(|average| 1.0 / average.as_secs_f32())
+    }
+
+    /// The line the overlay shows, e.g. `59.9 FPS 16.7 MS`
+    pub fn summary(&self) -> String {
+        match (self.fps(), self.average()) {
+            (Some(fps), Some(average)) => f!("{fps:.1} FPS {:.1} MS", average.as_secs_f32() * 1000.0),
+            _ => "-- FPS -- MS".to_string(),
+        }
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(STATS_WINDOW)
+    }
+}
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+/// 3x5 bitmap font, one byte per row with the leftmost pixel in bit 2
+///
+/// Only what [`FrameStats::summary`] prints; other characters draw as gaps.
+const GLYPHS: &[(char, [u8; 5])] = &[
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('P', [0b111, 0b101, 0b111, 0b100, 0b100]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+];
+
+/// Atlas cell after the glyphs that is fully covered, stretched behind the text as a backdrop
+const BLOCK_CELL: usize = GLYPHS.len();
+const ATLAS_WIDTH: u32 = (GLYPHS.len() as u32 + 1) * GLYPH_WIDTH;
+
+/// Screen pixels per font pixel
+const PIXEL_SCALE: u32 = 3;
+/// Distance from the top-left corner of the surface, in screen pixels
+const MARGIN: u32 = 8;
+/// Longest text drawn, which bounds the vertex buffer
+const MAX_CHARS: usize = 32;
+const VERTICES_PER_QUAD: usize = 6;
+
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BACKDROP_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+
+fn glyph_cell(c: char) -> Option<usize> {
+    let c = c.to_ascii_uppercase();
+    GLYPHS.iter().position(|(glyph, _)| *glyph == c)
+}
+
+/// Coverage for the whole atlas, a row of glyph cells followed by the solid block
+fn atlas_pixels() -> Vec<u8> {
+    let mut pixels = vec![0u8; (ATLAS_WIDTH * GLYPH_HEIGHT) as usize];
+    for y in 0..GLYPH_HEIGHT as usize {
+        let row = &mut pixels[y * ATLAS_WIDTH as usize..(y + 1) * ATLAS_WIDTH as usize];
+        for (cell, (_, rows)) in GLYPHS.iter().enumerate() {
+            for x in 0..GLYPH_WIDTH as usize {
+                if (rows[y] >> (GLYPH_WIDTH as usize - 1 - x)) & 1 == 1 {
+                    row[cell * GLYPH_WIDTH as usize + x] = u8::MAX;
+                }
+            }
+        }
+        let block = BLOCK_CELL * GLYPH_WIDTH as usize;
+        row[block..block + GLYPH_WIDTH as usize].fill(u8::MAX);
+    }
+    pixels
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+struct OverlayVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    color: [f32; 4],
+}
+
+impl OverlayVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Two triangles covering `rect` (x, y, width, height in screen pixels) textured with atlas `cell`
+fn push_quad(vertices: &mut Vec<OverlayVertex>, surface: (u32, u32), rect: [u32; 4], cell: usize, color: [f32; 4]) {
+    let to_clip_x = |px: u32| px as f32 / surface.0.max(1) as f32 * 2.0 - 1.0;
+    let to_clip_y = |px: u32| 1.0 - px as f32 / surface.1.max(1) as f32 * 2.0;
+    let [x, y, width, height] = rect;
+    let (left, right) = (to_clip_x(x), to_clip_x(x + width));
+    let (top, bottom) = (to_clip_y(y), to_clip_y(y + height));
+
+    let u0 = (cell as u32 * GLYPH_WIDTH) as f32 / ATLAS_WIDTH as f32;
+    let u1 = ((cell as u32 + 1) * GLYPH_WIDTH) as f32 / ATLAS_WIDTH as f32;
+
+    let corner = |position: [f32; 2], tex_coords: [f32; 2]| OverlayVertex {
+        position,
+        tex_coords,
+        color,
+    };
+    let top_left = corner([left, top], [u0, 0.0]);
+    let top_right = corner([right, top], [u1, 0.0]);
+    let bottom_left = corner([left, bottom], [u0, 1.0]);
+    let bottom_right = corner([right, bottom], [u1, 1.0]);
+
+    vertices.extend([top_left, bottom_left, top_right, top_right, bottom_left, bottom_right]);
+}
+
+/// Backdrop and glyph quads for `text` in the top-left corner of a `surface`-sized target
+fn text_vertices(text: &str, surface: (u32, u32)) -> Vec<OverlayVertex> {
+    let chars: Vec<char> = text.chars().take(MAX_CHARS).collect();
+    let advance = (GLYPH_WIDTH + 1) * PIXEL_SCALE;
+    let glyph_size = (GLYPH_WIDTH * PIXEL_SCALE, GLYPH_HEIGHT * PIXEL_SCALE);
+    let text_width = (chars.len() as u32 * advance).saturating_sub(PIXEL_SCALE);
+
+    let mut vertices = Vec::with_capacity((chars.len() + 1) * VERTICES_PER_QUAD);
+    push_quad(
+        &mut vertices,
+        surface,
+        [MARGIN, MARGIN, text_width + 2 * PIXEL_SCALE, glyph_size.1 + 2 * PIXEL_SCALE],
+        BLOCK_CELL,
+        BACKDROP_COLOR,
+    );
+
+    let origin = MARGIN + PIXEL_SCALE;
+    for (i, c) in chars.iter().enumerate() {
+        if let Some(cell) = glyph_cell(*c) {
+            let x = origin + i as u32 * advance;
+            push_quad(
+                &mut vertices,
+                surface,
+                [x, origin, glyph_size.0, glyph_size.1],
+                cell,
+                TEXT_COLOR,
+            );
+        }
+    }
+    vertices
+}
+
+/// Frame-time text drawn over a surface after the source has rendered
+///
+/// Only exists while the overlay is toggled on, so it costs nothing otherwise.
+pub struct Overlay {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+}
+
+impl Overlay {
+    pub fn new(gpu: &GpuContext, format: wgpu::TextureFormat) -> Self {
+        let device = gpu.device();
+
+        let size = wgpu::Extent3d {
+            width: ATLAS_WIDTH,
+            height: GLYPH_HEIGHT,
+            depth_or_array_layers: 1,
+        };
+        let atlas = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("overlay_atlas"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        gpu.queue().write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &atlas,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &atlas_pixels(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(ATLAS_WIDTH),
+                rows_per_image: Some(GLYPH_HEIGHT),
+            },
+            size,
+        );
+
+        let view = atlas.create_view(&Default::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("overlay_atlas"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlay_atlas"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("overlay_vertices"),
+            size: ((MAX_CHARS + 1) * VERTICES_PER_QUAD * std::mem::size_of::<OverlayVertex>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline: create_overlay_pipeline(device, &bind_group_layout, format),
+            bind_group,
+            vertex_buffer,
+            vertex_count: 0,
+        }
+    }
+
+    /// Lays out `text` for a target of `surface` pixels, shown from the next draw on
+    pub fn set_text(&mut self, queue: &wgpu::Queue, text: &str, surface: (u32, u32)) {
+        let vertices = text_vertices(text, surface);
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+        self.vertex_count = vertices.len() as u32;
+    }
+
+    /// Draws over the existing contents of `target`
+    pub fn draw(&self, gpu: &GpuContext, target: &wgpu::TextureView) {
+        let mut encoder = gpu.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("overlay"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..self.vertex_count, 0..1);
+        }
+        gpu.queue().submit(once(encoder.finish()));
+    }
+}
+
+fn create_overlay_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(crate::wgsl!("./shaders/overlay.wgsl"));
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("overlay"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("overlay"),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[OverlayVertex::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_stats_empty() {
+        let stats = FrameStats::default();
+        assert_eq!(stats.average(), None);
+        assert_eq!(stats.fps(), None);
+        assert_eq!(stats.summary(), "-- FPS -- MS");
+    }
+
+    #[test]
+    fn test_frame_stats_averages_samples() {
+        let mut stats = FrameStats::new(4);
+        stats.push(Duration::from_millis(10));
+        stats.push(Duration::from_millis(30));
+
+        assert_eq!(stats.average(), Some(Duration::from_millis(20)));
+        assert!((stats.fps().unwrap() - 50.0).abs() < 1e-3);
+        assert_eq!(stats.summary(), "50.0 FPS 20.0 MS");
+    }
+
+    #[test]
+    fn test_frame_stats_drops_oldest_when_full() {
+        let mut stats = FrameStats::new(2);
+        stats.push(Duration::from_millis(100));
+        stats.push(Duration::from_millis(10));
+        stats.push(Duration::from_millis(30));
+
+        assert_eq!(stats.average(), Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_summary_only_uses_atlas_glyphs() {
+        let mut stats = FrameStats::default();
+        stats.push(Duration::from_micros(16_667));
+
+        assert!(stats.summary().chars().all(|c| c == ' ' || glyph_cell(c).is_some()));
+    }
+
+    #[test]
+    fn test_atlas_block_cell_is_solid() {
+        let pixels = atlas_pixels();
+        let block = BLOCK_CELL * GLYPH_WIDTH as usize;
+        for y in 0..GLYPH_HEIGHT as usize {
+            let row = &pixels[y * ATLAS_WIDTH as usize..(y + 1) * ATLAS_WIDTH as usize];
+            assert!(row[block..].iter().all(|&p| p == u8::MAX));
+        }
+    }
+
+    #[test]
+    fn test_text_vertices_skip_spaces_and_stay_on_screen() {
+        let vertices = text_vertices("1 2", (1920, 1080));
+
+        // Backdrop plus two glyphs, the space adds no quad
+        assert_eq!(vertices.len(), 3 * VERTICES_PER_QUAD);
+        assert!(vertices.iter().all(|v| v.position.iter().all(|p| (-1.0..=1.0).contains(p))));
+        assert_eq!(vertices[0].color, BACKDROP_COLOR);
+        assert_eq!(vertices[VERTICES_PER_QUAD].color, TEXT_COLOR);
+    }
+
+    #[test]
+    fn test_text_vertices_truncate_long_text() {
+        let text = "8".repeat(MAX_CHARS * 2);
+        assert_eq!(
+            text_vertices(&text, (1920, 1080)).len(),
+            (MAX_CHARS + 1) * VERTICES_PER_QUAD
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::borrow::Cow;
+
+use crate::{engine::error::ContextError, prelude::*};
+
+/// `wgpu::include_wgsl!` for one or more files joined in order
+///
+/// Built with the `dev` feature and run with `--watch-shaders`, each file is read from
+/// the watched directory instead of the copy baked into the binary.
+#[macro_export]
+macro_rules! wgsl {
+    ($first:literal $(, $rest:literal)* $(,)?) => {
+        $crate::engine::graphics::shader_module(&[($first, include_str!($first)) $(, ($rest, include_str!($rest)))*])
+    };
+}
+
+/// Descriptor for `files`, given as `(path, baked source)` pairs, labelled after the first
+pub fn shader_module(files: &[(&'static str, &'static str)]) -> wgpu::ShaderModuleDescriptor<'static> {
+    let source = match files {
+        [(path, baked)] => live_source(path).map_or(Cow::Borrowed(*baked), Cow::Owned),
+        _ => Cow::Owned(
+            files
+                .iter()
+                .map(|(path, baked)| live_source(path).unwrap_or_else(|| (*baked).to_string()))
+                .collect(),
+        ),
+    };
+
+    wgpu::ShaderModuleDescriptor {
+        label: files.first().map(|(path, _)| *path),
+        source: wgpu::ShaderSource::Wgsl(source),
+    }
+}
+
+#[cfg(feature = "dev")]
+fn live_source(path: &str) -> Option<String> {
+    let name = std::path::Path::new(path).file_name()?.to_str()?;
+    super::shader_store::active()?.source(name)
+}
+
+#[cfg(not(feature = "dev"))]
+fn live_source(_path: &str) -> Option<String> {
+    None
+}
+
+/// Runs `create` inside a validation error scope, so a shader that doesn't compile or a
+/// pipeline that doesn't match its shader comes back as an error rather than an object
+/// wgpu has quietly marked invalid
+pub fn validated<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> Result<T> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let created = create();
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(e) => Err(ContextError::Wgpu(e).into()),
+        None => Ok(created),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A device on whatever adapter is around, or `None` on machines without one
+    fn test_device() -> Option<wgpu::Device> {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&Default::default()))?;
+        let (device, _queue) = pollster::block_on(adapter.request_device(&Default::default(), None)).ok()?;
+        Some(device)
+    }
+
+    fn wgsl(source: &'static str) -> wgpu::ShaderModuleDescriptor<'static> {
+        wgpu::ShaderModuleDescriptor {
+            label: Some("test"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+        }
+    }
+
+    #[test]
+    fn test_validated_rejects_invalid_wgsl() {
+        let Some(device) = test_device() else {
+            warn!("No GPU adapter available, skipping");
+            return;
+        };
+
+        let broken = validated(&device, || device.create_shader_module(wgsl("fn fs_main( -> {")));
+        assert!(broken.is_err());
+
+        let fine = validated(&device, || {
+            device.create_shader_module(wgsl(
+                "@fragment fn fs_main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }",
+            ))
+        });
+        assert!(fine.is_ok());
+    }
+}
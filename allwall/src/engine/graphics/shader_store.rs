@@ -0,0 +1,158 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::prelude::*;
+
+static STORE: OnceLock<ShaderStore> = OnceLock::new();
+
+/// Directories never searched for shaders, holding build output or VCS state
+const SKIPPED_DIRS: [&str; 2] = ["target", ".git"];
+
+/// WGSL files under the directory given to `--watch-shaders`, by file name
+///
+/// Shader file names are unique across the crate, so `fade.wgsl` finds
+/// `transitions/shaders/fade.wgsl` wherever in the tree the directory points.
+#[derive(Debug)]
+pub struct ShaderStore {
+    dir: PathBuf,
+    files: HashMap<String, PathBuf>,
+}
+
+impl ShaderStore {
+    /// Indexes every `.wgsl` file under `dir`
+    pub fn scan(dir: &Path) -> Result<Self> {
+        let dir = dir.canonicalize()?;
+        let mut files = HashMap::new();
+        collect_wgsl(&dir, &mut files)?;
+        Ok(Self { dir, files })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Current contents of the shader called `name`, or `None` to use the built-in copy
+    pub fn source(&self, name: &str) -> Option<String> {
+        let path = self.files.get(name)?;
+        fs::read_to_string(path)
+            .inspect_err(|e| warn!("Could not read {}, using the built-in shader: {e}", path.display()))
+            .ok()
+    }
+
+    /// Names of the shaders among `paths`, such as the paths of a file change event
+    pub fn changed<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> BTreeSet<String> {
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                (self.files.get(name)? == path).then(|| name.to_string())
+            })
+            .collect()
+    }
+}
+
+fn collect_wgsl(dir: &Path, files: &mut HashMap<String, PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()).map(str::to_string) else {
+            continue;
+        };
+
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_wgsl(&path, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "wgsl") {
+            if let Some(first) = files.get(&name) {
+                warn!(
+                    "Ignoring {}, {name} is already loaded from {}",
+                    path.display(),
+                    first.display()
+                );
+            } else {
+                files.insert(name, path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Loads shaders from `dir` for the rest of the process, see [`ShaderStore`]
+pub fn watch(dir: &Path) -> Result<&'static ShaderStore> {
+    let store = ShaderStore::scan(dir)?;
+    info!("Loading {} shaders from {}", store.len(), store.dir().display());
+    STORE
+        .set(store)
+        .map_err(|_| Error::Generic("Shaders are already being watched".to_string()))?;
+    active().ok_or_else(|| Error::Generic("Shader store missing after install".to_string()))
+}
+
+/// The store installed by [`watch`], if any
+pub fn active() -> Option<&'static ShaderStore> {
+    STORE.get()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    #[test]
+    fn test_scan_finds_nested_shaders() {
+        let dir = ScratchDir::new("shaders-scan");
+        fs::create_dir_all(dir.join("transitions/shaders")).unwrap();
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+        fs::write(dir.join("transitions/shaders/fade.wgsl"), "// fade").unwrap();
+        fs::write(dir.join("target/debug/stale.wgsl"), "// stale").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let store = ShaderStore::scan(&dir).unwrap();
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.source("fade.wgsl").as_deref(), Some("// fade"));
+        assert_eq!(store.source("stale.wgsl"), None);
+    }
+
+    #[test]
+    fn test_changed_maps_paths_to_shader_names() {
+        let dir = ScratchDir::new("shaders-changed");
+        fs::create_dir_all(dir.join("smoke")).unwrap();
+        fs::write(dir.join("smoke/advection.wgsl"), "").unwrap();
+        fs::write(dir.join("wipe.wgsl"), "").unwrap();
+
+        let store = ShaderStore::scan(&dir).unwrap();
+        let root = store.dir().to_path_buf();
+        let paths = [
+            root.join("smoke/advection.wgsl"),
+            root.join("smoke/advection.wgsl"),
+            root.join("wipe.wgsl~"),
+            root.join("smoke/.advection.wgsl.swp"),
+            PathBuf::from("/elsewhere/wipe.wgsl"),
+        ];
+
+        let changed = store.changed(paths.iter().map(PathBuf::as_path));
+        assert_eq!(changed, BTreeSet::from(["advection.wgsl".to_string()]));
+    }
+
+    #[test]
+    fn test_source_reads_latest_contents() {
+        let dir = ScratchDir::new("shaders-latest");
+        fs::write(dir.join("dip.wgsl"), "// one").unwrap();
+
+        let store = ShaderStore::scan(&dir).unwrap();
+        fs::write(dir.join("dip.wgsl"), "// two").unwrap();
+        assert_eq!(store.source("dip.wgsl").as_deref(), Some("// two"));
+    }
+}
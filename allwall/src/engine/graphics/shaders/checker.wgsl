@@ -0,0 +1,35 @@
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    // Cells across and down the surface
+    cells: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+const LIGHT: vec3<f32> = vec3<f32>(0.8, 0.8, 0.8);
+const DARK: vec3<f32> = vec3<f32>(0.6, 0.6, 0.6);
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    return VertexOutput(
+        vec4<f32>(in.position, 1.0),
+        in.tex_coords,
+    );
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let cell = floor(in.tex_coords * uniforms.cells);
+    let dark = (i32(cell.x) + i32(cell.y)) % 2 == 1;
+    return vec4<f32>(select(LIGHT, DARK, dark), 1.0);
+}
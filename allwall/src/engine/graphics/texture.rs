@@ -1,9 +1,15 @@
-use std::{path::Path, sync::Arc};
+use std::{borrow::Cow, path::Path, sync::Arc};
 
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView, imageops::FilterType};
 
-use super::Context;
-use crate::prelude::{Result, info};
+use super::{
+    Context,
+    mipmap::{generate_mipmaps, mip_level_count},
+};
+use crate::{
+    engine::Scaling,
+    prelude::{Result, info, warn},
+};
 
 #[derive(Clone)]
 pub struct Texture {
@@ -11,6 +17,8 @@ pub struct Texture {
     size: wgpu::Extent3d,
     view: Arc<wgpu::TextureView>,
     sampler: Arc<wgpu::Sampler>,
+    /// Whether any texel is translucent, in which case the colors are stored premultiplied
+    has_alpha: bool,
 }
 
 impl std::fmt::Debug for Texture {
@@ -25,16 +33,24 @@ impl std::fmt::Debug for Texture {
 impl Texture {
     pub fn open(path: &Path, ctx: &Context) -> Result<Self> {
         let img = image::open(path)?;
-        Ok(Self::from_image(&img, ctx))
+        Ok(Self::from_image(&img, ctx, Scaling::default(), false))
     }
 
-    pub fn from_image(img: &image::DynamicImage, ctx: &Context) -> Self {
+    /// Uploads `img`, with a full mip chain when `mipmaps` is set so large images downscale cleanly
+    pub fn from_image(img: &image::DynamicImage, ctx: &Context, scaling: Scaling, mipmaps: bool) -> Self {
         info!("Texture::from_image called");
         let device = ctx.device();
         let queue = ctx.queue();
-        let rgba = img.to_rgba8();
+        let img = fit_texture_limit(img, device.limits().max_texture_dimension_2d);
+        let mut rgba = img.to_rgba8();
         let (width, height) = img.dimensions();
 
+        // Opaque images skip the pass entirely, premultiplying them would change nothing
+        let has_alpha = img.color().has_alpha() && rgba.pixels().any(|p| p[3] < u8::MAX);
+        if has_alpha {
+            premultiply_alpha(&mut rgba);
+        }
+
         info!("Creating texture: {}x{}, RGBA format", width, height);
 
         let size = wgpu::Extent3d {
@@ -43,17 +59,19 @@ impl Texture {
             depth_or_array_layers: 1,
         };
 
+        let mip_level_count = if mipmaps { mip_level_count(width, height) } else { 1 };
+
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("wallpaper_texture"),
             size,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
             usage: wgpu::TextureUsages::TEXTURE_BINDING
                 | wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
+            view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
         });
         info!("Texture created successfully");
 
@@ -67,23 +85,17 @@ impl Texture {
             },
             size,
         );
+        generate_mipmaps(device, queue, &texture);
 
-        let view = texture.create_view(&Default::default());
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        let view = create_sampled_view(&texture, ctx.config().format);
+        let sampler = device.create_sampler(&sampler_descriptor(scaling, mipmaps));
 
         Self {
             texture: Arc::new(texture),
             size,
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            has_alpha,
         }
     }
 
@@ -111,10 +123,10 @@ impl Texture {
             dimension: wgpu::TextureDimension::D2,
             format,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
+            view_formats: &[sampled_view_format(format, ctx.config().format)],
         });
 
-        let view = texture.create_view(&Default::default());
+        let view = create_sampled_view(&texture, ctx.config().format);
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
@@ -130,6 +142,7 @@ impl Texture {
             size,
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            has_alpha: false,
         }
     }
 
@@ -151,10 +164,10 @@ impl Texture {
             usage: wgpu::TextureUsages::TEXTURE_BINDING
                 | wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
+            view_formats: &[sampled_view_format(format, ctx.config().format)],
         });
 
-        let view = texture.create_view(&Default::default());
+        let view = create_sampled_view(&texture, ctx.config().format);
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
@@ -170,6 +183,7 @@ impl Texture {
             size,
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            has_alpha: false,
         }
     }
 
@@ -185,6 +199,11 @@ impl Texture {
         &self.sampler
     }
 
+    /// Whether the texture has translucent texels, stored with premultiplied alpha
+    pub fn has_alpha(&self) -> bool {
+        self.has_alpha
+    }
+
     pub fn aspect_ratio(&self) -> f32 {
         let width = self.size.width as f32;
         let height = self.size.height as f32;
@@ -202,6 +221,7 @@ impl Texture {
             size,
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            has_alpha: false,
         }
     }
 
@@ -274,3 +294,162 @@ impl Texture {
         Ok(())
     }
 }
+
+/// Scales each texel's color by its alpha, rounding to nearest
+///
+/// Works on the sRGB-encoded bytes, which is close enough to hide the dark fringes straight
+/// alpha leaves around transparent edges, and matches what the compositor expects.
+fn premultiply_alpha(rgba: &mut image::RgbaImage) {
+    for pixel in rgba.pixels_mut() {
+        let alpha = u16::from(pixel[3]);
+        for channel in &mut pixel.0[..3] {
+            *channel = ((u16::from(*channel) * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Format sources sample a texture of `format` through when drawing to a `surface_format` surface
+///
+/// Media is uploaded as sRGB, which sampling decodes to linear. That suits sRGB surfaces,
+/// which encode what's written, but surfaces storing output as-is, such as the deep ones
+/// `hdr` picks, would show the linear values and come out too dark. Those view the texels
+/// as plain `Rgba8Unorm` instead, handing the sRGB values straight through as
+/// [`surface_rgb`](crate::sources::types::surface_rgb) does for config colors.
+fn sampled_view_format(format: wgpu::TextureFormat, surface_format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+    if format == wgpu::TextureFormat::Rgba8UnormSrgb && !surface_format.is_srgb() {
+        wgpu::TextureFormat::Rgba8Unorm
+    } else {
+        format
+    }
+}
+
+/// View of `texture` in its [`sampled_view_format`] for a `surface_format` surface
+///
+/// The texture has to list that format in its `view_formats` when it differs from its own.
+pub fn create_sampled_view(texture: &wgpu::Texture, surface_format: wgpu::TextureFormat) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        format: Some(sampled_view_format(texture.format(), surface_format)),
+        ..Default::default()
+    })
+}
+
+fn sampler_descriptor(scaling: Scaling, mipmaps: bool) -> wgpu::SamplerDescriptor<'static> {
+    let filter = match scaling {
+        Scaling::Linear => wgpu::FilterMode::Linear,
+        Scaling::Nearest => wgpu::FilterMode::Nearest,
+    };
+    // Blending between levels only matters once there are levels to blend
+    let mipmap_filter = if mipmaps {
+        wgpu::FilterMode::Linear
+    } else {
+        wgpu::FilterMode::Nearest
+    };
+
+    wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: filter,
+        min_filter: filter,
+        mipmap_filter,
+        ..Default::default()
+    }
+}
+
+/// `img`, downscaled with its aspect ratio kept when either side is over `max_dimension`,
+/// since the GPU can't create a texture that large
+fn fit_texture_limit(img: &DynamicImage, max_dimension: u32) -> Cow<'_, DynamicImage> {
+    let (width, height) = img.dimensions();
+    if width <= max_dimension && height <= max_dimension {
+        return Cow::Borrowed(img);
+    }
+
+    warn!("{width}x{height} image is over the GPU's {max_dimension}px texture limit, downscaling it");
+    Cow::Owned(img.resize(max_dimension, max_dimension, FilterType::CatmullRom))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_texture_limit_keeps_small_images() {
+        let img = DynamicImage::new_rgba8(64, 32);
+        assert!(matches!(fit_texture_limit(&img, 64), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_fit_texture_limit_downscales_keeping_aspect() {
+        let wide = DynamicImage::new_rgba8(600, 200);
+        assert_eq!(fit_texture_limit(&wide, 120).dimensions(), (120, 40));
+
+        let tall = DynamicImage::new_rgb8(90, 360);
+        assert_eq!(fit_texture_limit(&tall, 180).dimensions(), (45, 180));
+    }
+
+    #[test]
+    fn test_premultiply_alpha() {
+        let mut img = image::RgbaImage::from_raw(
+            4,
+            1,
+            vec![
+                255, 128, 0, 255, // opaque, unchanged
+                255, 128, 0, 0, // fully transparent, black
+                255, 128, 0, 128, // half transparent, halved
+                200, 100, 50, 51, // 20% opaque
+            ],
+        )
+        .unwrap();
+
+        premultiply_alpha(&mut img);
+
+        assert_eq!(
+            img.into_raw(),
+            vec![255, 128, 0, 255, 0, 0, 0, 0, 128, 64, 0, 128, 40, 20, 10, 51]
+        );
+    }
+
+    #[test]
+    fn test_sampled_view_format_decodes_srgb_only_for_srgb_surfaces() {
+        use wgpu::TextureFormat::*;
+
+        for surface in [Bgra8UnormSrgb, Rgba8UnormSrgb] {
+            assert_eq!(sampled_view_format(Rgba8UnormSrgb, surface), Rgba8UnormSrgb);
+        }
+        for surface in [Bgra8Unorm, Rgba8Unorm, Rgb10a2Unorm, Rgba16Float] {
+            assert_eq!(sampled_view_format(Rgba8UnormSrgb, surface), Rgba8Unorm);
+        }
+        // Textures already in a plain or surface format are sampled as they are
+        assert_eq!(sampled_view_format(Rgba16Float, Rgb10a2Unorm), Rgba16Float);
+        assert_eq!(sampled_view_format(R8Unorm, Rgba16Float), R8Unorm);
+        assert_eq!(sampled_view_format(Rgb10a2Unorm, Rgb10a2Unorm), Rgb10a2Unorm);
+    }
+
+    #[test]
+    fn test_sampler_descriptor_linear() {
+        let descriptor = sampler_descriptor(Scaling::Linear, false);
+        assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Linear);
+        assert_eq!(descriptor.min_filter, wgpu::FilterMode::Linear);
+    }
+
+    #[test]
+    fn test_sampler_descriptor_nearest() {
+        let descriptor = sampler_descriptor(Scaling::Nearest, false);
+        assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(descriptor.min_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(descriptor.address_mode_u, wgpu::AddressMode::ClampToEdge);
+    }
+
+    #[test]
+    fn test_sampler_descriptor_mipmaps_blend_levels() {
+        assert_eq!(
+            sampler_descriptor(Scaling::Linear, false).mipmap_filter,
+            wgpu::FilterMode::Nearest
+        );
+        assert_eq!(
+            sampler_descriptor(Scaling::Linear, true).mipmap_filter,
+            wgpu::FilterMode::Linear
+        );
+    }
+}
@@ -2,78 +2,266 @@ pub mod error;
 pub mod events;
 pub mod graphics;
 pub mod scene;
+pub mod state;
 pub mod wayland;
 
-use std::time::{Duration, Instant};
+use std::{
+    os::unix::net::UnixListener,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
 
 use calloop::{
-    Interest, Mode, PostAction,
+    Interest, LoopHandle, LoopSignal, Mode, PostAction, RegistrationToken,
     generic::Generic,
     timer::{TimeoutAction, Timer},
 };
-pub use graphics::{Context, GpuContext, RenderSurface, Texture};
+pub use graphics::{Context, FrameStats, GpuContext, RenderSurface, Texture};
 use scene::Scene;
 use smithay_client_toolkit::{
     compositor::CompositorState,
     output::OutputState,
-    reexports::client::{self, Connection, globals::registry_queue_init},
+    reexports::{
+        client::{self, Connection, EventQueue, globals::registry_queue_init},
+        protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+    },
     registry::RegistryState,
+    seat::SeatState,
     shell::wlr_layer::LayerShell,
 };
+use state::SavedState;
+use wayland::{PointerTracker, WindowTracker};
 
 use crate::{
-    cli::ipc::protocol::socket_path,
-    config::AppConfig,
+    cli::ipc::protocol::bind_socket,
+    config::{AppConfig, ScheduleEntry, SourceConfigs, TimeOfDay, active_entry, until_next},
     engine::error::EngineError,
-    prelude::{Result, error, info},
+    prelude::{Result, debug, error, info, warn},
     sources::{InteractionState, SourceKind},
 };
-pub use scene::{Fit, Layout, MonitorsSpec, SceneConfig};
+pub use scene::{
+    DEFAULT_FALLBACK_COLOR, DEFAULT_LETTERBOX_BLUR, DEFAULT_SMART_MAX_CROP, Fit, Layout, LetterboxStyle, MonitorsSpec,
+    OffsetMode, Placement, RotationOrder, Scaling, SceneConfig, Upscale,
+};
+
+/// Slowest idle rate allowed, so a zero `idle_fps` can't stall the frame timer forever
+const MIN_IDLE_FPS: f32 = 0.1;
+
+/// Longest step animations advance in one frame, so a stalled loop doesn't skip a transition
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
+
+/// Longest the schedule timer sleeps before checking the wall clock again
+///
+/// Event loop timers run on the monotonic clock, which stops while the machine is
+/// suspended, so a switch due during suspend would otherwise wait out its whole delay again.
+const SCHEDULE_POLL: Duration = Duration::from_secs(60);
+
+/// How long the schedule timer should sleep when the next switch is `due` and the wall
+/// clock reads `now`, or `None` once it's time to switch
+fn schedule_wait(due: SystemTime, now: SystemTime) -> Option<Duration> {
+    due.duration_since(now)
+        .ok()
+        .filter(|remaining| !remaining.is_zero())
+        .map(|remaining| remaining.min(SCHEDULE_POLL))
+}
+
+/// Time to advance animations by, given the real time since the last frame
+fn frame_delta(elapsed: Duration) -> Duration {
+    elapsed.min(MAX_FRAME_DELTA)
+}
+
+/// Framerate a scene renders at, its own `fps` when set and otherwise the global one
+fn effective_fps(scene: Option<u32>, global: f32) -> f32 {
+    scene.filter(|&fps| fps > 0).map_or(global, |fps| fps as f32)
+}
+
+/// Whether a scene at `fps` should render, `since_render` after its last frame
+///
+/// The frame timer ticks at the fastest scene's rate, so slower scenes skip ticks. Half a
+/// `tick` of slack keeps timer jitter from pushing a due frame to the tick after.
+fn frame_due(since_render: Duration, tick: Duration, fps: f32) -> bool {
+    since_render + tick / 2 >= Duration::from_secs_f32(1.0 / fps)
+}
+
+/// Framerate for the next frame: the full rate while anything animates, otherwise the idle rate
+fn frame_fps(fps: f32, idle_fps: f32, needs_redraw: bool) -> f32 {
+    if needs_redraw {
+        fps
+    } else {
+        idle_fps.max(MIN_IDLE_FPS).min(fps)
+    }
+}
+
+/// Delays between Wayland connection attempts, doubling from `base`
+///
+/// Yields one delay per retry, so `attempts` connection attempts wait `attempts - 1` times.
+pub fn backoff_schedule(attempts: u32, base: Duration) -> impl Iterator<Item = Duration> {
+    (0..attempts.saturating_sub(1)).map(move |retry| base.saturating_mul(2u32.saturating_pow(retry)))
+}
+
+/// Wayland connection with the protocols the engine binds at startup
+struct WaylandSession {
+    conn: Connection,
+    queue: EventQueue<Engine>,
+    registry_state: RegistryState,
+    compositor_state: CompositorState,
+    output_state: OutputState,
+    seat_state: SeatState,
+    layer_shell: LayerShell,
+    /// Present when window tracking was requested and the compositor supports it
+    toplevel_manager: Option<ZwlrForeignToplevelManagerV1>,
+}
+
+impl WaylandSession {
+    fn connect(track_windows: bool) -> std::result::Result<Self, EngineError> {
+        let conn = Connection::connect_to_env().map_err(|e| EngineError::WaylandConnect(e.to_string()))?;
+        let (globals, queue) =
+            registry_queue_init::<Engine>(&conn).map_err(|e| EngineError::WaylandRegistry(e.to_string()))?;
+        let qh = queue.handle();
+
+        let toplevel_manager = if track_windows {
+            globals
+                .bind::<ZwlrForeignToplevelManagerV1, _, _>(&qh, 1..=3, ())
+                .inspect_err(|e| warn!("Window tracking unavailable, pause_on_fullscreen has no effect: {e}"))
+                .ok()
+        } else {
+            None
+        };
+
+        Ok(Self {
+            toplevel_manager,
+            registry_state: RegistryState::new(&globals),
+            compositor_state: CompositorState::bind(&globals, &qh).map_err(|_| EngineError::NoCompositor)?,
+            output_state: OutputState::new(&globals, &qh),
+            seat_state: SeatState::new(&globals, &qh),
+            layer_shell: LayerShell::bind(&globals, &qh).map_err(|_| EngineError::NoLayerShell {
+                desktop: std::env::var("XDG_CURRENT_DESKTOP").ok(),
+            })?,
+            conn,
+            queue,
+        })
+    }
+
+    /// Connects, retrying with exponential backoff while the compositor isn't ready yet
+    fn connect_with_retry(
+        attempts: u32,
+        base: Duration,
+        track_windows: bool,
+    ) -> std::result::Result<Self, EngineError> {
+        let mut delays = backoff_schedule(attempts, base);
+        loop {
+            match Self::connect(track_windows) {
+                Ok(session) => return Ok(session),
+                // The compositor is up but will never offer layer shell, so waiting won't help
+                Err(e @ EngineError::NoLayerShell { .. }) => return Err(e),
+                Err(e) => match delays.next() {
+                    Some(delay) => {
+                        warn!("Wayland not ready ({e}), retrying in {delay:?}");
+                        std::thread::sleep(delay);
+                    },
+                    None => {
+                        return Err(match e {
+                            EngineError::WaylandConnect(_) => e,
+                            other => EngineError::WaylandConnect(other.to_string()),
+                        });
+                    },
+                },
+            }
+        }
+    }
+}
 
 pub struct Engine {
     pub registry_state: RegistryState,
     pub output_state: OutputState,
+    pub seat_state: SeatState,
     pub compositor_state: CompositorState,
     pub layer_shell: LayerShell,
     pub conn: Connection,
     pub gpu: std::sync::Arc<GpuContext>,
     pub scenes: Vec<Scene>,
     pub fps: f32,
+    pub idle_fps: f32,
+    pub restore_last: bool,
     pub source_kind: SourceKind,
     pub interaction_state: InteractionState,
+    pub interactive: bool,
+    pub pointer: PointerTracker,
+    pub windows: WindowTracker,
+    /// Scenes hidden behind fullscreen windows at the last check, indexed like `scenes`
+    suspended: Vec<bool>,
     pub qh: client::QueueHandle<Engine>,
+    loop_handle: LoopHandle<'static, Engine>,
+    /// Stops the event loop when asked to quit over IPC
+    loop_signal: LoopSignal,
+    frame_timer: Option<RegistrationToken>,
+    last_frame: Instant,
+    /// Draw the frame-time overlay, fed from `frame_stats`
+    show_overlay: bool,
+    frame_stats: FrameStats,
+    idle: bool,
+    /// IPC socket the daemon listens on, removed again on shutdown, or `None` with IPC disabled
+    socket: Option<PathBuf>,
+    /// Watches the `--watch-shaders` directory, stopping when dropped
+    #[cfg(feature = "dev")]
+    shader_watcher: Option<notify::RecommendedWatcher>,
 }
 
 impl Engine {
-    pub fn run(config: AppConfig, source_kind: SourceKind) -> Result<()> {
+    pub fn run(config: AppConfig, source_kind: SourceKind, socket: Option<PathBuf>) -> Result<()> {
         let total_start = Instant::now();
         info!("Starting Allwall...");
 
+        // Checked before anything else, so a second instance bows out without touching
+        // Wayland, the GPU or any media
+        let listener = match setup_ipc(socket.as_deref())? {
+            Ipc::Disabled => {
+                info!("IPC disabled, commands like `allwall next` won't reach this daemon");
+                None
+            },
+            Ipc::Taken => {
+                warn!("allwall already running");
+                return Ok(());
+            },
+            Ipc::Listening(listener) => Some(listener),
+        };
+
         let fps = config.general.fps;
-        let smoke_config = config.smoke.clone();
+        let source_configs = config.source_configs();
 
         let start = Instant::now();
         info!("Connecting to Wayland...");
-        let conn = Connection::connect_to_env().map_err(|e| EngineError::WaylandConnect(e.to_string()))?;
-        let (globals, queue) =
-            registry_queue_init::<Engine>(&conn).map_err(|e| EngineError::WaylandRegistry(e.to_string()))?;
+        let WaylandSession {
+            conn,
+            queue,
+            registry_state,
+            compositor_state,
+            output_state,
+            seat_state,
+            layer_shell,
+            toplevel_manager,
+        } = WaylandSession::connect_with_retry(
+            config.general.connect_attempts,
+            config.general.connect_delay(),
+            config.general.pause_on_fullscreen,
+        )?;
         let qh = queue.handle();
-        info!("Wayland connected in {:?}", start.elapsed());
+        info!("Wayland connected and protocols bound in {:?}", start.elapsed());
 
         let start = Instant::now();
-        let registry_state = RegistryState::new(&globals);
-        let compositor_state = CompositorState::bind(&globals, &qh).map_err(|_| EngineError::NoCompositor)?;
-        let output_state = OutputState::new(&globals, &qh);
-        let layer_shell = LayerShell::bind(&globals, &qh).map_err(|_| EngineError::NoLayerShell)?;
-        info!("Wayland protocols bound in {:?}", start.elapsed());
-
-        let start = Instant::now();
-        let gpu = pollster::block_on(GpuContext::new())?;
+        let gpu = pollster::block_on(GpuContext::new(&config.general))?;
         info!("WGPU context created in {:?}", start.elapsed());
 
         let start = Instant::now();
         let gpu = std::sync::Arc::new(gpu);
-        let scenes = create_scenes(&config, source_kind, smoke_config);
+        let mut scenes = create_scenes(&config, source_kind, source_configs);
+        apply_schedule(&mut scenes, &config.schedule, TimeOfDay::now());
+        if config.general.restore_last {
+            let saved = SavedState::load();
+            for (index, scene) in scenes.iter_mut().enumerate() {
+                scene.restore_media(saved.scene(index));
+            }
+        }
         info!("Scenes created in {:?}", start.elapsed());
 
         if scenes.is_empty() {
@@ -89,73 +277,115 @@ impl Engine {
             conn,
             registry_state,
             output_state,
+            seat_state,
             compositor_state,
             layer_shell,
             gpu,
             scenes,
             fps: fps as f32,
+            idle_fps: config.general.idle_fps as f32,
+            restore_last: config.general.restore_last,
             source_kind,
             interaction_state: InteractionState::default(),
+            interactive: config.general.interactive,
+            pointer: PointerTracker::new(),
+            windows: WindowTracker::new(toplevel_manager),
+            suspended: Vec::new(),
             qh,
+            loop_handle: event_loop_handler.clone(),
+            loop_signal: event_loop.get_signal(),
+            frame_timer: None,
+            last_frame: Instant::now(),
+            show_overlay: false,
+            frame_stats: FrameStats::default(),
+            idle: false,
+            socket,
+            #[cfg(feature = "dev")]
+            shader_watcher: None,
         };
 
         info!("Engine initialized in {:?}", engine_init_start.elapsed());
         info!("Total startup time: {:?}", total_start.elapsed());
 
-        let socket = socket_path();
-        if let Some(parent) = socket.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        let listener = std::os::unix::net::UnixListener::bind(&socket)?;
-        listener.set_nonblocking(true)?;
-        info!("IPC socket listening at {}", socket.display());
-
-        let _ = event_loop_handler.insert_source(
-            Generic::new(listener, Interest::READ, Mode::Level),
-            |_readiness, listener, engine| {
-                if let Ok((client, _)) = listener.accept()
-                    && let Err(e) = engine.handle_ipc_client(client)
-                {
-                    error!("IPC client error: {e}");
-                }
-                Ok(PostAction::Continue)
-            },
-        );
+        if let Some(listener) = listener {
+            let _ = event_loop_handler.insert_source(
+                Generic::new(listener, Interest::READ, Mode::Level),
+                |_readiness, listener, engine| {
+                    if let Ok((client, _)) = listener.accept()
+                        && let Err(e) = engine.handle_ipc_client(client)
+                    {
+                        error!("IPC client error: {e}");
+                    }
+                    Ok(PostAction::Continue)
+                },
+            );
+        }
 
-        let frame_fps = engine.fps;
-        let _ = event_loop_handler.insert_source(
-            Timer::from_duration(Duration::from_secs_f32(1.0 / frame_fps)),
-            move |_, _, engine| {
-                let dt = Duration::from_secs_f32(1.0 / engine.fps);
-                for scene in &mut engine.scenes {
-                    scene.update(dt);
-                    scene.render(&engine.interaction_state);
-                }
-                TimeoutAction::ToDuration(Duration::from_secs_f32(1.0 / engine.fps))
-            },
-        );
+        #[cfg(feature = "dev")]
+        if let Some(store) = graphics::shader_store::active() {
+            engine.shader_watcher = Some(watch_shaders(store, &event_loop_handler)?);
+        }
+
+        engine.schedule_frames(Duration::from_secs_f32(1.0 / engine.fps));
 
         for scene_idx in 0..engine.scenes.len() {
             if engine.scenes[scene_idx].is_media() {
-                let rotation_interval = engine.scenes[scene_idx].rotation_interval();
-                let _ =
-                    event_loop_handler.insert_source(Timer::from_duration(rotation_interval), move |_, _, engine| {
-                        if let Some(scene) = engine.scenes.get_mut(scene_idx) {
-                            if let Err(e) = scene.advance_source() {
+                // Each scene samples its own next deadline so jittered scenes drift apart
+                let first_rotation = engine.scenes[scene_idx].schedule_rotation();
+                let _ = event_loop_handler.insert_source(Timer::from_duration(first_rotation), move |_, _, engine| {
+                    let Some(scene) = engine.scenes.get_mut(scene_idx) else {
+                        return TimeoutAction::Drop;
+                    };
+                    let due = scene.take_due_rotations();
+                    if engine.suspended.get(scene_idx).copied().unwrap_or(false) {
+                        debug!("Scene {scene_idx} is hidden by a fullscreen window, skipping rotation");
+                    } else {
+                        for monitor in due {
+                            if let Err(e) = scene.advance_source(monitor.as_deref()) {
                                 error!("Could not advance source: {e}");
                             }
                         }
-                        TimeoutAction::ToDuration(rotation_interval)
-                    });
+                    }
+                    let next_rotation = scene.rotation_remaining().unwrap_or_default();
+                    engine.save_state();
+                    engine.wake();
+                    TimeoutAction::ToDuration(next_rotation)
+                });
             }
         }
 
+        let schedule = config.schedule.clone();
+        if engine.scenes.iter().any(Scene::is_media)
+            && let Some(first_switch) = until_next(&schedule, TimeOfDay::now())
+        {
+            let mut due = SystemTime::now() + first_switch;
+            let first_wait = first_switch.min(SCHEDULE_POLL);
+            let _ = event_loop_handler.insert_source(Timer::from_duration(first_wait), move |_, _, engine| {
+                if let Some(wait) = schedule_wait(due, SystemTime::now()) {
+                    return TimeoutAction::ToDuration(wait);
+                }
+                let now = TimeOfDay::now();
+                apply_schedule(&mut engine.scenes, &schedule, now);
+                engine.save_state();
+                engine.wake();
+                match until_next(&schedule, now) {
+                    Some(next_switch) => {
+                        due = SystemTime::now() + next_switch;
+                        TimeoutAction::ToDuration(next_switch.min(SCHEDULE_POLL))
+                    },
+                    None => TimeoutAction::Drop,
+                }
+            });
+        }
+
         ctrlc::set_handler({
             let loop_signal = event_loop.get_signal();
-            let socket = socket_path();
+            let socket = engine.socket.clone();
             move || {
                 info!("SIGTERM/SIGINT/SIGHUP received, exiting");
-                let _ = std::fs::remove_file(&socket);
+                if let Some(socket) = &socket {
+                    let _ = std::fs::remove_file(socket);
+                }
                 loop_signal.stop();
                 loop_signal.wakeup();
             }
@@ -168,22 +398,253 @@ impl Engine {
 
         info!("Starting event loop - outputs will be handled via Wayland events");
         event_loop.run(None, &mut engine, |_| ())?;
+        engine.save_state();
 
         Ok(())
     }
+
+    /// Records what each scene is showing so the next start can pick up from it
+    pub fn save_state(&self) {
+        if !self.restore_last {
+            return;
+        }
+
+        let state = SavedState {
+            scenes: self.scenes.iter().map(Scene::saved_media).collect(),
+        };
+        if let Err(e) = state.save() {
+            warn!("Could not save state: {e}");
+        }
+    }
+
+    fn on_frame(&mut self) -> TimeoutAction {
+        let now = Instant::now();
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+
+        if self.show_overlay {
+            self.frame_stats.push(elapsed);
+            let summary = self.frame_stats.summary();
+            for scene in &mut self.scenes {
+                scene.set_overlay_text(&summary);
+            }
+        }
+
+        self.suspended = self.suspended_scenes();
+        for (scene, _) in self.scenes.iter_mut().zip(&self.suspended).filter(|(_, hidden)| !**hidden) {
+            if let Some(dt) = scene.tick(elapsed, self.fps) {
+                scene.update(dt);
+                scene.render(&self.interaction_state);
+            }
+        }
+
+        let visible = || self.scenes.iter().zip(&self.suspended).filter(|(_, hidden)| !**hidden);
+        let needs_redraw = visible().any(|(scene, _)| scene.needs_redraw());
+        let fps = visible()
+            .map(|(scene, _)| scene.fps(self.fps))
+            .reduce(f32::max)
+            .unwrap_or(self.fps);
+        self.idle = !needs_redraw;
+        TimeoutAction::ToDuration(Duration::from_secs_f32(
+            1.0 / frame_fps(fps, self.idle_fps, needs_redraw),
+        ))
+    }
+
+    /// Which scenes have every output hidden behind a fullscreen or maximized window
+    fn suspended_scenes(&self) -> Vec<bool> {
+        self.scenes
+            .iter()
+            .map(|scene| {
+                self.windows.is_active()
+                    && scene.has_outputs()
+                    && scene.wl_outputs().all(|output| self.windows.output_covered(output))
+            })
+            .collect()
+    }
+
+    /// Re-checks which scenes are hidden after a window changed, resuming rendering for uncovered ones
+    pub fn on_windows_changed(&mut self) {
+        let suspended = self.suspended_scenes();
+        if suspended != self.suspended {
+            info!("Scenes hidden by fullscreen windows: {suspended:?}");
+            self.suspended = suspended;
+            self.wake();
+        }
+    }
+
+    fn schedule_frames(&mut self, delay: Duration) {
+        self.frame_timer = self
+            .loop_handle
+            .insert_source(Timer::from_duration(delay), |_, _, engine| engine.on_frame())
+            .ok();
+    }
+
+    /// Renders a frame right away if the frame timer has backed off to the idle rate
+    ///
+    /// Call after anything that can start an animation, such as a source change
+    /// or a new output, so it doesn't wait out an idle interval.
+    pub fn wake(&mut self) {
+        if !self.idle {
+            return;
+        }
+
+        if let Some(token) = self.frame_timer.take() {
+            self.loop_handle.remove(token);
+        }
+        self.idle = false;
+        // Whatever just started shouldn't be advanced by the idle interval
+        self.last_frame = Instant::now();
+        for scene in &mut self.scenes {
+            scene.restart_clock();
+        }
+        self.schedule_frames(Duration::ZERO);
+    }
+
+    /// Rebuilds every scene's sources so pipelines pick up edited shaders
+    ///
+    /// Transitions build their pipelines as they start, so the next one uses the new source anyway.
+    #[cfg(feature = "dev")]
+    fn reload_shaders(&mut self, changed: &std::collections::BTreeSet<String>) {
+        info!("Shaders changed: {changed:?}, rebuilding sources");
+        for scene in &mut self.scenes {
+            if let Err(e) = scene.reload_sources() {
+                error!("Could not rebuild sources after a shader change: {e}");
+            }
+        }
+        self.wake();
+    }
+
+    /// Removes the IPC socket and stops the event loop, like the signal handler does
+    pub fn quit(&self) {
+        info!("Quit requested over IPC, exiting");
+        if let Some(socket) = &self.socket {
+            let _ = std::fs::remove_file(socket);
+        }
+        self.loop_signal.stop();
+        self.loop_signal.wakeup();
+    }
+
+    /// Renders the first scene once into an offscreen texture of `size`, without connecting to Wayland
+    pub fn render_once(config: AppConfig, source_kind: SourceKind, size: (u32, u32)) -> Result<image::RgbaImage> {
+        let gpu = std::sync::Arc::new(pollster::block_on(GpuContext::new(&config.general))?);
+        let ctx = Context::headless(gpu, size);
+
+        let scene = create_scenes(&config, source_kind, config.source_configs())
+            .into_iter()
+            .next()
+            .ok_or(EngineError::NoScenes)?;
+
+        scene.render_once(&ctx)?;
+        ctx.read_pixels()
+    }
+
+    /// Times `frames` frames of the first scene drawn into an offscreen texture of `size`,
+    /// without connecting to Wayland
+    pub fn bench(config: AppConfig, source_kind: SourceKind, size: (u32, u32), frames: usize) -> Result<Vec<Duration>> {
+        let gpu = std::sync::Arc::new(pollster::block_on(GpuContext::new(&config.general))?);
+        let ctx = Context::headless(gpu, size);
+
+        let scene = create_scenes(&config, source_kind, config.source_configs())
+            .into_iter()
+            .next()
+            .ok_or(EngineError::NoScenes)?;
+
+        scene.bench(&ctx, frames)
+    }
 }
 
-fn create_scenes(config: &AppConfig, source_kind: SourceKind, smoke_config: crate::config::SmokeConfig) -> Vec<Scene> {
+/// Reloads the scenes whenever a shader under the store's directory changes
+#[cfg(feature = "dev")]
+fn watch_shaders(
+    store: &'static graphics::shader_store::ShaderStore,
+    handle: &LoopHandle<'static, Engine>,
+) -> Result<notify::RecommendedWatcher> {
+    use notify::Watcher;
+
+    let (sender, receiver) = calloop::channel::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = sender.send(event);
+    })
+    .map_err(|e| EngineError::ShaderWatch(e.to_string()))?;
+    watcher
+        .watch(store.dir(), notify::RecursiveMode::Recursive)
+        .map_err(|e| EngineError::ShaderWatch(e.to_string()))?;
+
+    handle
+        .insert_source(receiver, move |event, _, engine| {
+            let calloop::channel::Event::Msg(event) = event else {
+                return;
+            };
+            match event {
+                Ok(event) if event.kind.is_create() || event.kind.is_modify() => {
+                    let changed = store.changed(event.paths.iter().map(PathBuf::as_path));
+                    if !changed.is_empty() {
+                        engine.reload_shaders(&changed);
+                    }
+                },
+                Ok(_) => {},
+                Err(e) => warn!("Shader watcher error: {e}"),
+            }
+        })
+        .map_err(|e| EngineError::ShaderWatch(e.error.to_string()))?;
+
+    info!("Watching {} for shader changes", store.dir().display());
+    Ok(watcher)
+}
+
+/// Points each media scene at the path its schedule has in effect at `now`, leaving
+/// scenes that already show it alone
+fn apply_schedule(scenes: &mut [Scene], schedule: &[ScheduleEntry], now: TimeOfDay) {
+    for (index, scene) in scenes.iter_mut().enumerate().filter(|(_, scene)| scene.is_media()) {
+        let Some(entry) = active_entry(schedule.iter().filter(|entry| entry.applies_to(index)), now) else {
+            continue;
+        };
+        if scene.config().path.as_ref() == Some(&entry.path) {
+            continue;
+        }
+
+        info!(
+            "Schedule switches scene {index} to {} from {}",
+            entry.path.display(),
+            entry.at
+        );
+        if let Err(e) = scene.set_path(entry.path.clone()) {
+            error!("Could not switch scene {index} to {}: {e}", entry.path.display());
+        }
+    }
+}
+
+fn create_scenes(config: &AppConfig, source_kind: SourceKind, source_configs: SourceConfigs) -> Vec<Scene> {
     if config.scenes.is_empty() {
         let scene_config = crate::config::MergedSceneConfig {
             path: None,
             layout: Default::default(),
+            independent_offset: Default::default(),
             fit: Default::default(),
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Default::default(),
+            blur: 0.0,
+            letterbox: Default::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Default::default(),
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: Default::default(),
+            recursive: false,
+            fps: None,
+            placement: Default::default(),
             monitors: Default::default(),
             transition: config.transition.clone(),
         };
         info!("Creating default scene (matches all monitors)");
-        return vec![Scene::new(scene_config, source_kind, smoke_config)];
+        return vec![Scene::new(
+            scene_config,
+            source_kind,
+            source_configs,
+            config.general.interactive,
+            config.general.startup_transition,
+        )];
     }
 
     config
@@ -200,7 +661,157 @@ fn create_scenes(config: &AppConfig, source_kind: SourceKind, smoke_config: crat
                     .map(|m| m.iter().map(|h| h.name()).collect::<Vec<_>>())
                     .unwrap_or_else(|| vec!["*"])
             );
-            Scene::new(scene_config.clone(), source_kind, smoke_config.clone())
+            Scene::new(
+                scene_config.clone(),
+                source_kind,
+                source_configs.clone(),
+                config.general.interactive,
+                config.general.startup_transition,
+            )
         })
         .collect()
 }
+
+/// Where the daemon takes IPC clients from
+enum Ipc {
+    /// Turned off in the config or with `--no-ipc`
+    Disabled,
+    /// A live daemon already owns the socket
+    Taken,
+    Listening(UnixListener),
+}
+
+/// Binds the IPC socket at `socket`, or leaves IPC out when it's `None`
+fn setup_ipc(socket: Option<&Path>) -> Result<Ipc> {
+    let Some(socket) = socket else {
+        return Ok(Ipc::Disabled);
+    };
+
+    if let Some(parent) = socket.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Some(listener) = bind_socket(socket)? else {
+        return Ok(Ipc::Taken);
+    };
+    listener.set_nonblocking(true)?;
+    info!("IPC socket listening at {}", socket.display());
+
+    Ok(Ipc::Listening(listener))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    #[test]
+    fn test_setup_ipc_disabled_binds_nothing() {
+        assert!(matches!(setup_ipc(None).unwrap(), Ipc::Disabled));
+    }
+
+    #[test]
+    fn test_setup_ipc_listens_on_socket() {
+        let dir = ScratchDir::new("engine-ipc");
+        let socket = dir.join("allwall.sock");
+
+        assert!(matches!(setup_ipc(Some(&socket)).unwrap(), Ipc::Listening(_)));
+        assert!(socket.exists());
+    }
+
+    #[test]
+    fn test_schedule_wait_polls_until_due() {
+        let now = SystemTime::now();
+        assert_eq!(
+            schedule_wait(now + Duration::from_secs(10), now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(schedule_wait(now + Duration::from_secs(3600), now), Some(SCHEDULE_POLL));
+        assert_eq!(schedule_wait(now, now), None);
+    }
+
+    #[test]
+    fn test_schedule_wait_catches_up_after_suspend() {
+        let armed = SystemTime::now();
+        let due = armed + Duration::from_secs(3600);
+        // The wall clock jumped past the switch while the monotonic timer was stopped
+        assert_eq!(schedule_wait(due, armed + Duration::from_secs(8 * 3600)), None);
+    }
+
+    #[test]
+    fn test_frame_fps_full_rate_while_animating() {
+        assert_eq!(frame_fps(60.0, 1.0, true), 60.0);
+    }
+
+    #[test]
+    fn test_frame_fps_idle_rate_when_settled() {
+        assert_eq!(frame_fps(60.0, 1.0, false), 1.0);
+    }
+
+    #[test]
+    fn test_frame_fps_idle_never_above_full_rate() {
+        assert_eq!(frame_fps(30.0, 120.0, false), 30.0);
+    }
+
+    #[test]
+    fn test_effective_fps_prefers_scene_override() {
+        assert_eq!(effective_fps(Some(15), 60.0), 15.0);
+        assert_eq!(effective_fps(Some(144), 60.0), 144.0);
+        assert_eq!(effective_fps(None, 60.0), 60.0);
+        assert_eq!(effective_fps(Some(0), 30.0), 30.0);
+    }
+
+    #[test]
+    fn test_frame_due_skips_ticks_for_slower_scenes() {
+        let tick = Duration::from_secs_f32(1.0 / 60.0);
+        assert!(!frame_due(tick, tick, 30.0));
+        assert!(frame_due(tick * 2, tick, 30.0));
+        assert!(frame_due(tick, tick, 60.0));
+    }
+
+    #[test]
+    fn test_frame_due_tolerates_early_ticks() {
+        let tick = Duration::from_millis(16);
+        assert!(frame_due(Duration::from_millis(31), tick, 30.0));
+        assert!(!frame_due(Duration::from_millis(20), tick, 30.0));
+    }
+
+    #[test]
+    fn test_frame_delta_passes_through_normal_frames() {
+        assert_eq!(frame_delta(Duration::from_millis(16)), Duration::from_millis(16));
+        assert_eq!(frame_delta(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_frame_delta_clamps_stalls() {
+        assert_eq!(frame_delta(MAX_FRAME_DELTA), MAX_FRAME_DELTA);
+        assert_eq!(frame_delta(Duration::from_secs(3)), MAX_FRAME_DELTA);
+    }
+
+    #[test]
+    fn test_frame_fps_idle_has_floor() {
+        assert_eq!(frame_fps(30.0, 0.0, false), MIN_IDLE_FPS);
+    }
+
+    #[test]
+    fn test_backoff_schedule_doubles_from_base() {
+        let delays: Vec<_> = backoff_schedule(6, Duration::from_millis(100)).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1600].map(Duration::from_millis).to_vec(),
+            "six attempts wait five times, up to 1.6s"
+        );
+    }
+
+    #[test]
+    fn test_backoff_schedule_single_attempt_never_waits() {
+        assert_eq!(backoff_schedule(1, Duration::from_millis(100)).count(), 0);
+        assert_eq!(backoff_schedule(0, Duration::from_millis(100)).count(), 0);
+    }
+
+    #[test]
+    fn test_backoff_schedule_saturates() {
+        let last = backoff_schedule(40, Duration::from_secs(1)).last().unwrap();
+        assert_eq!(last, Duration::from_secs(1).saturating_mul(u32::MAX));
+    }
+}
@@ -1,18 +1,30 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use clap::ValueEnum;
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use smithay_client_toolkit::shell::wlr_layer::{Anchor, Layer};
 
 use super::monitor::MonitorsSpec;
-use crate::transitions::config::TransitionConfig;
+use crate::{config::error::ConfigError, sources::media::remote, transitions::config::TransitionConfig};
+
+/// Opaque black, shown by media scenes whose directory has nothing to display
+pub const DEFAULT_FALLBACK_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// Share of an image the `smart` fit may crop away before it letterboxes instead
+pub const DEFAULT_SMART_MAX_CROP: f32 = 0.2;
+
+/// Blur radius in pixels behind a `contain` image under the `blur` letterbox
+pub const DEFAULT_LETTERBOX_BLUR: f32 = 40.0;
 
 /// Monitor layout strategy for multi-monitor setups
 ///
 /// Determines how wallpaper content is distributed across monitors
 /// in a scene. This affects rendering efficiency and visual consistency.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[clap(rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
 pub enum Layout {
     /// Mirror content to all monitors (most efficient)
@@ -26,7 +38,8 @@ pub enum Layout {
 
     /// Each monitor has its own wallpaper instance
     ///
-    /// Each monitor gets its own random selection from the source path.
+    /// Each monitor gets its own random selection from the source path,
+    /// or from its own path when `monitors` maps monitors to paths.
     /// Transitions occur independently per monitor.
     Independent,
 
@@ -37,12 +50,34 @@ pub enum Layout {
     Span,
 }
 
+/// How monitors in an independent layout space out their rotations
+///
+/// Only the first rotation of each monitor is offset, later ones follow its own interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum OffsetMode {
+    /// Every monitor rotates at the same time
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    None,
+
+    /// Monitors take turns, spread evenly across the rotation interval
+    ///
+    /// With two monitors the second changes halfway between changes of the first.
+    Even,
+
+    /// Each monitor starts its cycle at a random point in the interval
+    Random,
+}
+
 /// How wallpaper content fits within monitor bounds
 ///
 /// Controls the scaling and positioning behavior when the wallpaper
 /// aspect ratio doesn't match the monitor's aspect ratio.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[clap(rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
 pub enum Fit {
     /// Stretch to fill entire area
@@ -73,6 +108,185 @@ pub enum Fit {
     ///
     /// Best for small repeating patterns or textures.
     Tile,
+
+    /// Cover when the aspect ratios are close, contain when they're far apart
+    ///
+    /// Chosen per image, so a mixed collection fills the screen without cutting
+    /// portraits in half. `smart_max_crop` sets where cover gives way to contain.
+    Smart,
+}
+
+/// What fills the bars left around an image under the `contain` fit
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum LetterboxStyle {
+    /// Fill bars with a fixed color, as sRGB plus alpha (0.0 - 1.0)
+    Color([f32; 4]),
+
+    /// Stretch the image's outermost pixels across the bars
+    EdgeExtend,
+
+    /// Fill the whole monitor with a blurred, cover-scaled copy of the image behind it
+    ///
+    /// `letterbox_blur` sets the blur radius.
+    Blur,
+}
+
+impl Default for LetterboxStyle {
+    fn default() -> Self {
+        Self::Color([0.0, 0.0, 0.0, 1.0])
+    }
+}
+
+/// How wallpaper pixels are filtered when scaled to the monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum Scaling {
+    /// Smoothly interpolate between pixels
+    ///
+    /// Best for photos and most artwork.
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    Linear,
+
+    /// Use the closest pixel without interpolation
+    ///
+    /// Keeps pixel art crisp when upscaled.
+    Nearest,
+}
+
+/// Filter used when a still wallpaper is drawn larger than its source pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum Upscale {
+    /// Hardware bilinear filtering
+    ///
+    /// Cheapest option, but small images look soft on large monitors.
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    Linear,
+
+    /// Catmull-Rom bicubic filtering over a 4x4 texel neighbourhood
+    ///
+    /// Sharper edges on low-resolution wallpapers at the cost of 16 samples per pixel.
+    Bicubic,
+}
+
+/// Order a media scene walks through the files of its directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum RotationOrder {
+    /// Pick a random file, avoiding the last few shown
+    ///
+    /// Over a long session some files can still come up more often than others.
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    Random,
+
+    /// Shuffle the directory and show every file once before reshuffling
+    Album,
+}
+
+/// Compositor layer the wallpaper surface is placed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+pub enum SurfaceLayer {
+    /// Below everything, as a wallpaper
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    Background,
+
+    /// Above the wallpaper but below regular windows
+    Bottom,
+
+    /// Above regular windows, for small overlays
+    Top,
+}
+
+impl From<SurfaceLayer> for Layer {
+    fn from(layer: SurfaceLayer) -> Self {
+        match layer {
+            SurfaceLayer::Background => Layer::Background,
+            SurfaceLayer::Bottom => Layer::Bottom,
+            SurfaceLayer::Top => Layer::Top,
+        }
+    }
+}
+
+/// Output edge a sized surface is pinned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Layer-shell anchor flags for `edges`, centering the surface when there are none
+pub fn anchor_flags(edges: &[Edge]) -> Anchor {
+    edges.iter().fold(Anchor::empty(), |anchor, edge| {
+        anchor
+            | match edge {
+                Edge::Top => Anchor::TOP,
+                Edge::Bottom => Anchor::BOTTOM,
+                Edge::Left => Anchor::LEFT,
+                Edge::Right => Anchor::RIGHT,
+            }
+    })
+}
+
+/// Where a scene's surface sits on each output
+///
+/// By default it covers the whole output on the background layer. Setting `size`
+/// draws a smaller surface instead, pinned to the `anchor` edges.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema))]
+pub struct Placement {
+    /// Layer to draw on, `"background"`, `"bottom"` or `"top"`
+    #[serde(default)]
+    pub layer: SurfaceLayer,
+
+    /// Edges to pin a sized surface to, such as `["bottom", "right"]` for the bottom-right corner
+    ///
+    /// Only used together with `size`. An empty list centers the surface.
+    #[serde(default)]
+    pub anchor: Vec<Edge>,
+
+    /// Surface width and height in logical pixels, or unset to cover the whole output
+    #[serde(default)]
+    pub size: Option<[u32; 2]>,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            layer: SurfaceLayer::Background,
+            anchor: Vec::new(),
+            size: None,
+        }
+    }
+}
+
+impl Placement {
+    /// Anchor flags for the surface, every edge when it covers the whole output
+    pub fn anchor(&self) -> Anchor {
+        match self.size {
+            Some(_) => anchor_flags(&self.anchor),
+            None => Anchor::all(),
+        }
+    }
+
+    /// Size to request from the compositor, `(0, 0)` letting it stretch between the anchors
+    pub fn requested_size(&self) -> (u32, u32) {
+        self.size.map_or((0, 0), |[width, height]| (width, height))
+    }
 }
 
 /// Scene configuration for monitor assignment
@@ -95,15 +309,20 @@ pub enum Fit {
 /// layout = "clone"
 /// fit = "center"
 /// monitors = ["HDMI-A-1"]
+///
+/// [[scene]]
+/// path = "wallpapers/fallback"
+/// layout = "independent"
+/// monitors = { "eDP-1" = "wallpapers/laptop", "DP-3" = "wallpapers/portrait" }
 /// ```
 #[derive(Debug, Clone, Deserialize)]
-#[cfg_attr(feature = "generate", derive(JsonSchema))]
+#[cfg_attr(feature = "generate", derive(JsonSchema, Serialize))]
 pub struct SceneConfig {
     /// Path to wallpaper directory or file
     ///
     /// For media sources, this should point to a directory containing
-    /// images or videos. Supports common image formats (PNG, JPG, WebP)
-    /// and video formats (MP4, WebM, GIF).
+    /// images or videos. Supports common image formats (PNG, JPG, WebP),
+    /// SVG when built with the `svg` feature, and video formats (MP4, WebM, GIF).
     ///
     /// Relative paths are resolved from the config file location.
     pub path: Option<PathBuf>,
@@ -111,16 +330,93 @@ pub struct SceneConfig {
     /// Monitor layout strategy
     pub layout: Layout,
 
+    /// How monitors space out their rotations when `layout = "independent"`
+    ///
+    /// `"none"` rotates them together, `"even"` spreads them evenly across the interval
+    /// and `"random"` starts each at a random point in it.
+    #[serde(default)]
+    pub independent_offset: OffsetMode,
+
     /// How wallpaper fits within monitor bounds
     #[serde(default)]
     pub fit: Fit,
 
+    /// Largest share of an image (0.0 - 1.0) `fit = "smart"` crops away to fill the monitor
+    ///
+    /// Images that would lose more are letterboxed as with `contain`.
+    #[serde(default = "default_smart_max_crop")]
+    pub smart_max_crop: f32,
+
+    /// Texture filtering used when scaling the wallpaper
+    #[serde(default)]
+    pub scaling: Scaling,
+
+    /// Gaussian blur radius in pixels applied to still wallpapers (0 = off)
+    #[serde(default)]
+    pub blur: f32,
+
+    /// What fills the letterbox bars when `fit = "contain"`
+    ///
+    /// Accepts `"edge-extend"`, `"blur"` or `{ color = [r, g, b, a] }`. Defaults to opaque black.
+    #[serde(default)]
+    pub letterbox: LetterboxStyle,
+
+    /// Gaussian blur radius in pixels for the background under `letterbox = "blur"`
+    #[serde(default = "default_letterbox_blur")]
+    pub letterbox_blur: f32,
+
+    /// Filter used to upscale still wallpapers, `"linear"` or `"bicubic"`
+    #[serde(default)]
+    pub upscale: Upscale,
+
+    /// Build mipmaps for still wallpapers so images much larger than the monitor downscale
+    /// without aliasing, at the cost of a third more VRAM and slower loading
+    #[serde(default)]
+    pub mipmaps: bool,
+
+    /// Slowly zoom and drift across still wallpapers while they're on screen
+    ///
+    /// The pan spreads over the rotation interval, or a minute for scenes that don't rotate.
+    #[serde(default)]
+    pub ken_burns: bool,
+
+    /// Color shown while the media directory is empty, as sRGB plus alpha (0.0 - 1.0)
+    ///
+    /// Rotation keeps checking the directory and switches back once media appears.
+    #[serde(default = "default_fallback_color")]
+    pub fallback_color: [f32; 4],
+
+    /// Order files from a media directory are shown in, `"random"` or `"album"`
+    #[serde(default)]
+    pub order: RotationOrder,
+
+    /// Also pick up media from subdirectories of `path`
+    ///
+    /// Symlinked directories are followed, but each directory is only scanned once.
+    #[serde(default)]
+    pub recursive: bool,
+
+    /// Framerate for this scene, overriding `general.fps`
+    ///
+    /// Lets a still scene on one monitor run slower than smoke on another.
+    #[serde(default)]
+    pub fps: Option<u32>,
+
+    /// Layer, anchor and size of the surface, covering each output by default
+    #[serde(default)]
+    pub placement: Placement,
+
     /// Which monitors this scene applies to
     ///
     /// Accepts:
     /// - `"*"` or `"any"`: All monitors
     /// - `"DP-1"`: Single monitor by name
     /// - `["DP-1", "HDMI-A-1"]`: Multiple specific monitors
+    /// - `["DP-*", "HDMI-A-?"]`: Glob patterns, `*` matching any run of characters
+    ///   and `?` a single one. If patterns from two scenes both match an output,
+    ///   the scene listed first takes it.
+    /// - `{ "DP-1" = "folderA", "HDMI-A-1" = "folderB" }`: Specific monitors, each
+    ///   drawing from its own path when the layout is independent
     #[serde(default)]
     pub monitors: MonitorsSpec,
 
@@ -137,13 +433,69 @@ impl Default for SceneConfig {
         Self {
             path: None,
             layout: Layout::Clone,
+            independent_offset: OffsetMode::None,
             fit: Fit::Cover,
+            smart_max_crop: DEFAULT_SMART_MAX_CROP,
+            scaling: Scaling::Linear,
+            blur: 0.0,
+            letterbox: LetterboxStyle::default(),
+            letterbox_blur: DEFAULT_LETTERBOX_BLUR,
+            upscale: Upscale::Linear,
+            mipmaps: false,
+            ken_burns: false,
+            fallback_color: DEFAULT_FALLBACK_COLOR,
+            order: RotationOrder::Random,
+            recursive: false,
+            fps: None,
+            placement: Placement::default(),
             monitors: MonitorsSpec::Any,
             transition: None,
         }
     }
 }
 
+fn default_fallback_color() -> [f32; 4] {
+    DEFAULT_FALLBACK_COLOR
+}
+
+fn default_smart_max_crop() -> f32 {
+    DEFAULT_SMART_MAX_CROP
+}
+
+fn default_letterbox_blur() -> f32 {
+    DEFAULT_LETTERBOX_BLUR
+}
+
+impl SceneConfig {
+    /// Anchors relative scene and monitor paths at `base`, leaving absolute paths and URLs alone
+    pub fn resolve_relative_paths(&mut self, base: &Path) {
+        let resolve = |path: &mut PathBuf| {
+            if path.is_relative() && !remote::is_remote(path) {
+                *path = base.join(&*path);
+            }
+        };
+
+        if let Some(path) = &mut self.path {
+            resolve(path);
+        }
+        if let MonitorsSpec::Mapped(entries) = &mut self.monitors {
+            entries.iter_mut().for_each(|(_, path)| resolve(path));
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(0.0..=1.0).contains(&self.smart_max_crop) {
+            return Err(ConfigError::InvalidSmartMaxCrop(self.smart_max_crop));
+        }
+        for (field, value) in [("blur", self.blur), ("letterbox_blur", self.letterbox_blur)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(ConfigError::InvalidBlur { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -212,6 +564,12 @@ mod tests {
         assert_eq!(config.fit, Fit::Contain);
     }
 
+    #[test]
+    fn test_fit_deserialize_smart() {
+        let config: FitConfig = toml::from_str(r#"fit = "smart""#).unwrap();
+        assert_eq!(config.fit, Fit::Smart);
+    }
+
     #[test]
     fn test_fit_deserialize_tile() {
         let config: FitConfig = toml::from_str(r#"fit = "tile""#).unwrap();
@@ -255,4 +613,229 @@ mod tests {
         assert_eq!(config.scene.layout, Layout::Independent);
         assert_eq!(config.scene.fit, Fit::Cover);
     }
+
+    #[derive(serde::Deserialize)]
+    struct ScalingConfig {
+        #[serde(default)]
+        scaling: Scaling,
+    }
+
+    #[test]
+    fn test_scaling_deserialize_nearest() {
+        let config: ScalingConfig = toml::from_str(r#"scaling = "nearest""#).unwrap();
+        assert_eq!(config.scaling, Scaling::Nearest);
+    }
+
+    #[test]
+    fn test_scaling_default_linear() {
+        let config: ScalingConfig = toml::from_str("").unwrap();
+        assert_eq!(config.scaling, Scaling::Linear);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct LetterboxConfig {
+        #[serde(default)]
+        letterbox: LetterboxStyle,
+    }
+
+    #[test]
+    fn test_letterbox_deserialize_edge_extend() {
+        let config: LetterboxConfig = toml::from_str(r#"letterbox = "edge-extend""#).unwrap();
+        assert_eq!(config.letterbox, LetterboxStyle::EdgeExtend);
+    }
+
+    #[test]
+    fn test_letterbox_deserialize_color() {
+        let config: LetterboxConfig = toml::from_str(r#"letterbox = { color = [1.0, 0.5, 0.0, 1.0] }"#).unwrap();
+        assert_eq!(config.letterbox, LetterboxStyle::Color([1.0, 0.5, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_letterbox_deserialize_blur() {
+        let config: LetterboxConfig = toml::from_str(r#"letterbox = "blur""#).unwrap();
+        assert_eq!(config.letterbox, LetterboxStyle::Blur);
+
+        let scene: SceneConfig = toml::from_str("layout = \"clone\"").unwrap();
+        assert_eq!(scene.letterbox_blur, DEFAULT_LETTERBOX_BLUR);
+    }
+
+    #[test]
+    fn test_letterbox_default_black() {
+        let config: LetterboxConfig = toml::from_str("").unwrap();
+        assert_eq!(config.letterbox, LetterboxStyle::Color([0.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct UpscaleConfig {
+        #[serde(default)]
+        upscale: Upscale,
+    }
+
+    #[test]
+    fn test_upscale_deserialize_bicubic() {
+        let config: UpscaleConfig = toml::from_str(r#"upscale = "bicubic""#).unwrap();
+        assert_eq!(config.upscale, Upscale::Bicubic);
+    }
+
+    #[test]
+    fn test_upscale_default_linear() {
+        let config: UpscaleConfig = toml::from_str("").unwrap();
+        assert_eq!(config.upscale, Upscale::Linear);
+    }
+
+    #[test]
+    fn test_fallback_color_defaults_to_black() {
+        let config: SceneConfigWrapper = toml::from_str("[scene]\nlayout = \"clone\"").unwrap();
+        assert_eq!(config.scene.fallback_color, DEFAULT_FALLBACK_COLOR);
+    }
+
+    #[test]
+    fn test_fallback_color_deserialize() {
+        let config: SceneConfigWrapper =
+            toml::from_str("[scene]\nlayout = \"clone\"\nfallback_color = [0.1, 0.2, 0.3, 1.0]").unwrap();
+        assert_eq!(config.scene.fallback_color, [0.1, 0.2, 0.3, 1.0]);
+    }
+
+    #[test]
+    fn test_order_defaults_to_random() {
+        let config: SceneConfigWrapper = toml::from_str("[scene]\nlayout = \"clone\"").unwrap();
+        assert_eq!(config.scene.order, RotationOrder::Random);
+    }
+
+    #[test]
+    fn test_order_deserialize_album() {
+        let config: SceneConfigWrapper = toml::from_str("[scene]\nlayout = \"clone\"\norder = \"album\"").unwrap();
+        assert_eq!(config.scene.order, RotationOrder::Album);
+    }
+
+    #[test]
+    fn test_anchor_flags_from_edges() {
+        assert_eq!(
+            anchor_flags(&[Edge::Bottom, Edge::Right]),
+            Anchor::BOTTOM | Anchor::RIGHT
+        );
+        assert_eq!(
+            anchor_flags(&[Edge::Top, Edge::Left, Edge::Top]),
+            Anchor::TOP | Anchor::LEFT
+        );
+        assert_eq!(anchor_flags(&[]), Anchor::empty());
+        assert_eq!(
+            anchor_flags(&[Edge::Top, Edge::Bottom, Edge::Left, Edge::Right]),
+            Anchor::all()
+        );
+    }
+
+    #[test]
+    fn test_placement_defaults_to_fullscreen_background() {
+        let config: SceneConfigWrapper = toml::from_str("[scene]\nlayout = \"clone\"").unwrap();
+        let placement = config.scene.placement;
+
+        assert_eq!(placement.layer, SurfaceLayer::Background);
+        assert_eq!(placement.anchor(), Anchor::all());
+        assert_eq!(placement.requested_size(), (0, 0));
+    }
+
+    #[test]
+    fn test_placement_deserialize_corner_overlay() {
+        let config: SceneConfigWrapper = toml::from_str(
+            r#"
+            [scene]
+            layout = "clone"
+
+            [scene.placement]
+            layer = "top"
+            anchor = ["bottom", "right"]
+            size = [400, 300]
+            "#,
+        )
+        .unwrap();
+        let placement = config.scene.placement;
+
+        assert_eq!(placement.layer, SurfaceLayer::Top);
+        assert_eq!(placement.anchor(), Anchor::BOTTOM | Anchor::RIGHT);
+        assert_eq!(placement.requested_size(), (400, 300));
+    }
+
+    #[test]
+    fn test_placement_anchor_ignored_without_size() {
+        let placement = Placement {
+            anchor: vec![Edge::Top],
+            ..Default::default()
+        };
+        assert_eq!(placement.anchor(), Anchor::all());
+    }
+
+    #[test]
+    fn test_scene_config_deserialize_monitor_map() {
+        let config: SceneConfigWrapper = toml::from_str(
+            r#"
+            [scene]
+            path = "/fallback"
+            layout = "independent"
+            monitors = { "DP-1" = "/folderA", "HDMI-A-1" = "/folderB" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.scene.monitors.names(), vec!["DP-1", "HDMI-A-1"]);
+        assert_eq!(
+            config.scene.monitors.path_for("HDMI-A-1"),
+            Some(std::path::Path::new("/folderB"))
+        );
+        assert_eq!(config.scene.path, Some(PathBuf::from("/fallback")));
+    }
+
+    #[test]
+    fn test_validate_smart_max_crop_range() {
+        for crop in [0.0, DEFAULT_SMART_MAX_CROP, 1.0] {
+            let scene = SceneConfig {
+                smart_max_crop: crop,
+                ..Default::default()
+            };
+            assert!(scene.validate().is_ok(), "{crop} should be accepted");
+        }
+        for crop in [-0.1, 1.5, f32::NAN] {
+            let scene = SceneConfig {
+                smart_max_crop: crop,
+                ..Default::default()
+            };
+            assert!(
+                matches!(scene.validate(), Err(ConfigError::InvalidSmartMaxCrop(_))),
+                "{crop} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_or_nan_blur() {
+        let scene = SceneConfig {
+            blur: 4.0,
+            letterbox_blur: 0.0,
+            ..Default::default()
+        };
+        assert!(scene.validate().is_ok());
+
+        for value in [-1.0, f32::NAN] {
+            let scene = SceneConfig {
+                letterbox_blur: value,
+                ..Default::default()
+            };
+            assert!(matches!(
+                scene.validate(),
+                Err(ConfigError::InvalidBlur {
+                    field: "letterbox_blur",
+                    ..
+                })
+            ));
+
+            let scene = SceneConfig {
+                blur: value,
+                ..Default::default()
+            };
+            assert!(matches!(
+                scene.validate(),
+                Err(ConfigError::InvalidBlur { field: "blur", .. })
+            ));
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use super::config::Fit;
+
+/// The fit an image is drawn with, settling [`Fit::Smart`] on cover or contain
+///
+/// Cover crops `1 - short / long` of the image, the ratio of the two aspect ratios.
+/// Smart covers while that stays within `max_crop` and contains beyond it.
+pub fn resolve_fit(fit: Fit, surface_to_image_arr: f32, max_crop: f32) -> Fit {
+    if fit != Fit::Smart {
+        return fit;
+    }
+
+    let cropped = 1.0 - surface_to_image_arr.min(surface_to_image_arr.recip());
+    if cropped <= max_crop { Fit::Cover } else { Fit::Contain }
+}
+
+/// Fraction of the surface an image covers when shown at 1:1 physical pixels
+///
+/// `surface` is the logical size of the output. With a `scale_factor` of 2, each
+/// logical pixel spans two physical ones, so the image takes up half as much of
+/// the surface as its pixel count alone would suggest.
+pub fn center_fit_size(image: (u32, u32), surface: (u32, u32), scale_factor: i32) -> [f32; 2] {
+    let scale = scale_factor.max(1) as f32;
+    [
+        image.0 as f32 / scale / surface.0.max(1) as f32,
+        image.1 as f32 / scale / surface.1.max(1) as f32,
+    ]
+}
+
+/// How far texture coordinates stretch around the centre when an image covers the surface
+///
+/// Mirrors `scale` in `static.wgsl`. Both factors are at most 1, so the whole surface
+/// samples inside the image and the longer side is cropped.
+pub fn cover_scale(surface_to_image_arr: f32) -> [f32; 2] {
+    if surface_to_image_arr > 1.0 {
+        [1.0, 1.0 / surface_to_image_arr]
+    } else {
+        [surface_to_image_arr, 1.0]
+    }
+}
+
+/// How far texture coordinates stretch around the centre when an image fits inside the surface
+///
+/// Mirrors `contain_scale` in `static.wgsl`. Both factors are at least 1, so the whole
+/// image shows and the shorter side runs into the letterbox bars.
+pub fn contain_scale(surface_to_image_arr: f32) -> [f32; 2] {
+    if surface_to_image_arr > 1.0 {
+        [surface_to_image_arr, 1.0]
+    } else {
+        [1.0, 1.0 / surface_to_image_arr]
+    }
+}
+
+/// Texture coordinates sampled at `uv` when an image is fit inside the surface
+///
+/// Mirrors the contain branch of `static.wgsl`. The image keeps its aspect ratio
+/// and is centered, so coordinates outside `0..1` land in the letterbox bars.
+/// Sampling there with `ClampToEdge` repeats the nearest edge texel, which is how
+/// the edge-extend letterbox fills the bars.
+pub fn contain_coords(uv: [f32; 2], surface_to_image_arr: f32) -> [f32; 2] {
+    let scale = contain_scale(surface_to_image_arr);
+    [(uv[0] - 0.5) * scale[0] + 0.5, (uv[1] - 0.5) * scale[1] + 0.5]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_fit_smart_covers_close_aspects() {
+        // 16:10 on 16:9 loses 10% of the image
+        let arr = (16.0 / 9.0) / (16.0 / 10.0);
+        assert_eq!(resolve_fit(Fit::Smart, arr, 0.2), Fit::Cover);
+        assert_eq!(resolve_fit(Fit::Smart, 1.0 / arr, 0.2), Fit::Cover);
+        assert_eq!(resolve_fit(Fit::Smart, 1.0, 0.0), Fit::Cover);
+    }
+
+    #[test]
+    fn test_resolve_fit_smart_contains_distant_aspects() {
+        // A 9:16 portrait on a 16:9 surface would lose about 68% of the image
+        let arr = (16.0 / 9.0) / (9.0 / 16.0);
+        assert_eq!(resolve_fit(Fit::Smart, arr, 0.2), Fit::Contain);
+        assert_eq!(resolve_fit(Fit::Smart, 1.0 / arr, 0.2), Fit::Contain);
+        assert_eq!(resolve_fit(Fit::Smart, arr, 0.7), Fit::Cover);
+    }
+
+    #[test]
+    fn test_resolve_fit_leaves_other_fits_alone() {
+        for fit in [Fit::Stretch, Fit::Center, Fit::Cover, Fit::Contain, Fit::Tile] {
+            assert_eq!(resolve_fit(fit, 3.0, 0.2), fit);
+        }
+    }
+
+    #[test]
+    fn test_center_fit_size_matches_surface_at_scale_one() {
+        assert_eq!(center_fit_size((1920, 1080), (1920, 1080), 1), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_center_fit_size_hidpi_shows_physical_pixels() {
+        // A 4K panel at scale 2 is 1920x1080 logical, so a 1920px image spans half of it
+        assert_eq!(center_fit_size((1920, 1080), (1920, 1080), 2), [0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_center_fit_size_larger_than_surface() {
+        assert_eq!(center_fit_size((3840, 1080), (1920, 1080), 1), [2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_center_fit_size_guards_zero_scale_and_surface() {
+        assert_eq!(center_fit_size((100, 100), (0, 0), 0), [100.0, 100.0]);
+    }
+
+    fn assert_coords(actual: [f32; 2], expected: [f32; 2]) {
+        assert!((actual[0] - expected[0]).abs() < 1e-5, "{actual:?} != {expected:?}");
+        assert!((actual[1] - expected[1]).abs() < 1e-5, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_blur_fill_cover_background_under_contain_foreground() {
+        // A 9:16 portrait on a 16:9 surface: the blurred fill covers, the sharp copy fits
+        let arr = (16.0 / 9.0) / (9.0 / 16.0);
+        let cover = cover_scale(arr);
+        let contain = contain_scale(arr);
+
+        assert_coords(cover, [1.0, 1.0 / arr]);
+        assert_coords(contain, [arr, 1.0]);
+        // The fill is the contained image zoomed by the aspect ratio, so it reaches every edge
+        assert_coords([contain[0] / cover[0], contain[1] / cover[1]], [arr, arr]);
+        assert!(cover.iter().all(|&s| s <= 1.0) && contain.iter().all(|&s| s >= 1.0));
+    }
+
+    #[test]
+    fn test_cover_and_contain_scale_agree_on_matching_aspect() {
+        assert_coords(cover_scale(1.0), [1.0, 1.0]);
+        assert_coords(contain_scale(1.0), [1.0, 1.0]);
+        // A wide image on a tall surface zooms the other axis
+        assert_coords(cover_scale(0.5), [0.5, 1.0]);
+        assert_coords(contain_scale(0.5), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_contain_coords_matching_aspect_is_identity() {
+        assert_coords(contain_coords([0.2, 0.7], 1.0), [0.2, 0.7]);
+    }
+
+    #[test]
+    fn test_contain_coords_pillarbox_on_wide_surface() {
+        // A 4:3 image on a 16:9 surface fills the middle three quarters horizontally
+        let arr = (16.0 / 9.0) / (4.0 / 3.0);
+        assert_coords(contain_coords([0.125, 0.5], arr), [0.0, 0.5]);
+        assert_coords(contain_coords([0.875, 0.5], arr), [1.0, 0.5]);
+        assert_coords(contain_coords([0.5, 0.0], arr), [0.5, 0.0]);
+
+        let bar = contain_coords([0.0, 0.3], arr);
+        assert!(bar[0] < 0.0, "left bar samples past the image edge: {bar:?}");
+        assert_coords(bar, [-1.0 / 6.0, 0.3]);
+    }
+
+    #[test]
+    fn test_contain_coords_letterbox_on_tall_surface() {
+        // A square image on a 1:2 surface fills the middle half vertically
+        let arr = 0.5;
+        assert_coords(contain_coords([0.5, 0.25], arr), [0.5, 0.0]);
+        assert_coords(contain_coords([0.5, 0.75], arr), [0.5, 1.0]);
+        assert_coords(contain_coords([0.3, 1.0], arr), [0.3, 1.5]);
+    }
+}
@@ -1,7 +1,14 @@
 mod config;
+mod fit;
 mod monitor;
 mod runtime;
+mod span;
 
-pub use config::{Fit, Layout, SceneConfig};
-pub use monitor::{Monitor, MonitorHandle, MonitorsSpec};
+pub use config::{
+    DEFAULT_FALLBACK_COLOR, DEFAULT_LETTERBOX_BLUR, DEFAULT_SMART_MAX_CROP, Fit, Layout, LetterboxStyle, OffsetMode,
+    Placement, RotationOrder, Scaling, SceneConfig, Upscale,
+};
+pub use fit::{center_fit_size, contain_coords, contain_scale, cover_scale, resolve_fit};
+pub use monitor::{Monitor, MonitorHandle, MonitorsSpec, display_size, is_quarter_turn, output_size};
 pub use runtime::Scene;
+pub use span::{OutputRegion, span_uv_rects};
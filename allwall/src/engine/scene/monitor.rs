@@ -1,13 +1,41 @@
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "generate")]
 use schemars::{JsonSchema, json_schema};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smithay_client_toolkit::{
-    output::OutputInfo, reexports::client::protocol::wl_output::WlOutput, shell::wlr_layer::LayerSurface,
+    output::OutputInfo,
+    reexports::client::protocol::wl_output::{Transform, WlOutput},
+    shell::wlr_layer::LayerSurface,
 };
 
+/// Whether `name` matches a glob `pattern`, where `*` matches any run of
+/// characters and `?` matches exactly one
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => glob_match(rest, name) || (!name.is_empty() && glob_match(pattern, &name[1..])),
+        (Some((&p, rest)), Some((&n, name_rest))) if p == '?' || p == n => glob_match(rest, name_rest),
+        _ => false,
+    }
+}
+
+/// Whether some monitor name could be matched by both glob patterns
+fn globs_overlap(a: &[char], b: &[char]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (None, None) => true,
+        (Some(('*', a_rest)), _) => globs_overlap(a_rest, b) || (!b.is_empty() && globs_overlap(a, &b[1..])),
+        (_, Some(('*', b_rest))) => globs_overlap(a, b_rest) || (!a.is_empty() && globs_overlap(&a[1..], b)),
+        (Some((&x, a_rest)), Some((&y, b_rest))) if x == '?' || y == '?' || x == y => globs_overlap(a_rest, b_rest),
+        _ => false,
+    }
+}
+
 /// A handle to a specific monitor
+///
+/// The name may be a glob like `DP-*`, in which case it stands for every
+/// output whose name matches it.
 #[derive(Clone, Debug, PartialEq)]
 pub struct MonitorHandle {
     name: String,
@@ -21,6 +49,29 @@ impl MonitorHandle {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Whether the name contains `*` or `?` wildcards
+    pub fn is_pattern(&self) -> bool {
+        self.name.contains(['*', '?'])
+    }
+
+    /// Whether this handle refers to the output called `output_name`
+    pub fn matches(&self, output_name: &str) -> bool {
+        if !self.is_pattern() {
+            return self.name == output_name;
+        }
+
+        let pattern: Vec<char> = self.name.chars().collect();
+        let name: Vec<char> = output_name.chars().collect();
+        glob_match(&pattern, &name)
+    }
+
+    /// Whether some output name could be matched by both handles
+    pub fn overlaps(&self, other: &MonitorHandle) -> bool {
+        let a: Vec<char> = self.name.chars().collect();
+        let b: Vec<char> = other.name.chars().collect();
+        globs_overlap(&a, &b)
+    }
 }
 
 impl std::hash::Hash for MonitorHandle {
@@ -37,6 +88,39 @@ impl std::fmt::Display for MonitorHandle {
     }
 }
 
+/// Whether `transform` turns the output a quarter turn, so portrait becomes landscape or back
+pub fn is_quarter_turn(transform: Transform) -> bool {
+    matches!(
+        transform,
+        Transform::_90 | Transform::_270 | Transform::Flipped90 | Transform::Flipped270
+    )
+}
+
+/// Size of a `width` x `height` panel as it appears once `transform` has rotated it
+///
+/// For sizes in the panel's native orientation, such as its modes. Logical sizes and
+/// layer configures already come rotated, and surfaces are drawn upright for the
+/// compositor to rotate, so those need no correction.
+pub fn display_size(width: u32, height: u32, transform: Transform) -> (u32, u32) {
+    if is_quarter_turn(transform) {
+        (height, width)
+    } else {
+        (width, height)
+    }
+}
+
+/// Logical size of the output, or its current mode turned by its transform when the
+/// compositor reports no logical size
+pub fn output_size(info: &OutputInfo) -> Option<(u32, u32)> {
+    info.logical_size.map(|(w, h)| (w as u32, h as u32)).or_else(|| {
+        let mode = info.modes.iter().find(|mode| mode.current)?;
+        let (width, height) = mode.dimensions;
+        let scale = info.scale_factor.max(1) as u32;
+        let (width, height) = display_size(width as u32 / scale, height as u32 / scale, info.transform);
+        Some((width, height))
+    })
+}
+
 /// Runtime monitor with layer surface
 pub struct Monitor {
     handle: MonitorHandle,
@@ -81,11 +165,7 @@ impl Monitor {
     }
 
     pub fn size(&self) -> (u32, u32) {
-        self.info
-            .as_ref()
-            .and_then(|i| i.logical_size)
-            .map(|(w, h)| (w as u32, h as u32))
-            .unwrap_or((1920, 1080))
+        self.info.as_ref().and_then(output_size).unwrap_or((1920, 1080))
     }
 
     pub fn scale_factor(&self) -> i32 {
@@ -109,6 +189,8 @@ pub enum MonitorsSpec {
     #[default]
     Any,
     Specific(Vec<MonitorHandle>),
+    /// Specific monitors, each drawing from its own path in independent layouts
+    Mapped(Vec<(MonitorHandle, PathBuf)>),
 }
 
 impl MonitorsSpec {
@@ -116,17 +198,45 @@ impl MonitorsSpec {
         matches!(self, MonitorsSpec::Any)
     }
 
-    pub fn monitors(&self) -> Option<&[MonitorHandle]> {
+    pub fn monitors(&self) -> Option<Vec<&MonitorHandle>> {
         match self {
             MonitorsSpec::Any => None,
-            MonitorsSpec::Specific(handles) => Some(handles),
+            MonitorsSpec::Specific(handles) => Some(handles.iter().collect()),
+            MonitorsSpec::Mapped(entries) => Some(entries.iter().map(|(h, _)| h).collect()),
         }
     }
 
+    /// Names of the monitors this spec claims, empty for [`MonitorsSpec::Any`]
+    pub fn names(&self) -> Vec<&str> {
+        self.monitors()
+            .map(|handles| handles.into_iter().map(MonitorHandle::name).collect())
+            .unwrap_or_default()
+    }
+
     pub fn matches(&self, name: &str) -> bool {
+        self.monitors()
+            .is_none_or(|handles| handles.into_iter().any(|handle| handle.matches(name)))
+    }
+
+    /// Path mapped to the named monitor, if this spec maps monitors to paths
+    ///
+    /// An exact name wins over a pattern; among patterns the first listed wins.
+    pub fn path_for(&self, name: &str) -> Option<&Path> {
+        match self {
+            MonitorsSpec::Mapped(entries) => entries
+                .iter()
+                .find(|(h, _)| h.name() == name)
+                .or_else(|| entries.iter().find(|(h, _)| h.matches(name)))
+                .map(|(_, path)| path.as_path()),
+            _ => None,
+        }
+    }
+
+    /// Every monitor-specific path, for validating a config before it runs
+    pub fn mapped_paths(&self) -> &[(MonitorHandle, PathBuf)] {
         match self {
-            MonitorsSpec::Any => true,
-            MonitorsSpec::Specific(handles) => handles.iter().any(|h| h.name() == name),
+            MonitorsSpec::Mapped(entries) => entries,
+            _ => &[],
         }
     }
 }
@@ -144,7 +254,9 @@ impl<'de> Deserialize<'de> for MonitorsSpec {
             type Value = MonitorsSpec;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a string (\"*\", \"any\", or monitor name) or an array of strings")
+                formatter.write_str(
+                    "a string (\"*\", \"any\", or monitor name), an array of strings, or a table of monitor paths",
+                )
             }
 
             fn visit_str<E>(self, v: &str) -> Result<MonitorsSpec, E>
@@ -179,12 +291,48 @@ impl<'de> Deserialize<'de> for MonitorsSpec {
                     Ok(MonitorsSpec::Specific(handles))
                 }
             }
+
+            fn visit_map<A>(self, mut map: A) -> Result<MonitorsSpec, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+
+                while let Some((name, path)) = map.next_entry::<String, PathBuf>()? {
+                    let lower = name.to_lowercase();
+                    if lower == "*" || lower == "any" {
+                        return Err(de::Error::custom(
+                            "'*' can't be mapped to a path, use the scene path for unlisted monitors",
+                        ));
+                    }
+                    entries.push((MonitorHandle::new(name), path));
+                }
+
+                if entries.is_empty() {
+                    Ok(MonitorsSpec::Any)
+                } else {
+                    Ok(MonitorsSpec::Mapped(entries))
+                }
+            }
         }
 
         deserializer.deserialize_any(MonitorsSpecVisitor)
     }
 }
 
+impl Serialize for MonitorsSpec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            MonitorsSpec::Any => serializer.serialize_str("*"),
+            MonitorsSpec::Specific(handles) => serializer.collect_seq(handles.iter().map(MonitorHandle::name)),
+            MonitorsSpec::Mapped(entries) => serializer.collect_map(entries.iter().map(|(h, path)| (h.name(), path))),
+        }
+    }
+}
+
 #[cfg(feature = "generate")]
 impl JsonSchema for MonitorsSpec {
     fn schema_name() -> Cow<'static, str> {
@@ -197,7 +345,7 @@ impl JsonSchema for MonitorsSpec {
 
     fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
         json_schema!({
-            "description": "Monitor specification. Use '*' or 'any' for all monitors, a single name like 'DP-1', or an array like ['DP-1', 'HDMI-A-1'].",
+            "description": "Monitor specification. Use '*' or 'any' for all monitors, a single name like 'DP-1', an array like ['DP-*', 'HDMI-A-1'] where '*' and '?' are wildcards, or a table like { 'DP-1' = 'folderA' } giving each monitor its own path.",
             "oneOf": [
                 {
                     "type": "string",
@@ -207,6 +355,11 @@ impl JsonSchema for MonitorsSpec {
                     "type": "array",
                     "items": { "type": "string" },
                     "description": "Array of monitor names. If any element is '*' or 'any', resolves to all monitors."
+                },
+                {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Monitor names mapped to the path each one draws from with the independent layout."
                 }
             ]
         })
@@ -249,4 +402,114 @@ mod tests {
         let config: TestConfig = toml::from_str(r#"monitors = ["DP-1", "*"]"#).unwrap();
         assert_eq!(config.monitors, MonitorsSpec::Any);
     }
+
+    #[test]
+    fn test_deserialize_array() {
+        let config: TestConfig = toml::from_str(r#"monitors = ["DP-1", "HDMI-A-1"]"#).unwrap();
+        assert_eq!(config.monitors.names(), vec!["DP-1", "HDMI-A-1"]);
+        assert!(config.monitors.path_for("DP-1").is_none());
+    }
+
+    #[test]
+    fn test_deserialize_map() {
+        let config: TestConfig =
+            toml::from_str(r#"monitors = { "DP-1" = "folderA", "HDMI-A-1" = "folderB" }"#).unwrap();
+
+        assert!(matches!(config.monitors, MonitorsSpec::Mapped(_)));
+        assert!(config.monitors.matches("DP-1"));
+        assert!(config.monitors.matches("HDMI-A-1"));
+        assert!(!config.monitors.matches("eDP-1"));
+        assert_eq!(config.monitors.path_for("DP-1"), Some(Path::new("folderA")));
+        assert_eq!(config.monitors.path_for("HDMI-A-1"), Some(Path::new("folderB")));
+        assert!(config.monitors.path_for("eDP-1").is_none());
+    }
+
+    #[test]
+    fn test_deserialize_empty_map() {
+        let config: TestConfig = toml::from_str("monitors = {}").unwrap();
+        assert_eq!(config.monitors, MonitorsSpec::Any);
+    }
+
+    #[test]
+    fn test_deserialize_map_rejects_wildcard() {
+        let result = toml::from_str::<TestConfig>(r#"monitors = { "*" = "folderA" }"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_glob_matches_displayport_outputs() {
+        let handle = MonitorHandle::new("DP-*".to_string());
+        assert!(handle.is_pattern());
+        assert!(handle.matches("DP-1"));
+        assert!(handle.matches("DP-10"));
+        assert!(!handle.matches("HDMI-1"));
+        assert!(!handle.matches("eDP-1"));
+    }
+
+    #[test]
+    fn test_glob_question_mark_matches_one_char() {
+        let handle = MonitorHandle::new("HDMI-A-?".to_string());
+        assert!(handle.matches("HDMI-A-1"));
+        assert!(!handle.matches("HDMI-A-10"));
+        assert!(!handle.matches("HDMI-A-"));
+    }
+
+    #[test]
+    fn test_exact_name_is_not_pattern() {
+        let handle = MonitorHandle::new("DP-1".to_string());
+        assert!(!handle.is_pattern());
+        assert!(handle.matches("DP-1"));
+        assert!(!handle.matches("DP-12"));
+    }
+
+    #[test]
+    fn test_spec_matches_glob_and_exact() {
+        let config: TestConfig = toml::from_str(r#"monitors = ["DP-*", "HDMI-A-1"]"#).unwrap();
+        assert!(config.monitors.matches("DP-1"));
+        assert!(config.monitors.matches("DP-3"));
+        assert!(config.monitors.matches("HDMI-A-1"));
+        assert!(!config.monitors.matches("HDMI-1"));
+    }
+
+    #[test]
+    fn test_path_for_prefers_exact_name() {
+        let config: TestConfig = toml::from_str(r#"monitors = { "DP-*" = "docked", "DP-1" = "main" }"#).unwrap();
+        assert_eq!(config.monitors.path_for("DP-1"), Some(Path::new("main")));
+        assert_eq!(config.monitors.path_for("DP-2"), Some(Path::new("docked")));
+    }
+
+    #[test]
+    fn test_glob_overlap() {
+        let handle = |name: &str| MonitorHandle::new(name.to_string());
+        assert!(handle("DP-*").overlaps(&handle("DP-1")));
+        assert!(handle("DP-*").overlaps(&handle("*-1")));
+        assert!(handle("DP-?").overlaps(&handle("DP-*")));
+        assert!(!handle("DP-*").overlaps(&handle("HDMI-*")));
+        assert!(!handle("DP-?").overlaps(&handle("DP-10")));
+    }
+
+    #[test]
+    fn test_names_empty_for_any() {
+        assert!(MonitorsSpec::Any.names().is_empty());
+        assert!(MonitorsSpec::Any.mapped_paths().is_empty());
+    }
+
+    #[test]
+    fn test_landscape_aspect_unchanged() {
+        assert_eq!(display_size(2560, 1440, Transform::Normal), (2560, 1440));
+        assert_eq!(display_size(2560, 1440, Transform::_180), (2560, 1440));
+        assert_eq!(display_size(2560, 1440, Transform::Flipped), (2560, 1440));
+    }
+
+    #[test]
+    fn test_quarter_turn_90_is_portrait() {
+        assert_eq!(display_size(2560, 1440, Transform::_90), (1440, 2560));
+        assert_eq!(display_size(2560, 1440, Transform::Flipped90), (1440, 2560));
+    }
+
+    #[test]
+    fn test_quarter_turn_270_is_portrait() {
+        assert_eq!(display_size(2560, 1440, Transform::_270), (1440, 2560));
+        assert_eq!(display_size(2560, 1440, Transform::Flipped270), (1440, 2560));
+    }
 }
@@ -1,63 +1,212 @@
-use std::collections::HashMap;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
 
 use smithay_client_toolkit::{
     compositor::CompositorState,
     output::OutputInfo,
-    reexports::client::{Connection, QueueHandle, protocol::wl_output::WlOutput},
+    reexports::client::{
+        Connection, QueueHandle,
+        protocol::{wl_output::WlOutput, wl_surface::WlSurface},
+    },
     shell::WaylandSurface,
-    shell::wlr_layer::{Anchor, Layer, LayerShell, LayerSurface},
+    shell::wlr_layer::{LayerShell, LayerSurface},
 };
 
 use crate::{
-    config::MergedSceneConfig,
+    config::{MergedSceneConfig, SourceConfigs},
     engine::{
+        effective_fps,
         error::EngineError,
+        frame_delta, frame_due,
         graphics::{Context, GpuContext, RenderSurface},
-        scene::{Fit, Layout, Monitor, MonitorHandle},
+        scene::{Fit, Layout, Monitor, MonitorHandle, OffsetMode, OutputRegion, output_size, span_uv_rects},
+        state::SHARED_SOURCE,
     },
     prelude::*,
-    sources::{InteractionState, SourceKind, SourceType, grass::GrassSource, media::MediaSource, smoke::SmokeSource},
-    transitions::TransitionType,
+    sources::{
+        InteractionState, RenderState, SourceKind, SourceType,
+        color::{ColorSource, GradientSource},
+        grass::GrassSource,
+        media::{MediaOptions, MediaSource},
+        smoke::SmokeSource,
+    },
+    transitions::{TransitionConfig, config::jittered_interval},
 };
 
+/// Simulation step taken before rendering a preview, about one frame at 60fps
+const PREVIEW_FRAME_TIME: Duration = Duration::from_millis(16);
+
 pub struct Scene {
     config: MergedSceneConfig,
     outputs: HashMap<WlOutput, SceneOutput>,
     sources: Vec<SourceType>,
     rotation_interval: Duration,
-    transition_duration: Duration,
-    transition_type: TransitionType,
+    rotation_jitter: Duration,
     source_kind: SourceKind,
-    smoke_config: crate::config::SmokeConfig,
+    source_configs: SourceConfigs,
     sources_initialized: bool,
+    /// Media to reopen when sources are first created, keyed like [`Scene::saved_media`]
+    restored: BTreeMap<String, PathBuf>,
+    /// Leave the input region alone so the pointer reaches the wallpaper
+    interactive: bool,
+    /// Fade sources in as they're created, rather than showing them straight away
+    startup_transition: bool,
+    /// First rotation of the cycle, which independent sources stagger their own first one from,
+    /// `None` if the scene doesn't rotate
+    rotation_start: Option<Instant>,
+    /// When each source rotates next, keyed like [`Scene::saved_media`]
+    rotation_deadlines: BTreeMap<String, Instant>,
+    /// Input seen by the last render, read when a transition starts
+    interaction: InteractionState,
+    /// Time since the last render, `None` to render on the next tick without advancing animations
+    since_render: Option<Duration>,
 }
 
 struct SceneOutput {
     monitor: Monitor,
     context: Context,
     configured: bool,
+    logical_position: (i32, i32),
+    /// Size as the output appears on the desktop. Logical sizes and layer configures
+    /// already come rotated by the output's transform, and the surface is drawn upright
+    /// for the compositor to turn, so neither the aspect nor the UVs need correcting.
+    logical_size: (u32, u32),
+}
+
+impl SceneOutput {
+    fn region(&self) -> OutputRegion {
+        OutputRegion::new(self.logical_position, self.logical_size)
+    }
+
+    /// Resizes the render surface to `logical` size at the output's current scale factor
+    fn resize_logical(&mut self, logical: (u32, u32)) {
+        let scale = self.context.scale_factor() as u32;
+        self.context.resize((logical.0 * scale, logical.1 * scale));
+    }
+}
+
+/// Draws `source` on `output`, first resyncing the source's clock if the surface had been
+/// lost, as after the monitor slept, so the resumed frame doesn't use the stale clock
+fn render_output(source: &mut SourceType, output: &SceneOutput, state: &InteractionState) {
+    if output.context.take_resumed() {
+        debug!(
+            "Output '{}' resumed, resyncing its source",
+            output.monitor.handle().name()
+        );
+        source.on_resume(&output.context);
+    }
+    source.render(&output.context, state);
+}
+
+/// Which saved media entry belongs to the source drawing on `output`
+fn source_key(layout: Layout, output: &SceneOutput) -> &str {
+    match layout {
+        Layout::Independent => output.monitor.handle().name(),
+        Layout::Clone | Layout::Span => SHARED_SOURCE,
+    }
+}
+
+/// Sources to step and the configured output whose context builds each replacement,
+/// as `(source, output)` indices, narrowed to `monitor`'s source when one is named
+///
+/// `outputs` are every output's name and whether it's configured, in the order their
+/// sources were created, which is also how rendering pairs them up. Sources of outputs
+/// that aren't configured yet are left alone.
+fn step_targets(
+    layout: Layout,
+    outputs: &[(&str, bool)],
+    sources: usize,
+    monitor: Option<&str>,
+) -> Vec<(usize, usize)> {
+    let mut configured = outputs.iter().enumerate().filter(|(_, (_, configured))| *configured);
+    match layout {
+        Layout::Clone | Layout::Span => {
+            let named = monitor.is_none_or(|monitor| configured.clone().any(|(_, (name, _))| *name == monitor));
+            match configured.next() {
+                Some((output, _)) if sources > 0 && named => vec![(0, output)],
+                _ => Vec::new(),
+            }
+        },
+        Layout::Independent => configured
+            .filter(|(i, (name, _))| *i < sources && monitor.is_none_or(|monitor| monitor == *name))
+            .map(|(i, _)| (i, i))
+            .collect(),
+    }
+}
+
+/// Delay before each of `count` independent sources first rotates, counted from the first
+/// rotation of the cycle
+fn stagger_offsets(mode: OffsetMode, interval: Duration, count: usize, rng: &mut impl Rng) -> Vec<Duration> {
+    (0..count)
+        .map(|i| match mode {
+            OffsetMode::None => Duration::ZERO,
+            OffsetMode::Even => interval.mul_f64(i as f64 / count as f64),
+            OffsetMode::Random => interval.mul_f64(rng.random()),
+        })
+        .collect()
 }
 
 impl Scene {
-    pub fn new(config: MergedSceneConfig, source_kind: SourceKind, smoke_config: crate::config::SmokeConfig) -> Self {
-        let transition_duration = config.transition.duration();
+    pub fn new(
+        mut config: MergedSceneConfig,
+        source_kind: SourceKind,
+        source_configs: SourceConfigs,
+        interactive: bool,
+        startup_transition: bool,
+    ) -> Self {
+        config.transition = config.transition.for_kind(source_kind);
         let rotation_interval = config.transition.interval();
-        let transition_type = config.transition.r#type;
+        let rotation_jitter = config.transition.interval_jitter();
 
         Self {
             config,
             outputs: HashMap::new(),
             sources: Vec::new(),
             rotation_interval,
-            transition_duration,
-            transition_type,
+            rotation_jitter,
             source_kind,
-            smoke_config,
+            source_configs,
             sources_initialized: false,
+            restored: BTreeMap::new(),
+            interactive,
+            startup_transition,
+            rotation_start: None,
+            rotation_deadlines: BTreeMap::new(),
+            interaction: InteractionState::default(),
+            since_render: Some(Duration::ZERO),
+        }
+    }
+
+    /// Framerate this scene renders at, given the engine-wide `global` rate
+    pub fn fps(&self, global: f32) -> f32 {
+        effective_fps(self.config.fps, global)
+    }
+
+    /// Counts `elapsed` towards this scene's next frame, returning how far to step its
+    /// animations once one is due at [`Scene::fps`]
+    pub fn tick(&mut self, elapsed: Duration, global_fps: f32) -> Option<Duration> {
+        let Some(since_render) = self.since_render.map(|since| since + elapsed) else {
+            self.since_render = Some(Duration::ZERO);
+            return Some(Duration::ZERO);
+        };
+
+        if frame_due(since_render, elapsed, self.fps(global_fps)) {
+            self.since_render = Some(Duration::ZERO);
+            Some(frame_delta(since_render))
+        } else {
+            self.since_render = Some(since_render);
+            None
         }
     }
 
+    /// Renders on the next tick without advancing animations, after the frame timer was idle
+    pub fn restart_clock(&mut self) {
+        self.since_render = None;
+    }
+
     pub fn should_handle_output(&self, output_name: &str) -> bool {
         self.config.monitors.matches(output_name)
     }
@@ -82,18 +231,28 @@ impl Scene {
             return Ok(());
         }
 
-        info!("Adding output '{}' to scene", output_name);
+        info!("Adding output '{}' to scene ({:?})", output_name, info.transform);
 
         let handle = MonitorHandle::new(output_name.to_string());
-        let size = info.logical_size.map(|(w, h)| (w as u32, h as u32)).unwrap_or((1920, 1080));
+        let placement = &self.config.placement;
+        let size = placement
+            .size
+            .map(|[w, h]| (w, h))
+            .or_else(|| output_size(info))
+            .unwrap_or((1920, 1080));
 
         let surface = compositor.create_surface(qh);
-        let layer = layer_shell.create_layer_surface(qh, surface, Layer::Background, Some("wallpaper"), Some(&output));
-        layer.set_anchor(Anchor::all());
-        layer.set_size(0, 0);
+        let layer =
+            layer_shell.create_layer_surface(qh, surface, placement.layer.into(), Some("wallpaper"), Some(&output));
+        layer.set_anchor(placement.anchor());
+        let (width, height) = placement.requested_size();
+        layer.set_size(width, height);
         layer.set_exclusive_zone(-1);
 
-        if let Ok(region) = smithay_client_toolkit::compositor::Region::new(compositor) {
+        // An empty input region lets clicks fall through to whatever is below
+        if !self.interactive
+            && let Ok(region) = smithay_client_toolkit::compositor::Region::new(compositor)
+        {
             layer.set_input_region(Some(region.wl_region()));
             region.wl_region().destroy();
         }
@@ -111,6 +270,8 @@ impl Scene {
                 monitor,
                 context,
                 configured: false,
+                logical_position: info.logical_position.unwrap_or((0, 0)),
+                logical_size: size,
             },
         );
 
@@ -121,6 +282,14 @@ impl Scene {
         Ok(())
     }
 
+    /// Logical size of this scene's surface `surface`, if it belongs to the scene
+    pub fn surface_size(&self, surface: &WlSurface) -> Option<(u32, u32)> {
+        self.outputs
+            .values()
+            .find(|output| output.monitor.layer().wl_surface() == surface)
+            .map(|output| output.logical_size)
+    }
+
     pub fn on_output_removed(&mut self, output: &WlOutput) {
         if let Some(scene_output) = self.outputs.remove(output) {
             let name = scene_output.monitor.handle().name();
@@ -130,9 +299,13 @@ impl Scene {
 
     pub fn on_output_updated(&mut self, output: &WlOutput, info: &OutputInfo) {
         if let Some(scene_output) = self.outputs.get_mut(output) {
+            if let Some(position) = info.logical_position {
+                scene_output.logical_position = position;
+            }
             if let Some(size) = info.logical_size {
                 let new_size = (size.0 as u32, size.1 as u32);
-                scene_output.context.resize(new_size);
+                scene_output.logical_size = new_size;
+                scene_output.resize_logical(new_size);
                 info!(
                     "Resized output '{}' to {:?}",
                     scene_output.monitor.handle().name(),
@@ -146,7 +319,7 @@ impl Scene {
         for scene_output in self.outputs.values_mut() {
             if std::ptr::eq(scene_output.monitor.layer().wl_surface(), layer.wl_surface()) {
                 if width > 0 && height > 0 {
-                    scene_output.context.resize((width, height));
+                    scene_output.resize_logical((width, height));
                 }
                 if !scene_output.configured {
                     scene_output.configured = true;
@@ -162,6 +335,28 @@ impl Scene {
         }
     }
 
+    /// Switches an output's buffer to `factor` so it renders at physical resolution
+    pub fn on_scale_factor_changed(&mut self, surface: &WlSurface, factor: i32) {
+        let Some(scene_output) = self.outputs.values_mut().find(|o| o.monitor.layer().wl_surface() == surface) else {
+            return;
+        };
+
+        if scene_output.context.scale_factor() == factor {
+            return;
+        }
+
+        let logical = scene_output.context.logical_size();
+        scene_output.context.set_scale_factor(factor);
+        surface.set_buffer_scale(scene_output.context.scale_factor());
+        scene_output.resize_logical(logical);
+
+        info!(
+            "Output '{}' scale factor changed to {}",
+            scene_output.monitor.handle().name(),
+            factor
+        );
+    }
+
     fn initialize_sources(&mut self) -> Result<()> {
         if self.outputs.is_empty() {
             return Ok(());
@@ -173,13 +368,18 @@ impl Scene {
         };
 
         self.sources.clear();
-        let contexts: Vec<_> = self.outputs.values().collect();
+        let outputs: Vec<_> = self.outputs.values().collect();
 
         for i in 0..num_sources {
-            let ctx = &contexts[i % contexts.len()].context;
-            let mut source = self.create_source(ctx)?;
+            let output = outputs[i % outputs.len()];
+            let ctx = &output.context;
+            let preferred = self.restored.remove(source_key(self.config.layout, output));
+            let mut source = self.create_source(output, preferred.as_deref())?;
+            // Loading leaves the source displaying, so without a transition it shows straight away
             source.load(ctx)?;
-            source.start_transition(None, self.transition_duration, ctx, self.transition_type);
+            if let Some(transition) = self.opening_transition() {
+                source.start_transition(None, transition, &self.interaction, ctx);
+            }
             self.sources.push(source);
         }
 
@@ -193,35 +393,133 @@ impl Scene {
         Ok(())
     }
 
-    fn create_source(&self, ctx: &Context) -> Result<SourceType> {
+    /// Transition new sources open with, `None` when they appear without one
+    fn opening_transition(&self) -> Option<&TransitionConfig> {
+        self.startup_transition.then_some(&self.config.transition)
+    }
+
+    /// Rebuilds every source, and with it every pipeline, keeping the media on screen
+    pub fn reload_sources(&mut self) -> Result<()> {
+        if !self.sources_initialized {
+            return Ok(());
+        }
+
+        self.restored = self.saved_media();
+        self.initialize_sources()
+    }
+
+    fn media_options(&self) -> MediaOptions {
+        MediaOptions::new(&self.source_configs, &self.config)
+    }
+
+    /// Media path for `output`, taken from the monitor map in independent layouts
+    fn media_path(&self, output: &SceneOutput) -> Option<PathBuf> {
+        let mapped = match self.config.layout {
+            Layout::Independent => self.config.monitors.path_for(output.monitor.handle().name()),
+            Layout::Clone | Layout::Span => None,
+        };
+
+        mapped.map(PathBuf::from).or_else(|| self.config.path.clone())
+    }
+
+    fn create_source(&self, output: &SceneOutput, preferred: Option<&Path>) -> Result<SourceType> {
+        self.build_source(self.media_path(output), preferred, &output.context)
+    }
+
+    fn build_source(&self, media_path: Option<PathBuf>, preferred: Option<&Path>, ctx: &Context) -> Result<SourceType> {
         match self.source_kind {
             SourceKind::Media => {
-                let path = self
-                    .config
-                    .path
-                    .as_ref()
-                    .ok_or_else(|| Error::Generic("Media source requires path".to_string()))?;
-                let source = MediaSource::from_directory(path, ctx)?;
+                let path = media_path.ok_or_else(|| Error::Generic("Media source requires path".to_string()))?;
+                let source = MediaSource::from_path(&path, preferred, ctx, self.media_options())?;
                 Ok(SourceType::Media(Box::new(source)))
             },
             SourceKind::Smoke => {
-                let source = SmokeSource::new(ctx, self.smoke_config.clone());
+                let source = SmokeSource::new(ctx, self.source_configs.smoke.clone())?;
                 Ok(SourceType::Smoke(Box::new(source)))
             },
             SourceKind::Grass => {
-                let source = GrassSource::new(ctx);
+                let source = GrassSource::new(ctx, self.source_configs.grass.clone());
                 Ok(SourceType::Grass(Box::new(source)))
             },
+            SourceKind::Color => {
+                let source = ColorSource::new(ctx, &self.source_configs.color)?;
+                Ok(SourceType::Color(Box::new(source)))
+            },
+            SourceKind::Gradient => {
+                let source = GradientSource::new(ctx, &self.source_configs.gradient)?;
+                Ok(SourceType::Gradient(Box::new(source)))
+            },
         }
     }
 
+    /// Builds this scene's source against `ctx` and draws a single frame, for previews
+    /// rendered into a headless context rather than a Wayland output
+    pub fn render_once(&self, ctx: &Context) -> Result<()> {
+        let mut source = self.build_source(self.config.path.clone(), None, ctx)?;
+        source.load(ctx)?;
+        source.update(PREVIEW_FRAME_TIME);
+        source.render(ctx, &InteractionState::default());
+        Ok(())
+    }
+
+    /// Builds this scene's source against `ctx` and times `frames` updates and draws,
+    /// waiting on the GPU after each so a frame's time includes its rendering
+    pub fn bench(&self, ctx: &Context, frames: usize) -> Result<Vec<Duration>> {
+        let mut source = self.build_source(self.config.path.clone(), None, ctx)?;
+        source.load(ctx)?;
+
+        let state = InteractionState::default();
+        let times = (0..frames)
+            .map(|_| {
+                let start = Instant::now();
+                source.update(PREVIEW_FRAME_TIME);
+                source.render(ctx, &state);
+                ctx.device().poll(wgpu::Maintain::Wait);
+                start.elapsed()
+            })
+            .collect();
+        Ok(times)
+    }
+
     pub fn update(&mut self, dt: Duration) {
         for source in &mut self.sources {
             source.update(dt);
         }
+        self.skip_stalled_sources();
+    }
+
+    /// Moves sources whose video stopped decoding on to the next file in their directory
+    fn skip_stalled_sources(&mut self) {
+        for (i, source) in self.sources.iter_mut().enumerate() {
+            if !source.take_stalled() {
+                continue;
+            }
+            // Clone and span scenes have one source, independent ones a source per output
+            let Some(output) = self.outputs.values().nth(i).filter(|o| o.configured) else {
+                continue;
+            };
+            match source.next(&output.context) {
+                Ok(next) => {
+                    let stalled = std::mem::replace(source, next);
+                    source.start_transition(
+                        Some(stalled),
+                        &self.config.transition,
+                        &self.interaction,
+                        &output.context,
+                    );
+                },
+                Err(e) => warn!("Could not skip the stalled video: {e}"),
+            }
+        }
+    }
+
+    /// Whether any source is animating, so the scene should render at the full frame rate
+    pub fn needs_redraw(&self) -> bool {
+        self.sources.iter().any(SourceType::needs_redraw)
     }
 
     pub fn render(&mut self, state: &InteractionState) {
+        self.interaction = *state;
         if self.sources.is_empty() || self.outputs.is_empty() {
             return;
         }
@@ -240,7 +538,7 @@ impl Scene {
         let source = &mut self.sources[0];
         for scene_output in self.outputs.values_mut() {
             if scene_output.configured {
-                source.render(&scene_output.context, state);
+                render_output(source, scene_output, state);
             }
         }
     }
@@ -249,59 +547,215 @@ impl Scene {
         if self.sources.is_empty() {
             return;
         }
+        let regions: Vec<_> = self
+            .outputs
+            .values()
+            .filter(|o| o.configured)
+            .map(SceneOutput::region)
+            .collect();
+        let uv_rects = span_uv_rects(&regions);
+
         let source = &mut self.sources[0];
-        if let Some(first) = self.outputs.values().find(|o| o.configured) {
-            source.render(&first.context, state);
+        let configured = self.outputs.values_mut().filter(|o| o.configured);
+        for (scene_output, uv_rect) in configured.zip(uv_rects) {
+            scene_output.context.set_uv_rect(uv_rect);
+            render_output(source, scene_output, state);
         }
     }
 
     fn render_independent(&mut self, state: &InteractionState) {
         for (i, scene_output) in self.outputs.values_mut().enumerate() {
             if i < self.sources.len() && scene_output.configured {
-                self.sources[i].render(&scene_output.context, state);
+                render_output(&mut self.sources[i], scene_output, state);
             }
         }
     }
 
-    pub fn advance_source(&mut self) -> Result<()> {
-        if self.outputs.is_empty() || self.sources.is_empty() {
-            return Ok(());
+    /// Draws `text` over every output of this scene from the next frame on
+    pub fn set_overlay_text(&mut self, text: &str) {
+        for scene_output in self.outputs.values_mut() {
+            scene_output.context.set_overlay_text(text);
+        }
+    }
+
+    pub fn clear_overlay(&mut self) {
+        for scene_output in self.outputs.values_mut() {
+            scene_output.context.clear_overlay();
         }
+    }
+
+    /// Moves every source on to its next media, or only `monitor`'s source when given
+    pub fn advance_source(&mut self, monitor: Option<&str>) -> Result<()> {
+        self.step_sources(SourceType::next, monitor)
+    }
 
-        let configured_contexts: Vec<_> = self.outputs.values().filter(|o| o.configured).collect();
+    /// Steps every source back to what it showed before, for media sources with history,
+    /// or only `monitor`'s source when given
+    pub fn previous_source(&mut self, monitor: Option<&str>) -> Result<()> {
+        self.step_sources(SourceType::prev, monitor)
+    }
 
-        if configured_contexts.is_empty() {
-            return Ok(());
+    /// Jumps every source to `target` in its directory, named by a bare file name or a path
+    pub fn goto_media(&mut self, target: &Path) -> Result<()> {
+        self.step_sources(|source, ctx| source.goto(target, ctx), None)
+    }
+
+    fn step_sources(
+        &mut self,
+        step: impl Fn(&SourceType, &Context) -> Result<SourceType>,
+        monitor: Option<&str>,
+    ) -> Result<()> {
+        let outputs: Vec<_> = self.outputs.values().collect();
+        let names: Vec<_> = outputs.iter().map(|o| (o.monitor.handle().name(), o.configured)).collect();
+
+        for (source, output) in step_targets(self.config.layout, &names, self.sources.len(), monitor) {
+            let ctx = &outputs[output].context;
+            let new_source = step(&self.sources[source], ctx)?;
+            let old_source = std::mem::replace(&mut self.sources[source], new_source);
+            self.sources[source].start_transition(Some(old_source), &self.config.transition, &self.interaction, ctx);
         }
+        Ok(())
+    }
 
-        match self.config.layout {
-            Layout::Clone | Layout::Span => {
-                let ctx = &configured_contexts[0].context;
-                let new_source = self.sources[0].next(ctx)?;
-                let old_source = std::mem::replace(&mut self.sources[0], new_source);
-                self.sources[0].start_transition(Some(old_source), self.transition_duration, ctx, self.transition_type);
-            },
-            Layout::Independent => {
-                for (i, scene_output) in configured_contexts.iter().enumerate() {
-                    if i < self.sources.len() {
-                        let ctx = &scene_output.context;
-                        let new_source = self.sources[i].next(ctx)?;
-                        let old_source = std::mem::replace(&mut self.sources[i], new_source);
-                        self.sources[i].start_transition(
-                            Some(old_source),
-                            self.transition_duration,
-                            ctx,
-                            self.transition_type,
-                        );
-                    }
-                }
-            },
+    /// Points the scene at a new media path, transitioning from the current sources
+    ///
+    /// Every replacement source is built before anything is swapped, so a path whose
+    /// media can't be opened leaves the current wallpaper untouched. An empty directory
+    /// switches to the scene's fallback color.
+    pub fn set_path(&mut self, path: PathBuf) -> Result<()> {
+        let Some(first_configured) = self.outputs.values().find(|o| o.configured) else {
+            self.config.path = Some(path);
+            return Ok(());
+        };
+
+        let new_sources = (0..self.sources.len())
+            .map(|i| {
+                // Each source is built on its own output, like `step_sources` does, while that's configured
+                let output = self.outputs.values().nth(i).filter(|o| o.configured);
+                let ctx = &output.unwrap_or(first_configured).context;
+                let mut source = SourceType::Media(Box::new(MediaSource::from_path(
+                    &path,
+                    None,
+                    ctx,
+                    self.media_options(),
+                )?));
+                source.load(ctx)?;
+                Ok((source, ctx))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, (new_source, ctx)) in new_sources.into_iter().enumerate() {
+            let old_source = std::mem::replace(&mut self.sources[i], new_source);
+            self.sources[i].start_transition(Some(old_source), &self.config.transition, &self.interaction, ctx);
         }
+
+        self.config.path = Some(path);
         Ok(())
     }
 
-    pub fn rotation_interval(&self) -> Duration {
-        self.rotation_interval
+    /// Media each source is showing, keyed by monitor name in independent layouts
+    /// and by [`SHARED_SOURCE`] otherwise
+    pub fn saved_media(&self) -> BTreeMap<String, PathBuf> {
+        self.outputs
+            .values()
+            .zip(&self.sources)
+            .filter_map(|(output, source)| {
+                let path = source.media_path()?;
+                Some((source_key(self.config.layout, output).to_string(), path.to_path_buf()))
+            })
+            .collect()
+    }
+
+    /// Media to reopen instead of a random pick when sources are first created
+    pub fn restore_media(&mut self, saved: BTreeMap<String, PathBuf>) {
+        self.restored = saved;
+    }
+
+    /// Time until the next rotation, sampled fresh each time when jitter is configured
+    pub fn next_rotation_interval(&self) -> Duration {
+        jittered_interval(self.rotation_interval, self.rotation_jitter, &mut rand::rng())
+    }
+
+    /// Starts the rotation cycle, returning the time until its first rotation
+    pub fn schedule_rotation(&mut self) -> Duration {
+        let interval = self.next_rotation_interval();
+        self.rotation_start = Some(Instant::now() + interval);
+        self.rotation_deadlines.clear();
+        interval
+    }
+
+    /// Re-arms every source whose rotation is due and returns the monitors to advance,
+    /// `None` standing for the shared source of clone and span layouts
+    pub fn take_due_rotations(&mut self) -> Vec<Option<String>> {
+        let now = Instant::now();
+        self.sync_rotation_deadlines(now);
+        if self.rotation_deadlines.is_empty() {
+            // No independent sources yet, so the cycle starts over for when they exist
+            self.rotation_start = Some(now + self.next_rotation_interval());
+            return Vec::new();
+        }
+
+        let due: Vec<_> = self
+            .rotation_deadlines
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &due {
+            let deadline = now + self.next_rotation_interval();
+            self.rotation_deadlines.insert(key.clone(), deadline);
+        }
+        due.into_iter().map(|key| (key != SHARED_SOURCE).then_some(key)).collect()
+    }
+
+    /// Gives each rotating source without a deadline its first one, staggered by
+    /// `independent_offset` in independent layouts, and drops those of removed outputs
+    fn sync_rotation_deadlines(&mut self, now: Instant) {
+        let Some(start) = self.rotation_start else {
+            return;
+        };
+
+        let keys: Vec<String> = match self.config.layout {
+            Layout::Clone | Layout::Span => vec![SHARED_SOURCE.to_string()],
+            Layout::Independent => self
+                .outputs
+                .values()
+                .filter(|output| output.configured)
+                .take(self.sources.len())
+                .map(|output| output.monitor.handle().name().to_string())
+                .collect(),
+        };
+        self.rotation_deadlines.retain(|key, _| keys.contains(key));
+
+        let offsets = match self.config.layout {
+            Layout::Independent => stagger_offsets(
+                self.config.independent_offset,
+                self.rotation_interval,
+                keys.len(),
+                &mut rand::rng(),
+            ),
+            Layout::Clone | Layout::Span => vec![Duration::ZERO; keys.len()],
+        };
+        // Outputs that show up after the cycle started count their offset from now
+        let first = start.max(now);
+        for (key, offset) in keys.into_iter().zip(offsets) {
+            self.rotation_deadlines.entry(key).or_insert(first + offset);
+        }
+    }
+
+    /// Time left until the next source rotates, `None` if the scene doesn't rotate
+    pub fn rotation_remaining(&self) -> Option<Duration> {
+        let start = self.rotation_start?;
+        let next = self.rotation_deadlines.values().min().copied().unwrap_or(start);
+        Some(next.saturating_duration_since(Instant::now()))
+    }
+
+    /// Progress of the first source's transition, `None` when nothing is transitioning
+    pub fn transition_progress(&self) -> Option<f32> {
+        match self.sources.first()?.state() {
+            RenderState::Transitioning(transition) => Some(transition.progress()),
+            _ => None,
+        }
     }
 
     pub fn is_media(&self) -> bool {
@@ -327,4 +781,124 @@ impl Scene {
     pub fn has_outputs(&self) -> bool {
         !self.outputs.is_empty()
     }
+
+    /// Outputs this scene currently draws on
+    pub fn wl_outputs(&self) -> impl Iterator<Item = &WlOutput> {
+        self.outputs.keys()
+    }
+
+    /// Whether this scene draws on the monitor called `name`
+    pub fn has_monitor(&self, name: &str) -> bool {
+        self.outputs.values().any(|output| output.monitor.handle().name() == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SceneConfig;
+
+    #[test]
+    fn test_step_targets_independent_monitor() {
+        let outputs = [("DP-1", true), ("HDMI-A-1", true)];
+
+        assert_eq!(
+            step_targets(Layout::Independent, &outputs, 2, Some("HDMI-A-1")),
+            vec![(1, 1)]
+        );
+        assert_eq!(
+            step_targets(Layout::Independent, &outputs, 2, Some("DP-1")),
+            vec![(0, 0)]
+        );
+        assert_eq!(
+            step_targets(Layout::Independent, &outputs, 2, None),
+            vec![(0, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_step_targets_unknown_monitor() {
+        let outputs = [("DP-1", true), ("HDMI-A-1", true)];
+
+        assert!(step_targets(Layout::Independent, &outputs, 2, Some("eDP-1")).is_empty());
+        assert!(step_targets(Layout::Clone, &outputs, 1, Some("eDP-1")).is_empty());
+    }
+
+    #[test]
+    fn test_step_targets_shared_source() {
+        let outputs = [("DP-1", true), ("HDMI-A-1", true)];
+
+        assert_eq!(step_targets(Layout::Span, &outputs, 1, Some("HDMI-A-1")), vec![(0, 0)]);
+        assert_eq!(step_targets(Layout::Clone, &outputs, 1, None), vec![(0, 0)]);
+        assert!(step_targets(Layout::Clone, &[], 1, None).is_empty());
+    }
+
+    #[test]
+    fn test_stagger_offsets_even() {
+        let interval = Duration::from_secs(60);
+        let offsets = stagger_offsets(OffsetMode::Even, interval, 4, &mut rand::rng());
+        assert_eq!(offsets, [0, 15, 30, 45].map(Duration::from_secs));
+
+        let offsets = stagger_offsets(OffsetMode::Even, interval, 2, &mut rand::rng());
+        assert_eq!(offsets, [Duration::ZERO, Duration::from_secs(30)]);
+        assert_eq!(
+            stagger_offsets(OffsetMode::Even, interval, 1, &mut rand::rng()),
+            [Duration::ZERO]
+        );
+    }
+
+    #[test]
+    fn test_stagger_offsets_none_and_random() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(
+            stagger_offsets(OffsetMode::None, interval, 3, &mut rand::rng()),
+            vec![Duration::ZERO; 3]
+        );
+
+        let offsets = stagger_offsets(OffsetMode::Random, interval, 8, &mut rand::rng());
+        assert_eq!(offsets.len(), 8);
+        assert!(offsets.iter().all(|offset| *offset < interval));
+    }
+
+    #[test]
+    fn test_step_targets_skips_outputs_without_source() {
+        let outputs = [("DP-1", true), ("HDMI-A-1", true)];
+        assert_eq!(step_targets(Layout::Independent, &outputs, 1, None), vec![(0, 0)]);
+        assert!(step_targets(Layout::Independent, &outputs, 1, Some("HDMI-A-1")).is_empty());
+    }
+
+    #[test]
+    fn test_step_targets_keeps_indices_past_unconfigured_outputs() {
+        let outputs = [("eDP-1", false), ("DP-1", true), ("HDMI-A-1", true)];
+
+        assert_eq!(
+            step_targets(Layout::Independent, &outputs, 3, Some("HDMI-A-1")),
+            vec![(2, 2)]
+        );
+        assert_eq!(
+            step_targets(Layout::Independent, &outputs, 3, None),
+            vec![(1, 1), (2, 2)]
+        );
+        assert!(step_targets(Layout::Independent, &outputs, 3, Some("eDP-1")).is_empty());
+
+        assert_eq!(step_targets(Layout::Clone, &outputs, 1, None), vec![(0, 1)]);
+        assert!(step_targets(Layout::Span, &outputs, 1, Some("eDP-1")).is_empty());
+    }
+
+    fn scene(startup_transition: bool) -> Scene {
+        let config = MergedSceneConfig::from_scene(&SceneConfig::default(), &TransitionConfig::default());
+        Scene::new(
+            config,
+            SourceKind::Color,
+            SourceConfigs::default(),
+            false,
+            startup_transition,
+        )
+    }
+
+    #[test]
+    fn test_opening_transition_follows_startup_flag() {
+        assert!(scene(true).opening_transition().is_some());
+        assert!(scene(false).opening_transition().is_none());
+    }
 }
@@ -0,0 +1,100 @@
+use crate::sources::types::UvRect;
+
+/// Logical placement of an output within the compositor's global space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl OutputRegion {
+    pub fn new(position: (i32, i32), size: (u32, u32)) -> Self {
+        Self {
+            x: position.0,
+            y: position.1,
+            width: size.0,
+            height: size.1,
+        }
+    }
+}
+
+/// Maps each output onto its slice of the bounding canvas covering all `regions`
+///
+/// Rects are returned in the same order as `regions`.
+pub fn span_uv_rects(regions: &[OutputRegion]) -> Vec<UvRect> {
+    let Some(min_x) = regions.iter().map(|r| r.x).min() else {
+        return Vec::new();
+    };
+    let min_y = regions.iter().map(|r| r.y).min().unwrap_or(0);
+    let max_x = regions.iter().map(|r| r.x + r.width as i32).max().unwrap_or(0);
+    let max_y = regions.iter().map(|r| r.y + r.height as i32).max().unwrap_or(0);
+
+    let canvas_width = (max_x - min_x).max(1) as f32;
+    let canvas_height = (max_y - min_y).max(1) as f32;
+
+    regions
+        .iter()
+        .map(|r| {
+            UvRect::new(
+                (r.x - min_x) as f32 / canvas_width,
+                (r.y - min_y) as f32 / canvas_height,
+                r.width as f32 / canvas_width,
+                r.height as f32 / canvas_height,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn assert_rect_eq(actual: UvRect, expected: UvRect) {
+        assert!((actual.x - expected.x).abs() < 1e-6, "{actual:?} != {expected:?}");
+        assert!((actual.y - expected.y).abs() < 1e-6, "{actual:?} != {expected:?}");
+        assert!(
+            (actual.width - expected.width).abs() < 1e-6,
+            "{actual:?} != {expected:?}"
+        );
+        assert!(
+            (actual.height - expected.height).abs() < 1e-6,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_span_empty() {
+        assert!(span_uv_rects(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_span_single_output_is_full() {
+        let rects = span_uv_rects(&[OutputRegion::new((0, 0), (1920, 1080))]);
+        assert_rect_eq(rects[0], UvRect::FULL);
+    }
+
+    #[test]
+    fn test_span_two_side_by_side() {
+        let rects = span_uv_rects(&[
+            OutputRegion::new((0, 0), (1920, 1080)),
+            OutputRegion::new((1920, 0), (1920, 1080)),
+        ]);
+
+        assert_rect_eq(rects[0], UvRect::new(0.0, 0.0, 0.5, 1.0));
+        assert_rect_eq(rects[1], UvRect::new(0.5, 0.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn test_span_uneven_and_offset() {
+        let rects = span_uv_rects(&[
+            OutputRegion::new((-1280, 360), (1280, 720)),
+            OutputRegion::new((0, 0), (2560, 1440)),
+        ]);
+
+        assert_rect_eq(rects[0], UvRect::new(0.0, 0.25, 1.0 / 3.0, 0.5));
+        assert_rect_eq(rects[1], UvRect::new(1.0 / 3.0, 0.0, 2.0 / 3.0, 1.0));
+    }
+}
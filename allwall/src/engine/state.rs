@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+const STATE_FILE: &str = "state.json";
+
+/// Key for a source shared by every output of a scene, as in clone and span layouts
+pub const SHARED_SOURCE: &str = "*";
+
+/// Media each scene was showing, saved so a restart picks up where it left off
+///
+/// Scenes are stored in config order, each mapping a monitor name (or
+/// [`SHARED_SOURCE`]) to the file it last displayed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedState {
+    #[serde(default)]
+    pub scenes: Vec<BTreeMap<String, PathBuf>>,
+}
+
+impl SavedState {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Saved paths for the scene at `index`, empty if it wasn't saved
+    pub fn scene(&self, index: usize) -> BTreeMap<String, PathBuf> {
+        self.scenes.get(index).cloned().unwrap_or_default()
+    }
+
+    /// Reads the state file, falling back to an empty state if it's missing or unreadable
+    pub fn load() -> Self {
+        let Some(path) = xdg::BaseDirectories::with_prefix("allwall").find_state_file(STATE_FILE) else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path)
+            .map_err(Error::from)
+            .and_then(|json| Self::from_json(&json))
+        {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Ignoring saved state at {}: {}", path.display(), e);
+                Self::default()
+            },
+        }
+    }
+
+    /// Writes the state file under `$XDG_STATE_HOME/allwall`
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = xdg::BaseDirectories::with_prefix("allwall").place_state_file(STATE_FILE)?;
+        write_atomically(&path, &self.to_json()?)?;
+        Ok(path)
+    }
+}
+
+/// Writes through a temporary file so a crash mid-write can't leave truncated JSON behind
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    fn sample_state() -> SavedState {
+        SavedState {
+            scenes: vec![
+                BTreeMap::from([(SHARED_SOURCE.to_string(), PathBuf::from("/walls/forest.png"))]),
+                BTreeMap::from([
+                    ("DP-1".to_string(), PathBuf::from("/walls/left.jpg")),
+                    ("HDMI-A-1".to_string(), PathBuf::from("/walls/right.mp4")),
+                ]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_saved_state_json_roundtrip() {
+        let state = sample_state();
+        let json = state.to_json().unwrap();
+        assert_eq!(SavedState::from_json(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_saved_state_json_shape() {
+        let json = sample_state().to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["scenes"][0]["*"], "/walls/forest.png");
+        assert_eq!(value["scenes"][1]["HDMI-A-1"], "/walls/right.mp4");
+    }
+
+    #[test]
+    fn test_saved_state_rejects_garbage() {
+        assert!(SavedState::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_saved_state_missing_scenes_is_empty() {
+        let state = SavedState::from_json("{}").unwrap();
+        assert!(state.scenes.is_empty());
+        assert!(state.scene(3).is_empty());
+    }
+
+    #[test]
+    fn test_saved_state_scene_lookup() {
+        let state = sample_state();
+        assert_eq!(state.scene(1).get("DP-1"), Some(&PathBuf::from("/walls/left.jpg")));
+    }
+
+    #[test]
+    fn test_write_atomically_replaces_file() {
+        let dir = ScratchDir::new("state-replace");
+        let path = dir.join(STATE_FILE);
+
+        write_atomically(&path, "first").unwrap();
+        write_atomically(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
@@ -1,18 +1,29 @@
 use smithay_client_toolkit::{
     compositor::CompositorHandler,
-    delegate_compositor, delegate_layer, delegate_output, delegate_registry,
+    delegate_compositor, delegate_layer, delegate_output, delegate_pointer, delegate_registry, delegate_seat,
     output::{OutputHandler, OutputState},
-    reexports::client::{
-        self, Connection, QueueHandle,
-        protocol::{wl_output, wl_surface},
+    reexports::{
+        client::{
+            self, Connection, Dispatch, QueueHandle, event_created_child,
+            protocol::{wl_output, wl_pointer, wl_seat, wl_surface},
+        },
+        protocols_wlr::foreign_toplevel::v1::client::{
+            zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+            zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+        },
     },
     registry::{ProvidesRegistryState, RegistryState},
+    seat::{
+        Capability, SeatHandler, SeatState,
+        pointer::{PointerEvent, PointerEventKind, PointerHandler},
+    },
     shell::wlr_layer::{self, LayerShellHandler},
 };
 
+use super::{pointer::normalize_position, window::WindowState};
 use crate::{
     engine::Engine,
-    prelude::{info, warn},
+    prelude::{debug, info, warn},
 };
 
 impl CompositorHandler for Engine {
@@ -20,18 +31,29 @@ impl CompositorHandler for Engine {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _surface: &wl_surface::WlSurface,
-        _new_factor: i32,
+        surface: &wl_surface::WlSurface,
+        new_factor: i32,
     ) {
+        for scene in &mut self.scenes {
+            scene.on_scale_factor_changed(surface, new_factor);
+        }
+        self.wake();
     }
 
+    /// The buffer transform the compositor would like for `surface`, not the output's
+    /// rotation. Frames are drawn upright and left for the compositor to turn, so the
+    /// hint only saves it a pass and isn't acted on.
     fn transform_changed(
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
         _surface: &wl_surface::WlSurface,
-        _new_transform: wl_output::Transform,
+        new_transform: wl_output::Transform,
     ) {
+        debug!(
+            "Compositor prefers buffer transform {:?}, drawing upright",
+            new_transform
+        );
     }
 
     fn frame(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _surface: &wl_surface::WlSurface, _time: u32) {}
@@ -118,21 +140,21 @@ impl OutputHandler for Engine {
         let output_name = info.name.as_deref().unwrap_or("unknown");
         info!("New output detected: {}", output_name);
 
-        for scene in &mut self.scenes {
-            if scene.should_handle_output(output_name) {
-                if let Err(e) = scene.on_output_added(
-                    output.clone(),
-                    &info,
-                    self.gpu.clone(),
-                    conn,
-                    &self.compositor_state,
-                    &self.layer_shell,
-                    qh,
-                ) {
-                    warn!("Failed to add output '{}' to scene: {}", output_name, e);
-                }
+        // Patterns in different scenes can overlap, the first listed scene takes the output
+        if let Some(scene) = self.scenes.iter_mut().find(|scene| scene.should_handle_output(output_name)) {
+            if let Err(e) = scene.on_output_added(
+                output.clone(),
+                &info,
+                self.gpu.clone(),
+                conn,
+                &self.compositor_state,
+                &self.layer_shell,
+                qh,
+            ) {
+                warn!("Failed to add output '{}' to scene: {}", output_name, e);
             }
         }
+        self.wake();
     }
 
     fn update_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, output: wl_output::WlOutput) {
@@ -145,6 +167,7 @@ impl OutputHandler for Engine {
         for scene in &mut self.scenes {
             scene.on_output_updated(&output, &info);
         }
+        self.wake();
     }
 
     fn output_destroyed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, output: wl_output::WlOutput) {
@@ -155,7 +178,136 @@ impl OutputHandler for Engine {
     }
 }
 
+impl SeatHandler for Engine {
+    fn seat_state(&mut self) -> &mut SeatState {
+        &mut self.seat_state
+    }
+
+    fn new_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: wl_seat::WlSeat) {}
+
+    fn new_capability(
+        &mut self,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+        seat: wl_seat::WlSeat,
+        capability: Capability,
+    ) {
+        if capability != Capability::Pointer || !self.interactive || self.pointer.is_attached() {
+            return;
+        }
+
+        match self.seat_state.get_pointer(qh, &seat) {
+            Ok(pointer) => {
+                info!("Tracking pointer for interactive sources");
+                self.pointer.attach(pointer);
+            },
+            Err(e) => warn!("Failed to get pointer: {}", e),
+        }
+    }
+
+    fn remove_capability(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _seat: wl_seat::WlSeat,
+        capability: Capability,
+    ) {
+        if capability == Capability::Pointer {
+            self.pointer.detach();
+            self.interaction_state.mouse = None;
+        }
+    }
+
+    fn remove_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: wl_seat::WlSeat) {}
+}
+
+impl PointerHandler for Engine {
+    fn pointer_frame(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _pointer: &wl_pointer::WlPointer,
+        events: &[PointerEvent],
+    ) {
+        for event in events {
+            match event.kind {
+                PointerEventKind::Enter { .. } | PointerEventKind::Motion { .. } => {
+                    self.interaction_state.mouse = self
+                        .scenes
+                        .iter()
+                        .find_map(|scene| scene.surface_size(&event.surface))
+                        .map(|size| normalize_position(event.position, size));
+                },
+                PointerEventKind::Leave { .. } => self.interaction_state.mouse = None,
+                _ => {},
+            }
+        }
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for Engine {
+    fn event(
+        engine: &mut Self,
+        _manager: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                engine.windows.pending_mut(&toplevel);
+            },
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                warn!("Compositor stopped reporting windows");
+                engine.windows.finish();
+                engine.on_windows_changed();
+            },
+            _ => {},
+        }
+    }
+
+    event_created_child!(Engine, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for Engine {
+    fn event(
+        engine: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                engine.windows.pending_mut(handle).state = WindowState::from_raw(&state);
+            },
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output } => {
+                engine.windows.pending_mut(handle).outputs.push(output);
+            },
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                engine.windows.pending_mut(handle).outputs.retain(|o| *o != output);
+            },
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                engine.windows.commit(handle);
+                engine.on_windows_changed();
+            },
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                engine.windows.remove(handle);
+                handle.destroy();
+                engine.on_windows_changed();
+            },
+            _ => {},
+        }
+    }
+}
+
 delegate_compositor!(Engine);
 delegate_layer!(Engine);
 delegate_output!(Engine);
+delegate_pointer!(Engine);
 delegate_registry!(Engine);
+delegate_seat!(Engine);
@@ -3,4 +3,4 @@ mod pointer;
 mod window;
 
 pub use pointer::PointerTracker;
-pub use window::{WindowInfo, WindowTracker};
+pub use window::{WindowInfo, WindowState, WindowTracker, should_suspend};
@@ -1,8 +1,28 @@
-pub struct PointerTracker;
+use smithay_client_toolkit::reexports::client::protocol::wl_pointer::WlPointer;
+
+/// Holds the seat's pointer while the wallpaper accepts input
+pub struct PointerTracker {
+    pointer: Option<WlPointer>,
+}
 
 impl PointerTracker {
     pub fn new() -> Self {
-        Self
+        Self { pointer: None }
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.pointer.is_some()
+    }
+
+    pub fn attach(&mut self, pointer: WlPointer) {
+        self.detach();
+        self.pointer = Some(pointer);
+    }
+
+    pub fn detach(&mut self) {
+        if let Some(pointer) = self.pointer.take() {
+            pointer.release();
+        }
     }
 }
 
@@ -11,3 +31,31 @@ impl Default for PointerTracker {
         Self::new()
     }
 }
+
+/// Surface-local pointer position as a fraction of the surface, clamped to `0.0..=1.0`
+pub fn normalize_position(position: (f64, f64), surface_size: (u32, u32)) -> (f32, f32) {
+    let axis = |value: f64, size: u32| (value / f64::from(size.max(1))).clamp(0.0, 1.0) as f32;
+    (axis(position.0, surface_size.0), axis(position.1, surface_size.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_position_corners_and_center() {
+        assert_eq!(normalize_position((0.0, 0.0), (1920, 1080)), (0.0, 0.0));
+        assert_eq!(normalize_position((960.0, 540.0), (1920, 1080)), (0.5, 0.5));
+        assert_eq!(normalize_position((1920.0, 1080.0), (1920, 1080)), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_normalize_position_clamps_outside_surface() {
+        assert_eq!(normalize_position((-5.0, 2000.0), (1920, 1080)), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_normalize_position_guards_empty_surface() {
+        assert_eq!(normalize_position((0.5, 0.0), (0, 0)), (0.5, 0.0));
+    }
+}
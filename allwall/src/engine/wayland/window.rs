@@ -1,24 +1,198 @@
+use std::collections::HashMap;
+
+use smithay_client_toolkit::reexports::{
+    client::protocol::wl_output::WlOutput,
+    protocols_wlr::foreign_toplevel::v1::client::{
+        zwlr_foreign_toplevel_handle_v1::{State, ZwlrForeignToplevelHandleV1},
+        zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+    },
+};
+
+/// Window states reported by the compositor that matter for hiding the wallpaper
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowState {
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+    pub activated: bool,
+}
+
+impl WindowState {
+    /// Parses the `state` event's array of native-endian `u32` state values, ignoring unknown ones
+    pub fn from_raw(raw: &[u8]) -> Self {
+        let mut state = Self::default();
+        for chunk in raw.chunks_exact(4) {
+            let value = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            match State::try_from(value) {
+                Ok(State::Maximized) => state.maximized = true,
+                Ok(State::Minimized) => state.minimized = true,
+                Ok(State::Fullscreen) => state.fullscreen = true,
+                Ok(State::Activated) => state.activated = true,
+                _ => {},
+            }
+        }
+        state
+    }
+
+    /// Whether a window in this state hides the whole wallpaper of the outputs it's on
+    ///
+    /// Only the focused window counts, since the protocol also reports windows on hidden
+    /// workspaces and gives no way to tell those apart. A covering window left behind when
+    /// focus moves to another output therefore stops counting as well.
+    pub fn covers_output(self) -> bool {
+        self.activated && !self.minimized && (self.fullscreen || self.maximized)
+    }
+}
+
+/// Whether an output showing windows in `states` has its wallpaper fully hidden
+pub fn should_suspend(states: impl IntoIterator<Item = WindowState>) -> bool {
+    states.into_iter().any(WindowState::covers_output)
+}
+
+/// What the compositor last reported about one toplevel window
+#[derive(Debug, Clone, Default)]
 pub struct WindowInfo {
-    pub x: f32,
-    pub y: f32,
-    pub width: u32,
-    pub height: u32,
+    pub state: WindowState,
+    pub outputs: Vec<WlOutput>,
 }
 
-pub struct WindowTracker;
+/// Toplevel windows announced through `wlr-foreign-toplevel-management`
+///
+/// Updates to a window are staged until its `done` event, so readers never see
+/// a half-applied state change.
+pub struct WindowTracker {
+    manager: Option<ZwlrForeignToplevelManagerV1>,
+    windows: HashMap<ZwlrForeignToplevelHandleV1, WindowInfo>,
+    pending: HashMap<ZwlrForeignToplevelHandleV1, WindowInfo>,
+}
 
 impl WindowTracker {
-    pub fn new() -> Self {
-        Self
+    pub fn new(manager: Option<ZwlrForeignToplevelManagerV1>) -> Self {
+        Self {
+            manager,
+            windows: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Whether the compositor offers the protocol and window states are being tracked
+    pub fn is_active(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Staged info for `handle`, starting from what was last committed
+    pub fn pending_mut(&mut self, handle: &ZwlrForeignToplevelHandleV1) -> &mut WindowInfo {
+        self.pending
+            .entry(handle.clone())
+            .or_insert_with(|| self.windows.get(handle).cloned().unwrap_or_default())
+    }
+
+    /// Applies the changes staged for `handle`
+    pub fn commit(&mut self, handle: &ZwlrForeignToplevelHandleV1) {
+        if let Some(info) = self.pending.remove(handle) {
+            self.windows.insert(handle.clone(), info);
+        }
+    }
+
+    pub fn remove(&mut self, handle: &ZwlrForeignToplevelHandleV1) {
+        self.pending.remove(handle);
+        self.windows.remove(handle);
     }
 
-    pub fn window_at_point(&self, _x: f32, _y: f32) -> Option<WindowInfo> {
-        None
+    /// Forgets every window once the compositor stops sending toplevel events
+    pub fn finish(&mut self) {
+        self.manager = None;
+        self.windows.clear();
+        self.pending.clear();
+    }
+
+    /// Whether the focused window is fullscreen or maximized on `output`
+    pub fn output_covered(&self, output: &WlOutput) -> bool {
+        should_suspend(
+            self.windows
+                .values()
+                .filter(|window| window.outputs.contains(output))
+                .map(|window| window.state),
+        )
     }
 }
 
 impl Default for WindowTracker {
     fn default() -> Self {
-        Self::new()
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(states: &[State]) -> Vec<u8> {
+        states.iter().flat_map(|state| (*state as u32).to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn test_window_state_from_raw() {
+        let state = WindowState::from_raw(&raw(&[State::Activated, State::Fullscreen]));
+        assert_eq!(
+            state,
+            WindowState {
+                fullscreen: true,
+                activated: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_window_state_from_raw_ignores_unknown_values() {
+        let mut bytes = raw(&[State::Maximized]);
+        bytes.extend_from_slice(&99u32.to_ne_bytes());
+        assert!(WindowState::from_raw(&bytes).maximized);
+    }
+
+    #[test]
+    fn test_should_suspend_for_fullscreen_or_maximized() {
+        let fullscreen = WindowState {
+            fullscreen: true,
+            activated: true,
+            ..Default::default()
+        };
+        let maximized = WindowState {
+            maximized: true,
+            activated: true,
+            ..Default::default()
+        };
+
+        assert!(should_suspend([WindowState::default(), fullscreen]));
+        assert!(should_suspend([maximized]));
+    }
+
+    #[test]
+    fn test_should_not_suspend_for_floating_or_minimized() {
+        let minimized_fullscreen = WindowState {
+            fullscreen: true,
+            minimized: true,
+            activated: true,
+            ..Default::default()
+        };
+
+        assert!(!should_suspend([]));
+        assert!(!should_suspend([WindowState::default(), minimized_fullscreen]));
+    }
+
+    #[test]
+    fn test_should_not_suspend_for_inactive_windows() {
+        // Such as a fullscreen window on a workspace that isn't shown
+        let hidden_fullscreen = WindowState {
+            fullscreen: true,
+            ..Default::default()
+        };
+        let hidden_maximized = WindowState {
+            maximized: true,
+            ..Default::default()
+        };
+
+        assert!(!should_suspend([hidden_fullscreen, hidden_maximized]));
     }
 }
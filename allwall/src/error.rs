@@ -41,6 +41,9 @@ pub enum Error {
     #[error(transparent)]
     Bincode(#[from] bincode::Error),
 
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
     #[error("Daemon is not running")]
     DaemonNotRunning,
 
@@ -1,4 +1,6 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
 
 pub mod cli;
 pub mod config;
@@ -19,6 +21,79 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Log output format
+    #[arg(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    pub log_format: LogFormat,
+
+    /// Config file to use instead of $XDG_CONFIG_HOME/allwall/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// IPC socket to use instead of $XDG_RUNTIME_DIR/allwall/allwall.sock, so several daemons can run at once
+    #[arg(long, global = true)]
+    pub socket: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: cli::Commands,
 }
+
+/// How log lines are written to stderr
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+
+    /// One JSON object per line, for journald and other log collectors
+    Json,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_defaults_to_text() {
+        let cli = Cli::try_parse_from(["allwall", "version"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Text);
+    }
+
+    #[test]
+    fn test_log_format_parses_both_values() {
+        let cli = Cli::try_parse_from(["allwall", "--log-format", "text", "version"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Text);
+
+        let cli = Cli::try_parse_from(["allwall", "version", "--log-format", "json"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn test_config_flag_is_global() {
+        let cli = Cli::try_parse_from(["allwall", "--config", "/tmp/a.toml", "version"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/a.toml")));
+
+        let cli = Cli::try_parse_from(["allwall", "validate", "--config", "/tmp/b.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/b.toml")));
+
+        let cli = Cli::try_parse_from(["allwall", "version"]).unwrap();
+        assert_eq!(cli.config, None);
+    }
+
+    #[test]
+    fn test_socket_flag_is_global() {
+        let cli = Cli::try_parse_from(["allwall", "--socket", "/tmp/a.sock", "run"]).unwrap();
+        assert_eq!(cli.socket, Some(PathBuf::from("/tmp/a.sock")));
+
+        let cli = Cli::try_parse_from(["allwall", "next", "--socket", "/tmp/b.sock"]).unwrap();
+        assert_eq!(cli.socket, Some(PathBuf::from("/tmp/b.sock")));
+
+        let cli = Cli::try_parse_from(["allwall", "next"]).unwrap();
+        assert_eq!(cli.socket, None);
+    }
+
+    #[test]
+    fn test_log_format_rejects_unknown_value() {
+        assert!(Cli::try_parse_from(["allwall", "--log-format", "xml", "version"]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use allwall::{
-    Cli,
+    Cli, LogFormat,
     cli::{AllwallCommand, Commands},
 };
 use clap::Parser;
@@ -10,15 +10,28 @@ async fn main() -> allwall::prelude::Result<()> {
     let cli = Cli::parse();
 
     let log_level = match cli.verbose {
-        0 => "warn",
-        1 => "info",
+        // Commands other than the daemon report their results at info
+        0 if matches!(cli.command, Commands::Run(_)) => "warn",
+        0 | 1 => "info",
         2 => "debug",
         _ => "trace",
     };
 
+    let (text_layer, json_layer) = match cli.log_format {
+        LogFormat::Text => (
+            Some(tracing_subscriber::fmt::layer().with_writer(std::io::stderr)),
+            None,
+        ),
+        LogFormat::Json => (
+            None,
+            Some(tracing_subscriber::fmt::layer().json().with_writer(std::io::stderr)),
+        ),
+    };
+
     tracing_subscriber::registry()
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| format!("allwall={}", log_level).into()))
-        .with(tracing_subscriber::fmt::layer())
+        .with(text_layer)
+        .with(json_layer)
         .init();
 
     if let Err(e) = gstreamer::init() {
@@ -27,12 +40,63 @@ async fn main() -> allwall::prelude::Result<()> {
     }
 
     match cli.command {
-        Commands::Run(cmd) => cmd.execute().await?,
+        Commands::Run(mut cmd) => {
+            cmd.config = cli.config;
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
         Commands::Version(cmd) => cmd.execute().await?,
         Commands::Completions(cmd) => cmd.execute().await?,
-        Commands::Next(cmd) => cmd.execute().await?,
-        Commands::Prev(cmd) => cmd.execute().await?,
-        Commands::Fps(cmd) => cmd.execute().await?,
+        Commands::Next(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Prev(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Fps(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Set(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Goto(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Timing(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Overlay(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Quit(mut cmd) => {
+            cmd.socket = cli.socket;
+            cmd.execute().await?
+        },
+        Commands::Validate(mut cmd) => {
+            cmd.config = cli.config;
+            cmd.execute().await?
+        },
+        Commands::Render(mut cmd) => {
+            cmd.config = cli.config;
+            cmd.execute().await?
+        },
+        Commands::Bench(mut cmd) => {
+            cmd.config = cli.config;
+            cmd.execute().await?
+        },
+        Commands::ListMonitors(cmd) => cmd.execute().await?,
+        Commands::List(cmd) => cmd.execute().await?,
+        Commands::GenerateConfig(mut cmd) => {
+            cmd.config = cli.config;
+            cmd.execute().await?
+        },
     }
 
     Ok(())
@@ -0,0 +1,219 @@
+use std::iter::once;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        INDICES, create_index_buffer, create_pipeline, create_uniform_binds, create_vertex_buffer, types::UvRect,
+    },
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub(super) struct FillUniforms {
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub uv_rect: UvRect,
+    pub direction: [f32; 2],
+    pub _padding: [f32; 2],
+}
+
+impl FillUniforms {
+    pub fn solid(color: [f32; 3]) -> Self {
+        Self::gradient(color, color, [1.0, 0.0])
+    }
+
+    pub fn solid_rgba(color: [f32; 4]) -> Self {
+        Self {
+            start_color: color,
+            end_color: color,
+            ..Self::solid([0.0; 3])
+        }
+    }
+
+    pub fn gradient(start: [f32; 3], end: [f32; 3], direction: [f32; 2]) -> Self {
+        Self {
+            start_color: [start[0], start[1], start[2], 1.0],
+            end_color: [end[0], end[1], end[2], 1.0],
+            uv_rect: UvRect::FULL,
+            direction,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Draws a full-surface quad filled from [`FillUniforms`], shared by the color and gradient sources
+///
+/// The fill is also kept drawn into a texture of the surface's size, for transitions from
+/// and into these sources to sample.
+#[derive(Debug)]
+pub(super) struct FillRenderer {
+    uniforms: FillUniforms,
+    texture: Texture,
+    /// Region the texture was last filled for, to redraw it when spanning changes
+    texture_uv: UvRect,
+
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl FillRenderer {
+    pub fn new(ctx: &Context, uniforms: FillUniforms) -> Result<Self> {
+        let vertex_buffer = create_vertex_buffer(ctx);
+        let index_buffer = create_index_buffer(ctx);
+
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<FillUniforms>() as u64, ctx);
+
+        let render_pipeline = create_pipeline(
+            ctx,
+            &[&uniform_bind_group_layout],
+            &ctx.device().create_shader_module(crate::wgsl!("./shaders/fill.wgsl")),
+            ctx.config(),
+        )?;
+
+        let (width, height) = ctx.size();
+        let renderer = Self {
+            uniforms,
+            texture: Texture::empty_writable_format(ctx, width, height, ctx.config().format),
+            texture_uv: ctx.uv_rect(),
+            vertex_buffer,
+            index_buffer,
+            uniform_buffer,
+            uniform_bind_group,
+            render_pipeline,
+        };
+        renderer.draw(ctx, renderer.texture.view(), wgpu::LoadOp::Clear(wgpu::Color::BLACK));
+
+        Ok(renderer)
+    }
+
+    /// The fill as last drawn into a texture, see [`FillRenderer::refresh`]
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Draws the fill into a new texture when the surface was resized or the spanned
+    /// region changed since the last one was drawn
+    pub fn refresh(&mut self, ctx: &Context) {
+        let (width, height) = ctx.size();
+        let size = self.texture.size();
+        if (size.width, size.height) == (width, height) && self.texture_uv == ctx.uv_rect() {
+            return;
+        }
+
+        self.texture = Texture::empty_writable_format(ctx, width, height, ctx.config().format);
+        self.texture_uv = ctx.uv_rect();
+        self.draw(ctx, self.texture.view(), wgpu::LoadOp::Clear(wgpu::Color::BLACK));
+    }
+
+    pub fn render(&self, ctx: &Context) {
+        let Some(output) = ctx.acquire_frame() else {
+            return;
+        };
+        self.draw(ctx, &output.view(), ctx.clear_op(wgpu::Color::BLACK));
+        output.present();
+    }
+
+    fn draw(&self, ctx: &Context, view: &wgpu::TextureView, load: wgpu::LoadOp<wgpu::Color>) {
+        let uniforms = FillUniforms {
+            uv_rect: ctx.uv_rect(),
+            ..self.uniforms
+        };
+        ctx.queue()
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = ctx.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("fill_render"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        ctx.queue().submit(once(encoder.finish()));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::{
+        config::GeneralConfig,
+        engine::GpuContext,
+        transitions::{FadeOptions, FadeTransition, Transition},
+    };
+
+    fn headless() -> Option<Context> {
+        let Ok(gpu) = pollster::block_on(GpuContext::new(&GeneralConfig::default())) else {
+            warn!("No GPU adapter available, skipping");
+            return None;
+        };
+        Some(Context::headless(Arc::new(gpu), (64, 64)))
+    }
+
+    /// Top-left pixel of the first frame of a fade out of `texture`
+    fn faded_from(ctx: &Context, texture: &Texture) -> [u8; 4] {
+        let fade = FadeTransition::new(
+            Some(texture.clone()),
+            Duration::from_secs(1),
+            FadeOptions::default(),
+            ctx,
+        )
+        .unwrap();
+        fade.render(ctx, texture);
+        ctx.read_pixels().unwrap().get_pixel(0, 0).0
+    }
+
+    #[test]
+    fn test_fill_uniforms_size() {
+        assert_eq!(std::mem::size_of::<FillUniforms>(), std::mem::size_of::<[f32; 16]>());
+    }
+
+    #[test]
+    fn test_fill_uniforms_solid_uses_same_color() {
+        let uniforms = FillUniforms::solid([0.1, 0.2, 0.3]);
+        assert_eq!(uniforms.start_color, [0.1, 0.2, 0.3, 1.0]);
+        assert_eq!(uniforms.end_color, uniforms.start_color);
+    }
+
+    #[test]
+    fn test_texture_holds_the_fill() {
+        let Some(mut ctx) = headless() else { return };
+
+        let mut fill = FillRenderer::new(&ctx, FillUniforms::solid([1.0, 0.0, 0.0])).unwrap();
+        assert_eq!(faded_from(&ctx, fill.texture()), [255, 0, 0, 255]);
+
+        ctx.resize((32, 16));
+        fill.refresh(&ctx);
+        let size = fill.texture().size();
+        assert_eq!((size.width, size.height), (32, 16));
+        assert_eq!(faded_from(&ctx, fill.texture()), [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use super::fill::{FillRenderer, FillUniforms};
+use crate::{
+    config::GradientConfig,
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{BasicSource, InteractionState, RenderState, Source, SourceType, types::surface_rgb},
+    transitions::{FadeTransition, Transition, TransitionConfig, boxed},
+};
+
+#[derive(Debug)]
+pub struct GradientSource {
+    state: RenderState,
+    fill: FillRenderer,
+}
+
+impl GradientSource {
+    pub fn new(ctx: &Context, config: &GradientConfig) -> Result<Self> {
+        debug!("Creating GradientSource");
+
+        let format = ctx.config().format;
+        let fill = FillRenderer::new(
+            ctx,
+            FillUniforms::gradient(
+                surface_rgb(config.start, format),
+                surface_rgb(config.end, format),
+                config.direction(),
+            ),
+        )?;
+
+        Ok(Self {
+            state: RenderState::default(),
+            fill,
+        })
+    }
+}
+
+impl Source for GradientSource {
+    fn texture(&self) -> &Texture {
+        self.fill.texture()
+    }
+
+    fn state(&self) -> &RenderState {
+        &self.state
+    }
+
+    fn load(&mut self, _ctx: &Context) -> Result<()> {
+        debug!("Loading GradientSource");
+        self.state = RenderState::Displaying;
+        Ok(())
+    }
+
+    fn start_transition(
+        &mut self,
+        previous: Option<SourceType>,
+        transition: &TransitionConfig,
+        _interaction: &InteractionState,
+        ctx: &Context,
+    ) {
+        let duration = transition.sampled_duration();
+        debug!(
+            "Starting {:?} transition with duration {:?}",
+            transition.transition_type(),
+            duration
+        );
+        let previous_texture = previous.map(|s| s.texture().clone());
+        let transition: Result<Box<dyn Transition>> =
+            FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed);
+        self.state = RenderState::transitioning(transition);
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if let RenderState::Transitioning(transition) = &mut self.state
+            && transition.update(dt)
+        {
+            self.state = RenderState::Displaying;
+        }
+    }
+
+    fn needs_redraw(&self) -> bool {
+        !matches!(self.state, RenderState::Displaying)
+    }
+}
+
+impl BasicSource for GradientSource {
+    fn render(&mut self, ctx: &Context) {
+        self.fill.refresh(ctx);
+        match &self.state {
+            RenderState::Transitioning(transition) => {
+                transition.render(ctx, self.fill.texture());
+            },
+            _ => {
+                self.fill.render(ctx);
+            },
+        }
+    }
+}
@@ -0,0 +1,6 @@
+mod fill;
+mod gradient;
+mod solid;
+
+pub use gradient::GradientSource;
+pub use solid::ColorSource;
@@ -0,0 +1,40 @@
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    start_color: vec4<f32>,
+    end_color: vec4<f32>,
+    // Slice of the content shown on this surface: xy offset, zw size
+    uv_rect: vec4<f32>,
+    direction: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    return VertexOutput(
+        vec4<f32>(in.position, 1.0),
+        in.tex_coords,
+    );
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let content_coords = uniforms.uv_rect.xy + in.tex_coords * uniforms.uv_rect.zw;
+    let centered = content_coords - vec2<f32>(0.5);
+
+    // Scale so the gradient reaches exactly 0 and 1 at the furthest corners
+    let extent = max(abs(uniforms.direction.x) + abs(uniforms.direction.y), 1e-4);
+    let t = clamp(dot(centered, uniforms.direction) / extent + 0.5, 0.0, 1.0);
+
+    return mix(uniforms.start_color, uniforms.end_color, t);
+}
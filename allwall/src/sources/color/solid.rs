@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use super::fill::{FillRenderer, FillUniforms};
+use crate::{
+    config::ColorConfig,
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        BasicSource, InteractionState, RenderState, Source, SourceType,
+        types::{surface_rgb, surface_rgba},
+    },
+    transitions::{FadeTransition, Transition, TransitionConfig, boxed},
+};
+
+#[derive(Debug)]
+pub struct ColorSource {
+    state: RenderState,
+    fill: FillRenderer,
+}
+
+impl ColorSource {
+    pub fn new(ctx: &Context, config: &ColorConfig) -> Result<Self> {
+        Self::with_uniforms(ctx, FillUniforms::solid(surface_rgb(config.color, ctx.config().format)))
+    }
+
+    /// Solid RGBA fill, used by media sources while their directory is empty
+    pub fn rgba(ctx: &Context, color: [f32; 4]) -> Result<Self> {
+        Self::with_uniforms(ctx, FillUniforms::solid_rgba(surface_rgba(color, ctx.config().format)))
+    }
+
+    fn with_uniforms(ctx: &Context, uniforms: FillUniforms) -> Result<Self> {
+        debug!("Creating ColorSource");
+
+        let fill = FillRenderer::new(ctx, uniforms)?;
+
+        Ok(Self {
+            state: RenderState::default(),
+            fill,
+        })
+    }
+}
+
+impl Source for ColorSource {
+    fn texture(&self) -> &Texture {
+        self.fill.texture()
+    }
+
+    fn state(&self) -> &RenderState {
+        &self.state
+    }
+
+    fn load(&mut self, _ctx: &Context) -> Result<()> {
+        debug!("Loading ColorSource");
+        self.state = RenderState::Displaying;
+        Ok(())
+    }
+
+    fn start_transition(
+        &mut self,
+        previous: Option<SourceType>,
+        transition: &TransitionConfig,
+        _interaction: &InteractionState,
+        ctx: &Context,
+    ) {
+        let duration = transition.sampled_duration();
+        debug!(
+            "Starting {:?} transition with duration {:?}",
+            transition.transition_type(),
+            duration
+        );
+        let previous_texture = previous.map(|s| s.texture().clone());
+        let transition: Result<Box<dyn Transition>> =
+            FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed);
+        self.state = RenderState::transitioning(transition);
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if let RenderState::Transitioning(transition) = &mut self.state
+            && transition.update(dt)
+        {
+            self.state = RenderState::Displaying;
+        }
+    }
+
+    fn needs_redraw(&self) -> bool {
+        !matches!(self.state, RenderState::Displaying)
+    }
+}
+
+impl BasicSource for ColorSource {
+    fn render(&mut self, ctx: &Context) {
+        self.fill.refresh(ctx);
+        match &self.state {
+            RenderState::Transitioning(transition) => {
+                transition.render(ctx, self.fill.texture());
+            },
+            _ => {
+                self.fill.render(ctx);
+            },
+        }
+    }
+}
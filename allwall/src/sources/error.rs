@@ -33,6 +33,39 @@ pub enum SourceError {
     #[error("No images available")]
     NoImagesAvailable,
 
+    #[error("None of the {tried} media files could be opened: {}", .errors.join("; "))]
+    NoReadableMedia { tried: usize, errors: Vec<String> },
+
+    #[error("Media path does not exist: {0}")]
+    PathNotFound(std::path::PathBuf),
+
+    #[error("HEIC/AVIF support not compiled in, rebuild with the `heif` feature: {0}")]
+    HeifUnsupported(std::path::PathBuf),
+
+    #[error("HEIC/AVIF decode failed: {0}")]
+    HeifDecode(String),
+
+    #[error("SVG support not compiled in, rebuild with the `svg` feature: {0}")]
+    SvgUnsupported(std::path::PathBuf),
+
+    #[error("SVG render failed: {0}")]
+    SvgRender(String),
+
+    #[error("Download failed: {0}")]
+    Fetch(String),
+
+    #[error("URL support not compiled in and nothing cached, rebuild with the `net` feature: {0}")]
+    NetUnsupported(String),
+
+    #[error("Unsupported media file: {0}")]
+    UnsupportedMedia(std::path::PathBuf),
+
     #[error("No previous image in history")]
     NoPreviousImage,
+
+    #[error("No media named {file} in {dir}")]
+    NotInDirectory {
+        file: std::path::PathBuf,
+        dir: std::path::PathBuf,
+    },
 }
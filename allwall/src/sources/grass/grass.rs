@@ -6,11 +6,11 @@ use wgpu::{
     AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingResource, BindingType, BlendState, Buffer, BufferBindingType, BufferUsages, Color,
     ColorTargetState, ColorWrites, Extent3d, FilterMode, FragmentState, FrontFace, ImageCopyTexture, ImageDataLayout,
-    IndexFormat, LoadOp, MultisampleState, Operations, Origin3d, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
+    IndexFormat, MultisampleState, Operations, Origin3d, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
     PrimitiveTopology, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
     SamplerBindingType, SamplerDescriptor, ShaderModule, ShaderStages, StoreOp, TextureAspect, TextureDescriptor,
-    TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureViewDescriptor, TextureViewDimension,
-    VertexState, include_wgsl,
+    TextureDimension, TextureFormat, TextureFormatFeatureFlags, TextureSampleType, TextureUsages, TextureView,
+    TextureViewDescriptor, TextureViewDimension, VertexState,
     util::{BufferInitDescriptor, DeviceExt},
 };
 
@@ -19,18 +19,19 @@ use super::{
     types::{BladeInstance, BladeVertex, DirtUniforms, DirtVertex, GrassUniforms},
 };
 use crate::{
+    config::GrassConfig,
     engine::{Context, Texture},
     prelude::*,
     sources::{
         RenderState, Source,
-        types::{Vec2f, Vec2u, Vec3f},
+        types::{Vec2f, Vec2u, Vec3f, surface_rgb},
     },
 };
 
 const WIND_TEXTURE_SIZE: u32 = 256;
-const WIND_STRENGTH: f32 = 0.03;
-const BLADE_HEIGHT_PERCENT: f32 = 0.085;
-const BLADE_SPACING: f32 = 3.5;
+const MIN_BLADE_SPACING: f32 = 1.0;
+/// Ground under the blades, as sRGB
+const DIRT_COLOR: [f32; 3] = [0.35, 0.25, 0.15];
 
 #[derive(Debug)]
 pub struct GrassSource {
@@ -60,11 +61,19 @@ pub struct GrassSource {
     wind_bind_group_layout: BindGroupLayout,
 
     grid_size: Vec2f,
+    grid_spacing: f32,
     current_resolution: Vec2u,
+
+    /// MSAA samples per pixel, 1 when blades are drawn straight into the frame
+    sample_count: u32,
+    /// Multisampled color target resolved into the frame, sized like the surface
+    msaa_target: Option<TextureView>,
+
+    config: GrassConfig,
 }
 
 impl GrassSource {
-    pub fn new(ctx: &Context) -> Self {
+    pub fn new(ctx: &Context, grass_config: GrassConfig) -> Self {
         debug!("Creating GrassSource");
 
         let config = ctx.config();
@@ -72,16 +81,17 @@ impl GrassSource {
 
         let texture = Texture::empty(ctx, config.width, config.height);
 
-        let grid_width = (config.width as f32 / BLADE_SPACING).ceil() as u32;
-        let grid_height = (config.height as f32 / BLADE_SPACING).ceil() as u32;
+        let grid_spacing = blade_spacing(&grass_config);
+        let (grid_width, grid_height) = grid_dimensions(config.width, config.height, grid_spacing);
         let instance_count = grid_width * grid_height;
         let grid_size = Vec2f::from_u32(grid_width, grid_height);
 
         let dirt_vertex_buffer = create_dirt_vertex_buffer(ctx);
         let dirt_index_buffer = create_dirt_index_buffer(ctx);
 
+        let [r, g, b] = surface_rgb(DIRT_COLOR, config.format);
         let dirt_uniforms = DirtUniforms {
-            color: Vec3f::new(0.35, 0.25, 0.15),
+            color: Vec3f::new(r, g, b),
             padding: 0.0,
         };
 
@@ -114,23 +124,18 @@ impl GrassSource {
             label: Some("dirt_bind_group"),
         });
 
-        let dirt_shader = device.create_shader_module(include_wgsl!("./shaders/dirt.wgsl"));
-        let dirt_pipeline = create_dirt_pipeline(ctx, &[&dirt_bind_group_layout], &dirt_shader);
+        let sample_count = msaa_sample_count(grass_config.msaa, ctx.gpu().format_flags(config.format));
+        let msaa_target = create_msaa_target(ctx, sample_count);
+
+        let dirt_shader = device.create_shader_module(crate::wgsl!("./shaders/dirt.wgsl"));
+        let dirt_pipeline = create_dirt_pipeline(ctx, &[&dirt_bind_group_layout], &dirt_shader, sample_count);
 
         let blade_vertex_buffer = create_blade_vertex_buffer(ctx);
         let blade_index_buffer = create_blade_index_buffer(ctx);
-        let instance_buffer = create_instance_buffer(ctx, grid_width, grid_height);
-
-        let blade_height_pixels = config.height as f32 * BLADE_HEIGHT_PERCENT;
-        let grass_uniforms = GrassUniforms {
-            resolution: Vec2f::from_u32(config.width, config.height),
-            time: 0.0,
-            wind_strength: WIND_STRENGTH,
-            blade_height: blade_height_pixels,
-            blade_spacing: BLADE_SPACING,
-            grid_size,
-            padding: Vec2f::new(0.0, 0.0),
-        };
+        let instance_buffer = create_instance_buffer(ctx, grid_width, grid_height, grass_config.seed);
+
+        let resolution = Vec2u::new(config.width, config.height);
+        let grass_uniforms = create_grass_uniforms(&grass_config, resolution, grid_size, 0.0, config.format);
         let grass_uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("grass_uniform"),
             contents: cast_slice(&[grass_uniforms]),
@@ -160,7 +165,7 @@ impl GrassSource {
             label: Some("grass_bind_group"),
         });
 
-        let wind_texture = create_wind_texture(ctx);
+        let wind_texture = create_wind_texture(ctx, grass_config.seed);
         let wind_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("wind_bind_group_layout"),
             entries: &[
@@ -198,9 +203,13 @@ impl GrassSource {
             label: Some("wind_bind_group"),
         });
 
-        let grass_shader = device.create_shader_module(include_wgsl!("./shaders/grass.wgsl"));
-        let grass_pipeline =
-            create_grass_pipeline(ctx, &[&grass_bind_group_layout, &wind_bind_group_layout], &grass_shader);
+        let grass_shader = device.create_shader_module(crate::wgsl!("./shaders/grass.wgsl"));
+        let grass_pipeline = create_grass_pipeline(
+            ctx,
+            &[&grass_bind_group_layout, &wind_bind_group_layout],
+            &grass_shader,
+            sample_count,
+        );
 
         let state = RenderState::default();
 
@@ -226,45 +235,47 @@ impl GrassSource {
             wind_bind_group,
             wind_bind_group_layout,
             grid_size,
-            current_resolution: Vec2u::new(config.width, config.height),
+            grid_spacing,
+            current_resolution: resolution,
+            sample_count,
+            msaa_target,
+            config: grass_config,
         }
     }
 
     fn resize_if_needed(&mut self, ctx: &Context) {
         let config = ctx.config();
-        if config.width == self.current_resolution.u && config.height == self.current_resolution.v {
+        let spacing = blade_spacing(&self.config);
+        if config.width == self.current_resolution.u
+            && config.height == self.current_resolution.v
+            && spacing == self.grid_spacing
+        {
             return;
         }
 
         debug!(
-            "Resizing grass from {:?} to {:?}",
+            "Resizing grass from {:?} to {:?} with blade spacing {}",
             self.current_resolution,
-            [config.width, config.height]
+            [config.width, config.height],
+            spacing
         );
 
-        let grid_width = (config.width as f32 / BLADE_SPACING).ceil() as u32;
-        let grid_height = (config.height as f32 / BLADE_SPACING).ceil() as u32;
+        let (grid_width, grid_height) = grid_dimensions(config.width, config.height, spacing);
 
+        self.msaa_target = create_msaa_target(ctx, self.sample_count);
         self.instance_count = grid_width * grid_height;
         self.grid_size = Vec2f::from_u32(grid_width, grid_height);
+        self.grid_spacing = spacing;
         self.current_resolution = Vec2u::new(config.width, config.height);
-        self.instance_buffer = create_instance_buffer(ctx, grid_width, grid_height);
+        self.instance_buffer = create_instance_buffer(ctx, grid_width, grid_height, self.config.seed);
     }
 
     fn update_uniforms(&self, ctx: &Context) {
         let elapsed = self.start_time.elapsed().as_secs_f32();
         let config = ctx.config();
 
-        let blade_height_pixels = config.height as f32 * BLADE_HEIGHT_PERCENT;
-        let uniforms = GrassUniforms {
-            resolution: Vec2f::from_u32(config.width, config.height),
-            time: elapsed,
-            wind_strength: WIND_STRENGTH,
-            blade_height: blade_height_pixels,
-            blade_spacing: BLADE_SPACING,
-            grid_size: self.grid_size,
-            padding: Vec2f::new(0.0, 0.0),
-        };
+        let resolution = Vec2u::new(config.width, config.height);
+        let uniforms = create_grass_uniforms(&self.config, resolution, self.grid_size, elapsed, config.format);
         ctx.queue().write_buffer(&self.grass_uniform_buffer, 0, cast_slice(&[uniforms]));
     }
 
@@ -272,30 +283,38 @@ impl GrassSource {
         self.resize_if_needed(ctx);
         self.update_uniforms(ctx);
 
-        let surface = ctx.surface();
-        let output = match surface.get_current_texture() {
-            Ok(o) => o,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
         };
-        let view = output.texture.create_view(&Default::default());
+        let view = output.view();
 
         let device = ctx.device();
         let mut encoder = device.create_command_encoder(&Default::default());
 
+        // With MSAA the samples are drawn off screen and averaged into the frame
+        let attachment = match &self.msaa_target {
+            Some(msaa_view) => RenderPassColorAttachment {
+                view: msaa_view,
+                resolve_target: Some(&view),
+                ops: Operations {
+                    load: ctx.clear_op(Color::BLACK),
+                    store: StoreOp::Discard,
+                },
+            },
+            None => RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: Operations {
+                    load: ctx.clear_op(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            },
+        };
+
         {
             let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("grass_render"),
-                color_attachments: &[Some(RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(Color::BLACK),
-                        store: StoreOp::Store,
-                    },
-                })],
+                color_attachments: &[Some(attachment)],
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
@@ -339,15 +358,19 @@ impl Source for GrassSource {
     fn start_transition(
         &mut self,
         _previous: Option<crate::sources::SourceType>,
-        _duration: std::time::Duration,
+        _transition: &crate::transitions::TransitionConfig,
+        _interaction: &crate::sources::InteractionState,
         _ctx: &Context,
-        _transition_type: crate::transitions::TransitionType,
     ) {
         debug!("Grass source does not support transitions");
         self.state = RenderState::Displaying;
     }
 
     fn update(&mut self, _dt: std::time::Duration) {}
+
+    fn on_resume(&mut self, _ctx: &Context) {
+        self.start_time = Instant::now();
+    }
 }
 
 impl crate::sources::ContextualSource for GrassSource {
@@ -368,6 +391,40 @@ impl crate::sources::ContextualSource for GrassSource {
     }
 }
 
+fn blade_spacing(config: &GrassConfig) -> f32 {
+    config.blade_spacing.max(MIN_BLADE_SPACING)
+}
+
+fn grid_dimensions(width: u32, height: u32, spacing: f32) -> (u32, u32) {
+    let grid_width = (width as f32 / spacing).ceil() as u32;
+    let grid_height = (height as f32 / spacing).ceil() as u32;
+    (grid_width, grid_height)
+}
+
+fn create_grass_uniforms(
+    config: &GrassConfig,
+    resolution: Vec2u,
+    grid_size: Vec2f,
+    time: f32,
+    format: wgpu::TextureFormat,
+) -> GrassUniforms {
+    let [dir_x, dir_y] = config.wind_direction();
+    let [r, g, b] = surface_rgb(config.blade_color, format);
+
+    GrassUniforms {
+        resolution: Vec2f::from_u32(resolution.u, resolution.v),
+        time,
+        wind_strength: config.wind_strength,
+        blade_height: resolution.v as f32 * config.blade_height_percent,
+        blade_spacing: blade_spacing(config),
+        grid_size,
+        wind_direction: Vec2f::new(dir_x, dir_y),
+        padding: Vec2f::new(0.0, 0.0),
+        blade_color: Vec3f::new(r, g, b),
+        color_padding: 0.0,
+    }
+}
+
 fn create_dirt_vertex_buffer(ctx: &Context) -> Buffer {
     const VERTICES: &[DirtVertex] = &[
         DirtVertex {
@@ -472,8 +529,9 @@ fn create_blade_index_buffer(ctx: &Context) -> Buffer {
     })
 }
 
-fn create_instance_buffer(ctx: &Context, grid_width: u32, grid_height: u32) -> Buffer {
-    let mut rng = StdRng::seed_from_u64(42);
+/// Jittered blade positions for the grid, identical for identical seeds
+fn blade_instances(grid_width: u32, grid_height: u32, seed: u64) -> Vec<BladeInstance> {
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut instances = Vec::with_capacity((grid_width * grid_height) as usize);
 
     for y in 0..grid_height {
@@ -490,9 +548,13 @@ fn create_instance_buffer(ctx: &Context, grid_width: u32, grid_height: u32) -> B
         }
     }
 
+    instances
+}
+
+fn create_instance_buffer(ctx: &Context, grid_width: u32, grid_height: u32, seed: u64) -> Buffer {
     ctx.device().create_buffer_init(&BufferInitDescriptor {
         label: None,
-        contents: cast_slice(&instances),
+        contents: cast_slice(&blade_instances(grid_width, grid_height, seed)),
         usage: BufferUsages::VERTEX,
     })
 }
@@ -524,8 +586,8 @@ fn f32_to_f16(value: f32) -> u16 {
     sign | ((exponent as u16) << 10) | f16_mantissa
 }
 
-fn create_wind_texture(ctx: &Context) -> Texture {
-    let data = generate_wind_texture(WIND_TEXTURE_SIZE, WIND_TEXTURE_SIZE, 42);
+fn create_wind_texture(ctx: &Context, seed: u64) -> Texture {
+    let data = generate_wind_texture(WIND_TEXTURE_SIZE, WIND_TEXTURE_SIZE, seed);
     let mut data_bytes = Vec::with_capacity((WIND_TEXTURE_SIZE * WIND_TEXTURE_SIZE * 8) as usize);
 
     for pixel in data {
@@ -583,10 +645,48 @@ fn create_wind_texture(ctx: &Context) -> Texture {
     Texture::from_existing(texture, view, sampler)
 }
 
+/// `requested` MSAA samples when `flags` allow them, otherwise 1
+fn msaa_sample_count(requested: u32, flags: TextureFormatFeatureFlags) -> u32 {
+    if requested <= 1 {
+        return 1;
+    }
+    if flags.sample_count_supported(requested) {
+        requested
+    } else {
+        warn!("The GPU can't render grass with {requested}x MSAA, drawing without it");
+        1
+    }
+}
+
+/// Multisampled color target for `sample_count` samples, `None` without MSAA
+fn create_msaa_target(ctx: &Context, sample_count: u32) -> Option<TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+
+    let config = ctx.config();
+    let texture = ctx.device().create_texture(&TextureDescriptor {
+        label: Some("grass_msaa_target"),
+        size: Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: TextureDimension::D2,
+        format: config.format,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&TextureViewDescriptor::default()))
+}
+
 fn create_dirt_pipeline(
     ctx: &Context,
     bind_group_layouts: &[&BindGroupLayout],
     shader: &ShaderModule,
+    sample_count: u32,
 ) -> RenderPipeline {
     let layout = ctx.device().create_pipeline_layout(&PipelineLayoutDescriptor {
         label: None,
@@ -622,7 +722,7 @@ fn create_dirt_pipeline(
         },
         depth_stencil: None,
         multisample: MultisampleState {
-            count: 1,
+            count: sample_count,
             mask: !0,
             alpha_to_coverage_enabled: false,
         },
@@ -634,6 +734,7 @@ fn create_grass_pipeline(
     ctx: &Context,
     bind_group_layouts: &[&BindGroupLayout],
     shader: &ShaderModule,
+    sample_count: u32,
 ) -> RenderPipeline {
     let layout = ctx.device().create_pipeline_layout(&PipelineLayoutDescriptor {
         label: None,
@@ -669,10 +770,48 @@ fn create_grass_pipeline(
         },
         depth_stencil: None,
         multisample: MultisampleState {
-            count: 1,
+            count: sample_count,
             mask: !0,
             alpha_to_coverage_enabled: false,
         },
         multiview: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance_bytes(seed: u64) -> Vec<u8> {
+        cast_slice(&blade_instances(16, 8, seed)).to_vec()
+    }
+
+    #[test]
+    fn test_blade_instances_same_seed_match() {
+        assert_eq!(instance_bytes(7), instance_bytes(7));
+    }
+
+    #[test]
+    fn test_blade_instances_different_seeds_differ() {
+        assert_ne!(instance_bytes(7), instance_bytes(8));
+    }
+
+    #[test]
+    fn test_msaa_sample_count_keeps_supported_count() {
+        let flags = TextureFormatFeatureFlags::MULTISAMPLE_X2 | TextureFormatFeatureFlags::MULTISAMPLE_X4;
+        assert_eq!(msaa_sample_count(2, flags), 2);
+        assert_eq!(msaa_sample_count(4, flags), 4);
+    }
+
+    #[test]
+    fn test_msaa_sample_count_falls_back_to_one() {
+        assert_eq!(msaa_sample_count(2, TextureFormatFeatureFlags::MULTISAMPLE_X4), 1);
+        assert_eq!(msaa_sample_count(4, TextureFormatFeatureFlags::empty()), 1);
+    }
+
+    #[test]
+    fn test_msaa_sample_count_off() {
+        assert_eq!(msaa_sample_count(1, TextureFormatFeatureFlags::empty()), 1);
+        assert_eq!(msaa_sample_count(0, TextureFormatFeatureFlags::MULTISAMPLE_X4), 1);
+    }
+}
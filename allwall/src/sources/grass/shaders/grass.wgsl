@@ -5,7 +5,10 @@ struct GrassUniforms {
 	blade_height: f32,
 	blade_spacing: f32,
 	grid_size: vec2f,
+	wind_direction: vec2f,
 	_padding: vec2f,
+	blade_color: vec3f,
+	_color_padding: f32,
 }
 
 @group(0) @binding(0) var<uniform> uniforms: GrassUniforms;
@@ -48,12 +51,13 @@ fn vs_main(vertex: BladeVertex, instance: BladeInstance) -> VertexOutput {
 	let world_x = (normalized_grid.x - 0.5) * 2.0 * (uniforms.resolution.x / uniforms.resolution.y);
 	let world_y = (normalized_grid.y - 0.5) * 2.0;
 	
-	let wind_uv = normalized_grid + vec2f(uniforms.time * 0.05, uniforms.time * 0.03);
+	let wind_uv = fract(normalized_grid - uniforms.wind_direction * uniforms.time * 0.06);
 	let tex_size = vec2f(textureDimensions(wind_texture, 0));
 	let tex_coords = vec2i(wind_uv * tex_size) % vec2i(tex_size);
 	let wind = textureLoad(wind_texture, tex_coords, 0).xy;
 	
-	let wind_offset = wind * uniforms.wind_strength * vertex.height_factor * vertex.height_factor;
+	let gust = uniforms.wind_direction * (1.0 + wind.x) + wind * 0.5;
+	let wind_offset = gust * uniforms.wind_strength * vertex.height_factor * vertex.height_factor;
 	
 	let rotated_pos = rotate2d(instance.rotation) * vertex.position.xy;
 	
@@ -79,11 +83,11 @@ fn vs_main(vertex: BladeVertex, instance: BladeInstance) -> VertexOutput {
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4f {
-	let base_green = vec3f(0.2, 0.5, 0.15);
-	let tip_green = vec3f(0.4, 0.7, 0.2);
+	let base_color = uniforms.blade_color;
+	let tip_color = clamp(base_color * 1.4, vec3f(0.0), vec3f(1.0));
 	
 	let variation = (input.random_seed - 0.5) * 0.15;
-	let color = mix(base_green, tip_green, input.height_factor);
+	let color = mix(base_color, tip_color, input.height_factor);
 	let varied_color = color + variation;
 	
 	let final_color = clamp(varied_color, vec3f(0.0), vec3f(1.0));
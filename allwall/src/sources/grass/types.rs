@@ -14,7 +14,10 @@ pub struct GrassUniforms {
     pub blade_height: f32,
     pub blade_spacing: f32,
     pub grid_size: Vec2f,
+    pub wind_direction: Vec2f,
     pub padding: Vec2f,
+    pub blade_color: Vec3f,
+    pub color_padding: f32,
 }
 
 #[repr(C)]
@@ -134,7 +137,7 @@ mod tests {
 
     #[test]
     fn test_grass_uniforms_size() {
-        assert_eq!(size_of::<GrassUniforms>(), size_of::<[f32; 10]>());
+        assert_eq!(size_of::<GrassUniforms>(), size_of::<[f32; 16]>());
     }
 
     #[test]
@@ -148,6 +151,17 @@ mod tests {
         assert!((zero.blade_spacing).abs() < f32::EPSILON);
         assert!((zero.grid_size.u).abs() < f32::EPSILON);
         assert!((zero.grid_size.v).abs() < f32::EPSILON);
+        assert!((zero.wind_direction.u).abs() < f32::EPSILON);
+        assert!((zero.wind_direction.v).abs() < f32::EPSILON);
+        assert!((zero.blade_color.x).abs() < f32::EPSILON);
+        assert!((zero.blade_color.y).abs() < f32::EPSILON);
+        assert!((zero.blade_color.z).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_grass_uniforms_blade_color_alignment() {
+        // WGSL aligns vec3f to 16 bytes
+        assert_eq!(std::mem::offset_of!(GrassUniforms, blade_color) % 16, 0);
     }
 
     #[test]
@@ -0,0 +1,127 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::config::ColorAdjustConfig;
+
+/// Daylight white point that a temperature shift of 0 leaves in place
+const NEUTRAL_TEMPERATURE: f32 = 6500.0;
+
+/// `ColorAdjust` in `color_adjust.wgsl`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct ColorAdjustUniforms {
+    tint: [f32; 3],
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    _padding: [f32; 2],
+}
+
+impl From<ColorAdjustConfig> for ColorAdjustUniforms {
+    fn from(config: ColorAdjustConfig) -> Self {
+        Self {
+            tint: temperature_tint(config.temperature),
+            brightness: config.brightness,
+            contrast: config.contrast,
+            saturation: config.saturation,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Color of a blackbody at `kelvin` as RGB from 0.0 to 1.0, using Tanner Helland's fit to the CIE tables
+fn blackbody_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let green = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+
+    [red, green, blue].map(|channel| channel.clamp(0.0, 255.0) / 255.0)
+}
+
+/// Per-channel multipliers that move white from 6500K to `6500 + shift` Kelvin
+pub fn temperature_tint(shift: f32) -> [f32; 3] {
+    if shift == 0.0 {
+        return [1.0; 3];
+    }
+
+    let neutral = blackbody_rgb(NEUTRAL_TEMPERATURE);
+    let target = blackbody_rgb(NEUTRAL_TEMPERATURE + shift);
+    let tint: [f32; 3] = std::array::from_fn(|i| target[i] / neutral[i]);
+
+    // Keep the brightest channel at 1 so shifting the temperature never brightens the picture
+    let peak = tint.iter().copied().fold(f32::MIN, f32::max);
+    tint.map(|channel| channel / peak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_tint(shift: f32, expected: [f32; 3]) {
+        let tint = temperature_tint(shift);
+        for (channel, (actual, expected)) in tint.iter().zip(expected).enumerate() {
+            assert!(
+                (actual - expected).abs() < 0.01,
+                "channel {channel} at {shift}: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_color_adjust_uniforms_layout_matches_shader() {
+        // vec3 tint packs with brightness into 16 bytes, struct padded to a multiple of 16
+        assert_eq!(std::mem::size_of::<ColorAdjustUniforms>(), 32);
+    }
+
+    #[test]
+    fn test_neutral_temperature_is_identity() {
+        assert_eq!(temperature_tint(0.0), [1.0; 3]);
+    }
+
+    #[test]
+    fn test_temperature_tint_table() {
+        // 4000K: warm white, blue cut hardest
+        assert_tint(-2500.0, [1.0, 0.81, 0.664]);
+        // 2000K: candle-like orange
+        assert_tint(-4500.0, [1.0, 0.539, 0.056]);
+        // 10000K: cool blue sky
+        assert_tint(3500.0, [0.776, 0.842, 1.0]);
+    }
+
+    #[test]
+    fn test_temperature_tint_never_brightens() {
+        for shift in [-5500.0, -3000.0, -500.0, 500.0, 3500.0] {
+            let tint = temperature_tint(shift);
+            assert!(
+                tint.iter().all(|channel| (0.0..=1.0).contains(channel)),
+                "{shift}: {tint:?}"
+            );
+            assert!(tint.contains(&1.0), "{shift}: {tint:?}");
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_neutral() {
+        let uniforms = ColorAdjustUniforms::from(ColorAdjustConfig::default());
+        assert_eq!(uniforms.tint, [1.0; 3]);
+        assert_eq!(
+            (uniforms.brightness, uniforms.contrast, uniforms.saturation),
+            (1.0, 1.0, 1.0)
+        );
+    }
+}
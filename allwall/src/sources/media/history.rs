@@ -0,0 +1,123 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+
+const MAX_HISTORY: usize = 10;
+
+/// Files shown from a media directory, regardless of whether they were stills or videos
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaHistory {
+    current: PathBuf,
+    previous: VecDeque<PathBuf>,
+}
+
+impl MediaHistory {
+    pub fn new(current: PathBuf) -> Self {
+        Self {
+            current,
+            previous: VecDeque::new(),
+        }
+    }
+
+    pub fn current(&self) -> &Path {
+        &self.current
+    }
+
+    /// History after moving on to `next`, forgetting the oldest entry once full
+    pub fn advanced(&self, next: PathBuf) -> Self {
+        let mut previous = self.previous.clone();
+        if !self.current.as_os_str().is_empty() {
+            if previous.len() >= MAX_HISTORY {
+                previous.pop_front();
+            }
+            previous.push_back(self.current.clone());
+        }
+
+        Self {
+            current: next,
+            previous,
+        }
+    }
+
+    /// History after stepping back to the most recently shown file, if there is one
+    pub fn rewound(&self) -> Option<Self> {
+        let mut previous = self.previous.clone();
+        let current = previous.pop_back()?;
+        Some(Self { current, previous })
+    }
+
+    /// Orders `files` for picking the next one: the current file is dropped and files
+    /// not shown recently come first, so small directories still rotate
+    pub fn next_candidates(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let (mut unseen, seen): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .filter(|path| *path != self.current)
+            .partition(|path| !self.previous.contains(path));
+        unseen.extend(seen);
+        unseen
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::prelude::f;
+
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(f!("/wallpapers/{name}"))
+    }
+
+    #[test]
+    fn test_still_video_still_back_navigation() {
+        let history = MediaHistory::new(path("a.png")).advanced(path("b.mp4")).advanced(path("c.png"));
+        assert_eq!(history.current(), path("c.png"));
+
+        let history = history.rewound().unwrap();
+        assert_eq!(history.current(), path("b.mp4"));
+
+        let history = history.rewound().unwrap();
+        assert_eq!(history.current(), path("a.png"));
+
+        assert!(history.rewound().is_none());
+    }
+
+    #[test]
+    fn test_advanced_caps_history() {
+        let mut history = MediaHistory::new(path("0.png"));
+        for i in 1..=MAX_HISTORY + 5 {
+            history = history.advanced(path(&f!("{i}.png")));
+        }
+
+        let mut steps = 0;
+        while let Some(previous) = history.rewound() {
+            history = previous;
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_HISTORY);
+    }
+
+    #[test]
+    fn test_advanced_from_empty_history() {
+        let history = MediaHistory::default().advanced(path("a.png"));
+        assert_eq!(history.current(), path("a.png"));
+        assert!(history.rewound().is_none());
+    }
+
+    #[test]
+    fn test_next_candidates_prefers_unseen() {
+        let history = MediaHistory::new(path("a.png")).advanced(path("b.mp4"));
+        let files = vec![path("a.png"), path("b.mp4"), path("c.png"), path("d.webm")];
+
+        let candidates = history.next_candidates(files);
+        assert_eq!(candidates, vec![path("c.png"), path("d.webm"), path("a.png")]);
+    }
+
+    #[test]
+    fn test_next_candidates_falls_back_to_seen_files() {
+        let history = MediaHistory::new(path("a.png")).advanced(path("b.png"));
+        let candidates = history.next_candidates(vec![path("a.png"), path("b.png")]);
+        assert_eq!(candidates, vec![path("a.png")]);
+    }
+}
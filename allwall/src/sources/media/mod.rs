@@ -1,19 +1,34 @@
+mod color_adjust;
+mod history;
+pub mod remote;
+mod shuffle;
+mod sidecar;
 pub mod still;
+mod texture_cache;
 pub mod video;
 
-use std::{path::PathBuf, time::Duration};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use rand::seq::SliceRandom;
 
 use crate::{
-    engine::{Context, Texture},
+    config::{ColorAdjustConfig, HwDecode, MergedSceneConfig, SourceConfigs},
+    engine::{Context, Fit, LetterboxStyle, RotationOrder, Scaling, Texture, Upscale},
     prelude::*,
-    sources::{BasicSource, RenderState, Source, SourceType, error::SourceError},
-    transitions::TransitionType,
+    sources::{BasicSource, InteractionState, RenderState, Source, SourceType, color::ColorSource, error::SourceError},
+    transitions::TransitionConfig,
 };
 
-use self::still::Still;
-use self::video::Video;
+use self::history::MediaHistory;
+use self::shuffle::ShuffleBag;
+use self::sidecar::MediaOverrides;
+use self::still::{AnimatedStill, Still};
+pub use self::texture_cache::TextureCache;
+use self::video::{FrameUpload, Video};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MediaKind {
@@ -21,7 +36,12 @@ enum MediaKind {
     Video,
 }
 
-fn detect_media_kind(path: &PathBuf) -> Option<MediaKind> {
+/// Whether `path` looks like an image or video allwall can display
+pub fn is_supported_media(path: &Path) -> bool {
+    detect_media_kind(path).is_some()
+}
+
+fn detect_media_kind(path: &Path) -> Option<MediaKind> {
     let mime = mime_guess::from_path(path).first()?;
     let type_ = mime.type_().as_str();
     match type_ {
@@ -31,128 +51,883 @@ fn detect_media_kind(path: &PathBuf) -> Option<MediaKind> {
     }
 }
 
-pub enum MediaSource {
+/// How many per-file errors to keep when no file in a directory could be opened
+const MAX_REPORTED_ERRORS: usize = 3;
+
+/// Returns the first of `candidates` that `open` succeeds on, warning about each file it skips
+///
+/// When every candidate fails, the error records how many were tried and the first
+/// few reasons, so a folder of corrupt or unsupported files doesn't fail silently.
+fn open_first<T>(
+    candidates: impl IntoIterator<Item = PathBuf>,
+    mut open: impl FnMut(&Path) -> Result<T>,
+) -> Result<(PathBuf, T)> {
+    let mut tried = 0;
+    let mut errors = Vec::new();
+
+    for path in candidates {
+        tried += 1;
+        match open(&path) {
+            Ok(opened) => return Ok((path, opened)),
+            Err(e) => {
+                warn!("Skipping unreadable media {}: {}", path.display(), e);
+                if errors.len() < MAX_REPORTED_ERRORS {
+                    errors.push(f!("{}: {}", path.display(), e));
+                }
+            },
+        }
+    }
+
+    if tried == 0 {
+        return Err(SourceError::NoImagesAvailable.into());
+    }
+
+    Err(SourceError::NoReadableMedia { tried, errors }.into())
+}
+
+/// Supported media files inside `dir`, and in its subdirectories too when `recursive`
+///
+/// Unreadable subdirectories are skipped with a warning. Only `dir` itself failing is an error.
+pub fn media_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    let mut visited = HashSet::new();
+
+    while let Some(next) = pending.pop() {
+        // A symlink can point back up the tree, so each real directory is only read once
+        if let Ok(real) = next.canonicalize()
+            && !visited.insert(real)
+        {
+            continue;
+        }
+
+        let entries = match next.read_dir() {
+            Ok(entries) => entries,
+            Err(_) if next == dir => return Err(SourceError::NoImageDirectory.into()),
+            Err(e) => {
+                warn!("Skipping unreadable directory {}: {}", next.display(), e);
+                continue;
+            },
+        };
+
+        for path in entries.filter_map(std::result::Result::ok).map(|d| d.path()) {
+            if path.is_file() && is_supported_media(&path) {
+                files.push(path);
+            } else if recursive && path.is_dir() {
+                pending.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// The one of `files` that `target` names: an absolute path, a path relative to `dir`,
+/// or a bare file name, which also matches files in subdirectories of a recursive scene
+fn resolve_in(dir: &Path, target: &Path, files: &[PathBuf]) -> Option<PathBuf> {
+    let wanted = if target.is_absolute() {
+        target.to_path_buf()
+    } else {
+        dir.join(target)
+    };
+    if let Some(found) = files.iter().find(|file| **file == wanted) {
+        return Some(found.clone());
+    }
+
+    let bare_name = target.components().count() == 1;
+    bare_name
+        .then(|| files.iter().find(|file| file.file_name() == Some(target.as_os_str())))
+        .flatten()
+        .cloned()
+}
+
+/// Opens the next of `files` after `history`, or `None` when there are no files at all
+///
+/// Having only the current file left is still an error, so a single-image directory
+/// keeps its wallpaper instead of dropping to the fallback color.
+fn open_from<T>(
+    files: Vec<PathBuf>,
+    history: &MediaHistory,
+    open: impl FnMut(&Path) -> Result<T>,
+) -> Result<Option<(PathBuf, T)>> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+    open_first(history.next_candidates(files), open).map(Some)
+}
+
+/// Opens the next file of `bag`'s pass over `files`, or `None` when there are no files at all
+///
+/// `current` is skipped like in [`open_from`], so it's only an error once nothing else is left.
+fn open_from_bag<T>(
+    files: Vec<PathBuf>,
+    bag: &ShuffleBag,
+    current: &Path,
+    open: impl FnMut(&Path) -> Result<T>,
+) -> Result<Option<(PathBuf, T, ShuffleBag)>> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let bag = bag.refreshed(&files, &mut rand::rng());
+    let candidates = bag.next_candidates().into_iter().filter(|path| path != current);
+    let (path, opened) = open_first(candidates, open)?;
+    let bag = bag.advanced(&path);
+    Ok(Some((path, opened, bag)))
+}
+
+/// Settings that apply to every file a [`MediaSource`] opens
+#[derive(Debug, Clone, Default)]
+pub struct MediaOptions {
+    /// [`FrameUpload::Copy`] unless built with the `dmabuf` feature, since DMA-BUF import
+    /// isn't implemented yet
+    pub upload: FrameUpload,
+    pub playback_rate: f64,
+    pub stall_frames: u32,
+    pub loop_playback: bool,
+    pub pace_frames: bool,
+    pub hw_decode: HwDecode,
+    pub scaling: Scaling,
+    pub blur: f32,
+    pub fit: Fit,
+    pub smart_max_crop: f32,
+    pub letterbox: LetterboxStyle,
+    pub letterbox_blur: f32,
+    pub upscale: Upscale,
+    pub mipmaps: bool,
+    /// How long stills pan for, `None` to keep them still
+    pub ken_burns: Option<Duration>,
+    pub fallback_color: [f32; 4],
+    pub order: RotationOrder,
+    pub recursive: bool,
+    pub color_adjust: ColorAdjustConfig,
+    /// Stills shown recently, handed on from each source to the next
+    pub cache: TextureCache,
+}
+
+impl MediaOptions {
+    pub fn new(sources: &SourceConfigs, scene: &MergedSceneConfig) -> Self {
+        Self {
+            #[cfg(not(feature = "dmabuf"))]
+            upload: FrameUpload::Copy,
+            #[cfg(feature = "dmabuf")]
+            upload: FrameUpload::DmaBuf,
+            playback_rate: sources.video.playback_rate,
+            stall_frames: sources.video.stall_frames,
+            loop_playback: sources.video.loop_playback,
+            pace_frames: sources.video.pace_frames,
+            hw_decode: sources.video.hw_decode,
+            scaling: scene.scaling,
+            blur: scene.blur,
+            fit: scene.fit,
+            smart_max_crop: scene.smart_max_crop,
+            letterbox: scene.letterbox,
+            letterbox_blur: scene.letterbox_blur,
+            upscale: scene.upscale,
+            mipmaps: scene.mipmaps,
+            ken_burns: scene.ken_burns.then(|| still::pan_period(scene.transition.interval)),
+            fallback_color: scene.fallback_color,
+            order: scene.order,
+            recursive: scene.recursive,
+            color_adjust: sources.color_adjust,
+            cache: TextureCache::new(sources.still.cache_size),
+        }
+    }
+}
+
+/// A still or video from a media directory, along with the files shown before it
+pub struct MediaSource {
+    media: Media,
+    history: MediaHistory,
+    /// Pass over the directory when the scene's order is [`RotationOrder::Album`]
+    bag: ShuffleBag,
+    options: MediaOptions,
+    /// URL the media was downloaded from, refreshed in the background on every rotation
+    remote: Option<PathBuf>,
+    /// Settings the file on screen overrides from its sidecar metadata
+    overrides: MediaOverrides,
+}
+
+enum Media {
     Still(Still),
+    Animated(AnimatedStill),
     Video(Video),
+    /// Solid color shown while the directory has no media
+    Fallback {
+        fill: ColorSource,
+        dir: PathBuf,
+    },
+}
+
+impl Media {
+    fn open(path: &Path, dir: PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
+        match detect_media_kind(path) {
+            #[cfg(feature = "svg")]
+            Some(MediaKind::Image) if still::is_svg(path) => Ok(Self::Still(Still::from_svg(path, dir, ctx, options)?)),
+            #[cfg(not(feature = "svg"))]
+            Some(MediaKind::Image) if still::is_svg(path) => Err(still::svg_unsupported(path)),
+            Some(MediaKind::Image) => {
+                if let Some(frames) = still::decode_animation(path)?
+                    && let Some(animated) = AnimatedStill::new(frames, dir.clone(), ctx, options.clone())?
+                {
+                    return Ok(Self::Animated(animated));
+                }
+                let texture = options.cache.get_or_load(path, |path| {
+                    let img = still::open_image(path)?;
+                    Ok(Texture::from_image(&img, ctx, options.scaling, options.mipmaps))
+                })?;
+                Ok(Self::Still(Still::from_texture(texture, dir, ctx, options)?))
+            },
+            Some(MediaKind::Video) => Ok(Self::Video(Video::new(path.to_path_buf(), dir, ctx, options)?)),
+            None => Err(SourceError::UnsupportedMedia(path.to_path_buf()).into()),
+        }
+    }
+
+    /// Opens `path` with the fit from its sidecar metadata applied over the scene's `options`
+    fn open_overridden(
+        path: &Path,
+        dir: PathBuf,
+        ctx: &Context,
+        options: &MediaOptions,
+    ) -> Result<(Self, MediaOverrides)> {
+        let overrides = MediaOverrides::load(path);
+        let media = Self::open(path, dir, ctx, overrides.options(options))?;
+        Ok((media, overrides))
+    }
 }
 
 impl MediaSource {
-    pub fn from_directory(dir: &PathBuf, ctx: &Context) -> Result<Self> {
-        let mut rng = rand::rng();
-        let mut files: Vec<_> = dir
-            .read_dir()
-            .map_err(|_| SourceError::NoImageDirectory)?
-            .filter_map(std::result::Result::ok)
-            .map(|d| d.path())
-            .filter(|p| p.is_file())
-            .collect();
+    /// Opens either a single media file or an entry from a directory, `preferred` if it's
+    /// still there and otherwise random
+    pub fn from_path(path: &PathBuf, preferred: Option<&Path>, ctx: &Context, options: MediaOptions) -> Result<Self> {
+        if remote::is_remote(path) {
+            return Self::from_url(path, ctx, options);
+        }
 
-        if files.is_empty() {
-            return Err(SourceError::NoImagesAvailable.into());
+        if !path.exists() {
+            return Err(SourceError::PathNotFound(path.clone()).into());
         }
 
-        files.shuffle(&mut rng);
+        if path.is_dir() {
+            Self::from_directory(path, preferred, ctx, options)
+        } else {
+            Self::from_file(path, ctx, options)
+        }
+    }
 
-        for path in files {
-            match detect_media_kind(&path) {
-                Some(MediaKind::Image) => {
-                    if let Ok(img) = image::open(&path) {
-                        let still = Still::new(&img, dir.clone(), ctx).with_current_path(path);
-                        return Ok(Self::Still(still));
-                    }
+    pub fn from_file(path: &PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
+        let dir = path.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+        let (media, overrides) = Media::open_overridden(path, dir, ctx, &options)?;
+
+        Ok(Self {
+            media,
+            history: MediaHistory::new(path.clone()),
+            bag: ShuffleBag::default(),
+            options,
+            remote: None,
+            overrides,
+        })
+    }
+
+    /// Opens the cached copy of the wallpaper at `url` while a fresh one downloads in the background
+    ///
+    /// Before the first download lands there's nothing to open, so the fallback color
+    /// shows until a later rotation finds the file in the cache.
+    pub fn from_url(url: &PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
+        let Some(cached) = remote::cached_or_refresh(&url.to_string_lossy())? else {
+            warn!("{} is still downloading, showing the fallback color", url.display());
+            return Ok(Self {
+                media: Media::Fallback {
+                    fill: ColorSource::rgba(ctx, options.fallback_color)?,
+                    dir: remote::cache_dir()?,
                 },
-                Some(MediaKind::Video) => match Video::new(path.clone(), dir.clone(), ctx) {
-                    Ok(video) => return Ok(Self::Video(video)),
-                    Err(e) => {
-                        warn!("Failed to create video source for {:?}: {}", path, e);
-                    },
+                history: MediaHistory::default(),
+                bag: ShuffleBag::default(),
+                options,
+                remote: Some(url.clone()),
+                overrides: MediaOverrides::default(),
+            });
+        };
+        // An earlier background download may have replaced the file under the same name
+        options.cache.forget(&cached);
+
+        Ok(Self {
+            remote: Some(url.clone()),
+            ..Self::from_file(&cached, ctx, options)?
+        })
+    }
+
+    /// Opens `preferred` when it's a file inside `dir`, such as the wallpaper shown before a
+    /// restart, falling back to a random entry
+    pub fn from_directory(
+        dir: &PathBuf,
+        preferred: Option<&Path>,
+        ctx: &Context,
+        options: MediaOptions,
+    ) -> Result<Self> {
+        if let Some(path) = preferred.filter(|p| p.starts_with(dir) && p.is_file()) {
+            match Media::open_overridden(path, dir.clone(), ctx, &options) {
+                Ok((media, overrides)) => {
+                    return Ok(Self {
+                        media,
+                        history: MediaHistory::new(path.to_path_buf()),
+                        bag: ShuffleBag::default(),
+                        options,
+                        remote: None,
+                        overrides,
+                    });
                 },
-                None => continue,
+                Err(e) => warn!("Could not restore {}: {}", path.display(), e),
             }
         }
 
-        Err(SourceError::NoImagesAvailable.into())
+        Self::open_next(dir, &MediaHistory::default(), &ShuffleBag::default(), options, ctx)
+    }
+
+    /// Opens the first usable file from `dir`, preferring files `history` hasn't shown recently,
+    /// or the next file of `bag` in album order
+    ///
+    /// An empty directory yields the fallback color, keeping `history` so media that
+    /// reappears is picked up by the next rotation.
+    fn open_next(
+        dir: &PathBuf,
+        history: &MediaHistory,
+        bag: &ShuffleBag,
+        options: MediaOptions,
+        ctx: &Context,
+    ) -> Result<Self> {
+        let mut files = media_files(dir, options.recursive)?;
+        let open = |path: &Path| Media::open_overridden(path, dir.clone(), ctx, &options);
+
+        let opened = match options.order {
+            RotationOrder::Random => {
+                files.shuffle(&mut rand::rng());
+                open_from(files, history, open)?.map(|(path, media)| (path, media, bag.clone()))
+            },
+            RotationOrder::Album => open_from_bag(files, bag, history.current(), open)?,
+        };
+        let Some((path, (media, overrides), bag)) = opened else {
+            warn!("No media in {}, showing the fallback color", dir.display());
+            return Ok(Self {
+                media: Media::Fallback {
+                    fill: ColorSource::rgba(ctx, options.fallback_color)?,
+                    dir: dir.clone(),
+                },
+                history: history.clone(),
+                bag: bag.clone(),
+                options,
+                remote: None,
+                overrides: MediaOverrides::default(),
+            });
+        };
+
+        Ok(Self {
+            media,
+            history: history.advanced(path),
+            bag,
+            options,
+            remote: None,
+            overrides,
+        })
+    }
+
+    /// Opens `target` from this source's directory, keeping the history and album pass
+    /// so rotation carries on from there
+    pub fn goto(&self, target: &Path, ctx: &Context) -> Result<Self> {
+        let dir = self.directory();
+        let not_found = || SourceError::NotInDirectory {
+            file: target.to_path_buf(),
+            dir: dir.clone(),
+        };
+        if self.remote.is_some() {
+            return Err(not_found().into());
+        }
+
+        let files = media_files(dir, self.options.recursive)?;
+        let path = resolve_in(dir, target, &files).ok_or_else(not_found)?;
+        let (media, overrides) = Media::open_overridden(&path, dir.clone(), ctx, &self.options)?;
+
+        let mut goto = Self {
+            media,
+            history: self.history.advanced(path),
+            bag: self.bag.clone(),
+            options: self.options.clone(),
+            remote: None,
+            overrides,
+        };
+        goto.load(ctx)?;
+        Ok(goto)
+    }
+
+    /// File currently on screen, or `None` while showing the fallback color
+    pub fn current_path(&self) -> Option<&Path> {
+        match &self.media {
+            Media::Fallback { .. } => None,
+            _ => Some(self.history.current()),
+        }
+    }
+
+    /// Whether the media moves on its own, as videos and animated images do, so it needs
+    /// the full frame rate even when no transition is running
+    ///
+    /// A video holding its last frame after playing once counts as a still.
+    pub fn is_animated(&self) -> bool {
+        match &self.media {
+            Media::Video(v) => v.is_playing(),
+            Media::Animated(_) => true,
+            Media::Still(s) => s.is_panning(),
+            Media::Fallback { .. } => false,
+        }
     }
 
     pub fn directory(&self) -> &PathBuf {
-        match self {
-            MediaSource::Still(s) => s.directory(),
-            MediaSource::Video(v) => v.directory(),
+        match &self.media {
+            Media::Still(s) => s.directory(),
+            Media::Animated(a) => a.directory(),
+            Media::Video(v) => v.directory(),
+            Media::Fallback { dir, .. } => dir,
         }
     }
 }
 
 impl Source for MediaSource {
     fn texture(&self) -> &Texture {
-        match self {
-            MediaSource::Still(s) => s.texture(),
-            MediaSource::Video(v) => v.texture(),
+        match &self.media {
+            Media::Still(s) => s.texture(),
+            Media::Animated(a) => a.texture(),
+            Media::Video(v) => v.texture(),
+            Media::Fallback { fill, .. } => fill.texture(),
         }
     }
 
     fn state(&self) -> &RenderState {
-        match self {
-            MediaSource::Still(s) => s.state(),
-            MediaSource::Video(v) => v.state(),
+        match &self.media {
+            Media::Still(s) => s.state(),
+            Media::Animated(a) => a.state(),
+            Media::Video(v) => v.state(),
+            Media::Fallback { fill, .. } => fill.state(),
         }
     }
 
     fn load(&mut self, ctx: &Context) -> Result<()> {
-        match self {
-            MediaSource::Still(s) => s.load(ctx),
-            MediaSource::Video(v) => v.load(ctx),
+        match &mut self.media {
+            Media::Still(s) => s.load(ctx),
+            Media::Animated(a) => a.load(ctx),
+            Media::Video(v) => v.load(ctx),
+            Media::Fallback { fill, .. } => fill.load(ctx),
         }
     }
 
     fn start_transition(
         &mut self,
         previous: Option<SourceType>,
-        duration: Duration,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
         ctx: &Context,
-        transition_type: TransitionType,
     ) {
-        match self {
-            MediaSource::Still(s) => s.start_transition(previous, duration, ctx, transition_type),
-            MediaSource::Video(v) => v.start_transition(previous, duration, ctx, transition_type),
+        // Settled again in case the file's sidecar picked `random`
+        let transition = self.overrides.transition(transition);
+        let transition = &*transition.sampled();
+        match &mut self.media {
+            Media::Still(s) => s.start_transition(previous, transition, interaction, ctx),
+            Media::Animated(a) => a.start_transition(previous, transition, interaction, ctx),
+            Media::Video(v) => v.start_transition(previous, transition, interaction, ctx),
+            Media::Fallback { fill, .. } => fill.start_transition(previous, transition, interaction, ctx),
         }
     }
 
     fn update(&mut self, dt: Duration) {
-        match self {
-            MediaSource::Still(s) => s.update(dt),
-            MediaSource::Video(v) => v.update(dt),
+        match &mut self.media {
+            Media::Still(s) => s.update(dt),
+            Media::Animated(a) => a.update(dt),
+            Media::Video(v) => v.update(dt),
+            Media::Fallback { fill, .. } => fill.update(dt),
         }
     }
 
-    fn next(&self, ctx: &Context) -> Result<Self> {
-        match self {
-            MediaSource::Still(s) => s.next(ctx).map(MediaSource::Still),
-            MediaSource::Video(_) => Err(SourceError::UnsupportedOperation("next for video".to_string()).into()),
+    /// Stills and the fallback color settle once their transition ends, dropping the engine to idle fps
+    fn needs_redraw(&self) -> bool {
+        self.is_animated() || matches!(self.state(), RenderState::Transitioning(_))
+    }
+
+    fn take_stalled(&mut self) -> bool {
+        match &mut self.media {
+            Media::Video(v) => v.take_stalled(),
+            Media::Still(_) | Media::Animated(_) | Media::Fallback { .. } => false,
         }
     }
 
-    fn prev(&self, ctx: &Context) -> Result<Self> {
-        match self {
-            MediaSource::Still(s) => s.prev(ctx).map(MediaSource::Still),
-            MediaSource::Video(_) => Err(SourceError::UnsupportedOperation("prev for video".to_string()).into()),
+    fn on_resume(&mut self, ctx: &Context) {
+        if let Media::Video(v) = &mut self.media {
+            v.on_resume(ctx);
         }
     }
+
+    fn next(&self, ctx: &Context) -> Result<Self> {
+        let mut next = match &self.remote {
+            Some(url) => Self::from_url(url, ctx, self.options.clone())?,
+            None => Self::open_next(self.directory(), &self.history, &self.bag, self.options.clone(), ctx)?,
+        };
+        next.load(ctx)?;
+        Ok(next)
+    }
+
+    fn prev(&self, ctx: &Context) -> Result<Self> {
+        let (history, bag) = match self.options.order {
+            RotationOrder::Random => (self.history.rewound(), self.bag.clone()),
+            RotationOrder::Album => {
+                let bag = self.bag.rewound().ok_or(SourceError::NoPreviousImage)?;
+                let history = bag.current().map(|path| MediaHistory::new(path.to_path_buf()));
+                (history, bag)
+            },
+        };
+        let history = history.ok_or(SourceError::NoPreviousImage)?;
+        let (media, overrides) =
+            Media::open_overridden(history.current(), self.directory().clone(), ctx, &self.options)?;
+
+        let mut prev = Self {
+            media,
+            history,
+            bag,
+            options: self.options.clone(),
+            remote: self.remote.clone(),
+            overrides,
+        };
+        prev.load(ctx)?;
+        Ok(prev)
+    }
 }
 
 impl BasicSource for MediaSource {
     fn render(&mut self, ctx: &Context) {
-        match self {
-            MediaSource::Still(s) => s.render(ctx),
-            MediaSource::Video(v) => v.render(ctx),
+        match &mut self.media {
+            Media::Still(s) => s.render(ctx),
+            Media::Animated(a) => a.render(ctx),
+            Media::Video(v) => v.render(ctx),
+            Media::Fallback { fill, .. } => fill.render(ctx),
         }
     }
 }
 
 impl std::fmt::Debug for MediaSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            MediaSource::Still(s) => s.fmt(f),
-            MediaSource::Video(v) => v.fmt(f),
+        match &self.media {
+            Media::Still(s) => s.fmt(f),
+            Media::Animated(a) => a.fmt(f),
+            Media::Video(v) => v.fmt(f),
+            Media::Fallback { fill, .. } => fill.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use super::*;
+    use crate::{
+        config::GeneralConfig,
+        engine::GpuContext,
+        sources::media::still::AnimationFrame,
+        transitions::{FadeOptions, FadeTransition, Transition},
+        utils::scratch::ScratchDir,
+    };
+
+    fn open_image(path: &Path) -> Result<image::DynamicImage> {
+        Ok(image::open(path)?)
+    }
+
+    fn headless() -> Option<Context> {
+        let Ok(gpu) = pollster::block_on(GpuContext::new(&GeneralConfig::default())) else {
+            warn!("No GPU adapter available, skipping");
+            return None;
+        };
+        Some(Context::headless(Arc::new(gpu), (64, 64)))
+    }
+
+    fn source(media: Media) -> MediaSource {
+        MediaSource {
+            media,
+            history: MediaHistory::default(),
+            bag: ShuffleBag::default(),
+            options: MediaOptions::default(),
+            remote: None,
+            overrides: MediaOverrides::default(),
+        }
+    }
+
+    /// Encodes a second of MJPEG to `path`, false when GStreamer or its plugins are missing
+    fn encode_clip(path: &Path) -> bool {
+        use gstreamer::{ClockTime, MessageType, MessageView, State, prelude::*};
+
+        let description = f!(
+            "videotestsrc num-buffers=30 ! video/x-raw,width=16,height=16,framerate=30/1 ! jpegenc ! avimux ! filesink location={}",
+            path.display()
+        );
+        if gstreamer::init().is_err() {
+            return false;
+        }
+        let Ok(pipeline) = gstreamer::parse::launch(&description) else {
+            return false;
+        };
+        if pipeline.set_state(State::Playing).is_err() {
+            let _ = pipeline.set_state(State::Null);
+            return false;
+        }
+        let finished = pipeline.bus().and_then(|bus| {
+            bus.timed_pop_filtered(ClockTime::from_seconds(10), &[MessageType::Eos, MessageType::Error])
+        });
+        let _ = pipeline.set_state(State::Null);
+        finished.is_some_and(|message| matches!(message.view(), MessageView::Eos(_)))
+    }
+
+    #[test]
+    fn test_open_first_skips_unreadable_files() {
+        let dir = ScratchDir::new("media-mixed");
+        let garbage_a = dir.join("a.png");
+        let garbage_b = dir.join("b.jpg");
+        let valid = dir.join("c.png");
+        fs::write(&garbage_a, b"not a png").unwrap();
+        fs::write(&garbage_b, b"not a jpeg either").unwrap();
+        image::RgbaImage::new(2, 2).save(&valid).unwrap();
+
+        let mut failures = 0;
+        let (path, img) = open_first([garbage_a, garbage_b, valid.clone()], |p| {
+            open_image(p).inspect_err(|_| failures += 1)
+        })
+        .unwrap();
+
+        assert_eq!(path, valid);
+        assert_eq!(img.to_rgba8().dimensions(), (2, 2));
+        assert_eq!(failures, 2);
+    }
+
+    #[test]
+    fn test_open_first_reports_every_failure() {
+        let dir = ScratchDir::new("media-corrupt");
+        let files: Vec<_> = (0..5)
+            .map(|i| {
+                let path = dir.join(f!("{i}.png"));
+                fs::write(&path, b"garbage").unwrap();
+                path
+            })
+            .collect();
+
+        let err = open_first(files, open_image).unwrap_err();
+
+        match err {
+            Error::Source(SourceError::NoReadableMedia { tried, errors }) => {
+                assert_eq!(tried, 5);
+                assert_eq!(errors.len(), MAX_REPORTED_ERRORS);
+                assert!(errors[0].contains("0.png"));
+            },
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_open_first_without_candidates() {
+        let err = open_first(Vec::new(), open_image).unwrap_err();
+        assert!(matches!(err, Error::Source(SourceError::NoImagesAvailable)));
+    }
+
+    #[test]
+    fn test_open_from_empty_directory_falls_back() {
+        let dir = ScratchDir::new("media-empty");
+        fs::write(dir.join("notes.txt"), b"not media").unwrap();
+
+        let files = media_files(&dir, false).unwrap();
+        let opened = open_from(files, &MediaHistory::default(), open_image).unwrap();
+
+        assert!(opened.is_none());
+    }
+
+    #[test]
+    fn test_media_files_recursive_includes_subdirectories() {
+        let dir = ScratchDir::new("media-nested");
+        fs::create_dir_all(dir.join("nature/forest")).unwrap();
+        fs::create_dir_all(dir.join("space")).unwrap();
+        for file in ["a.png", "nature/b.jpg", "nature/forest/c.png", "space/d.mp4", "space/notes.txt"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+
+        assert_eq!(media_files(&dir, false).unwrap(), vec![dir.join("a.png")]);
+
+        let mut files = media_files(&dir, true).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            ["a.png", "nature/b.jpg", "nature/forest/c.png", "space/d.mp4"].map(|file| dir.join(file))
+        );
+    }
+
+    #[test]
+    fn test_media_files_recursive_survives_symlink_loop() {
+        let dir = ScratchDir::new("media-loop");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.png"), b"").unwrap();
+        fs::write(dir.join("sub/b.png"), b"").unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("sub/back")).unwrap();
+
+        assert_eq!(media_files(&dir, true).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_open_from_bag_empty_directory_falls_back() {
+        let opened = open_from_bag(Vec::new(), &ShuffleBag::default(), Path::new(""), open_image).unwrap();
+        assert!(opened.is_none());
+    }
+
+    #[test]
+    fn test_open_from_bag_only_current_file_is_an_error() {
+        let dir = ScratchDir::new("media-bag-single");
+        let only = dir.join("a.png");
+        image::RgbaImage::new(2, 2).save(&only).unwrap();
+
+        let files = media_files(&dir, false).unwrap();
+        let (path, _, bag) = open_from_bag(files.clone(), &ShuffleBag::default(), Path::new(""), open_image)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path, only);
+
+        let err = open_from_bag(files, &bag, &only, open_image).unwrap_err();
+        assert!(matches!(err, Error::Source(SourceError::NoImagesAvailable)));
+    }
+
+    #[test]
+    fn test_open_from_only_current_file_is_an_error() {
+        let dir = ScratchDir::new("media-single");
+        let only = dir.join("a.png");
+        image::RgbaImage::new(2, 2).save(&only).unwrap();
+
+        let files = media_files(&dir, false).unwrap();
+        let err = open_from(files, &MediaHistory::new(only), open_image).unwrap_err();
+
+        assert!(matches!(err, Error::Source(SourceError::NoImagesAvailable)));
+    }
+
+    #[test]
+    fn test_resolve_in_finds_file_by_name_or_path() {
+        let dir = Path::new("/walls");
+        let files = vec![PathBuf::from("/walls/sunset.jpg"), PathBuf::from("/walls/beach/sunrise.png")];
+
+        let sunset = Some(PathBuf::from("/walls/sunset.jpg"));
+        assert_eq!(resolve_in(dir, Path::new("sunset.jpg"), &files), sunset);
+        assert_eq!(resolve_in(dir, Path::new("/walls/sunset.jpg"), &files), sunset);
+
+        let sunrise = Some(PathBuf::from("/walls/beach/sunrise.png"));
+        assert_eq!(resolve_in(dir, Path::new("beach/sunrise.png"), &files), sunrise);
+        // A bare name reaches into the subdirectories a recursive scene picked up
+        assert_eq!(resolve_in(dir, Path::new("sunrise.png"), &files), sunrise);
+    }
+
+    #[test]
+    fn test_resolve_in_rejects_files_outside_directory() {
+        let dir = Path::new("/walls");
+        let files = vec![PathBuf::from("/walls/sunset.jpg")];
+
+        assert_eq!(resolve_in(dir, Path::new("missing.jpg"), &files), None);
+        assert_eq!(resolve_in(dir, Path::new("/elsewhere/sunset.jpg"), &files), None);
+        assert_eq!(resolve_in(dir, Path::new("other/sunset.jpg"), &files), None);
+    }
+
+    #[test]
+    fn test_still_is_not_animated() {
+        let Some(ctx) = headless() else { return };
+        let img = image::DynamicImage::new_rgba8(4, 4);
+        let still = Still::new(&img, PathBuf::from("/walls"), &ctx, MediaOptions::default()).unwrap();
+
+        let source = source(Media::Still(still));
+        assert!(!source.is_animated());
+        assert!(!source.needs_redraw());
+    }
+
+    #[test]
+    fn test_panning_still_is_animated() {
+        let Some(ctx) = headless() else { return };
+        let img = image::DynamicImage::new_rgba8(4, 4);
+        let options = MediaOptions {
+            ken_burns: Some(Duration::from_secs(30)),
+            ..MediaOptions::default()
+        };
+        let still = Still::new(&img, PathBuf::from("/walls"), &ctx, options).unwrap();
+
+        let source = source(Media::Still(still));
+        assert!(source.is_animated());
+        assert!(source.needs_redraw());
+    }
+
+    #[test]
+    fn test_animated_still_is_animated() {
+        let Some(ctx) = headless() else { return };
+        let frames = [[255, 0, 0, 255], [0, 0, 255, 255]]
+            .into_iter()
+            .map(|color| AnimationFrame {
+                delay: Duration::from_millis(100),
+                image: image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(1, 1, image::Rgba(color))),
+            })
+            .collect();
+        let animated = AnimatedStill::new(frames, PathBuf::from("/walls"), &ctx, MediaOptions::default())
+            .unwrap()
+            .expect("two frames should animate");
+
+        let source = source(Media::Animated(animated));
+        assert!(source.is_animated());
+        assert!(source.needs_redraw());
+    }
+
+    #[test]
+    fn test_playing_video_is_animated() {
+        let Some(ctx) = headless() else { return };
+        let dir = ScratchDir::new("media-video");
+        let clip = dir.join("clip.avi");
+        if !encode_clip(&clip) {
+            warn!("GStreamer can't encode a test clip, skipping");
+            return;
         }
+        let options = MediaOptions {
+            playback_rate: 1.0,
+            ..MediaOptions::default()
+        };
+        let video = Video::new(clip, dir.clone(), &ctx, options).unwrap();
+
+        let source = source(Media::Video(video));
+        assert!(source.is_animated());
+        assert!(source.needs_redraw());
+    }
+
+    #[test]
+    fn test_fallback_is_not_animated() {
+        let Some(ctx) = headless() else { return };
+        let dir = ScratchDir::new("media-fallback");
+
+        let source = MediaSource::from_directory(&dir, None, &ctx, MediaOptions::default()).unwrap();
+        assert!(matches!(source.media, Media::Fallback { .. }));
+        assert!(!source.is_animated());
+        assert!(!source.needs_redraw());
+    }
+
+    #[test]
+    fn test_fallback_texture_holds_the_fallback_color() {
+        let Some(ctx) = headless() else { return };
+        let dir = ScratchDir::new("media-fallback-texture");
+        let options = MediaOptions {
+            fallback_color: [0.0, 0.0, 1.0, 1.0],
+            ..MediaOptions::default()
+        };
+
+        let source = MediaSource::from_directory(&dir, None, &ctx, options).unwrap();
+
+        // Media that turns up in the directory later fades in from this texture
+        let fade = FadeTransition::new(
+            Some(source.texture().clone()),
+            Duration::from_secs(1),
+            FadeOptions::default(),
+            &ctx,
+        )
+        .unwrap();
+        fade.render(&ctx, source.texture());
+        assert_eq!(ctx.read_pixels().unwrap().get_pixel(0, 0).0, [0, 0, 255, 255]);
     }
 }
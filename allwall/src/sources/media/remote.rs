@@ -0,0 +1,281 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+
+use crate::{prelude::*, sources::error::SourceError};
+
+/// How long a download may take before the cached copy is used instead
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whether a scene path is an `http://` or `https://` URL rather than a file
+pub fn is_remote(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|s| s.starts_with("http://") || s.starts_with("https://"))
+}
+
+/// URLs with a download running in the background, so rotations don't pile up requests
+static IN_FLIGHT: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Mutex::default);
+
+/// Directory downloads are cached in, `$XDG_CACHE_HOME/allwall`
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(xdg::BaseDirectories::with_prefix("allwall").create_cache_directory("")?)
+}
+
+/// The copy of `url` left by an earlier download, or `None` before the first one lands
+///
+/// Never waits on the network: a fresh copy is fetched on a background thread and shows
+/// from the next call on, so a slow server can't stall the event loop.
+pub fn cached_or_refresh(url: &str) -> Result<Option<PathBuf>> {
+    let cache_dir = cache_dir()?;
+    let cached = cached_copy(url, &cache_dir);
+    refresh(url, cache_dir);
+    Ok(cached)
+}
+
+/// Downloads `url` into `cache_dir` on a background thread, unless one is already running
+fn refresh(url: &str, cache_dir: PathBuf) {
+    let in_flight = || IN_FLIGHT.lock().unwrap_or_else(PoisonError::into_inner);
+    if !in_flight().insert(url.to_string()) {
+        return;
+    }
+
+    let owned = url.to_string();
+    let spawned = thread::Builder::new().name("allwall-fetch".into()).spawn(move || {
+        if let Err(e) = fetch_into(&owned, &cache_dir, FETCH_TIMEOUT) {
+            warn!("Could not fetch {owned}: {e}");
+        }
+        in_flight().remove(&owned);
+    });
+
+    if let Err(e) = spawned {
+        warn!("Could not start fetching {url}: {e}");
+        in_flight().remove(url);
+    }
+}
+
+/// Downloads `url` into `cache_dir` and returns the file, or the copy left by an earlier
+/// download when the server can't be reached
+pub fn fetch_into(url: &str, cache_dir: &Path, timeout: Duration) -> Result<PathBuf> {
+    match download(url, cache_dir, timeout) {
+        Ok(path) => Ok(path),
+        Err(e) => match cached_copy(url, cache_dir) {
+            Some(path) => {
+                warn!("Could not fetch {url}, using the cached copy: {e}");
+                Ok(path)
+            },
+            None => Err(e),
+        },
+    }
+}
+
+/// Stem of the cache file for `url`, a hash so any URL makes a valid file name
+fn cache_stem(url: &str) -> String {
+    // FNV-1a, spelled out because std's hasher may change between releases and orphan the cache
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    f!("{hash:016x}")
+}
+
+/// Extension for the cached file, so the media type can still be guessed from its name
+///
+/// Taken from the URL's last segment, or from `content_type` for URLs without one.
+fn cache_extension(url: &str, content_type: Option<&str>) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let from_url = path
+        .rsplit('/')
+        .next()
+        .and_then(|segment| Path::new(segment).extension())
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| mime_guess::from_ext(ext).first().is_some());
+
+    from_url.map(str::to_string).or_else(|| {
+        let essence = content_type?.split(';').next()?.trim();
+        mime_guess::get_mime_extensions_str(essence)?
+            .first()
+            .map(|ext| (*ext).to_string())
+    })
+}
+
+/// File an earlier download of `url` left in `cache_dir`
+fn cached_copy(url: &str, cache_dir: &Path) -> Option<PathBuf> {
+    let stem = cache_stem(url);
+    fs::read_dir(cache_dir)
+        .ok()?
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.is_file() && path.file_stem().is_some_and(|s| s == stem.as_str()))
+}
+
+/// Fetches `url` and stores it in `cache_dir`, through a temporary file so an interrupted
+/// download can't replace a good copy with half an image
+#[cfg(feature = "net")]
+fn download(url: &str, cache_dir: &Path, timeout: Duration) -> Result<PathBuf> {
+    let fetch_error = |e: reqwest::Error| SourceError::Fetch(f!("{url}: {e}"));
+
+    let get = || -> std::result::Result<_, reqwest::Error> {
+        let client = reqwest::blocking::Client::builder().timeout(timeout).build()?;
+        let response = client.get(url).send()?.error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok((response.bytes()?, content_type))
+    };
+
+    // The blocking client panics when used on a tokio runtime thread, which the daemon runs on
+    let (bytes, content_type) = thread::scope(|scope| scope.spawn(get).join())
+        .map_err(|_| SourceError::Fetch(f!("{url}: download thread panicked")))?
+        .map_err(fetch_error)?;
+
+    let stem = cache_stem(url);
+    let name = match cache_extension(url, content_type.as_deref()) {
+        Some(ext) => f!("{stem}.{ext}"),
+        None => stem,
+    };
+
+    fs::create_dir_all(cache_dir)?;
+    let path = cache_dir.join(name);
+    let tmp = cache_dir.join(f!(".{}.part", cache_stem(url)));
+    fs::write(&tmp, &bytes)?;
+    fs::rename(&tmp, &path)?;
+
+    debug!("Fetched {url} into {}", path.display());
+    Ok(path)
+}
+
+/// Stand-in when built without the `net` feature, so only previously cached copies can be shown
+#[cfg(not(feature = "net"))]
+fn download(url: &str, _cache_dir: &Path, _timeout: Duration) -> Result<PathBuf> {
+    Err(SourceError::NetUnsupported(url.to_string()).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    /// A port nothing listens on, so connecting fails straight away
+    fn unreachable_url(file: &str) -> String {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        f!("http://127.0.0.1:{port}/{file}")
+    }
+
+    #[test]
+    fn test_is_remote() {
+        assert!(is_remote(Path::new("https://example.com/wall.jpg")));
+        assert!(is_remote(Path::new("http://example.com/")));
+        assert!(!is_remote(Path::new("/home/me/walls")));
+        assert!(!is_remote(Path::new("httpdocs/wall.jpg")));
+    }
+
+    #[test]
+    fn test_cache_stem_is_stable_and_distinct() {
+        assert_eq!(cache_stem("https://a/b.png"), cache_stem("https://a/b.png"));
+        assert_ne!(cache_stem("https://a/b.png"), cache_stem("https://a/c.png"));
+        assert_eq!(cache_stem("").len(), 16);
+    }
+
+    #[test]
+    fn test_cache_extension() {
+        assert_eq!(
+            cache_extension("https://a/b.jpg?size=4k#top", None).as_deref(),
+            Some("jpg")
+        );
+        assert_eq!(
+            cache_extension("https://a/b.png", Some("image/jpeg")).as_deref(),
+            Some("png")
+        );
+        assert!(cache_extension("https://a/latest", Some("image/png; charset=binary")).is_some());
+        assert_eq!(cache_extension("https://a/latest", None), None);
+        assert_eq!(cache_extension("https://example.com", None), None);
+    }
+
+    #[test]
+    fn test_fetch_falls_back_to_cached_copy() {
+        let dir = ScratchDir::new("remote-fallback");
+        let url = unreachable_url("wall.png");
+        let cached = dir.join(f!("{}.png", cache_stem(&url)));
+        fs::write(&cached, b"earlier download").unwrap();
+
+        assert_eq!(fetch_into(&url, &dir, Duration::from_secs(2)).unwrap(), cached);
+    }
+
+    #[test]
+    fn test_fetch_without_cache_fails() {
+        let dir = ScratchDir::new("remote-uncached");
+        assert!(fetch_into(&unreachable_url("wall.png"), &dir, Duration::from_secs(2)).is_err());
+    }
+
+    #[cfg(feature = "net")]
+    mod net {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        use super::*;
+
+        /// Serves one request with `status`, `content_type` and `body`, returning the server's base URL
+        fn serve_once(status: &'static str, content_type: &'static str, body: &'static [u8]) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                let head = f!(
+                    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(body).unwrap();
+            });
+
+            f!("http://{addr}")
+        }
+
+        #[test]
+        fn test_fetch_downloads_into_cache() {
+            let dir = ScratchDir::new("remote-download");
+            let url = f!("{}/wall.png", serve_once("200 OK", "image/png", b"pixels"));
+
+            let path = fetch_into(&url, &dir, Duration::from_secs(5)).unwrap();
+            assert_eq!(path, dir.join(f!("{}.png", cache_stem(&url))));
+            assert_eq!(fs::read(&path).unwrap(), b"pixels");
+        }
+
+        #[test]
+        fn test_fetch_names_extensionless_urls_by_content_type() {
+            let dir = ScratchDir::new("remote-content-type");
+            let url = f!("{}/latest", serve_once("200 OK", "image/jpeg", b"pixels"));
+
+            let path = fetch_into(&url, &dir, Duration::from_secs(5)).unwrap();
+            assert!(crate::sources::media::is_supported_media(&path));
+        }
+
+        #[test]
+        fn test_fetch_error_status_keeps_cached_copy() {
+            let dir = ScratchDir::new("remote-status");
+            let url = f!("{}/wall.png", serve_once("404 Not Found", "text/plain", b"gone"));
+            let cached = dir.join(f!("{}.png", cache_stem(&url)));
+            fs::write(&cached, b"earlier download").unwrap();
+
+            assert_eq!(fetch_into(&url, &dir, Duration::from_secs(5)).unwrap(), cached);
+            assert_eq!(fs::read(&cached).unwrap(), b"earlier download");
+        }
+    }
+}
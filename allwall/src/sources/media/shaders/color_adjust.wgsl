@@ -0,0 +1,19 @@
+
+struct ColorAdjust {
+    // Per-channel multipliers moving the white point for the temperature setting
+    tint: vec3<f32>,
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+};
+
+// Grades a premultiplied color, leaving it untouched for the neutral defaults
+fn adjust_color(color: vec4<f32>, adjust: ColorAdjust) -> vec4<f32> {
+    let mid = 0.5 * color.a;
+    var rgb = color.rgb * adjust.brightness;
+    rgb = (rgb - mid) * adjust.contrast + mid;
+    let luma = dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+    rgb = mix(vec3<f32>(luma), rgb, adjust.saturation);
+    rgb = rgb * adjust.tint;
+    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(color.a)), color.a);
+}
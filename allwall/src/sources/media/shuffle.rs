@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use rand::{Rng, seq::SliceRandom};
+
+/// One shuffled pass over a media directory, so every file is shown once before any repeats
+///
+/// The first `shown` entries of `cycle` have been displayed this pass, the last of them
+/// being the current file. The rest are still waiting their turn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShuffleBag {
+    cycle: Vec<PathBuf>,
+    shown: usize,
+}
+
+impl ShuffleBag {
+    /// File shown most recently this pass, if any
+    pub fn current(&self) -> Option<&Path> {
+        self.shown.checked_sub(1).map(|i| self.cycle[i].as_path())
+    }
+
+    /// Bag matched against the directory's current `files`, reshuffled once the pass is over
+    ///
+    /// Deleted files drop out and new ones join the unshown part in random order. A fresh
+    /// pass moves the current file to the end so it never plays twice in a row.
+    pub fn refreshed(&self, files: &[PathBuf], rng: &mut impl Rng) -> Self {
+        let shown = self.cycle[..self.shown].iter().filter(|path| files.contains(path)).count();
+        let mut cycle: Vec<_> = self.cycle.iter().filter(|path| files.contains(path)).cloned().collect();
+        let mut added: Vec<_> = files.iter().filter(|path| !self.cycle.contains(path)).cloned().collect();
+        added.shuffle(rng);
+        cycle.extend(added);
+
+        if shown < cycle.len() {
+            return Self { cycle, shown };
+        }
+
+        cycle.shuffle(rng);
+        if let Some(current) = self.current()
+            && let Some(i) = cycle.iter().position(|path| path == current)
+        {
+            let last = cycle.remove(i);
+            cycle.push(last);
+        }
+        Self { cycle, shown: 0 }
+    }
+
+    /// Files not yet shown this pass, in the order they're due
+    pub fn next_candidates(&self) -> Vec<PathBuf> {
+        self.cycle[self.shown..].to_vec()
+    }
+
+    /// Bag after showing `next`, one of [`Self::next_candidates`]
+    ///
+    /// Candidates before `next` couldn't be opened, so they count as shown for this pass
+    /// instead of being retried every rotation.
+    pub fn advanced(&self, next: &Path) -> Self {
+        let shown = self.cycle[self.shown..]
+            .iter()
+            .position(|path| path == next)
+            .map_or(self.shown, |i| self.shown + i + 1);
+
+        Self {
+            cycle: self.cycle.clone(),
+            shown,
+        }
+    }
+
+    /// Bag after stepping back to the file shown before the current one this pass
+    pub fn rewound(&self) -> Option<Self> {
+        (self.shown >= 2).then(|| Self {
+            cycle: self.cycle.clone(),
+            shown: self.shown - 1,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::prelude::f;
+
+    fn files(count: usize) -> Vec<PathBuf> {
+        (0..count).map(|i| PathBuf::from(f!("/wallpapers/{i}.png"))).collect()
+    }
+
+    /// Shows the next file `count` times, returning the bag and every file shown
+    fn rotate(mut bag: ShuffleBag, files: &[PathBuf], count: usize, rng: &mut StdRng) -> (ShuffleBag, Vec<PathBuf>) {
+        let mut shown = Vec::new();
+        for _ in 0..count {
+            bag = bag.refreshed(files, rng);
+            let next = bag.next_candidates().into_iter().next().unwrap();
+            bag = bag.advanced(&next);
+            shown.push(next);
+        }
+        (bag, shown)
+    }
+
+    #[test]
+    fn test_full_cycle_shows_every_file_once() {
+        let files = files(8);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (bag, mut shown) = rotate(ShuffleBag::default(), &files, files.len(), &mut rng);
+        assert_eq!(bag.current(), shown.last().map(PathBuf::as_path));
+
+        shown.sort();
+        assert_eq!(shown, files);
+    }
+
+    #[test]
+    fn test_every_cycle_shows_every_file_once() {
+        let files = files(5);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let (_, shown) = rotate(ShuffleBag::default(), &files, files.len() * 4, &mut rng);
+        for (cycle, window) in shown.chunks(files.len()).enumerate() {
+            let mut window = window.to_vec();
+            window.sort();
+            assert_eq!(window, files, "cycle {cycle} repeated a file");
+        }
+    }
+
+    #[test]
+    fn test_new_cycle_does_not_repeat_last_file() {
+        let files = files(3);
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (_, shown) = rotate(ShuffleBag::default(), &files, files.len() * 3, &mut rng);
+            assert!(
+                shown.windows(2).all(|pair| pair[0] != pair[1]),
+                "seed {seed}: {shown:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rewound_steps_back_within_cycle() {
+        let files = files(4);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let (bag, shown) = rotate(ShuffleBag::default(), &files, 3, &mut rng);
+        let bag = bag.rewound().unwrap();
+        assert_eq!(bag.current(), Some(shown[1].as_path()));
+
+        let bag = bag.rewound().unwrap();
+        assert_eq!(bag.current(), Some(shown[0].as_path()));
+        assert!(bag.rewound().is_none());
+
+        let bag = bag.refreshed(&files, &mut rng);
+        assert_eq!(bag.next_candidates().first(), Some(&shown[1]));
+    }
+
+    #[test]
+    fn test_advanced_past_unreadable_files_counts_them_shown() {
+        let files = files(4);
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let bag = ShuffleBag::default().refreshed(&files, &mut rng);
+        let candidates = bag.next_candidates();
+        let bag = bag.advanced(&candidates[2]);
+
+        assert_eq!(bag.current(), Some(candidates[2].as_path()));
+        assert_eq!(bag.next_candidates(), vec![candidates[3].clone()]);
+    }
+
+    #[test]
+    fn test_refreshed_picks_up_added_and_removed_files() {
+        let mut files = files(3);
+        let mut rng = StdRng::seed_from_u64(9);
+
+        let (bag, shown) = rotate(ShuffleBag::default(), &files, 1, &mut rng);
+        files.retain(|path| *path != shown[0]);
+        files.push(PathBuf::from("/wallpapers/new.png"));
+
+        let bag = bag.refreshed(&files, &mut rng);
+        assert_eq!(bag.current(), None);
+
+        let mut candidates = bag.next_candidates();
+        candidates.sort();
+        files.sort();
+        assert_eq!(candidates, files);
+    }
+}
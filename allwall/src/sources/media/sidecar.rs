@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::MediaOptions;
+use crate::{
+    engine::Fit,
+    prelude::*,
+    transitions::{TransitionType, config::TransitionConfig},
+};
+
+/// Extension appended to a media file's name for its own sidecar, as in `photo.jpg.meta`
+const SIDECAR_EXTENSION: &str = "meta";
+/// Per-directory sidecar holding a table for each file, keyed by file name
+const DIRECTORY_SIDECAR: &str = ".allwall.toml";
+
+/// Settings a single media file overrides over its scene's, read from sidecar metadata
+///
+/// # Example
+///
+/// ```toml
+/// # photo.jpg.meta
+/// fit = "contain"
+/// transition = "wipe"
+/// duration = 3
+///
+/// # .allwall.toml, next to the files it describes
+/// ["panorama.png"]
+/// fit = "cover"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MediaOverrides {
+    /// How the file fits within monitor bounds
+    pub fit: Option<Fit>,
+
+    /// Transition played when the file comes on screen
+    pub transition: Option<TransitionType>,
+
+    /// Duration of that transition in seconds
+    pub duration: Option<u64>,
+}
+
+impl MediaOverrides {
+    /// Overrides from a file's own `.meta` sidecar
+    pub fn parse(text: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Overrides for every file listed in a directory's `.allwall.toml`
+    pub fn parse_directory(text: &str) -> std::result::Result<HashMap<String, Self>, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// These overrides, with any setting they leave unset taken from `fallback`
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            fit: self.fit.or(fallback.fit),
+            transition: self.transition.or(fallback.transition),
+            duration: self.duration.or(fallback.duration),
+        }
+    }
+
+    /// The scene's media options with this file's fit applied
+    pub fn options(&self, scene: &MediaOptions) -> MediaOptions {
+        MediaOptions {
+            fit: self.fit.unwrap_or(scene.fit),
+            ..scene.clone()
+        }
+    }
+
+    /// The scene's transition with this file's type and duration applied
+    pub fn transition(&self, scene: &TransitionConfig) -> TransitionConfig {
+        TransitionConfig {
+            r#type: self.transition.or(scene.r#type),
+            duration: self.duration.unwrap_or(scene.duration),
+            ..scene.clone()
+        }
+    }
+
+    /// Overrides for `path` from its `.meta` sidecar, layered over its entry in the
+    /// directory's `.allwall.toml`
+    ///
+    /// Missing sidecars leave the scene's settings alone. Unreadable or malformed ones
+    /// are skipped with a warning rather than keeping the file off screen.
+    pub fn load(path: &Path) -> Self {
+        let Some(name) = path.file_name() else {
+            return Self::default();
+        };
+
+        let directory = path
+            .parent()
+            .map(|dir| dir.join(DIRECTORY_SIDECAR))
+            .and_then(|sidecar| read_sidecar(&sidecar, Self::parse_directory))
+            .and_then(|mut entries| entries.remove(name.to_string_lossy().as_ref()))
+            .unwrap_or_default();
+
+        let own = read_sidecar(&sidecar_path(path, name), Self::parse).unwrap_or_default();
+        own.or(directory)
+    }
+}
+
+/// `photo.jpg.meta` for `photo.jpg`
+fn sidecar_path(path: &Path, name: &OsStr) -> PathBuf {
+    let mut sidecar = OsString::from(name);
+    sidecar.push(".");
+    sidecar.push(SIDECAR_EXTENSION);
+    path.with_file_name(sidecar)
+}
+
+/// Parses the sidecar at `path`, or `None` when there isn't a usable one
+fn read_sidecar<T>(path: &Path, parse: impl FnOnce(&str) -> std::result::Result<T, toml::de::Error>) -> Option<T> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Ignoring unreadable sidecar {}: {}", path.display(), e);
+            return None;
+        },
+    };
+
+    parse(&text)
+        .inspect_err(|e| warn!("Ignoring malformed sidecar {}: {}", path.display(), e))
+        .ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    #[test]
+    fn test_parse_file_sidecar() {
+        let overrides = MediaOverrides::parse("fit = \"contain\"\ntransition = \"wipe\"\nduration = 3").unwrap();
+        assert_eq!(
+            overrides,
+            MediaOverrides {
+                fit: Some(Fit::Contain),
+                transition: Some(TransitionType::Wipe),
+                duration: Some(3),
+            }
+        );
+        assert_eq!(MediaOverrides::parse("").unwrap(), MediaOverrides::default());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys_and_values() {
+        assert!(MediaOverrides::parse("fitt = \"cover\"").is_err());
+        assert!(MediaOverrides::parse("fit = \"squash\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_directory_sidecar() {
+        let entries = MediaOverrides::parse_directory(
+            r#"
+            ["photo.jpg"]
+            fit = "cover"
+
+            ["portrait.png"]
+            fit = "contain"
+            transition = "fade"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries["photo.jpg"].fit, Some(Fit::Cover));
+        assert_eq!(entries["portrait.png"].transition, Some(TransitionType::Fade));
+    }
+
+    #[test]
+    fn test_file_sidecar_wins_over_directory_entry() {
+        let own = MediaOverrides {
+            fit: Some(Fit::Contain),
+            ..Default::default()
+        };
+        let directory = MediaOverrides {
+            fit: Some(Fit::Cover),
+            duration: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(
+            own.or(directory),
+            MediaOverrides {
+                fit: Some(Fit::Contain),
+                transition: None,
+                duration: Some(4),
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_over_scene_defaults() {
+        let scene_options = MediaOptions {
+            fit: Fit::Cover,
+            blur: 2.0,
+            ..Default::default()
+        };
+        let scene_transition = TransitionConfig {
+            r#type: Some(TransitionType::Fade),
+            duration: 1,
+            interval: 30,
+            ..Default::default()
+        };
+        let overrides = MediaOverrides {
+            fit: Some(Fit::Contain),
+            transition: Some(TransitionType::Dip),
+            duration: None,
+        };
+
+        let options = overrides.options(&scene_options);
+        assert_eq!(options.fit, Fit::Contain);
+        assert_eq!(options.blur, 2.0);
+
+        let transition = overrides.transition(&scene_transition);
+        assert_eq!(transition.transition_type(), TransitionType::Dip);
+        assert_eq!(transition.duration, 1);
+        assert_eq!(transition.interval, 30);
+    }
+
+    #[test]
+    fn test_no_overrides_keep_scene_defaults() {
+        let scene_transition = TransitionConfig::default();
+        let transition = MediaOverrides::default().transition(&scene_transition);
+        assert_eq!(transition.r#type, scene_transition.r#type);
+        assert_eq!(transition.duration, scene_transition.duration);
+        assert_eq!(
+            MediaOverrides::default().options(&MediaOptions::default()).fit,
+            Fit::default()
+        );
+    }
+
+    #[test]
+    fn test_sidecar_path_appends_extension() {
+        let path = Path::new("/walls/photo.jpg");
+        assert_eq!(
+            sidecar_path(path, path.file_name().unwrap()),
+            PathBuf::from("/walls/photo.jpg.meta")
+        );
+    }
+
+    #[test]
+    fn test_load_missing_sidecars_is_default() {
+        let dir = ScratchDir::new("sidecar-missing");
+        assert_eq!(MediaOverrides::load(&dir.join("photo.jpg")), MediaOverrides::default());
+    }
+
+    #[test]
+    fn test_load_layers_file_over_directory() {
+        let dir = ScratchDir::new("sidecar-layered");
+        std::fs::write(
+            dir.join(DIRECTORY_SIDECAR),
+            "[\"photo.jpg\"]\nfit = \"cover\"\nduration = 2\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("photo.jpg.meta"), "fit = \"contain\"\n").unwrap();
+
+        let overrides = MediaOverrides::load(&dir.join("photo.jpg"));
+        let other = MediaOverrides::load(&dir.join("other.jpg"));
+
+        assert_eq!(overrides.fit, Some(Fit::Contain));
+        assert_eq!(overrides.duration, Some(2));
+        assert_eq!(other, MediaOverrides::default());
+    }
+}
@@ -0,0 +1,148 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{BasicSource, InteractionState, RenderState, Source, SourceType},
+    transitions::TransitionConfig,
+};
+
+use super::{AnimationFrame, MediaOptions, Still};
+
+/// Shortest frame delay honoured, since many encoders write 0 to mean "as fast as possible"
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// Frame to show once `elapsed` has passed since `current` came up, and the time already spent on it
+fn advance_frame(delays: &[Duration], mut current: usize, mut elapsed: Duration) -> (usize, Duration) {
+    if delays.is_empty() {
+        return (0, Duration::ZERO);
+    }
+
+    while elapsed >= delays[current] {
+        elapsed -= delays[current];
+        current = (current + 1) % delays.len();
+    }
+    (current, elapsed)
+}
+
+/// A multi-frame image such as an animated WebP, drawn through [`Still`] one frame at a time
+#[derive(Debug)]
+pub struct AnimatedStill {
+    still: Still,
+    textures: Vec<Texture>,
+    delays: Vec<Duration>,
+    current: usize,
+    /// Frame the still is currently bound to, which lags `current` until the next render
+    shown: usize,
+    elapsed: Duration,
+}
+
+impl AnimatedStill {
+    /// Uploads every frame, returning `None` when there are none to show
+    pub fn new(
+        frames: Vec<AnimationFrame>,
+        img_dir: PathBuf,
+        ctx: &Context,
+        options: MediaOptions,
+    ) -> Result<Option<Self>> {
+        debug!("Creating AnimatedStill source with {} frames", frames.len());
+        let textures: Vec<_> = frames
+            .iter()
+            .map(|frame| Texture::from_image(&frame.image, ctx, options.scaling, options.mipmaps))
+            .collect();
+        let delays = frames.iter().map(|frame| frame.delay.max(MIN_FRAME_DELAY)).collect();
+        let Some(first) = textures.first() else {
+            return Ok(None);
+        };
+        let still = Still::from_texture(first.clone(), img_dir, ctx, options)?;
+
+        Ok(Some(Self {
+            still,
+            textures,
+            delays,
+            current: 0,
+            shown: 0,
+            elapsed: Duration::ZERO,
+        }))
+    }
+
+    pub fn directory(&self) -> &PathBuf {
+        self.still.directory()
+    }
+}
+
+impl Source for AnimatedStill {
+    fn texture(&self) -> &Texture {
+        self.still.texture()
+    }
+
+    fn state(&self) -> &RenderState {
+        self.still.state()
+    }
+
+    fn load(&mut self, ctx: &Context) -> Result<()> {
+        self.still.load(ctx)
+    }
+
+    fn start_transition(
+        &mut self,
+        previous: Option<SourceType>,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
+        ctx: &Context,
+    ) {
+        self.still.start_transition(previous, transition, interaction, ctx);
+    }
+
+    fn update(&mut self, dt: Duration) {
+        self.still.update(dt);
+        (self.current, self.elapsed) = advance_frame(&self.delays, self.current, self.elapsed + dt);
+    }
+
+    fn needs_redraw(&self) -> bool {
+        true
+    }
+}
+
+impl BasicSource for AnimatedStill {
+    fn render(&mut self, ctx: &Context) {
+        if self.shown != self.current
+            && let Some(texture) = self.textures.get(self.current)
+        {
+            self.still.set_texture(texture.clone(), ctx);
+            self.shown = self.current;
+        }
+        self.still.render(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAYS: [Duration; 2] = [Duration::from_millis(100), Duration::from_millis(50)];
+
+    #[test]
+    fn test_advance_frame_holds_until_delay() {
+        assert_eq!(
+            advance_frame(&DELAYS, 0, Duration::from_millis(99)),
+            (0, Duration::from_millis(99))
+        );
+    }
+
+    #[test]
+    fn test_advance_frame_carries_remainder() {
+        assert_eq!(
+            advance_frame(&DELAYS, 0, Duration::from_millis(120)),
+            (1, Duration::from_millis(20))
+        );
+    }
+
+    #[test]
+    fn test_advance_frame_wraps_to_first() {
+        assert_eq!(
+            advance_frame(&DELAYS, 1, Duration::from_millis(160)),
+            (0, Duration::from_millis(10))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        INDICES, create_index_buffer, create_pipeline, create_texture_bind_group, create_texture_bind_group_layout,
+        create_uniform_binds, create_vertex_buffer,
+    },
+};
+
+/// Taps on each side of the centre sample, including the centre itself
+const BLUR_TAPS: usize = 16;
+
+/// How many standard deviations the outermost tap sits from the centre
+const BLUR_SIGMAS: f32 = 3.0;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct BlurUniforms {
+    step: [f32; 2],
+    _padding: [f32; 2],
+    weights: [[f32; 4]; BLUR_TAPS / 4],
+}
+
+/// One-sided Gaussian kernel with the outermost tap at [`BLUR_SIGMAS`] standard deviations
+///
+/// The centre weight is counted once and every other weight twice, once per side,
+/// and the whole kernel is normalized so the blur neither brightens nor darkens.
+fn gaussian_weights() -> [f32; BLUR_TAPS] {
+    let mut weights = [0.0; BLUR_TAPS];
+    for (i, weight) in weights.iter_mut().enumerate() {
+        let x = i as f32 / (BLUR_TAPS - 1) as f32 * BLUR_SIGMAS;
+        *weight = (-0.5 * x * x).exp();
+    }
+
+    let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+    for weight in &mut weights {
+        *weight /= total;
+    }
+    weights
+}
+
+/// Distance in pixels between neighbouring taps so the kernel spans `radius`
+fn tap_spacing(radius: f32) -> f32 {
+    radius / (BLUR_TAPS - 1) as f32
+}
+
+fn blur_uniforms(step: [f32; 2]) -> BlurUniforms {
+    let weights = gaussian_weights();
+    let mut packed = [[0.0; 4]; BLUR_TAPS / 4];
+    for (i, weight) in weights.into_iter().enumerate() {
+        packed[i / 4][i % 4] = weight;
+    }
+
+    BlurUniforms {
+        step,
+        _padding: [0.0; 2],
+        weights: packed,
+    }
+}
+
+/// Offscreen textures the still is drawn into before blurring, sized to the surface
+#[derive(Debug)]
+struct BlurTargets {
+    width: u32,
+    height: u32,
+    scene: Texture,
+    scene_bind_group: wgpu::BindGroup,
+    horizontal: Texture,
+    horizontal_bind_group: wgpu::BindGroup,
+}
+
+impl BlurTargets {
+    fn new(layout: &wgpu::BindGroupLayout, ctx: &Context) -> Self {
+        let config = ctx.config();
+        let (width, height) = (config.width.max(1), config.height.max(1));
+
+        let scene = Texture::empty_writable_format(ctx, width, height, config.format);
+        let horizontal = Texture::empty_writable_format(ctx, width, height, config.format);
+
+        Self {
+            width,
+            height,
+            scene_bind_group: create_texture_bind_group(layout, &[&scene], ctx),
+            horizontal_bind_group: create_texture_bind_group(layout, &[&horizontal], ctx),
+            scene,
+            horizontal,
+        }
+    }
+
+    fn matches(&self, ctx: &Context) -> bool {
+        let config = ctx.config();
+        self.width == config.width.max(1) && self.height == config.height.max(1)
+    }
+}
+
+/// Separable Gaussian blur applied between drawing a still and presenting it
+///
+/// The still is rendered into an offscreen texture, blurred horizontally into a
+/// second texture, then blurred vertically onto the surface.
+#[derive(Debug)]
+pub struct BlurPass {
+    radius: f32,
+    pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    horizontal_uniforms: wgpu::Buffer,
+    horizontal_uniform_bind_group: wgpu::BindGroup,
+    vertical_uniforms: wgpu::Buffer,
+    vertical_uniform_bind_group: wgpu::BindGroup,
+
+    targets: BlurTargets,
+}
+
+impl BlurPass {
+    pub fn new(radius: f32, ctx: &Context) -> Result<Self> {
+        let device = ctx.device();
+        let targets_layout = create_texture_bind_group_layout(1, ctx);
+
+        let size = std::mem::size_of::<BlurUniforms>() as u64;
+        let (horizontal_uniforms, uniform_bind_group_layout, horizontal_uniform_bind_group) =
+            create_uniform_binds(size, ctx);
+        let vertical_uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("blur_vertical_uniforms"),
+            size,
+            mapped_at_creation: false,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let vertical_uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("blur_vertical_uniforms"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: vertical_uniforms.as_entire_binding(),
+            }],
+        });
+
+        let pipeline = create_pipeline(
+            ctx,
+            &[&targets_layout, &uniform_bind_group_layout],
+            &device.create_shader_module(crate::wgsl!("./shaders/blur.wgsl")),
+            ctx.config(),
+        )?;
+
+        Ok(Self {
+            radius,
+            pipeline,
+            targets: BlurTargets::new(&targets_layout, ctx),
+            texture_bind_group_layout: targets_layout,
+            vertex_buffer: create_vertex_buffer(ctx),
+            index_buffer: create_index_buffer(ctx),
+            horizontal_uniforms,
+            horizontal_uniform_bind_group,
+            vertical_uniforms,
+            vertical_uniform_bind_group,
+        })
+    }
+
+    /// Resizes the offscreen targets to the surface and uploads the per-direction tap offsets
+    pub fn prepare(&mut self, ctx: &Context) {
+        if !self.targets.matches(ctx) {
+            self.targets = BlurTargets::new(&self.texture_bind_group_layout, ctx);
+        }
+
+        let spacing = tap_spacing(self.radius);
+        let horizontal = blur_uniforms([spacing / self.targets.width as f32, 0.0]);
+        let vertical = blur_uniforms([0.0, spacing / self.targets.height as f32]);
+
+        let queue = ctx.queue();
+        queue.write_buffer(&self.horizontal_uniforms, 0, bytemuck::cast_slice(&[horizontal]));
+        queue.write_buffer(&self.vertical_uniforms, 0, bytemuck::cast_slice(&[vertical]));
+    }
+
+    /// Texture the still should be drawn into before [`BlurPass::encode`] runs
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        self.targets.scene.view()
+    }
+
+    /// Records the horizontal and vertical passes, writing the blurred image to `output`
+    ///
+    /// `load` applies to `output` only, the intermediate target is always cleared.
+    pub fn encode(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        output: &wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) {
+        self.draw(
+            encoder,
+            self.targets.horizontal.view(),
+            [&self.targets.scene_bind_group, &self.horizontal_uniform_bind_group],
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+        );
+        self.draw(
+            encoder,
+            output,
+            [&self.targets.horizontal_bind_group, &self.vertical_uniform_bind_group],
+            load,
+        );
+    }
+
+    fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        bind_groups: [&wgpu::BindGroup; 2],
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("blur_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        for (i, bind_group) in bind_groups.into_iter().enumerate() {
+            render_pass.set_bind_group(i as u32, bind_group, &[]);
+        }
+        render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaussian_weights_sum_to_one() {
+        let weights = gaussian_weights();
+        let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+        assert!((total - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_gaussian_weights_fall_off() {
+        let weights = gaussian_weights();
+        assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(weights[BLUR_TAPS - 1] < weights[0] * 0.02);
+    }
+
+    #[test]
+    fn test_tap_spacing_spans_radius() {
+        assert_eq!(tap_spacing(0.0), 0.0);
+        assert!((tap_spacing(30.0) * (BLUR_TAPS - 1) as f32 - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_blur_uniforms_pack_weights_in_order() {
+        let weights = gaussian_weights();
+        let uniforms = blur_uniforms([0.5, 0.0]);
+        assert_eq!(uniforms.step, [0.5, 0.0]);
+        assert_eq!(uniforms.weights[0][0], weights[0]);
+        assert_eq!(uniforms.weights[3][3], weights[BLUR_TAPS - 1]);
+    }
+
+    #[test]
+    fn test_blur_uniforms_layout_matches_shader() {
+        // vec2 step padded to 16 bytes, then array<vec4<f32>, 4>
+        assert_eq!(std::mem::size_of::<BlurUniforms>(), 16 + 16 * (BLUR_TAPS / 4));
+    }
+}
@@ -0,0 +1,216 @@
+use std::{fs::File, io::BufReader, path::Path, time::Duration};
+
+use image::{AnimationDecoder, DynamicImage, codecs::webp::WebPDecoder};
+
+use crate::{prelude::*, sources::error::SourceError};
+
+/// Decodes the image at `path`, handing HEIC/AVIF to [`decode_special`] since `image` can't read them
+pub fn open_image(path: &Path) -> Result<DynamicImage> {
+    if needs_special_decoder(path) {
+        return decode_special(path);
+    }
+
+    Ok(image::open(path)?)
+}
+
+/// One frame of an animated image and how long it stays on screen
+pub struct AnimationFrame {
+    pub image: DynamicImage,
+    pub delay: Duration,
+}
+
+/// Every frame of an animated WebP, or `None` for a single-frame file that belongs on the plain still path
+pub fn decode_animation(path: &Path) -> Result<Option<Vec<AnimationFrame>>> {
+    let is_webp = mime_guess::from_path(path)
+        .iter()
+        .any(|mime| mime.essence_str() == "image/webp");
+    if !is_webp {
+        return Ok(None);
+    }
+
+    let decoder = WebPDecoder::new(BufReader::new(File::open(path)?))?;
+    if !decoder.has_animation() {
+        return Ok(None);
+    }
+
+    let frames = decoder.into_frames().collect_frames()?;
+    if frames.len() <= 1 {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        frames
+            .into_iter()
+            .map(|frame| AnimationFrame {
+                delay: Duration::from(frame.delay()),
+                image: DynamicImage::ImageRgba8(frame.into_buffer()),
+            })
+            .collect(),
+    ))
+}
+
+/// Whether `path` is a HEIF-family container (HEIC or AVIF)
+fn needs_special_decoder(path: &Path) -> bool {
+    mime_guess::from_path(path)
+        .iter()
+        .any(|mime| matches!(mime.essence_str(), "image/heic" | "image/heif" | "image/avif"))
+}
+
+/// Decodes a HEIC or AVIF file through libheif
+#[cfg(feature = "heif")]
+pub fn decode_special(path: &Path) -> Result<DynamicImage> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let decode_error = |e: libheif_rs::HeifError| SourceError::HeifDecode(f!("{}: {e}", path.display()));
+
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| SourceError::HeifDecode(f!("{}: path is not valid UTF-8", path.display())))?;
+    let context = HeifContext::read_from_file(path_str).map_err(decode_error)?;
+    let handle = context.primary_image_handle().map_err(decode_error)?;
+    let decoded = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        .map_err(decode_error)?;
+
+    let plane = decoded
+        .planes()
+        .interleaved
+        .ok_or_else(|| SourceError::HeifDecode(f!("{}: no interleaved RGBA plane", path.display())))?;
+
+    // Rows may be padded past width * 4 bytes, so copy them out one at a time
+    let row_bytes = plane.width as usize * 4;
+    let pixels = plane
+        .data
+        .chunks(plane.stride)
+        .take(plane.height as usize)
+        .flat_map(|row| &row[..row_bytes])
+        .copied()
+        .collect();
+
+    image::RgbaImage::from_raw(plane.width, plane.height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| SourceError::HeifDecode(f!("{}: truncated pixel data", path.display())).into())
+}
+
+/// Stand-in when built without the `heif` feature, so HEIC/AVIF files are skipped with a clear reason
+#[cfg(not(feature = "heif"))]
+pub fn decode_special(path: &Path) -> Result<DynamicImage> {
+    warn!(
+        "HEIC support not compiled in, skipping {} (rebuild with --features heif)",
+        path.display()
+    );
+    Err(SourceError::HeifUnsupported(path.to_path_buf()).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::utils::scratch::ScratchDir;
+
+    /// The lone VP8L chunk of a lossless 1x1 WebP, to reuse as an animation frame
+    fn lossless_frame(color: [u8; 4]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut encoded)
+            .encode(&color, 1, 1, image::ExtendedColorType::Rgba8)
+            .unwrap();
+        let start = encoded.windows(4).position(|w| w == b"VP8L").unwrap();
+        encoded[start..].to_vec()
+    }
+
+    fn chunk(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut chunk = fourcc.to_vec();
+        chunk.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(payload);
+        if payload.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    /// A 1x1 animated WebP showing each of `colors` for 100ms
+    fn animated_webp(colors: &[[u8; 4]]) -> Vec<u8> {
+        // Animation and alpha flags, then the canvas size minus one as two 24-bit values
+        let vp8x = [0x12, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut body = b"WEBP".to_vec();
+        body.extend(chunk(b"VP8X", &vp8x));
+        body.extend(chunk(b"ANIM", &[0; 6]));
+        for color in colors {
+            // Offset, size minus one, 100ms duration, then blend/dispose flags
+            let mut frame = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 100, 0, 0, 0];
+            frame.extend(lossless_frame(*color));
+            body.extend(chunk(b"ANMF", &frame));
+        }
+        chunk(b"RIFF", &body)
+    }
+
+    #[test]
+    fn test_decode_animation_reads_every_webp_frame() {
+        let dir = ScratchDir::new("decode-animated");
+        let path = dir.join("animated.webp");
+        std::fs::write(&path, animated_webp(&[[255, 0, 0, 255], [0, 0, 255, 255]])).unwrap();
+
+        let frames = decode_animation(&path).unwrap().expect("two frames should animate");
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].delay, Duration::from_millis(100));
+        assert_eq!(frames[1].image.to_rgba8().get_pixel(0, 0).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_decode_animation_leaves_static_webp_to_still_path() {
+        let mut encoded = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut encoded)
+            .encode(&[0, 255, 0, 255], 1, 1, image::ExtendedColorType::Rgba8)
+            .unwrap();
+        let dir = ScratchDir::new("decode-static");
+        let path = dir.join("static.webp");
+        std::fs::write(&path, encoded).unwrap();
+
+        let frames = decode_animation(&path).unwrap();
+
+        assert!(frames.is_none());
+    }
+
+    #[test]
+    fn test_decode_animation_ignores_other_formats() {
+        assert!(decode_animation(Path::new("/nonexistent/a.png")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_needs_special_decoder() {
+        assert!(needs_special_decoder(Path::new("/photos/a.heic")));
+        assert!(needs_special_decoder(Path::new("/photos/b.avif")));
+        assert!(!needs_special_decoder(Path::new("/photos/c.png")));
+        assert!(!needs_special_decoder(Path::new("/photos/d.jpg")));
+    }
+
+    #[cfg(not(feature = "heif"))]
+    #[test]
+    fn test_decode_special_without_feature_is_unsupported() {
+        let result = decode_special(Path::new("/photos/a.heic"));
+        assert!(matches!(result, Err(Error::Source(SourceError::HeifUnsupported(_)))));
+    }
+
+    #[cfg(feature = "heif")]
+    #[test]
+    fn test_decode_special_heic_has_dimensions() {
+        use libheif_rs::{Channel, ColorSpace, CompressionFormat, HeifContext, Image, LibHeif, RgbChroma};
+
+        let dir = ScratchDir::new("decode-heic");
+        let path = dir.join("sample.heic");
+
+        // libheif can encode as well as decode, so build the sample rather than check one in
+        let lib_heif = LibHeif::new();
+        let mut sample = Image::new(16, 8, ColorSpace::Rgb(RgbChroma::Rgb)).unwrap();
+        sample.create_plane(Channel::Interleaved, 16, 8, 8).unwrap();
+        let mut encoder = lib_heif.encoder_for_format(CompressionFormat::Hevc).unwrap();
+        let mut context = HeifContext::new().unwrap();
+        context.encode_image(&sample, &mut encoder, None).unwrap();
+        context.write_to_file(path.to_str().unwrap()).unwrap();
+
+        let img = decode_special(&path).unwrap();
+
+        assert_eq!((img.width(), img.height()), (16, 8));
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::sources::types::UvRect;
+
+/// Zoom reached at the end of a pan
+const MAX_ZOOM: f32 = 1.1;
+
+/// How long a pan lasts on scenes that never rotate
+const STATIC_PAN_PERIOD: Duration = Duration::from_secs(60);
+
+/// Time a pan spreads over for a scene rotating every `interval_secs`
+pub fn pan_period(interval_secs: u64) -> Duration {
+    match interval_secs {
+        0 => STATIC_PAN_PERIOD,
+        secs => Duration::from_secs(secs),
+    }
+}
+
+/// Part of the content shown at `t` (0.0 - 1.0) of a pan drifting towards `drift`
+///
+/// The window shrinks from the whole image to `1 / MAX_ZOOM` of it while its center
+/// moves towards `drift`, each axis from -1.0 to 1.0, never past the image's edges.
+pub fn pan_window(t: f32, drift: [f32; 2]) -> UvRect {
+    let t = t.clamp(0.0, 1.0);
+    let size = 1.0 / (1.0 + (MAX_ZOOM - 1.0) * t);
+    let slack = (1.0 - size) / 2.0;
+    let offset = [
+        slack + drift[0].clamp(-1.0, 1.0) * slack,
+        slack + drift[1].clamp(-1.0, 1.0) * slack,
+    ];
+    UvRect::new(offset[0], offset[1], size, size)
+}
+
+/// `slice` of the content, as a span layout hands each output, seen through `window`
+pub fn zoomed(slice: UvRect, window: UvRect) -> UvRect {
+    UvRect::new(
+        window.x + slice.x * window.width,
+        window.y + slice.y * window.height,
+        slice.width * window.width,
+        slice.height * window.height,
+    )
+}
+
+/// Slow zoom and drift across a still for as long as it's on screen
+#[derive(Debug, Clone, Copy)]
+pub struct KenBurns {
+    period: Duration,
+    elapsed: Duration,
+    drift: [f32; 2],
+}
+
+impl KenBurns {
+    /// A pan over `period` in a random direction
+    pub fn new(period: Duration) -> Self {
+        let mut rng = rand::rng();
+        Self {
+            period,
+            elapsed: Duration::ZERO,
+            drift: [rng.random_range(-1.0..=1.0), rng.random_range(-1.0..=1.0)],
+        }
+    }
+
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.period);
+    }
+
+    /// Whether the pan has further to go, after which the last window holds
+    pub fn is_moving(&self) -> bool {
+        self.elapsed < self.period
+    }
+
+    /// Part of the content to show now
+    pub fn window(&self) -> UvRect {
+        let t = if self.period.is_zero() {
+            1.0
+        } else {
+            self.elapsed.as_secs_f32() / self.period.as_secs_f32()
+        };
+        pan_window(t, self.drift)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rect(actual: UvRect, expected: UvRect) {
+        let pairs = [
+            (actual.x, expected.x),
+            (actual.y, expected.y),
+            (actual.width, expected.width),
+            (actual.height, expected.height),
+        ];
+        assert!(
+            pairs.iter().all(|(a, e)| (a - e).abs() < 1e-5),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_pan_window_starts_on_whole_image() {
+        assert_rect(pan_window(0.0, [1.0, -1.0]), UvRect::FULL);
+    }
+
+    #[test]
+    fn test_pan_window_ends_zoomed_in() {
+        let size = 1.0 / MAX_ZOOM;
+        let slack = (1.0 - size) / 2.0;
+        assert_rect(pan_window(1.0, [0.0, 0.0]), UvRect::new(slack, slack, size, size));
+    }
+
+    #[test]
+    fn test_pan_window_drifts_to_the_edge() {
+        let end = pan_window(1.0, [1.0, -1.0]);
+        assert!(
+            (end.x + end.width - 1.0).abs() < 1e-5,
+            "drifts right to the edge: {end:?}"
+        );
+        assert!(end.y.abs() < 1e-5, "drifts up to the edge: {end:?}");
+    }
+
+    #[test]
+    fn test_pan_window_zooms_steadily_and_stays_inside() {
+        let mut last = pan_window(0.0, [0.7, 0.3]);
+        for step in 1..=10 {
+            let window = pan_window(step as f32 / 10.0, [0.7, 0.3]);
+            assert!(window.width < last.width);
+            assert!(window.x >= 0.0 && window.x + window.width <= 1.0);
+            assert!(window.y >= 0.0 && window.y + window.height <= 1.0);
+            last = window;
+        }
+    }
+
+    #[test]
+    fn test_pan_window_clamps_time() {
+        assert_rect(pan_window(2.0, [0.5, 0.5]), pan_window(1.0, [0.5, 0.5]));
+        assert_rect(pan_window(-1.0, [0.5, 0.5]), UvRect::FULL);
+    }
+
+    #[test]
+    fn test_zoomed_full_slice_is_window() {
+        let window = UvRect::new(0.1, 0.05, 0.9, 0.9);
+        assert_rect(zoomed(UvRect::FULL, window), window);
+    }
+
+    #[test]
+    fn test_zoomed_span_slice_stays_in_window() {
+        // The right half of a span, seen through the left 80% of the content
+        let slice = UvRect::new(0.5, 0.0, 0.5, 1.0);
+        let window = UvRect::new(0.0, 0.1, 0.8, 0.8);
+        assert_rect(zoomed(slice, window), UvRect::new(0.4, 0.1, 0.4, 0.8));
+    }
+
+    #[test]
+    fn test_ken_burns_stops_after_period() {
+        let mut pan = KenBurns::new(Duration::from_secs(10));
+        pan.advance(Duration::from_secs(4));
+        assert!(pan.is_moving());
+        pan.advance(Duration::from_secs(7));
+        assert!(!pan.is_moving());
+        assert!((pan.window().width - 1.0 / MAX_ZOOM).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_pan_period_for_static_scene() {
+        assert_eq!(pan_period(0), STATIC_PAN_PERIOD);
+        assert_eq!(pan_period(300), Duration::from_secs(300));
+    }
+}
@@ -0,0 +1,87 @@
+use super::{StillPass, StillUniforms, blur::BlurPass};
+use crate::{
+    engine::Context,
+    prelude::*,
+    sources::{create_blended_pipeline, create_pipeline, create_uniform_binds},
+};
+
+/// Blurred, cover-scaled copy of a still drawn behind it under `letterbox = "blur"`
+///
+/// The copy is drawn into the blur pass's offscreen target and blurred onto the frame,
+/// then the still is drawn contained on top with transparent bars so the fill shows
+/// through them.
+#[derive(Debug)]
+pub struct LetterboxFill {
+    blur: BlurPass,
+    background_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    /// The still's own pipeline with blending, so the bars keep the fill under them
+    overlay_pipeline: wgpu::RenderPipeline,
+}
+
+impl LetterboxFill {
+    pub fn new(
+        radius: f32,
+        texture_layout: &wgpu::BindGroupLayout,
+        still_uniform_layout: &wgpu::BindGroupLayout,
+        shader: &wgpu::ShaderModule,
+        ctx: &Context,
+    ) -> Result<Self> {
+        let (uniform_buffer, uniform_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<StillUniforms>() as u64, ctx);
+
+        Ok(Self {
+            blur: BlurPass::new(radius, ctx)?,
+            background_pipeline: create_pipeline(ctx, &[texture_layout, &uniform_layout], shader, ctx.config())?,
+            uniform_buffer,
+            uniform_bind_group,
+            overlay_pipeline: create_blended_pipeline(
+                ctx,
+                &[texture_layout, still_uniform_layout],
+                shader,
+                ctx.config(),
+                wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            )?,
+        })
+    }
+
+    /// Uploads the still's uniforms for the background, switched from contain to cover
+    pub fn prepare(&mut self, still: StillUniforms, ctx: &Context) {
+        let background = StillUniforms {
+            contained: 0,
+            centered: 0,
+            ..still
+        };
+        ctx.queue()
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[background]));
+        self.blur.prepare(ctx);
+    }
+
+    /// Pass and target for drawing the cover-scaled copy that [`LetterboxFill::blur_background`] blurs
+    pub fn background(&self) -> (StillPass<'_>, &wgpu::TextureView) {
+        let pass = StillPass {
+            pipeline: &self.background_pipeline,
+            uniforms: &self.uniform_bind_group,
+        };
+        (pass, self.blur.scene_view())
+    }
+
+    /// Blurs the drawn background onto `target`
+    pub fn blur_background(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) {
+        self.blur.encode(encoder, target, load);
+    }
+
+    /// Draws the contained still over the fill with the still's own uniforms
+    pub fn overlay<'a>(&'a self, uniforms: &'a wgpu::BindGroup) -> StillPass<'a> {
+        StillPass {
+            pipeline: &self.overlay_pipeline,
+            uniforms,
+        }
+    }
+}
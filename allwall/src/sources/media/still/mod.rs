@@ -1,23 +1,142 @@
-use std::{collections::VecDeque, iter::once, path::PathBuf, time::Duration};
+mod animated;
+mod blur;
+mod decode;
+mod ken_burns;
+mod letterbox;
+mod svg;
 
+use std::{iter::once, path::PathBuf, time::Duration};
+
+use bytemuck::{Pod, Zeroable};
 use image::DynamicImage;
-use rand::seq::SliceRandom;
 
 use crate::{
-    engine::{Context, Texture},
+    engine::{
+        Context, Fit, LetterboxStyle, Texture, Upscale,
+        scene::{center_fit_size, resolve_fit},
+    },
     prelude::*,
     sources::{
-        BasicSource, INDICES, RenderState, Source, SourceType, create_index_buffer, create_pipeline,
-        create_texture_binds, create_uniform_binds, create_vertex_buffer, error::SourceError,
+        BasicSource, INDICES, InteractionState, RenderState, Source, SourceType, create_blended_pipeline,
+        create_index_buffer, create_texture_bind_group, create_texture_binds, create_uniform_binds,
+        create_vertex_buffer,
+        types::{UvRect, surface_rgba},
+    },
+    transitions::{
+        CircleOptions, CircleOrigin, CircleRevealTransition, DipTransition, FadeTransition, PixelateTransition,
+        Transition, TransitionConfig, TransitionType, WipeTransition, boxed,
     },
-    transitions::{CircleOrigin, CircleRevealTransition, FadeTransition, Transition, TransitionType},
 };
 
-const MAX_HISTORY: usize = 10;
+pub use self::animated::AnimatedStill;
+use self::blur::BlurPass;
+pub use self::decode::{AnimationFrame, decode_animation, decode_special, open_image};
+pub use self::ken_burns::pan_period;
+use self::ken_burns::{KenBurns, zoomed};
+use self::letterbox::LetterboxFill;
+#[cfg(feature = "svg")]
+use self::svg::SvgImage;
+pub use self::svg::is_svg;
+#[cfg(not(feature = "svg"))]
+pub use self::svg::unsupported as svg_unsupported;
+use super::{MediaOptions, color_adjust::ColorAdjustUniforms};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct StillUniforms {
+    surface_to_image_arr: f32,
+    _padding: [f32; 3],
+    uv_rect: UvRect,
+    center_size: [f32; 2],
+    centered: u32,
+    contained: u32,
+    letterbox_color: [f32; 4],
+    edge_extend: u32,
+    _padding2: u32,
+    texture_size: [f32; 2],
+    color_adjust: ColorAdjustUniforms,
+}
+
+/// Pipeline and uniforms for one draw of the still's texture
+#[derive(Debug, Clone, Copy)]
+struct StillPass<'a> {
+    pipeline: &'a wgpu::RenderPipeline,
+    uniforms: &'a wgpu::BindGroup,
+}
+
+/// Whether a settled still has to draw again, or its last frame is still on screen
+///
+/// The picture is dirty from construction and after anything that changes it, and stays
+/// dirty until the frame that drew it ends, so every output of a cloned or spanned scene
+/// gets the new picture before redraws stop.
+#[derive(Debug, Clone, Copy)]
+struct Redraw {
+    dirty: bool,
+    /// Whether a frame has drawn the picture since it was last marked dirty
+    drawn: bool,
+}
+
+impl Redraw {
+    fn new() -> Self {
+        Self {
+            dirty: true,
+            drawn: false,
+        }
+    }
+
+    /// The picture changed, so every surface needs it again
+    fn invalidate(&mut self) {
+        self.dirty = true;
+        self.drawn = false;
+    }
+
+    /// Whether to draw into a surface, which may need a frame of its own after a resize
+    fn should_draw(&self, surface_needs_frame: bool) -> bool {
+        self.dirty || surface_needs_frame
+    }
+
+    fn drew(&mut self) {
+        self.drawn = true;
+    }
+
+    /// Marks the picture clean once the frame that drew it is over
+    fn end_frame(&mut self) {
+        if self.drawn {
+            self.dirty = false;
+        }
+    }
+}
+
+/// `static.wgsl` with the `sample_texture` implementation matching `upscale`
+fn shader_for(upscale: Upscale) -> wgpu::ShaderModuleDescriptor<'static> {
+    let (label, shader) = match upscale {
+        Upscale::Linear => (
+            "static_linear",
+            crate::wgsl!(
+                "./shaders/static.wgsl",
+                "./shaders/sample_linear.wgsl",
+                "../shaders/color_adjust.wgsl"
+            ),
+        ),
+        Upscale::Bicubic => (
+            "static_bicubic",
+            crate::wgsl!(
+                "./shaders/static.wgsl",
+                "./shaders/sample_bicubic.wgsl",
+                "../shaders/color_adjust.wgsl"
+            ),
+        ),
+    };
+    wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        ..shader
+    }
+}
 
 #[derive(Debug)]
 pub struct Still {
     texture: Texture,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
     texture_bind_group: wgpu::BindGroup,
 
     vertex_buffer: wgpu::Buffer,
@@ -27,102 +146,125 @@ pub struct Still {
     uniform_bind_group: wgpu::BindGroup,
 
     render_pipeline: wgpu::RenderPipeline,
+    blur: Option<BlurPass>,
+    /// Blurred background for `letterbox = "blur"`
+    letterbox_fill: Option<LetterboxFill>,
+    fit: Fit,
+    smart_max_crop: f32,
+    letterbox: LetterboxStyle,
+    color_adjust: ColorAdjustUniforms,
+    ken_burns: Option<KenBurns>,
+    /// Source drawing for SVG wallpapers, rasterized again when the surface is resized
+    #[cfg(feature = "svg")]
+    svg: Option<SvgImage>,
 
     state: RenderState,
+    redraw: Redraw,
 
     img_dir: PathBuf,
-    current_path: PathBuf,
-    image_history: VecDeque<PathBuf>,
 }
 
 impl Still {
-    pub fn new(img: &DynamicImage, img_dir: PathBuf, ctx: &Context) -> Self {
-        Self::new_with_history(img, img_dir, VecDeque::new(), ctx)
-    }
-
-    pub fn new_with_history(
-        img: &DynamicImage,
-        img_dir: PathBuf,
-        image_history: VecDeque<PathBuf>,
-        ctx: &Context,
-    ) -> Self {
+    pub fn new(img: &DynamicImage, img_dir: PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
         debug!("Creating Still source from image");
-        let texture = Texture::from_image(img, ctx);
-        let current_path = PathBuf::new();
+        Self::from_texture(
+            Texture::from_image(img, ctx, options.scaling, options.mipmaps),
+            img_dir,
+            ctx,
+            options,
+        )
+    }
 
+    pub fn from_texture(texture: Texture, img_dir: PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
         let (texture_bind_group_layout, texture_bind_group) = create_texture_binds(&[&texture], ctx);
 
         let vertex_buffer = create_vertex_buffer(ctx);
         let index_buffer = create_index_buffer(ctx);
 
-        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) = create_uniform_binds(32, ctx);
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<StillUniforms>() as u64, ctx);
 
-        let render_pipeline = create_pipeline(
+        // Translucent textures are uploaded premultiplied, so blend them that way
+        let blend = if texture.has_alpha() {
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+        } else {
+            wgpu::BlendState::REPLACE
+        };
+        let shader = ctx.device().create_shader_module(shader_for(options.upscale));
+        let render_pipeline = create_blended_pipeline(
             ctx,
             &[&texture_bind_group_layout, &uniform_bind_group_layout],
-            &ctx.device().create_shader_module(wgpu::include_wgsl!("./shaders/static.wgsl")),
+            &shader,
             ctx.config(),
-        );
+            blend,
+        )?;
+
+        let blur = (options.blur > 0.0).then(|| BlurPass::new(options.blur, ctx)).transpose()?;
+        let letterbox_fill = (options.letterbox == LetterboxStyle::Blur)
+            .then(|| {
+                LetterboxFill::new(
+                    options.letterbox_blur,
+                    &texture_bind_group_layout,
+                    &uniform_bind_group_layout,
+                    &shader,
+                    ctx,
+                )
+            })
+            .transpose()?;
 
         let state = RenderState::default();
 
-        Self {
+        Ok(Self {
             texture,
+            texture_bind_group_layout,
             texture_bind_group,
             vertex_buffer,
             index_buffer,
             uniform_buffer,
             uniform_bind_group,
             render_pipeline,
+            blur,
+            letterbox_fill,
+            fit: options.fit,
+            smart_max_crop: options.smart_max_crop,
+            letterbox: options.letterbox,
+            color_adjust: options.color_adjust.into(),
+            ken_burns: options.ken_burns.map(KenBurns::new),
+            #[cfg(feature = "svg")]
+            svg: None,
             state,
+            redraw: Redraw::new(),
             img_dir,
-            current_path,
-            image_history,
-        }
+        })
     }
 
-    pub fn with_current_path(mut self, path: PathBuf) -> Self {
-        self.current_path = path;
-        self
+    /// Whether a Ken Burns pan is still moving across the picture
+    pub fn is_panning(&self) -> bool {
+        self.ken_burns.is_some_and(|pan| pan.is_moving())
     }
 
     pub fn directory(&self) -> &PathBuf {
         &self.img_dir
     }
 
-    fn load_next_image(&self) -> Result<(DynamicImage, PathBuf)> {
-        let mut rng = rand::rng();
-        let mut files: Vec<_> = self
-            .img_dir
-            .read_dir()
-            .map_err(|_| SourceError::NoImageDirectory)?
-            .filter_map(std::result::Result::ok)
-            .map(|d| d.path())
-            .filter(|p| p.is_file())
-            .collect();
-
-        if files.is_empty() {
-            return Err(SourceError::NoImagesAvailable.into());
-        }
+    /// Shows `texture` from the next render on, such as the next frame of an animation
+    pub fn set_texture(&mut self, texture: Texture, ctx: &Context) {
+        self.texture_bind_group = create_texture_bind_group(&self.texture_bind_group_layout, &[&texture], ctx);
+        self.texture = texture;
+        self.redraw.invalidate();
+    }
 
-        files.shuffle(&mut rng);
+    fn render_normal(&mut self, ctx: &Context) {
+        #[cfg(feature = "svg")]
+        self.rasterize_if_resized(ctx);
 
-        for path in files {
-            if self.image_history.contains(&path) || path == self.current_path {
-                continue;
-            }
-            if let Ok(img) = image::open(&path) {
-                return Ok((img, path));
-            }
+        // The last frame is still on screen, so skip acquiring a new one until something changes
+        if !self.redraw.should_draw(ctx.needs_frame()) {
+            return;
         }
 
-        Err(SourceError::NoImagesAvailable.into())
-    }
-
-    fn render_normal(&self, ctx: &Context) {
         let queue = ctx.queue();
         let device = ctx.device();
-        let surface = ctx.surface();
 
         debug!(
             "Still rendering, surface aspect: {:.2}, texture aspect: {:.2}",
@@ -130,51 +272,115 @@ impl Still {
             self.texture.aspect_ratio()
         );
 
-        let output = match surface.get_current_texture() {
-            Ok(output) => output,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
+        };
+        let view = output.view();
+
+        let size = self.texture.size();
+        let (letterbox_color, edge_extend) = match self.letterbox {
+            LetterboxStyle::Color(color) => (surface_rgba(color, ctx.config().format), false),
+            LetterboxStyle::EdgeExtend => ([0.0; 4], true),
+            // Transparent bars, so the blurred fill drawn first shows through
+            LetterboxStyle::Blur => ([0.0; 4], false),
         };
-        let view = output.texture.create_view(&Default::default());
+        let surface_to_image_arr = ctx.content_aspect_ratio() / self.texture.aspect_ratio();
+        let fit = resolve_fit(self.fit, surface_to_image_arr, self.smart_max_crop);
+        let uniforms = StillUniforms {
+            surface_to_image_arr,
+            _padding: [0.0; 3],
+            uv_rect: self.ken_burns.map_or(ctx.uv_rect(), |pan| zoomed(ctx.uv_rect(), pan.window())),
+            center_size: center_fit_size((size.width, size.height), ctx.logical_size(), ctx.scale_factor()),
+            centered: u32::from(fit == Fit::Center),
+            contained: u32::from(fit == Fit::Contain),
+            letterbox_color,
+            edge_extend: u32::from(edge_extend),
+            _padding2: 0,
+            texture_size: [size.width as f32, size.height as f32],
+            color_adjust: self.color_adjust,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
-        queue.write_buffer(
-            &self.uniform_buffer,
-            0,
-            bytemuck::cast_slice(&[ctx.surface_aspect_ratio() / self.texture.aspect_ratio()]),
-        );
+        if let Some(blur) = &mut self.blur {
+            blur.prepare(ctx);
+        }
+        // Cover and center leave no bars, so only a contained image needs its fill
+        if let Some(fill) = self.letterbox_fill.as_mut().filter(|_| fit == Fit::Contain) {
+            fill.prepare(uniforms, ctx);
+        }
 
         let mut encoder = device.create_command_encoder(&Default::default());
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.set_bind_group(0, &self.texture_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
-            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        let (target, load) = match &self.blur {
+            Some(blur) => (blur.scene_view(), wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+            None => (&view, ctx.clear_op(wgpu::Color::TRANSPARENT)),
+        };
+        match self.letterbox_fill.as_ref().filter(|_| fit == Fit::Contain) {
+            Some(fill) => {
+                let (background, fill_target) = fill.background();
+                self.draw(
+                    &mut encoder,
+                    fill_target,
+                    wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    background,
+                );
+                fill.blur_background(&mut encoder, target, load);
+                self.draw(
+                    &mut encoder,
+                    target,
+                    wgpu::LoadOp::Load,
+                    fill.overlay(&self.uniform_bind_group),
+                );
+            },
+            None => self.draw(&mut encoder, target, load, self.pass()),
+        }
+        if let Some(blur) = &self.blur {
+            blur.encode(&mut encoder, &view, ctx.clear_op(wgpu::Color::TRANSPARENT));
         }
 
         queue.submit(once(encoder.finish()));
         output.present();
+        self.redraw.drew();
 
         debug!("Still render complete");
     }
+
+    /// The still's own pipeline and uniforms
+    fn pass(&self) -> StillPass<'_> {
+        StillPass {
+            pipeline: &self.render_pipeline,
+            uniforms: &self.uniform_bind_group,
+        }
+    }
+
+    fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+        pass: StillPass<'_>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(pass.pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.set_bind_group(0, &self.texture_bind_group, &[]);
+        render_pass.set_bind_group(1, pass.uniforms, &[]);
+        render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+    }
 }
 
 impl Source for Still {
@@ -189,99 +395,130 @@ impl Source for Still {
     fn load(&mut self, _ctx: &Context) -> Result<()> {
         debug!("Loading Still source");
         self.state = RenderState::Displaying;
+        self.redraw.invalidate();
         Ok(())
     }
 
     fn start_transition(
         &mut self,
         previous: Option<SourceType>,
-        duration: Duration,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
         ctx: &Context,
-        transition_type: TransitionType,
     ) {
-        debug!("Starting {:?} transition with duration {:?}", transition_type, duration);
+        let duration = transition.sampled_duration();
+        debug!(
+            "Starting {:?} transition with duration {:?}",
+            transition.transition_type(),
+            duration
+        );
         let previous_texture = previous.map(|s| s.texture().clone());
-        let transition: Box<dyn Transition> = match transition_type {
-            TransitionType::Fade => Box::new(FadeTransition::new(previous_texture, duration, ctx)),
-            TransitionType::CircleTopLeft => Box::new(CircleRevealTransition::new(
+        let transition: Result<Box<dyn Transition>> = match transition.transition_type() {
+            // Random is settled in `SourceType::start_transition`, so it only lands here when called directly
+            TransitionType::Fade | TransitionType::Random => {
+                FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed)
+            },
+            TransitionType::Dip => DipTransition::new(previous_texture, duration, transition.dip.color, ctx).map(boxed),
+            TransitionType::CircleTopLeft => CircleRevealTransition::new(
+                previous_texture,
+                duration,
+                CircleOptions {
+                    origin: CircleOrigin::TopLeft,
+                    ..transition.circle
+                },
+                None,
+                ctx,
+            )
+            .map(boxed),
+            TransitionType::CircleTopRight => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::TopLeft,
+                CircleOptions {
+                    origin: CircleOrigin::TopRight,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleTopRight => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleBottomLeft => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::TopRight,
+                CircleOptions {
+                    origin: CircleOrigin::BottomLeft,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleBottomLeft => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleBottomRight => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::BottomLeft,
+                CircleOptions {
+                    origin: CircleOrigin::BottomRight,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleBottomRight => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleCenter => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::BottomRight,
+                CircleOptions {
+                    origin: CircleOrigin::Center,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleCenter => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleRandom => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::Center,
+                CircleOptions {
+                    origin: CircleOrigin::Random,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleRandom => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleMouse => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::Random,
+                CircleOptions {
+                    origin: CircleOrigin::Mouse,
+                    ..transition.circle
+                },
+                interaction.mouse.map(|(x, y)| [x, y]),
                 ctx,
-            )),
+            )
+            .map(boxed),
+            TransitionType::Wipe => WipeTransition::new(previous_texture, duration, transition.wipe, ctx).map(boxed),
+            TransitionType::Pixelate => PixelateTransition::new(previous_texture, duration, ctx).map(boxed),
         };
-        self.state = RenderState::Transitioning(transition);
+        self.state = RenderState::transitioning(transition);
     }
 
     fn update(&mut self, dt: Duration) {
+        self.redraw.end_frame();
         if let RenderState::Transitioning(transition) = &mut self.state {
             let complete = transition.update(dt);
             if complete {
                 debug!("Transition complete, switching to Displaying");
                 self.state = RenderState::Displaying;
+                self.redraw.invalidate();
             }
+        } else if let Some(pan) = self.ken_burns.as_mut().filter(|pan| pan.is_moving()) {
+            pan.advance(dt);
+            self.redraw.invalidate();
         }
     }
 
-    fn next(&self, ctx: &Context) -> Result<Self> {
-        let (img, path) = self.load_next_image()?;
-
-        let mut new_history = self.image_history.clone();
-        if !self.current_path.as_os_str().is_empty() {
-            if new_history.len() >= MAX_HISTORY {
-                new_history.pop_front();
-            }
-            new_history.push_back(self.current_path.clone());
-        }
-
-        let mut new_still =
-            Still::new_with_history(&img, self.img_dir.clone(), new_history, ctx).with_current_path(path);
-        new_still.load(ctx)?;
-        Ok(new_still)
-    }
-
-    fn prev(&self, ctx: &Context) -> Result<Self> {
-        let prev_path = self.image_history.back().ok_or(SourceError::NoPreviousImage)?.clone();
-
-        let img = image::open(&prev_path)?;
-
-        let mut new_history = self.image_history.clone();
-        new_history.pop_back();
-
-        let mut new_still =
-            Still::new_with_history(&img, self.img_dir.clone(), new_history, ctx).with_current_path(prev_path);
-        new_still.load(ctx)?;
-        Ok(new_still)
+    fn needs_redraw(&self) -> bool {
+        !matches!(self.state, RenderState::Displaying) || self.is_panning()
     }
 }
 
@@ -297,3 +534,95 @@ impl BasicSource for Still {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redraw_starts_dirty() {
+        let redraw = Redraw::new();
+        assert!(redraw.should_draw(false));
+    }
+
+    #[test]
+    fn test_redraw_clean_after_drawn_frame() {
+        let mut redraw = Redraw::new();
+        redraw.drew();
+        // Other outputs of the same frame still get the picture
+        assert!(redraw.should_draw(false));
+
+        redraw.end_frame();
+        assert!(!redraw.should_draw(false));
+    }
+
+    #[test]
+    fn test_redraw_stays_dirty_until_drawn() {
+        let mut redraw = Redraw::new();
+        redraw.end_frame();
+        assert!(redraw.should_draw(false));
+    }
+
+    #[test]
+    fn test_redraw_resized_surface_draws_when_clean() {
+        let mut redraw = Redraw::new();
+        redraw.drew();
+        redraw.end_frame();
+        assert!(redraw.should_draw(true));
+    }
+
+    #[test]
+    fn test_redraw_invalidated_by_transition_end() {
+        let mut redraw = Redraw::new();
+        redraw.drew();
+        redraw.end_frame();
+
+        redraw.invalidate();
+        assert!(redraw.should_draw(false));
+        redraw.end_frame();
+        assert!(redraw.should_draw(false));
+    }
+
+    #[test]
+    fn test_still_uniforms_layout_matches_shader() {
+        // vec4 uv_rect and letterbox_color aligned to 16 bytes, vec2 texture_size to 8,
+        // the color_adjust struct to 16, and the whole struct padded to a multiple of 16
+        assert_eq!(std::mem::size_of::<StillUniforms>(), 112);
+        assert_eq!(std::mem::offset_of!(StillUniforms, color_adjust), 80);
+    }
+
+    /// Mirrors `catmull_rom_weights` in `sample_bicubic.wgsl`
+    fn catmull_rom_weights(t: f32) -> [f32; 4] {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        [
+            -0.5 * t3 + t2 - 0.5 * t,
+            1.5 * t3 - 2.5 * t2 + 1.0,
+            -1.5 * t3 + 2.0 * t2 + 0.5 * t,
+            0.5 * t3 - 0.5 * t2,
+        ]
+    }
+
+    #[test]
+    fn test_catmull_rom_weights_sum_to_one() {
+        for t in [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0] {
+            let sum: f32 = catmull_rom_weights(t).iter().sum();
+            assert!((sum - 1.0).abs() < 1e-6, "weights at {t} sum to {sum}");
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom_weights_interpolate_texel_centers() {
+        assert_eq!(catmull_rom_weights(0.0), [0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(catmull_rom_weights(1.0), [0.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_bicubic_shader_includes_sampler() {
+        let wgpu::ShaderSource::Wgsl(source) = shader_for(Upscale::Bicubic).source else {
+            panic!("expected WGSL source");
+        };
+        assert!(source.contains("fn catmull_rom_weights"));
+        assert!(source.contains("fn fs_main"));
+    }
+}
@@ -0,0 +1,53 @@
+struct VertexInput {
+  @location(0) position: vec3<f32>,
+  @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    return VertexOutput(
+        vec4<f32>(in.position, 1.0),
+        in.tex_coords,
+    );
+}
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+// Must match BLUR_TAPS in blur.rs
+const TAPS: u32 = 16u;
+
+struct BlurUniforms {
+    // UV distance between neighbouring taps along the blur direction
+    step: vec2<f32>,
+    // One-sided Gaussian weights packed four per vec4, centre tap first
+    weights: array<vec4<f32>, 4>,
+};
+
+@group(1) @binding(0)
+var<uniform> uniforms: BlurUniforms;
+
+fn weight(i: u32) -> f32 {
+    return uniforms.weights[i / 4u][i % 4u];
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(t_source, s_source, in.tex_coords) * weight(0u);
+
+    for (var i = 1u; i < TAPS; i++) {
+        let offset = uniforms.step * f32(i);
+        color += textureSample(t_source, s_source, in.tex_coords + offset) * weight(i);
+        color += textureSample(t_source, s_source, in.tex_coords - offset) * weight(i);
+    }
+
+    return color;
+}
@@ -0,0 +1,36 @@
+
+// Catmull-Rom weights for the four texels around a sample, t being the
+// offset past the second texel's center
+fn catmull_rom_weights(t: f32) -> vec4<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    return vec4<f32>(
+        -0.5 * t3 + t2 - 0.5 * t,
+        1.5 * t3 - 2.5 * t2 + 1.0,
+        -1.5 * t3 + 2.0 * t2 + 0.5 * t,
+        0.5 * t3 - 0.5 * t2,
+    );
+}
+
+// 16-tap Catmull-Rom filtering, sharper than bilinear when upscaling small images
+fn sample_texture(coords: vec2<f32>) -> vec4<f32> {
+    let size = uniforms.texture_size;
+    let texel = coords * size - 0.5;
+    let base = floor(texel);
+    let fraction = texel - base;
+    var wx = catmull_rom_weights(fraction.x);
+    var wy = catmull_rom_weights(fraction.y);
+
+    var color = vec4<f32>(0.0);
+    for (var y = 0; y < 4; y++) {
+        for (var x = 0; x < 4; x++) {
+            let offset = vec2<f32>(f32(x) - 1.0, f32(y) - 1.0);
+            // Sampling at texel centers makes the linear sampler return exact texels
+            let uv = (base + offset + 0.5) / size;
+            color += textureSample(t_diffuse, s_diffuse, uv) * wx[x] * wy[y];
+        }
+    }
+
+    // Catmull-Rom overshoots near hard edges
+    return clamp(color, vec4<f32>(0.0), vec4<f32>(1.0));
+}
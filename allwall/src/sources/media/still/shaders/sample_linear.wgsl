@@ -0,0 +1,5 @@
+
+// Hardware bilinear filtering through the texture's sampler
+fn sample_texture(coords: vec2<f32>) -> vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, coords);
+}
@@ -22,20 +22,58 @@ var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
 var s_diffuse: sampler;
 
+struct Uniforms {
+    surface_to_image_arr: f32,
+    // Slice of the content shown on this surface: xy offset, zw size
+    uv_rect: vec4<f32>,
+    // Fraction of the surface the image covers at 1:1 physical pixels
+    center_size: vec2<f32>,
+    // Nonzero to draw the image centered at center_size instead of cropping to fill
+    centered: u32,
+    // Nonzero to fit the whole image inside the surface, leaving letterbox bars
+    contained: u32,
+    // Bar color under contain when edge_extend is zero
+    letterbox_color: vec4<f32>,
+    // Nonzero to fill bars with the image's edge texels (the sampler clamps to edge)
+    edge_extend: u32,
+    // Source image size in texels, for samplers that read neighbouring texels
+    texture_size: vec2<f32>,
+    color_adjust: ColorAdjust,
+};
+
 @group(1) @binding(0)
-var<uniform> surface_to_image_arr: f32;
+var<uniform> uniforms: Uniforms;
 
+// sample_texture(coords) is appended from sample_linear.wgsl or sample_bicubic.wgsl,
+// then ColorAdjust and adjust_color from color_adjust.wgsl
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let arr = uniforms.surface_to_image_arr;
     let scale = select(
-        vec2<f32>(surface_to_image_arr, 1.0),
-        vec2<f32>(1.0, 1.0 / surface_to_image_arr),
-        surface_to_image_arr > 1.0,
+        vec2<f32>(arr, 1.0),
+        vec2<f32>(1.0, 1.0 / arr),
+        arr > 1.0,
     );
 
-    return textureSample(
-        t_diffuse,
-        s_diffuse,
-        in.tex_coords * scale + 0.5 * (vec2<f32>(1.0) - scale),
+    let content_coords = uniforms.uv_rect.xy + in.tex_coords * uniforms.uv_rect.zw;
+
+    let contain_scale = select(
+        vec2<f32>(1.0, 1.0 / arr),
+        vec2<f32>(arr, 1.0),
+        arr > 1.0,
     );
+
+    let fill_coords = content_coords * scale + 0.5 * (vec2<f32>(1.0) - scale);
+    let contain_coords = (content_coords - 0.5) * contain_scale + 0.5;
+    let center_coords = (content_coords - 0.5) / uniforms.center_size + 0.5;
+    let centered = uniforms.centered != 0u;
+    let contained = uniforms.contained != 0u;
+    let coords = select(select(fill_coords, contain_coords, contained), center_coords, centered);
+
+    let color = adjust_color(sample_texture(coords), uniforms.color_adjust);
+    let outside = any(coords < vec2<f32>(0.0)) || any(coords > vec2<f32>(1.0));
+
+    let bar = select(uniforms.letterbox_color, color, uniforms.edge_extend != 0u);
+    let letterboxed = select(color, bar, contained && outside);
+    return select(letterboxed, vec4<f32>(0.0), centered && outside);
 }
@@ -0,0 +1,196 @@
+use std::path::Path;
+#[cfg(feature = "svg")]
+use std::path::PathBuf;
+
+#[cfg(feature = "svg")]
+use image::DynamicImage;
+
+#[cfg(feature = "svg")]
+use super::{MediaOptions, Still};
+#[cfg(feature = "svg")]
+use crate::engine::{Context, Scaling, Texture};
+use crate::{prelude::*, sources::error::SourceError};
+
+/// Whether `path` is an SVG, which gets rasterized for the surface instead of decoded
+pub fn is_svg(path: &Path) -> bool {
+    mime_guess::from_path(path)
+        .iter()
+        .any(|mime| mime.essence_str() == "image/svg+xml")
+}
+
+/// Why an SVG can't be shown in a build without the `svg` feature
+#[cfg(not(feature = "svg"))]
+pub fn unsupported(path: &Path) -> Error {
+    SourceError::SvgUnsupported(path.to_path_buf()).into()
+}
+
+/// Pixel size that keeps an `intrinsic`-sized drawing's aspect ratio while covering `surface`
+#[cfg(feature = "svg")]
+fn raster_size(intrinsic: (f32, f32), surface: (u32, u32)) -> (u32, u32) {
+    let scale = (surface.0 as f32 / intrinsic.0).max(surface.1 as f32 / intrinsic.1);
+    (
+        ((intrinsic.0 * scale).round() as u32).max(1),
+        ((intrinsic.1 * scale).round() as u32).max(1),
+    )
+}
+
+/// An SVG wallpaper and the surface size it was last rasterized for
+#[cfg(feature = "svg")]
+#[derive(Debug)]
+pub struct SvgImage {
+    path: PathBuf,
+    surface: (u32, u32),
+    /// Texture options each rasterization is uploaded with
+    scaling: Scaling,
+    mipmaps: bool,
+}
+
+#[cfg(feature = "svg")]
+impl SvgImage {
+    /// Rasterizes `path` to cover `surface`
+    pub fn open(path: &Path, surface: (u32, u32), options: &MediaOptions) -> Result<(Self, DynamicImage)> {
+        let img = rasterize(path, surface)?;
+        Ok((
+            Self {
+                path: path.to_path_buf(),
+                surface,
+                scaling: options.scaling,
+                mipmaps: options.mipmaps,
+            },
+            img,
+        ))
+    }
+
+    /// A fresh rasterization when `surface` differs from the last one, so a resized surface stays crisp
+    pub fn rerasterize(&mut self, surface: (u32, u32)) -> Result<Option<DynamicImage>> {
+        if surface == self.surface {
+            return Ok(None);
+        }
+
+        let img = rasterize(&self.path, surface)?;
+        self.surface = surface;
+        Ok(Some(img))
+    }
+}
+
+#[cfg(feature = "svg")]
+impl Still {
+    /// Rasterizes the SVG at `path` for the current surface size
+    pub fn from_svg(path: &Path, img_dir: PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
+        debug!("Creating Still source from SVG {}", path.display());
+        let (svg, img) = SvgImage::open(path, ctx.size(), &options)?;
+        let mut still = Self::new(&img, img_dir, ctx, options)?;
+        still.svg = Some(svg);
+        Ok(still)
+    }
+
+    /// Rasterizes an SVG wallpaper again if the surface changed size since it was last drawn
+    pub(super) fn rasterize_if_resized(&mut self, ctx: &Context) {
+        let Some(svg) = &mut self.svg else {
+            return;
+        };
+
+        match svg.rerasterize(ctx.size()) {
+            Ok(Some(img)) => {
+                let texture = Texture::from_image(&img, ctx, svg.scaling, svg.mipmaps);
+                self.set_texture(texture, ctx);
+            },
+            Ok(None) => {},
+            Err(e) => warn!("Keeping the previous SVG rasterization: {e}"),
+        }
+    }
+}
+
+/// Renders the SVG at `path` through resvg, scaled to cover `surface`
+#[cfg(feature = "svg")]
+pub fn rasterize(path: &Path, surface: (u32, u32)) -> Result<DynamicImage> {
+    use resvg::{tiny_skia, usvg};
+
+    let render_error = |reason: String| SourceError::SvgRender(f!("{}: {reason}", path.display()));
+
+    let data = std::fs::read(path)?;
+    let mut options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        ..Default::default()
+    };
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_data(&data, &options).map_err(|e| render_error(e.to_string()))?;
+
+    let intrinsic = tree.size();
+    let (width, height) = raster_size((intrinsic.width(), intrinsic.height()), surface);
+    let mut pixmap =
+        tiny_skia::Pixmap::new(width, height).ok_or_else(|| render_error(f!("can't allocate {width}x{height}")))?;
+    let transform =
+        tiny_skia::Transform::from_scale(width as f32 / intrinsic.width(), height as f32 / intrinsic.height());
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // tiny-skia works in premultiplied alpha, but textures are uploaded from straight alpha
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    image::RgbaImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| render_error("truncated pixel data".to_string()).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_svg() {
+        assert!(is_svg(Path::new("/walls/a.svg")));
+        assert!(!is_svg(Path::new("/walls/b.png")));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_raster_size_covers_surface() {
+        assert_eq!(raster_size((100.0, 50.0), (400, 200)), (400, 200));
+        assert_eq!(raster_size((100.0, 50.0), (400, 400)), (800, 400));
+        assert_eq!(raster_size((100.0, 50.0), (100, 200)), (400, 200));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_raster_size_never_empty() {
+        assert_eq!(raster_size((100.0, 50.0), (0, 0)), (1, 1));
+    }
+
+    #[cfg(not(feature = "svg"))]
+    #[test]
+    fn test_svg_without_feature_is_unsupported() {
+        let error = unsupported(Path::new("/walls/a.svg"));
+        assert!(matches!(error, Error::Source(SourceError::SvgUnsupported(_))));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_rasterize_matches_surface_resolution() {
+        use crate::utils::scratch::ScratchDir;
+
+        let dir = ScratchDir::new("svg-rasterize");
+        let path = dir.join("wall.svg");
+        std::fs::write(
+            &path,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="50"><rect width="100" height="50" fill="#ff0000"/></svg>"##,
+        )
+        .unwrap();
+
+        let (mut svg, img) = SvgImage::open(&path, (400, 200), &MediaOptions::default()).unwrap();
+        let resized = svg.rerasterize((800, 400)).unwrap().expect("a new size should rasterize again");
+        let unchanged = svg.rerasterize((800, 400)).unwrap();
+
+        assert_eq!((img.width(), img.height()), (400, 200));
+        assert_eq!(img.to_rgba8().get_pixel(200, 100).0, [255, 0, 0, 255]);
+        assert_eq!((resized.width(), resized.height()), (800, 400));
+        assert!(unchanged.is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{engine::Texture, prelude::*};
+
+/// Entries by path, most recently used at the front
+#[derive(Debug)]
+struct Lru<T> {
+    capacity: usize,
+    entries: VecDeque<(PathBuf, T)>,
+}
+
+impl<T: Clone> Lru<T> {
+    fn get(&mut self, path: &Path) -> Option<T> {
+        let index = self.entries.iter().position(|(p, _)| p == path)?;
+        let entry = self.entries.remove(index)?;
+        let value = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(value)
+    }
+
+    /// Stores `value` as the most recent entry, dropping the least recently used past capacity
+    fn insert(&mut self, path: PathBuf, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.entries.retain(|(p, _)| *p != path);
+        self.entries.push_front((path, value));
+        self.entries.truncate(self.capacity);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        self.entries.retain(|(p, _)| p != path);
+    }
+}
+
+/// Stills decoded and uploaded recently, shared by a media source and every source it
+/// rotates to so stepping back and forth skips the decode
+///
+/// Cloning hands out another handle to the same cache. A capacity of 0 caches nothing.
+#[derive(Debug, Clone)]
+pub struct TextureCache<T = Texture> {
+    lru: Arc<Mutex<Lru<T>>>,
+}
+
+impl<T: Clone> TextureCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lru: Arc::new(Mutex::new(Lru {
+                capacity,
+                entries: VecDeque::with_capacity(capacity),
+            })),
+        }
+    }
+
+    /// The cached value for `path`, or whatever `load` makes of it, kept for next time
+    pub fn get_or_load(&self, path: &Path, load: impl FnOnce(&Path) -> Result<T>) -> Result<T> {
+        if let Some(value) = self.lock().get(path) {
+            debug!("Texture cache hit for {}", path.display());
+            return Ok(value);
+        }
+
+        let value = load(path)?;
+        self.lock().insert(path.to_path_buf(), value.clone());
+        Ok(value)
+    }
+
+    /// Drops the entry for `path`, such as a file that was just replaced on disk
+    pub fn forget(&self, path: &Path) {
+        self.lock().remove(path);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru<T>> {
+        // Entries are only ever whole values, so a panic mid-update can't leave one half written
+        self.lru.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Clone> Default for TextureCache<T> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Loads the file name as the value, counting how often it's called
+    fn loader(loads: &Cell<usize>) -> impl Fn(&Path) -> Result<String> + '_ {
+        |path| {
+            loads.set(loads.get() + 1);
+            Ok(path.to_string_lossy().into_owned())
+        }
+    }
+
+    #[test]
+    fn test_hit_skips_loader() {
+        let cache = TextureCache::new(2);
+        let loads = Cell::new(0);
+
+        assert_eq!(cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap(), "a.png");
+        assert_eq!(cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap(), "a.png");
+        assert_eq!(loads.get(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = TextureCache::new(2);
+        let loads = Cell::new(0);
+
+        for path in ["a.png", "b.png", "a.png", "c.png"] {
+            cache.get_or_load(Path::new(path), loader(&loads)).unwrap();
+        }
+        assert_eq!(loads.get(), 3);
+
+        // b was used least recently, so c pushed it out while a stayed
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 3);
+        cache.get_or_load(Path::new("b.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 4);
+    }
+
+    #[test]
+    fn test_zero_capacity_always_loads() {
+        let cache = TextureCache::default();
+        let loads = Cell::new(0);
+
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 2);
+    }
+
+    #[test]
+    fn test_failed_load_is_not_cached() {
+        let cache = TextureCache::<String>::new(2);
+
+        let err = cache.get_or_load(Path::new("a.png"), |_| Err(Error::Generic("corrupt".to_string())));
+        assert!(err.is_err());
+
+        let loads = Cell::new(0);
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 1);
+    }
+
+    #[test]
+    fn test_clones_share_entries_and_forget() {
+        let cache = TextureCache::new(2);
+        let handle = cache.clone();
+        let loads = Cell::new(0);
+
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        handle.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 1);
+
+        handle.forget(Path::new("a.png"));
+        cache.get_or_load(Path::new("a.png"), loader(&loads)).unwrap();
+        assert_eq!(loads.get(), 2);
+    }
+}
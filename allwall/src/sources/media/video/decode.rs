@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::config::HwDecode;
+
+/// Hardware decoders tried for `hw_decode = "auto"`, in order
+const AUTO_DECODERS: [&str; 2] = [VAAPI_DECODER, NVDEC_DECODER];
+const VAAPI_DECODER: &str = "vaapidecodebin";
+const NVDEC_DECODER: &str = "nvh264dec";
+
+/// Element that turns the file's compressed stream into raw frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodePath {
+    /// Whatever `decodebin` picks, which may well be a software decoder
+    #[default]
+    Software,
+    /// A hardware decoder element, fed by `parsebin` since it can't demux the file itself
+    Hardware(&'static str),
+}
+
+impl DecodePath {
+    /// Hardware decode paths to try for `hw_decode`, in order, keeping only the elements
+    /// `installed` reports
+    ///
+    /// Software decoding is the fallback once every one of these has failed, or when
+    /// there are none.
+    pub fn hardware(hw_decode: HwDecode, installed: impl Fn(&str) -> bool) -> Vec<Self> {
+        let wanted: &[&'static str] = match hw_decode {
+            HwDecode::Off => &[],
+            HwDecode::Auto => &AUTO_DECODERS,
+            HwDecode::Vaapi => &[VAAPI_DECODER],
+            HwDecode::Nvdec => &[NVDEC_DECODER],
+        };
+
+        wanted
+            .iter()
+            .filter(|element| installed(element))
+            .map(|element| Self::Hardware(element))
+            .collect()
+    }
+
+    /// Whether GStreamer has an element called `name`
+    pub fn installed(name: &str) -> bool {
+        gstreamer::ElementFactory::find(name).is_some()
+    }
+
+    /// The decode stage of a `gst-launch` pipeline description
+    pub fn description(self) -> String {
+        match self {
+            Self::Software => "decodebin".to_string(),
+            Self::Hardware(element) => format!("parsebin ! {element}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Software => write!(f, "decodebin"),
+            Self::Hardware(element) => write!(f, "{element}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(names: &'static [&'static str]) -> impl Fn(&str) -> bool {
+        move |name| names.contains(&name)
+    }
+
+    #[test]
+    fn test_off_ignores_installed_decoders() {
+        assert!(DecodePath::hardware(HwDecode::Off, |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_explicit_decoder_when_installed() {
+        assert_eq!(
+            DecodePath::hardware(HwDecode::Vaapi, only(&["vaapidecodebin", "nvh264dec"])),
+            [DecodePath::Hardware("vaapidecodebin")]
+        );
+        assert_eq!(
+            DecodePath::hardware(HwDecode::Nvdec, only(&["vaapidecodebin", "nvh264dec"])),
+            [DecodePath::Hardware("nvh264dec")]
+        );
+    }
+
+    #[test]
+    fn test_missing_decoder_falls_back_to_software() {
+        assert!(DecodePath::hardware(HwDecode::Vaapi, only(&["nvh264dec"])).is_empty());
+        assert!(DecodePath::hardware(HwDecode::Auto, only(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_auto_tries_vaapi_then_nvdec() {
+        assert_eq!(
+            DecodePath::hardware(HwDecode::Auto, only(&["nvh264dec", "vaapidecodebin"])),
+            [DecodePath::Hardware("vaapidecodebin"), DecodePath::Hardware("nvh264dec")]
+        );
+        assert_eq!(
+            DecodePath::hardware(HwDecode::Auto, only(&["nvh264dec"])),
+            [DecodePath::Hardware("nvh264dec")]
+        );
+    }
+
+    #[test]
+    fn test_description_feeds_hardware_decoder_from_parsebin() {
+        assert_eq!(DecodePath::Software.description(), "decodebin");
+        assert_eq!(
+            DecodePath::Hardware("vaapidecodebin").description(),
+            "parsebin ! vaapidecodebin"
+        );
+    }
+}
@@ -15,6 +15,9 @@ pub enum VideoError {
     #[error("Failed to start pipeline: {0}")]
     PipelineStart(String),
 
+    #[error("Failed to seek pipeline: {0}")]
+    Seek(String),
+
     #[error("Video file not found: {0}")]
     FileNotFound(std::path::PathBuf),
 
@@ -1,26 +1,240 @@
+mod decode;
 pub mod error;
+mod upload;
 
 use std::{iter::once, path::PathBuf, time::Duration};
 
-use gstreamer::{Caps, Pipeline, State, prelude::*};
+use bytemuck::{Pod, Zeroable};
+
+use gstreamer::{
+    BufferRef, Caps, CapsRef, ClockTime, Fraction, Pipeline, Sample, SeekFlags, SeekType, State, prelude::*,
+};
 use gstreamer_app::AppSink;
 use gstreamer_video::VideoFrame;
 
 use crate::{
-    engine::{Context, Texture},
+    config::HwDecode,
+    engine::{Context, Texture, graphics::create_sampled_view},
     prelude::*,
     sources::{
-        BasicSource, INDICES, RenderState, Source, SourceType, create_index_buffer, create_pipeline,
-        create_texture_binds, create_uniform_binds, create_vertex_buffer,
+        BasicSource, INDICES, InteractionState, RenderState, Source, SourceType, create_index_buffer, create_pipeline,
+        create_texture_bind_group, create_texture_binds, create_uniform_binds, create_vertex_buffer,
+    },
+    transitions::{
+        CircleOptions, CircleOrigin, CircleRevealTransition, DipTransition, FadeTransition, PixelateTransition,
+        Transition, TransitionConfig, TransitionType, WipeTransition, boxed,
     },
-    transitions::{CircleOrigin, CircleRevealTransition, FadeTransition, Transition, TransitionType},
 };
 
+use self::decode::DecodePath;
 use self::error::VideoError;
+pub use self::upload::FrameUpload;
+#[cfg(feature = "dmabuf")]
+use self::upload::dmabuf_frame_from_buffer;
+use super::{MediaOptions, color_adjust::ColorAdjustUniforms};
+
+/// How long to wait for a DMA-BUF or hardware decoding pipeline to preroll before falling
+/// back to copying or software decoding
+const NEGOTIATION_TIMEOUT: ClockTime = ClockTime::from_seconds(2);
+/// How long to wait for a pipeline to preroll before changing its playback rate
+const PLAYBACK_RATE_TIMEOUT: ClockTime = ClockTime::from_seconds(2);
+/// Pipeline rebuilds in a row that may still produce no frames before the video is skipped
+const MAX_STALL_RESTARTS: u32 = 3;
+/// How far ahead of the playback position a frame may be before it's taken as a jump in
+/// the stream, such as a seek, and shown straight away instead of waited for
+const MAX_FRAME_LEAD: Duration = Duration::from_secs(1);
+/// Slack for timestamps that land a hair before their frame boundary after rounding
+const FRAME_EPSILON: f64 = 1e-6;
+
+/// Index of the frame on screen `time` into a stream running at `fps`
+fn frame_at(time: Duration, fps: f64) -> u64 {
+    (time.as_secs_f64() * fps + FRAME_EPSILON).floor() as u64
+}
+
+fn clock_duration(time: ClockTime) -> Duration {
+    Duration::from_nanos(time.nseconds())
+}
+
+/// Picks decoded frames by presentation timestamp against the playback position, so each
+/// frame stays up for its own duration whatever rate the display renders at
+#[derive(Debug, Clone, Copy, Default)]
+struct FramePacer {
+    /// Native frame rate from the caps, `None` until known or for variable-rate streams
+    fps: Option<f64>,
+}
+
+impl FramePacer {
+    /// Reads the native frame rate from a sample's caps, where `0/1` means variable
+    fn set_framerate(&mut self, framerate: Fraction) {
+        self.fps = (framerate.numer() > 0 && framerate.denom() > 0)
+            .then(|| f64::from(framerate.numer()) / f64::from(framerate.denom()));
+    }
+
+    /// Whether a frame stamped `pts` belongs on screen at playback `position`
+    ///
+    /// Frames without a timestamp, or too far ahead to be waiting on, show right away.
+    fn is_due(self, pts: Option<Duration>, position: Duration) -> bool {
+        let Some(pts) = pts else {
+            return true;
+        };
+        if pts > position + MAX_FRAME_LEAD {
+            return true;
+        }
+
+        match self.fps {
+            Some(fps) => frame_at(pts, fps) <= frame_at(position, fps),
+            None => pts <= position,
+        }
+    }
+}
+
+/// Whether a video is still decoding or has settled on its final frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Playback {
+    Playing,
+    /// Played to the end without looping, so the last frame stays up as a still
+    Holding,
+}
+
+impl Playback {
+    /// State after the stream ends, `None` when it starts over instead
+    fn at_end(loop_playback: bool) -> Option<Self> {
+        (!loop_playback).then_some(Playback::Holding)
+    }
+
+    fn pulls_frames(self) -> bool {
+        self == Playback::Playing
+    }
+
+    /// Decides what a render tick does before drawing, calling `pull` for a new frame
+    /// only while the video is still playing
+    ///
+    /// A held video draws the texture it already has and never touches the appsink.
+    fn step(self, loop_playback: bool, pull: impl FnOnce() -> FramePull) -> FrameStep {
+        if !self.pulls_frames() {
+            return FrameStep::Cached;
+        }
+        match pull() {
+            FramePull::Uploaded => FrameStep::Uploaded,
+            FramePull::Missed => FrameStep::Missed,
+            FramePull::Failed => FrameStep::Failed,
+            FramePull::Ended => match Self::at_end(loop_playback) {
+                None => FrameStep::Rewind,
+                Some(_) => FrameStep::Hold,
+            },
+        }
+    }
+}
+
+/// What pulling from the appsink produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FramePull {
+    Uploaded,
+    /// Nothing decoded yet, which the stall watchdog counts
+    Missed,
+    /// The stream reached its end
+    Ended,
+    Failed,
+}
+
+/// What a render tick does about the decoder before drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameStep {
+    /// Held on the last frame, so the texture is drawn as it is
+    Cached,
+    Uploaded,
+    Missed,
+    Failed,
+    /// Seek back to the start and keep playing
+    Rewind,
+    /// Drop the pipeline and keep the last frame on screen
+    Hold,
+}
+
+/// What to do about a video that produced no frame this tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StallAction {
+    Wait,
+    Restart,
+    Skip,
+}
+
+/// Counts ticks without a new frame, so a wedged decoder doesn't freeze the wallpaper forever
+#[derive(Debug)]
+struct StallWatchdog {
+    /// Missed frames in a row before acting, 0 to never act
+    threshold: u32,
+    missed: u32,
+    restarts: u32,
+}
+
+impl StallWatchdog {
+    fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            missed: 0,
+            restarts: 0,
+        }
+    }
+
+    /// A frame arrived, so the pipeline is healthy again
+    fn pulled(&mut self) {
+        self.missed = 0;
+        self.restarts = 0;
+    }
+
+    fn missed(&mut self) -> StallAction {
+        if self.threshold == 0 {
+            return StallAction::Wait;
+        }
+
+        self.missed += 1;
+        if self.missed < self.threshold {
+            return StallAction::Wait;
+        }
+
+        self.missed = 0;
+        if self.restarts < MAX_STALL_RESTARTS {
+            self.restarts += 1;
+            StallAction::Restart
+        } else {
+            self.restarts = 0;
+            StallAction::Skip
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct VideoUniforms {
+    surface_to_video_arr: f32,
+    _padding: [f32; 3],
+    color_adjust: ColorAdjustUniforms,
+}
+
+/// Shader drawing frames uploaded by `upload`, with `color_adjust.wgsl` appended
+fn shader_for(upload: FrameUpload) -> wgpu::ShaderModuleDescriptor<'static> {
+    let (label, shader) = match upload {
+        FrameUpload::Copy => (
+            "video",
+            crate::wgsl!("./shaders/video.wgsl", "../shaders/color_adjust.wgsl"),
+        ),
+        #[cfg(feature = "dmabuf")]
+        FrameUpload::DmaBuf => (
+            "video_nv12",
+            crate::wgsl!("./shaders/nv12.wgsl", "../shaders/color_adjust.wgsl"),
+        ),
+    };
+    wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        ..shader
+    }
+}
 
 #[derive(Debug)]
 pub struct Video {
     texture: Texture,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
     texture_bind_group: wgpu::BindGroup,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -28,41 +242,76 @@ pub struct Video {
     uniform_bind_group: wgpu::BindGroup,
     render_pipeline: wgpu::RenderPipeline,
     state: RenderState,
-    _video_path: PathBuf,
+    video_path: PathBuf,
     video_dir: PathBuf,
     pipeline: Option<Pipeline>,
     appsink: Option<AppSink>,
+    upload: FrameUpload,
+    decode: DecodePath,
+    playback_rate: f64,
+    loop_playback: bool,
+    /// Show frames when their timestamp comes due rather than one per render
+    pace_frames: bool,
+    pacer: FramePacer,
+    /// Decoded frame waiting for the playback position to reach it
+    pending: Option<Sample>,
+    playback: Playback,
     frame_aspect_ratio: f32,
+    color_adjust: ColorAdjustUniforms,
+    watchdog: StallWatchdog,
+    /// Set once rebuilding the pipeline stopped helping, until the scene takes it
+    stalled: bool,
 }
 
 impl Video {
-    pub fn new(video_path: PathBuf, video_dir: PathBuf, ctx: &Context) -> Result<Self> {
+    pub fn new(video_path: PathBuf, video_dir: PathBuf, ctx: &Context, options: MediaOptions) -> Result<Self> {
         debug!("Creating Video source from {:?}", video_path);
 
         if !video_path.exists() {
             return Err(VideoError::FileNotFound(video_path).into());
         }
 
-        let texture = Self::create_placeholder_texture(ctx);
+        let (pipeline, appsink, upload, decode) =
+            Self::open_pipeline(&video_path, options.upload, options.hw_decode, options.playback_rate)?;
 
-        let (texture_bind_group_layout, texture_bind_group) = create_texture_binds(&[&texture], ctx);
+        // Resized to the real frame dimensions once the first sample arrives
+        let texture = Self::create_frame_texture(ctx, 1920, 1080);
 
         let vertex_buffer = create_vertex_buffer(ctx);
         let index_buffer = create_index_buffer(ctx);
 
-        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) = create_uniform_binds(32, ctx);
-
-        let render_pipeline = create_pipeline(
-            ctx,
-            &[&texture_bind_group_layout, &uniform_bind_group_layout],
-            &ctx.device().create_shader_module(wgpu::include_wgsl!("./shaders/video.wgsl")),
-            ctx.config(),
-        );
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<VideoUniforms>() as u64, ctx);
 
-        let (pipeline, appsink) = Self::create_pipeline_and_sink(&video_path)?;
+        let (texture_bind_group_layout, texture_bind_group, render_pipeline) = match upload {
+            FrameUpload::Copy => {
+                let (texture_bind_group_layout, texture_bind_group) = create_texture_binds(&[&texture], ctx);
+                let render_pipeline = create_pipeline(
+                    ctx,
+                    &[&texture_bind_group_layout, &uniform_bind_group_layout],
+                    &ctx.device().create_shader_module(shader_for(upload)),
+                    ctx.config(),
+                )?;
+                (texture_bind_group_layout, texture_bind_group, render_pipeline)
+            },
+            #[cfg(feature = "dmabuf")]
+            FrameUpload::DmaBuf => {
+                let y_plane = Texture::empty_format(ctx, 1, 1, wgpu::TextureFormat::R8Unorm);
+                let uv_plane = Texture::empty_format(ctx, 1, 1, wgpu::TextureFormat::Rg8Unorm);
+                let (texture_bind_group_layout, texture_bind_group) = create_texture_binds(&[&y_plane, &uv_plane], ctx);
+                let render_pipeline = create_pipeline(
+                    ctx,
+                    &[&texture_bind_group_layout, &uniform_bind_group_layout],
+                    &ctx.device().create_shader_module(shader_for(upload)),
+                    ctx.config(),
+                )?;
+                (texture_bind_group_layout, texture_bind_group, render_pipeline)
+            },
+        };
 
         Ok(Self {
             texture,
+            texture_bind_group_layout,
             texture_bind_group,
             vertex_buffer,
             index_buffer,
@@ -70,24 +319,35 @@ impl Video {
             uniform_bind_group,
             render_pipeline,
             state: RenderState::default(),
-            _video_path: video_path,
+            video_path,
             video_dir,
             pipeline: Some(pipeline),
             appsink: Some(appsink),
+            upload,
+            decode,
+            playback_rate: options.playback_rate,
+            loop_playback: options.loop_playback,
+            pace_frames: options.pace_frames,
+            pacer: FramePacer::default(),
+            pending: None,
+            playback: Playback::Playing,
             frame_aspect_ratio: 16.0 / 9.0,
+            color_adjust: options.color_adjust.into(),
+            watchdog: StallWatchdog::new(options.stall_frames),
+            stalled: false,
         })
     }
 
-    fn create_placeholder_texture(ctx: &Context) -> Texture {
+    fn create_frame_texture(ctx: &Context, width: u32, height: u32) -> Texture {
         let device = ctx.device();
         let size = wgpu::Extent3d {
-            width: 1920,
-            height: 1080,
+            width,
+            height,
             depth_or_array_layers: 1,
         };
 
         let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("video_placeholder_texture"),
+            label: Some("video_frame_texture"),
             size,
             mip_level_count: 1,
             sample_count: 1,
@@ -96,10 +356,10 @@ impl Video {
             usage: wgpu::TextureUsages::TEXTURE_BINDING
                 | wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
+            view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
         });
 
-        let view = texture.create_view(&Default::default());
+        let view = create_sampled_view(&texture, ctx.config().format);
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
@@ -113,14 +373,71 @@ impl Video {
         Texture::from_existing(texture, view, sampler)
     }
 
-    fn create_pipeline_and_sink(video_path: &PathBuf) -> Result<(Pipeline, AppSink)> {
+    /// Builds the pipeline with the hardware decoder `hw_decode` asks for, falling back to
+    /// software decoding when none is installed or it can't play the file
+    fn open_pipeline(
+        video_path: &PathBuf,
+        preferred: FrameUpload,
+        hw_decode: HwDecode,
+        playback_rate: f64,
+    ) -> Result<(Pipeline, AppSink, FrameUpload, DecodePath)> {
+        let hardware = DecodePath::hardware(hw_decode, DecodePath::installed);
+        if hardware.is_empty() && hw_decode != HwDecode::Off {
+            warn!(
+                "No {hw_decode:?} decoder installed, decoding {:?} in software",
+                video_path
+            );
+        }
+
+        for decode in hardware {
+            match Self::negotiate_pipeline(video_path, preferred, decode, playback_rate) {
+                Ok((pipeline, appsink, upload)) => {
+                    info!("Decoding {:?} in hardware with {decode}", video_path);
+                    return Ok((pipeline, appsink, upload, decode));
+                },
+                Err(e) => warn!("Hardware decoding with {decode} failed for {:?}: {e}", video_path),
+            }
+        }
+
+        debug!("Decoding {:?} with decodebin", video_path);
+
+        let (pipeline, appsink, upload) =
+            Self::negotiate_pipeline(video_path, preferred, DecodePath::Software, playback_rate)?;
+        Ok((pipeline, appsink, upload, DecodePath::Software))
+    }
+
+    /// Builds the pipeline for the preferred upload strategy, falling back to copying frames
+    /// when the decoder cannot hand out DMA-BUF memory
+    fn negotiate_pipeline(
+        video_path: &PathBuf,
+        preferred: FrameUpload,
+        decode: DecodePath,
+        playback_rate: f64,
+    ) -> Result<(Pipeline, AppSink, FrameUpload)> {
+        if preferred != FrameUpload::Copy {
+            match Self::create_pipeline_and_sink(video_path, preferred, decode, playback_rate) {
+                Ok((pipeline, appsink)) => return Ok((pipeline, appsink, preferred)),
+                Err(e) => warn!(
+                    "DMA-BUF decoding unavailable for {:?}, copying frames instead: {e}",
+                    video_path
+                ),
+            }
+        }
+
+        let (pipeline, appsink) = Self::create_pipeline_and_sink(video_path, FrameUpload::Copy, decode, playback_rate)?;
+        Ok((pipeline, appsink, FrameUpload::Copy))
+    }
+
+    fn create_pipeline_and_sink(
+        video_path: &PathBuf,
+        upload: FrameUpload,
+        decode: DecodePath,
+        playback_rate: f64,
+    ) -> Result<(Pipeline, AppSink)> {
         let path_str = video_path
             .to_str()
             .ok_or_else(|| VideoError::Generic("Invalid path encoding".to_string()))?;
-        let pipeline_str = format!(
-            "filesrc location='{}' ! decodebin ! videoconvert ! videoscale ! video/x-raw,format=RGBA,width=1920,height=1080 ! appsink name=sink caps=video/x-raw,format=RGBA",
-            path_str
-        );
+        let pipeline_str = upload.pipeline_description(path_str, decode);
 
         let pipeline = gstreamer::parse::launch(&pipeline_str)
             .map_err(|e| VideoError::PipelineParse(e.to_string()))?
@@ -133,7 +450,39 @@ impl Video {
             .downcast::<AppSink>()
             .map_err(|_| VideoError::SinkCast)?;
 
-        appsink.set_caps(Some(&Caps::builder("video/x-raw").field("format", "RGBA").build()));
+        let caps = upload
+            .appsink_caps()
+            .parse::<Caps>()
+            .map_err(|e| VideoError::PipelineParse(e.to_string()))?;
+        appsink.set_caps(Some(&caps));
+
+        if upload != FrameUpload::Copy || decode != DecodePath::Software {
+            // Caps negotiation only happens once data flows, so preroll before committing to it
+            let prerolled = pipeline
+                .set_state(State::Paused)
+                .and_then(|_| pipeline.state(NEGOTIATION_TIMEOUT).0);
+            if let Err(e) = prerolled {
+                let _ = pipeline.set_state(State::Null);
+                return Err(VideoError::PipelineStart(e.to_string()).into());
+            }
+        }
+
+        if (playback_rate - 1.0).abs() > f64::EPSILON {
+            // Seeks are only handled once the pipeline has prerolled
+            let prerolled = pipeline
+                .set_state(State::Paused)
+                .and_then(|_| pipeline.state(PLAYBACK_RATE_TIMEOUT).0);
+            let applied = match prerolled {
+                Ok(_) => Self::apply_playback_rate(&pipeline, playback_rate),
+                Err(e) => Err(VideoError::PipelineStart(e.to_string()).into()),
+            };
+            if let Err(e) = applied {
+                warn!(
+                    "Playing {:?} at normal speed instead of {playback_rate}x: {e}",
+                    video_path
+                );
+            }
+        }
 
         pipeline
             .set_state(State::Playing)
@@ -142,13 +491,68 @@ impl Video {
         Ok((pipeline, appsink))
     }
 
+    /// Plays on from the current position at `rate` times normal speed
+    ///
+    /// A seek without a rate resets it to 1.0, so this has to run again after
+    /// any seek back to the start of the video.
+    fn apply_playback_rate(pipeline: &Pipeline, rate: f64) -> Result<()> {
+        let position = pipeline.query_position::<ClockTime>().unwrap_or(ClockTime::ZERO);
+        pipeline
+            .seek(
+                rate,
+                SeekFlags::FLUSH | SeekFlags::ACCURATE,
+                SeekType::Set,
+                position,
+                SeekType::End,
+                ClockTime::ZERO,
+            )
+            .map_err(|e| VideoError::Seek(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Uploads the newest decoded frame that's due at the current playback position
+    ///
+    /// Frames decoded early wait in `pending` until the position reaches them, and frames
+    /// that fell behind are dropped for newer ones. Only a decoder with nothing to hand
+    /// over errs with `NoFrames`, which the stall watchdog counts.
     fn pull_frame(&mut self, ctx: &Context) -> Result<()> {
         let appsink = self.appsink.as_ref().ok_or(VideoError::NoFrames)?;
+        let position = self
+            .pipeline
+            .as_ref()
+            .and_then(|pipeline| pipeline.query_position::<ClockTime>())
+            .map(clock_duration);
+
+        let Some(position) = position.filter(|_| self.pace_frames) else {
+            let sample = appsink
+                .try_pull_sample(ClockTime::from_mseconds(10))
+                .ok_or(VideoError::NoFrames)?;
+            return self.upload_sample(&sample, ctx);
+        };
+
+        let mut due = None;
+        loop {
+            let Some(sample) = self.pending.take().or_else(|| appsink.try_pull_sample(ClockTime::ZERO)) else {
+                break;
+            };
+            let pts = sample.buffer().and_then(BufferRef::pts).map(clock_duration);
+            if self.pacer.is_due(pts, position) {
+                due = Some(sample);
+            } else {
+                self.pending = Some(sample);
+                break;
+            }
+        }
 
-        let sample = appsink
-            .try_pull_sample(gstreamer::ClockTime::from_mseconds(10))
-            .ok_or_else(|| VideoError::NoFrames)?;
+        match due {
+            Some(sample) => self.upload_sample(&sample, ctx),
+            // The next frame is decoded and just not due yet, so the decoder is keeping up
+            None if self.pending.is_some() => Ok(()),
+            None => Err(VideoError::NoFrames.into()),
+        }
+    }
 
+    fn upload_sample(&mut self, sample: &Sample, ctx: &Context) -> Result<()> {
         let buffer = sample.buffer().ok_or(VideoError::BufferNotFound)?;
         let caps = sample
             .caps()
@@ -161,50 +565,34 @@ impl Video {
         let height = structure.get::<i32>("height").map_err(|e| VideoError::Generic(e.to_string()))? as u32;
 
         self.frame_aspect_ratio = width as f32 / height as f32;
+        if let Ok(framerate) = structure.get::<Fraction>("framerate") {
+            self.pacer.set_framerate(framerate);
+        }
 
-        let video_info =
-            gstreamer_video::VideoInfo::from_caps(&caps).map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
-
-        let frame = VideoFrame::from_buffer_readable(buffer.to_owned(), &video_info)
-            .map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
-
-        let data = frame.plane_data(0).map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
-
-        let device = ctx.device();
-        let queue = ctx.queue();
+        match self.upload {
+            FrameUpload::Copy => self.upload_copy(buffer, caps, ctx),
+            #[cfg(feature = "dmabuf")]
+            FrameUpload::DmaBuf => self.upload_dmabuf(buffer, width, height, ctx),
+        }
+    }
 
-        let size = wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
+    #[cfg(feature = "dmabuf")]
+    fn upload_dmabuf(&mut self, buffer: &BufferRef, width: u32, height: u32, ctx: &Context) -> Result<()> {
+        let frame = dmabuf_frame_from_buffer(buffer, width, height)?;
+        let (y_plane, uv_plane) = crate::engine::graphics::import_dmabuf_frame(ctx.device(), &frame)?;
 
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("video_frame_texture"),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::COPY_DST
-                | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
+        let y_plane = Self::wrap_plane(y_plane, ctx);
+        let uv_plane = Self::wrap_plane(uv_plane, ctx);
+        self.texture_bind_group =
+            create_texture_bind_group(&self.texture_bind_group_layout, &[&y_plane, &uv_plane], ctx);
 
-        queue.write_texture(
-            texture.as_image_copy(),
-            data,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
-            },
-            size,
-        );
+        Ok(())
+    }
 
+    #[cfg(feature = "dmabuf")]
+    fn wrap_plane(texture: wgpu::Texture, ctx: &Context) -> Texture {
         let view = texture.create_view(&Default::default());
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        let sampler = ctx.device().create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
@@ -214,34 +602,35 @@ impl Video {
             ..Default::default()
         });
 
-        self.texture = Texture::from_existing(texture, view, sampler);
+        Texture::from_existing(texture, view, sampler)
+    }
 
-        let (texture_bind_group_layout, texture_bind_group) = create_texture_binds(&[&self.texture], ctx);
+    fn upload_copy(&mut self, buffer: &BufferRef, caps: &CapsRef, ctx: &Context) -> Result<()> {
+        let video_info =
+            gstreamer_video::VideoInfo::from_caps(caps).map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
+        let (width, height) = (video_info.width(), video_info.height());
 
-        let (_, _, _uniform_bind_group) = create_uniform_binds(32, ctx);
+        let frame = VideoFrame::from_buffer_readable(buffer.to_owned(), &video_info)
+            .map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
 
-        self.texture_bind_group = texture_bind_group;
+        let data = frame.plane_data(0).map_err(|e| VideoError::BufferMap(format!("{:?}", e)))?;
 
-        let device = ctx.device();
-        let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: None,
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::all(),
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-        });
+        let size = self.texture.size();
+        if size.width != width || size.height != height {
+            debug!("Resizing video texture to {}x{}", width, height);
+            self.texture = Self::create_frame_texture(ctx, width, height);
+            self.texture_bind_group = create_texture_bind_group(&self.texture_bind_group_layout, &[&self.texture], ctx);
+        }
 
-        self.render_pipeline = create_pipeline(
-            ctx,
-            &[&texture_bind_group_layout, &uniform_bind_group_layout],
-            &device.create_shader_module(wgpu::include_wgsl!("./shaders/video.wgsl")),
-            ctx.config(),
+        ctx.queue().write_texture(
+            self.texture.texture().as_image_copy(),
+            data,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            self.texture.size(),
         );
 
         Ok(())
@@ -251,14 +640,116 @@ impl Video {
         &self.video_dir
     }
 
-    fn render_normal(&mut self, ctx: &Context) {
-        if let Err(e) = self.pull_frame(ctx) {
-            debug!("Failed to pull frame: {}", e);
+    /// Whether new frames can still arrive, `false` once the final frame is held
+    pub fn is_playing(&self) -> bool {
+        self.playback == Playback::Playing
+    }
+
+    /// Stops decoding and keeps the last frame on screen
+    fn hold(&mut self) {
+        debug!("{:?} ended, holding its last frame", self.video_path);
+        // The texture or imported planes stay bound, so the pipeline can go
+        if let Some(pipeline) = self.pipeline.take() {
+            let _ = pipeline.set_state(State::Null);
         }
+        self.appsink = None;
+        self.pending = None;
+        self.playback = Playback::Holding;
+    }
+
+    /// Seeks back to the start, keeping the playback rate a plain seek would reset
+    fn rewind(&mut self) -> Result<()> {
+        // Decoded before the seek, so its timestamp belongs to the previous pass
+        self.pending = None;
+        let pipeline = self.pipeline.as_ref().ok_or(VideoError::NoFrames)?;
+        pipeline
+            .seek(
+                self.playback_rate,
+                SeekFlags::FLUSH | SeekFlags::ACCURATE,
+                SeekType::Set,
+                ClockTime::ZERO,
+                SeekType::End,
+                ClockTime::ZERO,
+            )
+            .map_err(|e| VideoError::Seek(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Whether rebuilding the pipeline gave up since the last call
+    pub fn take_stalled(&mut self) -> bool {
+        std::mem::take(&mut self.stalled)
+    }
+
+    /// Tears the pipeline down and builds it again from the start of the file
+    fn restart_pipeline(&mut self) {
+        if let Some(pipeline) = self.pipeline.take() {
+            let _ = pipeline.set_state(State::Null);
+        }
+        self.appsink = None;
+        self.pending = None;
+
+        match Self::create_pipeline_and_sink(&self.video_path, self.upload, self.decode, self.playback_rate) {
+            Ok((pipeline, appsink)) => {
+                self.pipeline = Some(pipeline);
+                self.appsink = Some(appsink);
+            },
+            Err(e) => warn!("Could not rebuild the pipeline for {:?}: {e}", self.video_path),
+        }
+    }
+
+    fn pull(&mut self, ctx: &Context) -> FramePull {
+        match self.pull_frame(ctx) {
+            Ok(()) => FramePull::Uploaded,
+            // A video that played to the end is finished, not stalled
+            Err(Error::Video(VideoError::NoFrames)) if self.appsink.as_ref().is_some_and(AppSink::is_eos) => {
+                FramePull::Ended
+            },
+            Err(Error::Video(VideoError::NoFrames)) => FramePull::Missed,
+            Err(e) => {
+                debug!("Failed to pull frame: {}", e);
+                FramePull::Failed
+            },
+        }
+    }
+
+    /// Uploads the next decoded frame, if there is one, and watches for stalls and the end
+    fn advance_frame(&mut self, ctx: &Context) {
+        let (playback, loop_playback) = (self.playback, self.loop_playback);
+        match playback.step(loop_playback, || self.pull(ctx)) {
+            FrameStep::Cached | FrameStep::Failed => {},
+            FrameStep::Uploaded => self.watchdog.pulled(),
+            FrameStep::Rewind => {
+                debug!("Looping {:?}", self.video_path);
+                if let Err(e) = self.rewind() {
+                    warn!("Could not loop {:?}: {e}", self.video_path);
+                }
+            },
+            FrameStep::Hold => self.hold(),
+            FrameStep::Missed => match self.watchdog.missed() {
+                StallAction::Wait => {},
+                StallAction::Restart => {
+                    warn!(
+                        "No frames from {:?} for {} ticks, restarting its pipeline",
+                        self.video_path, self.watchdog.threshold
+                    );
+                    self.restart_pipeline();
+                },
+                StallAction::Skip => {
+                    warn!(
+                        "{:?} is still stalled after {MAX_STALL_RESTARTS} pipeline restarts, skipping it",
+                        self.video_path
+                    );
+                    self.stalled = true;
+                },
+            },
+        }
+    }
+
+    fn render_normal(&mut self, ctx: &Context) {
+        self.advance_frame(ctx);
 
         let queue = ctx.queue();
         let device = ctx.device();
-        let surface = ctx.surface();
 
         debug!(
             "Video rendering, surface aspect: {:.2}, frame aspect: {:.2}",
@@ -266,19 +757,19 @@ impl Video {
             self.frame_aspect_ratio
         );
 
-        let output = match surface.get_current_texture() {
-            Ok(output) => output,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
         };
-        let view = output.texture.create_view(&Default::default());
+        let view = output.view();
 
         queue.write_buffer(
             &self.uniform_buffer,
             0,
-            bytemuck::cast_slice(&[ctx.surface_aspect_ratio() / self.frame_aspect_ratio]),
+            bytemuck::cast_slice(&[VideoUniforms {
+                surface_to_video_arr: ctx.surface_aspect_ratio() / self.frame_aspect_ratio,
+                _padding: [0.0; 3],
+                color_adjust: self.color_adjust,
+            }]),
         );
 
         let mut encoder = device.create_command_encoder(&Default::default());
@@ -289,7 +780,7 @@ impl Video {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        load: ctx.clear_op(wgpu::Color::TRANSPARENT),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -331,52 +822,104 @@ impl Source for Video {
     fn start_transition(
         &mut self,
         previous: Option<SourceType>,
-        duration: Duration,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
         ctx: &Context,
-        transition_type: TransitionType,
     ) {
-        debug!("Starting {:?} transition with duration {:?}", transition_type, duration);
+        let duration = transition.sampled_duration();
+        debug!(
+            "Starting {:?} transition with duration {:?}",
+            transition.transition_type(),
+            duration
+        );
         let previous_texture = previous.map(|s| s.texture().clone());
-        let transition: Box<dyn Transition> = match transition_type {
-            TransitionType::Fade => Box::new(FadeTransition::new(previous_texture, duration, ctx)),
-            TransitionType::CircleTopLeft => Box::new(CircleRevealTransition::new(
+        let transition: Result<Box<dyn Transition>> = match transition.transition_type() {
+            // Random is settled in `SourceType::start_transition`, so it only lands here when called directly
+            TransitionType::Fade | TransitionType::Random => {
+                FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed)
+            },
+            TransitionType::Dip => DipTransition::new(previous_texture, duration, transition.dip.color, ctx).map(boxed),
+            TransitionType::CircleTopLeft => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::TopLeft,
+                CircleOptions {
+                    origin: CircleOrigin::TopLeft,
+                    ..transition.circle
+                },
+                None,
+                ctx,
+            )
+            .map(boxed),
+            TransitionType::CircleTopRight => CircleRevealTransition::new(
+                previous_texture,
+                duration,
+                CircleOptions {
+                    origin: CircleOrigin::TopRight,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleTopRight => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleBottomLeft => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::TopRight,
+                CircleOptions {
+                    origin: CircleOrigin::BottomLeft,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleBottomLeft => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleBottomRight => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::BottomLeft,
+                CircleOptions {
+                    origin: CircleOrigin::BottomRight,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleBottomRight => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleCenter => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::BottomRight,
+                CircleOptions {
+                    origin: CircleOrigin::Center,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleCenter => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleRandom => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::Center,
+                CircleOptions {
+                    origin: CircleOrigin::Random,
+                    ..transition.circle
+                },
+                None,
                 ctx,
-            )),
-            TransitionType::CircleRandom => Box::new(CircleRevealTransition::new(
+            )
+            .map(boxed),
+            TransitionType::CircleMouse => CircleRevealTransition::new(
                 previous_texture,
                 duration,
-                CircleOrigin::Random,
+                CircleOptions {
+                    origin: CircleOrigin::Mouse,
+                    ..transition.circle
+                },
+                interaction.mouse.map(|(x, y)| [x, y]),
                 ctx,
-            )),
+            )
+            .map(boxed),
+            TransitionType::Wipe => WipeTransition::new(previous_texture, duration, transition.wipe, ctx).map(boxed),
+            TransitionType::Pixelate => PixelateTransition::new(previous_texture, duration, ctx).map(boxed),
         };
-        self.state = RenderState::Transitioning(transition);
+        self.state = RenderState::transitioning(transition);
     }
 
     fn update(&mut self, dt: Duration) {
@@ -388,6 +931,22 @@ impl Source for Video {
             }
         }
     }
+
+    /// Flushes the pipeline in place, dropping frames queued before the monitor slept so
+    /// the pacer isn't comparing them against a running time that has moved on
+    fn on_resume(&mut self, _ctx: &Context) {
+        if !self.playback.pulls_frames() {
+            return;
+        }
+        self.pending = None;
+        self.watchdog.pulled();
+
+        if let Some(pipeline) = &self.pipeline
+            && let Err(e) = Self::apply_playback_rate(pipeline, self.playback_rate)
+        {
+            warn!("Could not resync {:?} after resume: {e}", self.video_path);
+        }
+    }
 }
 
 impl BasicSource for Video {
@@ -410,3 +969,169 @@ impl Drop for Video {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_frame_at_native_fps() {
+        assert_eq!(frame_at(Duration::ZERO, 24.0), 0);
+        assert_eq!(frame_at(ms(41), 24.0), 0);
+        assert_eq!(frame_at(ms(42), 24.0), 1);
+        assert_eq!(frame_at(Duration::from_secs(1), 24.0), 24);
+        // 1001/30000 s is frame 1 of 29.97fps, even though it rounds a hair low
+        assert_eq!(frame_at(Duration::from_nanos(33_366_667), 30_000.0 / 1001.0), 1);
+    }
+
+    #[test]
+    fn test_pacer_waits_for_frame_boundary() {
+        let mut pacer = FramePacer::default();
+        pacer.set_framerate(Fraction::new(24, 1));
+
+        let second_frame = Some(Duration::from_secs_f64(1.0 / 24.0));
+        // At 60fps the display ticks every ~16.7ms: ticks 0-2 still show the first frame
+        assert!(!pacer.is_due(second_frame, ms(17)));
+        assert!(!pacer.is_due(second_frame, ms(33)));
+        assert!(pacer.is_due(second_frame, ms(50)));
+        assert!(pacer.is_due(Some(Duration::ZERO), ms(50)));
+    }
+
+    #[test]
+    fn test_pacer_without_framerate_compares_timestamps() {
+        let mut pacer = FramePacer::default();
+        pacer.set_framerate(Fraction::new(0, 1));
+
+        assert!(pacer.fps.is_none());
+        assert!(!pacer.is_due(Some(ms(40)), ms(39)));
+        assert!(pacer.is_due(Some(ms(40)), ms(40)));
+    }
+
+    #[test]
+    fn test_pacer_shows_untimed_and_far_ahead_frames() {
+        let pacer = FramePacer { fps: Some(30.0) };
+        assert!(pacer.is_due(None, Duration::ZERO));
+        assert!(pacer.is_due(Some(Duration::from_secs(5)), ms(100)));
+    }
+
+    #[test]
+    fn test_playback_loops_or_holds_at_end() {
+        assert_eq!(Playback::at_end(true), None);
+        assert_eq!(Playback::at_end(false), Some(Playback::Holding));
+    }
+
+    #[test]
+    fn test_holding_playback_stops_pulling_frames() {
+        assert!(Playback::Playing.pulls_frames());
+        assert!(!Playback::Holding.pulls_frames());
+    }
+
+    #[test]
+    fn test_holding_draws_cached_frame_without_pulling() {
+        for loop_playback in [true, false] {
+            let mut pulls = 0;
+            let step = Playback::Holding.step(loop_playback, || {
+                pulls += 1;
+                FramePull::Uploaded
+            });
+            assert_eq!(step, FrameStep::Cached);
+            assert_eq!(pulls, 0);
+        }
+    }
+
+    #[test]
+    fn test_playing_pulls_every_tick() {
+        let mut pulls = 0;
+        for _ in 0..3 {
+            let step = Playback::Playing.step(false, || {
+                pulls += 1;
+                FramePull::Uploaded
+            });
+            assert_eq!(step, FrameStep::Uploaded);
+        }
+        assert_eq!(pulls, 3);
+
+        assert_eq!(Playback::Playing.step(false, || FramePull::Missed), FrameStep::Missed);
+        assert_eq!(Playback::Playing.step(false, || FramePull::Failed), FrameStep::Failed);
+    }
+
+    #[test]
+    fn test_end_of_stream_rewinds_or_holds() {
+        assert_eq!(Playback::Playing.step(true, || FramePull::Ended), FrameStep::Rewind);
+        assert_eq!(Playback::Playing.step(false, || FramePull::Ended), FrameStep::Hold);
+    }
+
+    #[test]
+    fn test_stall_watchdog_trips_after_threshold() {
+        let mut watchdog = StallWatchdog::new(3);
+        assert_eq!(watchdog.missed(), StallAction::Wait);
+        assert_eq!(watchdog.missed(), StallAction::Wait);
+        assert_eq!(watchdog.missed(), StallAction::Restart);
+        // The count starts over after each restart
+        assert_eq!(watchdog.missed(), StallAction::Wait);
+    }
+
+    #[test]
+    fn test_stall_watchdog_resets_on_frame() {
+        let mut watchdog = StallWatchdog::new(3);
+        watchdog.missed();
+        watchdog.missed();
+        watchdog.pulled();
+        assert_eq!(watchdog.missed(), StallAction::Wait);
+        assert_eq!(watchdog.missed(), StallAction::Wait);
+        assert_eq!(watchdog.missed(), StallAction::Restart);
+    }
+
+    #[test]
+    fn test_stall_watchdog_skips_after_failed_restarts() {
+        let mut watchdog = StallWatchdog::new(1);
+        for _ in 0..MAX_STALL_RESTARTS {
+            assert_eq!(watchdog.missed(), StallAction::Restart);
+        }
+        assert_eq!(watchdog.missed(), StallAction::Skip);
+    }
+
+    #[test]
+    fn test_stall_watchdog_frame_forgives_restarts() {
+        let mut watchdog = StallWatchdog::new(1);
+        for _ in 0..MAX_STALL_RESTARTS {
+            watchdog.missed();
+        }
+        watchdog.pulled();
+        assert_eq!(watchdog.missed(), StallAction::Restart);
+    }
+
+    #[test]
+    fn test_stall_watchdog_disabled_at_zero() {
+        let mut watchdog = StallWatchdog::new(0);
+        for _ in 0..1000 {
+            assert_eq!(watchdog.missed(), StallAction::Wait);
+        }
+    }
+
+    #[test]
+    fn test_video_uniforms_layout_matches_shader() {
+        // The ColorAdjust struct is aligned to 16 bytes, after the aspect ratio
+        assert_eq!(std::mem::offset_of!(VideoUniforms, color_adjust), 16);
+        assert_eq!(std::mem::size_of::<VideoUniforms>(), 48);
+    }
+
+    #[test]
+    fn test_shaders_include_color_adjust() {
+        let mut uploads = vec![FrameUpload::Copy];
+        #[cfg(feature = "dmabuf")]
+        uploads.push(FrameUpload::DmaBuf);
+
+        for upload in uploads {
+            let wgpu::ShaderSource::Wgsl(source) = shader_for(upload).source else {
+                panic!("expected WGSL source");
+            };
+            assert!(source.contains("fn adjust_color"));
+            assert!(source.contains("fn fs_main"));
+        }
+    }
+}
@@ -28,8 +28,14 @@ var t_uv: texture_2d<f32>;
 @group(0) @binding(3)
 var s_uv: sampler;
 
+struct Uniforms {
+    surface_to_video_arr: f32,
+    color_adjust: ColorAdjust,
+};
+
+// ColorAdjust and adjust_color are appended from color_adjust.wgsl
 @group(1) @binding(0)
-var<uniform> surface_to_video_arr: f32;
+var<uniform> uniforms: Uniforms;
 
 fn nv12_to_rgb(y: f32, u: f32, v: f32) -> vec3<f32> {
     let y_norm = y;
@@ -45,10 +51,11 @@ fn nv12_to_rgb(y: f32, u: f32, v: f32) -> vec3<f32> {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let arr = uniforms.surface_to_video_arr;
     let scale = select(
-        vec2<f32>(surface_to_video_arr, 1.0),
-        vec2<f32>(1.0, 1.0 / surface_to_video_arr),
-        surface_to_video_arr > 1.0,
+        vec2<f32>(arr, 1.0),
+        vec2<f32>(1.0, 1.0 / arr),
+        arr > 1.0,
     );
 
     let uv = in.tex_coords * scale + 0.5 * (vec2<f32>(1.0) - scale);
@@ -60,5 +67,5 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 
     let rgb = nv12_to_rgb(y, u, v);
 
-    return vec4<f32>(rgb, 1.0);
+    return adjust_color(vec4<f32>(rgb, 1.0), uniforms.color_adjust);
 }
@@ -22,20 +22,28 @@ var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
 var s_diffuse: sampler;
 
+struct Uniforms {
+    surface_to_video_arr: f32,
+    color_adjust: ColorAdjust,
+};
+
+// ColorAdjust and adjust_color are appended from color_adjust.wgsl
 @group(1) @binding(0)
-var<uniform> surface_to_video_arr: f32;
+var<uniform> uniforms: Uniforms;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let arr = uniforms.surface_to_video_arr;
     let scale = select(
-        vec2<f32>(surface_to_video_arr, 1.0),
-        vec2<f32>(1.0, 1.0 / surface_to_video_arr),
-        surface_to_video_arr > 1.0,
+        vec2<f32>(arr, 1.0),
+        vec2<f32>(1.0, 1.0 / arr),
+        arr > 1.0,
     );
 
-    return textureSample(
+    let color = textureSample(
         t_diffuse,
         s_diffuse,
         in.tex_coords * scale + 0.5 * (vec2<f32>(1.0) - scale),
     );
+    return adjust_color(color, uniforms.color_adjust);
 }
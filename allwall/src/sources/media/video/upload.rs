@@ -0,0 +1,132 @@
+#[cfg(feature = "dmabuf")]
+use gstreamer::BufferRef;
+#[cfg(feature = "dmabuf")]
+use gstreamer_allocators::DmaBufMemory;
+#[cfg(feature = "dmabuf")]
+use gstreamer_video::VideoMeta;
+
+use super::decode::DecodePath;
+#[cfg(feature = "dmabuf")]
+use super::error::VideoError;
+#[cfg(feature = "dmabuf")]
+use crate::{
+    engine::graphics::{DmabufFormat, DmabufFrame, DmabufPlane},
+    prelude::*,
+};
+
+/// How decoded frames get from GStreamer into GPU textures
+///
+/// Chosen once when the pipeline is built, since the appsink caps (and so the
+/// buffers we receive) differ between the two strategies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameUpload {
+    /// Map each RGBA buffer into system memory and copy it with `write_texture`
+    #[default]
+    Copy,
+    /// Hand the decoder's NV12 DMA-BUF planes straight to the GPU
+    ///
+    /// Only built with the `dmabuf` feature, and every frame fails to upload until
+    /// [`import_dmabuf_frame`] can import the planes.
+    ///
+    /// [`import_dmabuf_frame`]: crate::engine::graphics::import_dmabuf_frame
+    #[cfg(feature = "dmabuf")]
+    DmaBuf,
+}
+
+impl FrameUpload {
+    pub fn appsink_caps(self) -> &'static str {
+        match self {
+            Self::Copy => "video/x-raw,format=RGBA",
+            #[cfg(feature = "dmabuf")]
+            Self::DmaBuf => "video/x-raw(memory:DMABuf),format=NV12",
+        }
+    }
+
+    pub fn pipeline_description(self, path: &str, decode: DecodePath) -> String {
+        let decode = decode.description();
+        match self {
+            Self::Copy => format!(
+                "filesrc location='{path}' ! {decode} ! videoconvert ! videoscale ! video/x-raw,format=RGBA,width=1920,height=1080 ! appsink name=sink caps={}",
+                self.appsink_caps()
+            ),
+            // Any conversion element would force the frame back into system memory
+            #[cfg(feature = "dmabuf")]
+            Self::DmaBuf => format!(
+                "filesrc location='{path}' ! {decode} ! appsink name=sink caps=\"{}\"",
+                self.appsink_caps()
+            ),
+        }
+    }
+}
+
+/// Collects the DMA-BUF file descriptors and plane layout of an NV12 buffer
+#[cfg(feature = "dmabuf")]
+pub fn dmabuf_frame_from_buffer(buffer: &BufferRef, width: u32, height: u32) -> Result<DmabufFrame> {
+    let meta = buffer
+        .meta::<VideoMeta>()
+        .ok_or_else(|| VideoError::BufferMap("DMA-BUF buffer has no video meta".to_string()))?;
+
+    let fds = (0..buffer.n_memory())
+        .map(|i| {
+            buffer
+                .peek_memory(i)
+                .downcast_memory_ref::<DmaBufMemory>()
+                .map(|m| m.fd())
+                .ok_or_else(|| VideoError::BufferMap(f!("Memory {i} is not a DMA-BUF")))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let planes = meta
+        .offset()
+        .iter()
+        .zip(meta.stride())
+        .enumerate()
+        .map(|(i, (offset, stride))| {
+            // Single-memory buffers carry every plane in one fd at different offsets
+            let fd = fds.get(i).or_else(|| fds.first()).copied();
+            fd.map(|fd| DmabufPlane {
+                fd,
+                offset: *offset as u32,
+                stride: *stride as u32,
+            })
+            .ok_or_else(|| VideoError::BufferMap(f!("No DMA-BUF fd for plane {i}")))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(DmabufFrame::new(width, height, planes, DmabufFormat::Nv12))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_upload_default_is_copy() {
+        assert_eq!(FrameUpload::default(), FrameUpload::Copy);
+    }
+
+    #[test]
+    fn test_copy_pipeline_converts_to_rgba() {
+        let description = FrameUpload::Copy.pipeline_description("/videos/a.mp4", DecodePath::Software);
+        assert!(description.starts_with("filesrc location='/videos/a.mp4' ! decodebin !"));
+        assert!(description.contains("videoconvert"));
+        assert!(description.ends_with("caps=video/x-raw,format=RGBA"));
+    }
+
+    #[cfg(feature = "dmabuf")]
+    #[test]
+    fn test_dmabuf_pipeline_skips_conversion() {
+        let description = FrameUpload::DmaBuf.pipeline_description("/videos/a.mp4", DecodePath::Software);
+        assert!(!description.contains("videoconvert"));
+        assert!(description.contains("memory:DMABuf"));
+    }
+
+    #[test]
+    fn test_hardware_pipeline_decodes_with_element() {
+        let decode = DecodePath::Hardware("vaapidecodebin");
+        let description = FrameUpload::Copy.pipeline_description("/videos/a.mp4", decode);
+        assert!(description.starts_with("filesrc location='/videos/a.mp4' ! parsebin ! vaapidecodebin !"));
+        assert!(!description.contains(" decodebin"));
+    }
+}
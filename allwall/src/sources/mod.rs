@@ -1,16 +1,19 @@
+pub mod color;
 pub mod error;
 pub mod grass;
 pub mod media;
 pub mod smoke;
 pub mod types;
 
-use std::{fmt, time::Duration};
+use std::{fmt, path::Path, time::Duration};
 
 use bitflags::bitflags;
 use bytemuck::cast_slice;
 use clap::ValueEnum;
 use derive_more::Display;
-use serde::Deserialize;
+#[cfg(feature = "generate")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
     BindingResource, BindingType, BlendState, Buffer, BufferBindingType, BufferDescriptor, BufferUsages,
@@ -21,10 +24,10 @@ use wgpu::{
 };
 
 use crate::{
-    engine::{Context, Texture},
-    prelude::Result,
+    engine::{Context, Texture, graphics::validated},
+    prelude::{Result, error},
     sources::types::{FVertex, Vec2f, Vec3f},
-    transitions::TransitionType,
+    transitions::{TransitionConfig, TransitionType},
 };
 
 bitflags! {
@@ -41,20 +44,43 @@ pub struct InteractionState {
     pub mouse: Option<(f32, f32)>,
 }
 
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Display, Deserialize, Default)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
 #[clap(rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
 pub enum SourceKind {
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
     #[default]
     Media,
     Smoke,
     Grass,
+    Color,
+    Gradient,
+}
+
+impl SourceKind {
+    /// Whether sources of this kind draw `transition` rather than swapping it for another
+    ///
+    /// Smoke only has the fade, dip, wipe and pixelate transitions, while grass, color and
+    /// gradient only fade.
+    pub fn supports_transition(self, transition: TransitionType) -> bool {
+        match self {
+            SourceKind::Smoke => matches!(
+                transition,
+                TransitionType::Fade | TransitionType::Dip | TransitionType::Wipe | TransitionType::Pixelate
+            ),
+            SourceKind::Grass | SourceKind::Color | SourceKind::Gradient => transition == TransitionType::Fade,
+            SourceKind::Media => true,
+        }
+    }
 }
 
 pub enum SourceType {
     Media(Box<media::MediaSource>),
     Smoke(Box<smoke::SmokeSource>),
     Grass(Box<grass::GrassSource>),
+    Color(Box<color::ColorSource>),
+    Gradient(Box<color::GradientSource>),
 }
 
 impl SourceType {
@@ -68,8 +94,15 @@ impl SourceType {
     pub fn render(&mut self, ctx: &Context, state: &InteractionState) {
         match self {
             SourceType::Media(m) => m.render(ctx),
-            SourceType::Smoke(s) => s.render(ctx),
+            SourceType::Smoke(s) => {
+                if let Some((x, y)) = state.mouse {
+                    s.update_mouse_position(x, y);
+                }
+                s.render(ctx)
+            },
             SourceType::Grass(g) => g.render(ctx, state),
+            SourceType::Color(c) => c.render(ctx),
+            SourceType::Gradient(g) => g.render(ctx),
         }
     }
 
@@ -78,6 +111,8 @@ impl SourceType {
             SourceType::Media(m) => m.texture(),
             SourceType::Smoke(s) => s.texture(),
             SourceType::Grass(g) => g.texture(),
+            SourceType::Color(c) => c.texture(),
+            SourceType::Gradient(g) => g.texture(),
         }
     }
 
@@ -86,6 +121,8 @@ impl SourceType {
             SourceType::Media(m) => m.state(),
             SourceType::Smoke(s) => s.state(),
             SourceType::Grass(g) => g.state(),
+            SourceType::Color(c) => c.state(),
+            SourceType::Gradient(g) => g.state(),
         }
     }
 
@@ -94,20 +131,26 @@ impl SourceType {
             SourceType::Media(m) => m.load(ctx),
             SourceType::Smoke(s) => s.load(ctx),
             SourceType::Grass(g) => g.load(ctx),
+            SourceType::Color(c) => c.load(ctx),
+            SourceType::Gradient(g) => g.load(ctx),
         }
     }
 
     pub fn start_transition(
         &mut self,
         previous: Option<SourceType>,
-        duration: Duration,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
         ctx: &Context,
-        transition_type: TransitionType,
     ) {
+        // Settled once here so every source sees a concrete type
+        let transition = &*transition.sampled();
         match self {
-            SourceType::Media(m) => m.start_transition(previous, duration, ctx, transition_type),
-            SourceType::Smoke(s) => s.start_transition(previous, duration, ctx, transition_type),
-            SourceType::Grass(g) => g.start_transition(previous, duration, ctx, transition_type),
+            SourceType::Media(m) => m.start_transition(previous, transition, interaction, ctx),
+            SourceType::Smoke(s) => s.start_transition(previous, transition, interaction, ctx),
+            SourceType::Grass(g) => g.start_transition(previous, transition, interaction, ctx),
+            SourceType::Color(c) => c.start_transition(previous, transition, interaction, ctx),
+            SourceType::Gradient(g) => g.start_transition(previous, transition, interaction, ctx),
         }
     }
 
@@ -116,13 +159,49 @@ impl SourceType {
             SourceType::Media(m) => m.update(dt),
             SourceType::Smoke(s) => s.update(dt),
             SourceType::Grass(g) => g.update(dt),
+            SourceType::Color(c) => c.update(dt),
+            SourceType::Gradient(g) => g.update(dt),
+        }
+    }
+
+    /// File on screen for media sources, `None` for generated ones
+    pub fn media_path(&self) -> Option<&Path> {
+        match self {
+            SourceType::Media(m) => m.current_path(),
+            SourceType::Smoke(_) | SourceType::Grass(_) | SourceType::Color(_) | SourceType::Gradient(_) => None,
+        }
+    }
+
+    pub fn needs_redraw(&self) -> bool {
+        match self {
+            SourceType::Media(m) => m.needs_redraw(),
+            SourceType::Smoke(s) => s.needs_redraw(),
+            SourceType::Grass(g) => g.needs_redraw(),
+            SourceType::Color(c) => c.needs_redraw(),
+            SourceType::Gradient(g) => g.needs_redraw(),
+        }
+    }
+
+    pub fn take_stalled(&mut self) -> bool {
+        match self {
+            SourceType::Media(m) => m.take_stalled(),
+            SourceType::Smoke(_) | SourceType::Grass(_) | SourceType::Color(_) | SourceType::Gradient(_) => false,
+        }
+    }
+
+    pub fn on_resume(&mut self, ctx: &Context) {
+        match self {
+            SourceType::Media(m) => m.on_resume(ctx),
+            SourceType::Smoke(s) => s.on_resume(ctx),
+            SourceType::Grass(g) => g.on_resume(ctx),
+            SourceType::Color(_) | SourceType::Gradient(_) => {},
         }
     }
 
     pub fn next(&self, ctx: &Context) -> Result<SourceType> {
         match self {
             SourceType::Media(m) => m.next(ctx).map(|s| SourceType::Media(Box::new(s))),
-            SourceType::Smoke(_) | SourceType::Grass(_) => {
+            SourceType::Smoke(_) | SourceType::Grass(_) | SourceType::Color(_) | SourceType::Gradient(_) => {
                 Err(error::SourceError::UnsupportedOperation("next".to_string()).into())
             },
         }
@@ -131,11 +210,20 @@ impl SourceType {
     pub fn prev(&self, ctx: &Context) -> Result<SourceType> {
         match self {
             SourceType::Media(m) => m.prev(ctx).map(|s| SourceType::Media(Box::new(s))),
-            SourceType::Smoke(_) | SourceType::Grass(_) => {
+            SourceType::Smoke(_) | SourceType::Grass(_) | SourceType::Color(_) | SourceType::Gradient(_) => {
                 Err(error::SourceError::UnsupportedOperation("prev".to_string()).into())
             },
         }
     }
+
+    pub fn goto(&self, target: &Path, ctx: &Context) -> Result<SourceType> {
+        match self {
+            SourceType::Media(m) => m.goto(target, ctx).map(|s| SourceType::Media(Box::new(s))),
+            SourceType::Smoke(_) | SourceType::Grass(_) | SourceType::Color(_) | SourceType::Gradient(_) => {
+                Err(error::SourceError::UnsupportedOperation("goto".to_string()).into())
+            },
+        }
+    }
 }
 
 impl fmt::Debug for SourceType {
@@ -144,6 +232,8 @@ impl fmt::Debug for SourceType {
             SourceType::Media(m) => m.fmt(f),
             SourceType::Smoke(s) => s.fmt(f),
             SourceType::Grass(g) => g.fmt(f),
+            SourceType::Color(c) => c.fmt(f),
+            SourceType::Gradient(g) => g.fmt(f),
         }
     }
 }
@@ -155,11 +245,30 @@ pub trait Source: fmt::Debug {
     fn start_transition(
         &mut self,
         previous: Option<SourceType>,
-        duration: Duration,
+        transition: &TransitionConfig,
+        interaction: &InteractionState,
         ctx: &Context,
-        transition_type: TransitionType,
     );
     fn update(&mut self, dt: Duration);
+    /// Whether the next frame can differ from the last one drawn
+    ///
+    /// Sources that only change during transitions return `false` once settled,
+    /// letting the engine drop to its idle frame rate.
+    fn needs_redraw(&self) -> bool {
+        true
+    }
+    /// Whether the source stopped producing frames since the last call and should be
+    /// replaced by the next media
+    fn take_stalled(&mut self) -> bool {
+        false
+    }
+    /// Called once the surface comes back after being lost, as when the monitor wakes
+    /// from DPMS sleep
+    ///
+    /// Sources keeping their own clock resync it here, so playback doesn't jump.
+    fn on_resume(&mut self, ctx: &Context) {
+        let _ = ctx;
+    }
     fn next(&self, ctx: &Context) -> Result<Self>
     where
         Self: Sized,
@@ -198,6 +307,20 @@ pub enum RenderState {
     Transitioning(Box<dyn crate::transitions::Transition>),
 }
 
+impl RenderState {
+    /// Starts `transition`, or shows the new source straight away when it couldn't be
+    /// built, such as after its shader failed to compile
+    pub fn transitioning(transition: Result<Box<dyn crate::transitions::Transition>>) -> Self {
+        match transition {
+            Ok(transition) => Self::Transitioning(transition),
+            Err(e) => {
+                error!("Skipping transition: {e}");
+                Self::Displaying
+            },
+        }
+    }
+}
+
 pub const VERTICES: &[FVertex] = &[
     FVertex {
         position: Vec3f::new(-1.0, 1.0, 0.0),
@@ -235,10 +358,16 @@ pub fn create_index_buffer(ctx: &Context) -> Buffer {
     })
 }
 pub fn create_texture_binds(textures: &[&Texture], ctx: &Context) -> (BindGroupLayout, BindGroup) {
-    let device = ctx.device();
-    let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+    let layout = create_texture_bind_group_layout(textures.len(), ctx);
+    let bind_group = create_texture_bind_group(&layout, textures, ctx);
+    (layout, bind_group)
+}
+
+/// Layout for `texture_count` texture/sampler pairs at consecutive bindings
+pub fn create_texture_bind_group_layout(texture_count: usize, ctx: &Context) -> BindGroupLayout {
+    ctx.device().create_bind_group_layout(&BindGroupLayoutDescriptor {
         label: None,
-        entries: (0..textures.len())
+        entries: (0..texture_count)
             .flat_map(|i| {
                 [
                     BindGroupLayoutEntry {
@@ -261,10 +390,13 @@ pub fn create_texture_binds(textures: &[&Texture], ctx: &Context) -> (BindGroupL
             })
             .collect::<Vec<_>>()
             .as_slice(),
-    });
+    })
+}
 
-    let bind_group = device.create_bind_group(&BindGroupDescriptor {
-        layout: &layout,
+/// Binds `textures` against a layout previously returned by [`create_texture_binds`]
+pub fn create_texture_bind_group(layout: &BindGroupLayout, textures: &[&Texture], ctx: &Context) -> BindGroup {
+    ctx.device().create_bind_group(&BindGroupDescriptor {
+        layout,
         entries: textures
             .iter()
             .enumerate()
@@ -283,8 +415,7 @@ pub fn create_texture_binds(textures: &[&Texture], ctx: &Context) -> (BindGroupL
             .collect::<Vec<_>>()
             .as_slice(),
         label: None,
-    });
-    (layout, bind_group)
+    })
 }
 
 pub fn create_uniform_binds(size: u64, ctx: &Context) -> (Buffer, BindGroupLayout, BindGroup) {
@@ -326,45 +457,61 @@ pub fn create_pipeline(
     bind_group_layouts: &[&BindGroupLayout],
     shader: &ShaderModule,
     config: &SurfaceConfiguration,
-) -> RenderPipeline {
+) -> Result<RenderPipeline> {
+    create_blended_pipeline(ctx, bind_group_layouts, shader, config, BlendState::REPLACE)
+}
+
+/// [`create_pipeline`] with a chosen blend state, such as premultiplied alpha for translucent images
+///
+/// Errs when `shader` failed to compile or doesn't fit the layouts, instead of handing
+/// back a pipeline that can't draw.
+pub fn create_blended_pipeline(
+    ctx: &Context,
+    bind_group_layouts: &[&BindGroupLayout],
+    shader: &ShaderModule,
+    config: &SurfaceConfiguration,
+    blend: BlendState,
+) -> Result<RenderPipeline> {
     let layout = ctx.device().create_pipeline_layout(&PipelineLayoutDescriptor {
         label: None,
         bind_group_layouts,
         push_constant_ranges: &[],
     });
 
-    ctx.device().create_render_pipeline(&RenderPipelineDescriptor {
-        label: None,
-        layout: Some(&layout),
-        vertex: VertexState {
-            module: shader,
-            entry_point: "vs_main",
-            buffers: &[FVertex::desc()],
-        },
-        fragment: Some(FragmentState {
-            module: shader,
-            entry_point: "fs_main",
-            targets: &[Some(ColorTargetState {
-                format: config.format,
-                blend: Some(BlendState::REPLACE),
-                write_mask: ColorWrites::ALL,
-            })],
-        }),
-        primitive: PrimitiveState {
-            topology: PrimitiveTopology::TriangleList,
-            strip_index_format: None,
-            front_face: FrontFace::Ccw,
-            cull_mode: Some(Face::Back),
-            polygon_mode: PolygonMode::Fill,
-            unclipped_depth: false,
-            conservative: false,
-        },
-        depth_stencil: None,
-        multisample: MultisampleState {
-            count: 1,
-            mask: !0,
-            alpha_to_coverage_enabled: false,
-        },
-        multiview: None,
+    validated(ctx.device(), || {
+        ctx.device().create_render_pipeline(&RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&layout),
+            vertex: VertexState {
+                module: shader,
+                entry_point: "vs_main",
+                buffers: &[FVertex::desc()],
+            },
+            fragment: Some(FragmentState {
+                module: shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format: config.format,
+                    blend: Some(blend),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: Some(Face::Back),
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        })
     })
 }
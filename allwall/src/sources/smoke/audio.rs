@@ -0,0 +1,155 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU32, Ordering},
+};
+
+#[cfg(feature = "audio")]
+use gstreamer::{Pipeline, State, prelude::*};
+#[cfg(feature = "audio")]
+use gstreamer_app::{AppSink, AppSinkCallbacks};
+
+use crate::prelude::*;
+
+/// Captures the default output's monitor, so the smoke follows whatever is playing
+#[cfg(feature = "audio")]
+const CAPTURE_PIPELINE: &str = "pulsesrc device=@DEFAULT_MONITOR@ ! audioconvert ! audioresample ! audio/x-raw,format=F32LE,channels=1,rate=48000 ! appsink name=sink sync=false max-buffers=4 drop=true";
+
+/// How much of the previous level survives each captured buffer, so peaks fall off smoothly
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+const LEVEL_DECAY: f32 = 0.85;
+
+/// Maps typical music RMS (~0.05 - 0.25) onto the full intensity range
+const LEVEL_GAIN: f32 = 4.0;
+
+/// Fraction of the configured intensity kept during silence
+const SILENCE_FLOOR: f32 = 0.2;
+
+/// Loudness of the system audio, updated from GStreamer's streaming thread
+#[derive(Debug)]
+pub struct AudioLevel {
+    level: Arc<AtomicU32>,
+    #[cfg(feature = "audio")]
+    pipeline: Pipeline,
+}
+
+impl AudioLevel {
+    #[cfg(feature = "audio")]
+    pub fn capture() -> Result<Self> {
+        let pipeline = gstreamer::parse::launch(CAPTURE_PIPELINE)
+            .map_err(|e| Error::Generic(f!("Failed to build audio capture pipeline: {e}")))?
+            .downcast::<Pipeline>()
+            .map_err(|_| Error::Generic("Audio capture pipeline downcast failed".to_string()))?;
+
+        let appsink = pipeline
+            .by_name("sink")
+            .and_then(|sink| sink.downcast::<AppSink>().ok())
+            .ok_or_else(|| Error::Generic("Audio capture appsink not found".to_string()))?;
+
+        let level = Arc::new(AtomicU32::new(0.0_f32.to_bits()));
+        let writer = level.clone();
+        appsink.set_callbacks(
+            AppSinkCallbacks::builder()
+                .new_sample(move |sink| {
+                    let sample = sink.pull_sample().map_err(|_| gstreamer::FlowError::Eos)?;
+                    if let Some(buffer) = sample.buffer()
+                        && let Ok(map) = buffer.map_readable()
+                    {
+                        let previous = f32::from_bits(writer.load(Ordering::Relaxed));
+                        let next = decayed_level(previous, rms(map.as_slice()));
+                        writer.store(next.to_bits(), Ordering::Relaxed);
+                    }
+                    Ok(gstreamer::FlowSuccess::Ok)
+                })
+                .build(),
+        );
+
+        pipeline
+            .set_state(State::Playing)
+            .map_err(|e| Error::Generic(f!("Failed to start audio capture: {e}")))?;
+
+        info!("Capturing system audio for smoke emission");
+        Ok(Self { level, pipeline })
+    }
+
+    #[cfg(not(feature = "audio"))]
+    pub fn capture() -> Result<Self> {
+        Err(Error::Generic(
+            "Audio emission requires allwall to be built with the `audio` feature".to_string(),
+        ))
+    }
+
+    /// Multiplier for the smoke intensity, between [`SILENCE_FLOOR`] and 1
+    pub fn intensity_scale(&self) -> f32 {
+        intensity_scale(f32::from_bits(self.level.load(Ordering::Relaxed)))
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Drop for AudioLevel {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(State::Null);
+    }
+}
+
+/// Root mean square of little-endian f32 samples
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+fn rms(bytes: &[u8]) -> f32 {
+    let samples = bytes.chunks_exact(4).len();
+    if samples == 0 {
+        return 0.0;
+    }
+
+    let sum: f32 = bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .map(|sample| sample * sample)
+        .sum();
+    (sum / samples as f32).sqrt()
+}
+
+/// Follows rises immediately and lets the level fall off gradually
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+fn decayed_level(previous: f32, current: f32) -> f32 {
+    current.max(previous * LEVEL_DECAY)
+}
+
+fn intensity_scale(level: f32) -> f32 {
+    SILENCE_FLOOR + (level * LEVEL_GAIN).clamp(0.0, 1.0) * (1.0 - SILENCE_FLOOR)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn to_bytes(samples: &[f32]) -> Vec<u8> {
+        samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_rms_of_silence_is_zero() {
+        assert_eq!(rms(&to_bytes(&[0.0; 64])), 0.0);
+        assert_eq!(rms(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_rms_of_constant_signal() {
+        let level = rms(&to_bytes(&[0.5, -0.5, 0.5, -0.5]));
+        assert!((level - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_decayed_level_rises_immediately_and_falls_slowly() {
+        assert_eq!(decayed_level(0.1, 0.8), 0.8);
+        let fallen = decayed_level(0.8, 0.0);
+        assert!(fallen > 0.0 && fallen < 0.8);
+    }
+
+    #[test]
+    fn test_intensity_scale_bounds() {
+        assert_eq!(intensity_scale(0.0), SILENCE_FLOOR);
+        assert_eq!(intensity_scale(10.0), 1.0);
+        let mid = intensity_scale(0.1);
+        assert!(mid > SILENCE_FLOOR && mid < 1.0);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod audio;
 pub mod smoke;
 
 pub use smoke::SmokeSource;
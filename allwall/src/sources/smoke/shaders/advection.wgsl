@@ -13,8 +13,13 @@ struct VertexOutput {
 struct Uniforms {
     resolution: vec2<f32>,
     time: f32,
+    // Nonzero to emit only from emission_point, ignoring the cursor
+    fixed_emitter: u32,
     mouse: vec2<f32>,
     mouse_prev: vec2<f32>,
+    // Fixed emitter center and radius, in simulation cells
+    emission_point: vec2<f32>,
+    emission_radius: f32,
 };
 
 @vertex
@@ -89,9 +94,11 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let turb = fbm(vec3<f32>(world_uv * 8.0, uniforms.time * 0.2), 4);
     velocity = velocity + turb * 0.05 * scale;
 
+    let interactive = uniforms.fixed_emitter == 0u;
+
     // CONTINUOUS EMISSION: Entire top edge
     let emit_height = uniforms.resolution.y * 0.1;
-    if (frag_coord.y < emit_height) {
+    if (interactive && frag_coord.y < emit_height) {
         let emit_strength = 1.0 - (frag_coord.y / emit_height);
         // Gentle downward velocity
         velocity.y = velocity.y + emit_strength * 0.15 * scale;
@@ -104,7 +111,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let burst_seed = floor(uniforms.time * 10.0);
     
     // Probability check (30% chance per frame)
-    if (hash(vec2<f32>(burst_seed, 0.0)) < 0.3) {
+    if (interactive && hash(vec2<f32>(burst_seed, 0.0)) < 0.3) {
         // Number of bursts: 2-8 (random)
         let num_bursts = 2 + i32(hash(vec2<f32>(burst_seed, 1.0)) * 6.0);
         
@@ -139,7 +146,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let dist = length(to_mouse);
     let influence_radius = 60.0 * scale;
 
-    if (dist < influence_radius) {
+    if (interactive && dist < influence_radius) {
         let influence = 1.0 - (dist / influence_radius);
         if (length(mouse_delta) > 0.01) {
             // Push smoke
@@ -149,6 +156,14 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         density = density + influence * 0.05 * scale;
     }
 
+    // FIXED EMITTER: Steady plume rising from emission_point
+    let emitter_dist = length(frag_coord - uniforms.emission_point);
+    if (!interactive && emitter_dist < uniforms.emission_radius) {
+        let strength = 1.0 - (emitter_dist / uniforms.emission_radius);
+        velocity.y = velocity.y + strength * 0.3 * scale;
+        density = density + strength * 0.05 * scale;
+    }
+
     // Gentle damping
     velocity = velocity * 0.998;
     density = density * 0.995;
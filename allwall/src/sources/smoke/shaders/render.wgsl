@@ -10,11 +10,13 @@ struct VertexOutput {
     @location(0) tex_coords: vec2<f32>,
 };
 
+// Matches `RenderUniforms` in smoke.rs
 struct Uniforms {
     resolution: vec2<f32>,
     background_color: vec3<f32>,
-    smoke_color: vec3<f32>,
     smoke_intensity: f32,
+    smoke_color_low: vec3<f32>,
+    smoke_color_high: vec3<f32>,
 };
 
 @group(0) @binding(0)
@@ -41,9 +43,13 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // High contrast visibility
     // Use threshold to make even low density visible
     let visibility = smoothstep(0.0, 0.1, density * uniforms.smoke_intensity);
-    
+
+    // Thin wisps take the low color, shading to the high color in dense plumes
+    let thickness = smoothstep(0.1, 1.0, density * uniforms.smoke_intensity);
+    let smoke_color = mix(uniforms.smoke_color_low, uniforms.smoke_color_high, thickness);
+
     // Mix with high contrast
-    var final_color = mix(uniforms.background_color, uniforms.smoke_color, visibility);
+    var final_color = mix(uniforms.background_color, smoke_color, visibility);
     
     // Add brightness for dense areas
     final_color = final_color + vec3<f32>(density * 0.3);
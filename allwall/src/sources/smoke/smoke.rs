@@ -1,15 +1,42 @@
-use std::{iter::once, time::Instant};
+use std::{
+    iter::once,
+    time::{Duration, Instant},
+};
 
 use wgpu::util::DeviceExt;
 
+use super::audio::AudioLevel;
 use crate::{
-    config::SmokeConfig,
+    config::{EmissionMode, SmokeConfig},
     engine::{Context, Texture},
     prelude::*,
-    sources::{INDICES, RenderState, Source, create_index_buffer, create_pipeline, create_vertex_buffer},
+    sources::{
+        INDICES, RenderState, Source, create_index_buffer, create_pipeline, create_vertex_buffer, types::surface_rgb,
+    },
 };
 
-const SIMULATION_RESOLUTION: u32 = 512;
+/// Frame rate `mouse_inertia` is expressed against, so decay doesn't depend on the real fps
+const INERTIA_REFERENCE_FPS: f32 = 60.0;
+
+/// Position in simulation cells for normalized surface coordinates with `y` pointing down
+///
+/// The simulation grid's y axis points up, so smoke pushed to positive y rises on screen.
+fn to_sim_coords(x: f32, y: f32, resolution: f32) -> [f32; 2] {
+    [x * resolution, (1.0 - y) * resolution]
+}
+
+/// Cursor velocity for a move from `last` to `position`, or `None` while it stays within
+/// `deadzone` of `last` and counts as standing still
+fn mouse_movement(last: [f32; 2], position: [f32; 2], deadzone: f32) -> Option<[f32; 2]> {
+    let delta = [position[0] - last[0], position[1] - last[1]];
+    (delta[0].hypot(delta[1]) > deadzone).then_some(delta)
+}
+
+/// Scales `velocity` by `inertia` once per reference frame elapsed in `dt`
+fn decay_velocity(velocity: [f32; 2], inertia: f32, dt: Duration) -> [f32; 2] {
+    let factor = inertia.clamp(0.0, 1.0).powf(dt.as_secs_f32() * INERTIA_REFERENCE_FPS);
+    [velocity[0] * factor, velocity[1] * factor]
+}
 
 #[derive(Debug)]
 pub struct SmokeSource {
@@ -54,21 +81,22 @@ pub struct SmokeSource {
     state: RenderState,
     start_time: Instant,
     mouse_position: [f32; 2],
-    mouse_prev_position: [f32; 2],
+    mouse_velocity: [f32; 2],
 
     sim_vertex_buffer: wgpu::Buffer,
     sim_index_buffer: wgpu::Buffer,
 
     config: SmokeConfig,
+    audio: Option<AudioLevel>,
 }
 
 impl SmokeSource {
-    pub fn new(ctx: &Context, config: SmokeConfig) -> Self {
+    pub fn new(ctx: &Context, config: SmokeConfig) -> Result<Self> {
         debug!("Creating SmokeSource");
 
         let sim_size = wgpu::Extent3d {
-            width: SIMULATION_RESOLUTION,
-            height: SIMULATION_RESOLUTION,
+            width: config.simulation_resolution,
+            height: config.simulation_resolution,
             depth_or_array_layers: 1,
         };
 
@@ -151,7 +179,7 @@ impl SmokeSource {
         let sim_vertex_buffer = create_sim_vertex_buffer(ctx);
         let sim_index_buffer = create_sim_index_buffer(ctx);
 
-        let uniform_size = 56u64;
+        let uniform_size = std::mem::size_of::<SmokeUniforms>() as u64;
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("smoke_uniform"),
             size: uniform_size,
@@ -180,7 +208,7 @@ impl SmokeSource {
                 resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
                     buffer: &uniform_buffer,
                     offset: 0,
-                    size: wgpu::BufferSize::new(56),
+                    size: wgpu::BufferSize::new(uniform_size),
                 }),
             }],
             label: Some("smoke_uniform_bind_group"),
@@ -282,9 +310,7 @@ impl SmokeSource {
 
         // Pressure bind groups are created dynamically in the render loop
 
-        let advection_shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/advection.wgsl"));
+        let advection_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/advection.wgsl"));
 
         let advection_pipeline = create_sim_pipeline(
             ctx,
@@ -292,9 +318,7 @@ impl SmokeSource {
             &advection_shader,
         );
 
-        let divergence_shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/divergence.wgsl"));
+        let divergence_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/divergence.wgsl"));
 
         let divergence_pipeline = create_sim_pipeline(
             ctx,
@@ -302,9 +326,7 @@ impl SmokeSource {
             &divergence_shader,
         );
 
-        let pressure1_shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/pressure1.wgsl"));
+        let pressure1_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/pressure1.wgsl"));
 
         let pressure_pipeline = create_sim_pipeline(
             ctx,
@@ -316,9 +338,7 @@ impl SmokeSource {
             &pressure1_shader,
         );
 
-        let pressure2_shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/pressure2.wgsl"));
+        let pressure2_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/pressure2.wgsl"));
 
         let pressure2_pipeline = create_sim_pipeline(
             ctx,
@@ -326,9 +346,7 @@ impl SmokeSource {
             &pressure2_shader,
         );
 
-        let subtract_shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/subtract.wgsl"));
+        let subtract_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/subtract.wgsl"));
 
         let subtract_pipeline = create_sim_pipeline(
             ctx,
@@ -340,9 +358,8 @@ impl SmokeSource {
             &subtract_shader,
         );
 
-        // Uniform buffer for render shader: resolution (vec2) + bg_color (vec3) + smoke_color (vec3) + intensity (f32)
-        // Size: 8 + 12 + 12 + 4 = 36 bytes, padded to 48 for alignment
-        let render_uniform_size = 48u64;
+        // Uniform buffer for render shader, laid out as `RenderUniforms`
+        let render_uniform_size = std::mem::size_of::<RenderUniforms>() as u64;
         let render_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("render_uniform"),
             size: render_uniform_size,
@@ -377,18 +394,18 @@ impl SmokeSource {
             label: Some("render_uniform_bind_group"),
         });
 
-        let render_shader = ctx.device().create_shader_module(wgpu::include_wgsl!("./shaders/render.wgsl"));
+        let render_shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/render.wgsl"));
 
         let render_pipeline = create_pipeline(
             ctx,
             &[&render_uniform_bind_group_layout, &texture_bind_group_layout],
             &render_shader,
             ctx.config(),
-        );
+        )?;
 
         let state = RenderState::default();
 
-        Self {
+        Ok(Self {
             texture,
             texture_bind_group,
             texture_bind_group_layout,
@@ -420,12 +437,40 @@ impl SmokeSource {
             render_uniform_bind_group_layout,
             state,
             start_time: Instant::now(),
-            mouse_position: [SIMULATION_RESOLUTION as f32 / 2.0, SIMULATION_RESOLUTION as f32 / 2.0],
-            mouse_prev_position: [SIMULATION_RESOLUTION as f32 / 2.0, SIMULATION_RESOLUTION as f32 / 2.0],
+            mouse_position: [
+                config.simulation_resolution as f32 / 2.0,
+                config.simulation_resolution as f32 / 2.0,
+            ],
+            mouse_velocity: [0.0; 2],
             sim_vertex_buffer,
             sim_index_buffer,
+            audio: Self::start_audio_capture(&config),
             config,
+        })
+    }
+
+    /// Side length of the simulation grid, as the shaders take it
+    fn sim_resolution(&self) -> f32 {
+        self.config.simulation_resolution as f32
+    }
+
+    fn start_audio_capture(config: &SmokeConfig) -> Option<AudioLevel> {
+        if config.emission_mode != EmissionMode::Audio {
+            return None;
         }
+
+        match AudioLevel::capture() {
+            Ok(audio) => Some(audio),
+            Err(e) => {
+                warn!("Audio emission unavailable, using a constant intensity: {e}");
+                None
+            },
+        }
+    }
+
+    fn smoke_intensity(&self) -> f32 {
+        let audio_scale = self.audio.as_ref().map_or(1.0, AudioLevel::intensity_scale);
+        self.config.emission_intensity * 5.0 * audio_scale // Base intensity multiplier
     }
 
     fn create_sim_texture(ctx: &Context, size: wgpu::Extent3d) -> Texture {
@@ -485,14 +530,21 @@ impl SmokeSource {
 
     fn update_uniforms(&self, queue: &wgpu::Queue) {
         let elapsed = self.start_time.elapsed().as_secs_f32();
+        let resolution = self.sim_resolution();
+        let [x, y] = self.config.emission_point.map(|coord| coord.clamp(0.0, 1.0));
         let uniforms = SmokeUniforms {
-            resolution: [SIMULATION_RESOLUTION as f32, SIMULATION_RESOLUTION as f32],
-            _pad1: [0.0; 2],
+            resolution: [resolution; 2],
             time: elapsed,
-            _pad2: [0.0; 3],
+            fixed_emitter: u32::from(self.config.emission_mode == EmissionMode::Fixed),
             mouse: self.mouse_position,
-            _pad3: [0.0; 2],
-            mouse_prev: self.mouse_prev_position,
+            // The advection shader injects velocity along mouse - mouse_prev
+            mouse_prev: [
+                self.mouse_position[0] - self.mouse_velocity[0],
+                self.mouse_position[1] - self.mouse_velocity[1],
+            ],
+            emission_point: to_sim_coords(x, y, resolution),
+            emission_radius: self.config.emission_radius.max(0.0) * resolution,
+            _padding: 0.0,
         };
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
     }
@@ -579,9 +631,17 @@ impl SmokeSource {
         queue.submit(once(encoder.finish()));
     }
 
+    /// Moves the smoke source to normalized surface coordinates, with `y` pointing down
     pub fn update_mouse_position(&mut self, x: f32, y: f32) {
-        self.mouse_prev_position = self.mouse_position;
-        self.mouse_position = [x * SIMULATION_RESOLUTION as f32, (1.0 - y) * SIMULATION_RESOLUTION as f32];
+        let resolution = self.sim_resolution();
+        let position = to_sim_coords(x, y, resolution);
+        // Still within the dead zone, so leave the velocity to its inertia decay
+        let Some(velocity) = mouse_movement(self.mouse_position, position, self.config.mouse_deadzone * resolution)
+        else {
+            return;
+        };
+        self.mouse_velocity = velocity;
+        self.mouse_position = position;
     }
 
     fn render_normal(&mut self, ctx: &Context) {
@@ -657,20 +717,13 @@ impl SmokeSource {
         // Swap textures for next frame
         std::mem::swap(&mut self.sim_texture_a, &mut self.sim_texture_c);
 
-        self.mouse_prev_position = self.mouse_position;
-
         let queue = ctx.queue();
         let device = ctx.device();
-        let surface = ctx.surface();
 
-        let output = match surface.get_current_texture() {
-            Ok(output) => output,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
         };
-        let view = output.texture.create_view(&Default::default());
+        let view = output.view();
 
         // After swap, sim_texture_a contains the final simulation result
         let texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -696,7 +749,7 @@ impl SmokeSource {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: ctx.clear_op(wgpu::Color::BLACK),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -713,13 +766,16 @@ impl SmokeSource {
             render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
         }
 
-        // Update render uniforms with configurable colors
+        // Update render uniforms with configurable colors, blended in linear light
+        let [low, high] = self.config.smoke_gradient();
+        let format = ctx.config().format;
         let render_uniforms = RenderUniforms {
-            resolution: [SIMULATION_RESOLUTION as f32, SIMULATION_RESOLUTION as f32],
-            background_color: self.config.background_color,
-            smoke_color: self.config.smoke_color,
-            smoke_intensity: self.config.emission_intensity * 5.0, // Base intensity multiplier
-            _padding: [0.0; 3],
+            resolution: [self.sim_resolution(); 2],
+            background_color: surface_rgb(self.config.background_color, format),
+            smoke_intensity: self.smoke_intensity(),
+            smoke_color_low: surface_rgb(low, format),
+            smoke_color_high: surface_rgb(high, format),
+            ..Default::default()
         };
         queue.write_buffer(&self.render_uniform_buffer, 0, bytemuck::cast_slice(&[render_uniforms]));
         queue.submit(once(encoder.finish()));
@@ -745,29 +801,53 @@ impl Source for SmokeSource {
     fn start_transition(
         &mut self,
         previous: Option<crate::sources::SourceType>,
-        duration: std::time::Duration,
+        transition: &crate::transitions::TransitionConfig,
+        _interaction: &crate::sources::InteractionState,
         ctx: &crate::engine::Context,
-        transition_type: crate::transitions::TransitionType,
     ) {
-        use crate::transitions::{FadeTransition, Transition};
-        debug!("Starting {:?} transition with duration {:?}", transition_type, duration);
+        use crate::transitions::{
+            DipTransition, FadeTransition, PixelateTransition, Transition, WipeTransition, boxed,
+        };
+        let duration = transition.sampled_duration();
+        debug!(
+            "Starting {:?} transition with duration {:?}",
+            transition.transition_type(),
+            duration
+        );
         let previous_texture = previous.map(|s| s.texture().clone());
-        let transition: Box<dyn Transition> = match transition_type {
-            crate::transitions::TransitionType::Fade => Box::new(FadeTransition::new(previous_texture, duration, ctx)),
-            _ => Box::new(FadeTransition::new(previous_texture, duration, ctx)),
+        let transition: Result<Box<dyn Transition>> = match transition.transition_type() {
+            crate::transitions::TransitionType::Fade => {
+                FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed)
+            },
+            crate::transitions::TransitionType::Dip => {
+                DipTransition::new(previous_texture, duration, transition.dip.color, ctx).map(boxed)
+            },
+            crate::transitions::TransitionType::Wipe => {
+                WipeTransition::new(previous_texture, duration, transition.wipe, ctx).map(boxed)
+            },
+            crate::transitions::TransitionType::Pixelate => {
+                PixelateTransition::new(previous_texture, duration, ctx).map(boxed)
+            },
+            _ => FadeTransition::new(previous_texture, duration, transition.fade, ctx).map(boxed),
         };
-        self.state = RenderState::Transitioning(transition);
+        self.state = RenderState::transitioning(transition);
     }
 
-    fn update(&mut self, _dt: std::time::Duration) {
+    fn update(&mut self, dt: std::time::Duration) {
+        self.mouse_velocity = decay_velocity(self.mouse_velocity, self.config.mouse_inertia, dt);
+
         if let RenderState::Transitioning(transition) = &mut self.state {
-            let complete = transition.update(_dt);
+            let complete = transition.update(dt);
             if complete {
                 debug!("Transition complete, switching to Displaying");
                 self.state = RenderState::Displaying;
             }
         }
     }
+
+    fn on_resume(&mut self, _ctx: &crate::engine::Context) {
+        self.start_time = Instant::now();
+    }
 }
 
 impl crate::sources::BasicSource for SmokeSource {
@@ -783,38 +863,47 @@ impl crate::sources::BasicSource for SmokeSource {
     }
 }
 
+// Matches `Uniforms` in advection.wgsl; the other simulation shaders only read `resolution`
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct SmokeUniforms {
     resolution: [f32; 2],
-    _pad1: [f32; 2],
     time: f32,
-    _pad2: [f32; 3],
+    fixed_emitter: u32,
     mouse: [f32; 2],
-    _pad3: [f32; 2],
     mouse_prev: [f32; 2],
+    emission_point: [f32; 2],
+    emission_radius: f32,
+    _padding: f32,
 }
 
-// Render uniforms for configurable colors
-// Total size: 48 bytes (with padding for alignment)
+// Matches `Uniforms` in render.wgsl. WGSL aligns each vec3 to 16 bytes, so the padding
+// fields stand in for the gaps it leaves; a scalar may share a vec3's last 4 bytes.
+// Total size: 64 bytes
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct RenderUniforms {
     resolution: [f32; 2],       // 8 bytes
-    background_color: [f32; 3], // 12 bytes (vec3 in WGSL is 12 bytes, aligned to 16)
-    smoke_color: [f32; 3],      // 12 bytes
-    smoke_intensity: f32,       // 4 bytes
-    _padding: [f32; 3],         // 12 bytes padding to reach 48 total
+    _padding_0: [f32; 2],       // 8 bytes, up to the vec3 alignment
+    background_color: [f32; 3], // 12 bytes
+    smoke_intensity: f32,       // 4 bytes, filling out background_color's slot
+    smoke_color_low: [f32; 3],  // 12 bytes, color of thin smoke
+    _padding_1: f32,            // 4 bytes
+    smoke_color_high: [f32; 3], // 12 bytes, color of dense smoke
+    _padding_2: f32,            // 4 bytes, rounding the struct up to 16
 }
 
 impl Default for RenderUniforms {
     fn default() -> Self {
         Self {
-            resolution: [SIMULATION_RESOLUTION as f32, SIMULATION_RESOLUTION as f32],
+            resolution: [SmokeConfig::default().simulation_resolution as f32; 2],
+            _padding_0: [0.0; 2],
             background_color: [0.0, 0.0, 0.0], // Black background
-            smoke_color: [0.7, 0.7, 0.75],     // Light-gray smoke with slight blue tint
             smoke_intensity: 5.0,              // High visibility multiplier
-            _padding: [0.0; 3],
+            smoke_color_low: [0.7, 0.7, 0.75], // Light-gray smoke with slight blue tint
+            _padding_1: 0.0,
+            smoke_color_high: [0.7, 0.7, 0.75],
+            _padding_2: 0.0,
         }
     }
 }
@@ -925,3 +1014,108 @@ fn create_sim_index_buffer(ctx: &Context) -> wgpu::Buffer {
         usage: wgpu::BufferUsages::INDEX,
     })
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{config::GeneralConfig, engine::GpuContext};
+
+    fn length(v: [f32; 2]) -> f32 {
+        (v[0] * v[0] + v[1] * v[1]).sqrt()
+    }
+
+    #[test]
+    fn test_decay_velocity_fades_within_half_second() {
+        let mut velocity = [10.0, -5.0];
+        let frame = Duration::from_secs_f32(1.0 / 60.0);
+        for _ in 0..30 {
+            velocity = decay_velocity(velocity, 0.9, frame);
+        }
+        assert!(length(velocity) < length([10.0, -5.0]) * 0.05);
+        assert!(length(velocity) > 0.0);
+    }
+
+    #[test]
+    fn test_decay_velocity_independent_of_frame_rate() {
+        let at_60 = (0..60).fold([8.0, 0.0], |v, _| {
+            decay_velocity(v, 0.9, Duration::from_secs_f32(1.0 / 60.0))
+        });
+        let at_30 = (0..30).fold([8.0, 0.0], |v, _| {
+            decay_velocity(v, 0.9, Duration::from_secs_f32(1.0 / 30.0))
+        });
+        assert!((at_60[0] - at_30[0]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_decay_velocity_without_inertia_stops() {
+        let velocity = decay_velocity([3.0, 4.0], 0.0, Duration::from_millis(16));
+        assert_eq!(velocity, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_smoke_uniforms_layout_matches_shader() {
+        // vec2 fields align to 8 bytes, and the struct pads to a multiple of that
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, time), 8);
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, fixed_emitter), 12);
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, mouse), 16);
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, mouse_prev), 24);
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, emission_point), 32);
+        assert_eq!(std::mem::offset_of!(SmokeUniforms, emission_radius), 40);
+        assert_eq!(std::mem::size_of::<SmokeUniforms>(), 48);
+    }
+
+    #[test]
+    fn test_render_uniforms_layout_matches_shader() {
+        // vec3 fields align to 16 bytes, and a following f32 packs into the 4 left over
+        assert_eq!(std::mem::offset_of!(RenderUniforms, resolution), 0);
+        assert_eq!(std::mem::offset_of!(RenderUniforms, background_color), 16);
+        assert_eq!(std::mem::offset_of!(RenderUniforms, smoke_intensity), 28);
+        assert_eq!(std::mem::offset_of!(RenderUniforms, smoke_color_low), 32);
+        assert_eq!(std::mem::offset_of!(RenderUniforms, smoke_color_high), 48);
+        assert_eq!(std::mem::size_of::<RenderUniforms>(), 64);
+    }
+
+    #[test]
+    fn test_to_sim_coords_flips_y() {
+        assert_eq!(to_sim_coords(0.5, 0.75, 512.0), [256.0, 128.0]);
+        assert_eq!(to_sim_coords(0.0, 0.0, 256.0), [0.0, 256.0]);
+        assert_eq!(to_sim_coords(1.0, 1.0, 256.0), [256.0, 0.0]);
+    }
+
+    #[test]
+    fn test_mouse_movement_ignores_moves_inside_deadzone() {
+        assert_eq!(mouse_movement([100.0, 100.0], [101.0, 101.0], 2.0), None);
+        assert_eq!(mouse_movement([100.0, 100.0], [100.0, 100.0], 0.0), None);
+    }
+
+    #[test]
+    fn test_mouse_movement_past_deadzone_has_velocity() {
+        assert_eq!(mouse_movement([100.0, 100.0], [103.0, 96.0], 2.0), Some([3.0, -4.0]));
+        assert_eq!(mouse_movement([100.0, 100.0], [100.5, 100.0], 0.0), Some([0.5, 0.0]));
+    }
+
+    #[test]
+    fn test_decay_velocity_zero_dt_unchanged() {
+        assert_eq!(decay_velocity([3.0, 4.0], 0.9, Duration::ZERO), [3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_on_resume_resets_start_time() {
+        let Ok(gpu) = pollster::block_on(GpuContext::new(&GeneralConfig::default())) else {
+            warn!("No GPU adapter available, skipping");
+            return;
+        };
+        let ctx = Context::headless(Arc::new(gpu), (64, 64));
+        let mut smoke = SmokeSource::new(&ctx, SmokeConfig::default()).unwrap();
+
+        let asleep = Duration::from_secs(60);
+        smoke.start_time = Instant::now().checked_sub(asleep).unwrap();
+        assert!(smoke.start_time.elapsed() >= asleep);
+
+        smoke.on_resume(&ctx);
+        assert!(smoke.start_time.elapsed() < asleep);
+    }
+}
@@ -2,7 +2,38 @@ use std::mem::size_of;
 
 use bytemuck::{Pod, Zeroable};
 use derive_more::From;
-use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+use wgpu::{BufferAddress, TextureFormat, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+
+/// Linear-light value of an sRGB-encoded `channel` (0.0 - 1.0)
+///
+/// Config colors are sRGB, as color pickers show them, while shaders work in linear
+/// light and the sRGB surface encodes their output again.
+pub fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// [`srgb_to_linear`] applied to each channel of `rgb`
+pub fn srgb_to_linear_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    rgb.map(srgb_to_linear)
+}
+
+/// Config color `rgb` as shaders drawing to a `format` surface should output it
+///
+/// sRGB surfaces encode shader output themselves, so they get linear values. Other
+/// formats, such as the deep ones `hdr` picks, store output as-is and keep the sRGB values.
+pub fn surface_rgb(rgb: [f32; 3], format: TextureFormat) -> [f32; 3] {
+    if format.is_srgb() { srgb_to_linear_rgb(rgb) } else { rgb }
+}
+
+/// [`surface_rgb`] for an RGBA color, whose alpha is never encoded
+pub fn surface_rgba([r, g, b, a]: [f32; 4], format: TextureFormat) -> [f32; 4] {
+    let [r, g, b] = surface_rgb([r, g, b], format);
+    [r, g, b, a]
+}
 
 #[derive(Debug, Clone, From, Copy, PartialEq, Pod, Zeroable)]
 #[repr(C)]
@@ -88,6 +119,30 @@ impl Vec2u {
     }
 }
 
+/// Normalized sub-rectangle of a texture, laid out to match a WGSL `vec4<f32>`
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct UvRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl UvRect {
+    pub const FULL: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+}
+
+impl Default for UvRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
 #[repr(C)]
 pub struct FVertex {
@@ -149,6 +204,55 @@ impl UVertex {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_srgb_to_linear_known_values() {
+        for (srgb, linear) in [
+            (0.0, 0.0),
+            (0.04045, 0.003_130_8),
+            (0.5, 0.214_041),
+            (0.8, 0.603_827),
+            (1.0, 1.0),
+        ] {
+            assert!(
+                (srgb_to_linear(srgb) - linear).abs() < 1e-5,
+                "{srgb} -> {}",
+                srgb_to_linear(srgb)
+            );
+        }
+    }
+
+    #[test]
+    fn test_srgb_to_linear_is_continuous_and_increasing() {
+        let below = srgb_to_linear(0.04045 - 1e-4);
+        let above = srgb_to_linear(0.04045 + 1e-4);
+        assert!(above > below && above - below < 1e-4);
+
+        let samples: Vec<_> = (0..=100).map(|i| srgb_to_linear(i as f32 / 100.0)).collect();
+        assert!(samples.windows(2).all(|pair| pair[1] > pair[0]));
+    }
+
+    #[test]
+    fn test_srgb_to_linear_rgb() {
+        let [r, g, b] = srgb_to_linear_rgb([0.0, 0.5, 1.0]);
+        assert_eq!(r, 0.0);
+        assert!((g - 0.214_041).abs() < 1e-5);
+        assert!((b - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_surface_colors_linear_only_for_srgb_surfaces() {
+        let srgb = TextureFormat::Bgra8UnormSrgb;
+        let deep = TextureFormat::Rgb10a2Unorm;
+
+        assert_eq!(surface_rgb([0.5, 0.5, 0.5], srgb), srgb_to_linear_rgb([0.5, 0.5, 0.5]));
+        assert_eq!(surface_rgb([0.5, 0.5, 0.5], deep), [0.5, 0.5, 0.5]);
+
+        let [r, g, b, a] = surface_rgba([0.8, 0.5, 0.0, 0.5], srgb);
+        assert_eq!([r, g, b], srgb_to_linear_rgb([0.8, 0.5, 0.0]));
+        assert_eq!(a, 0.5);
+        assert_eq!(surface_rgba([0.8, 0.5, 0.0, 0.5], deep), [0.8, 0.5, 0.0, 0.5]);
+    }
+
     #[test]
     fn test_vec3f_new() {
         let v = Vec3f::new(1.0, 2.0, 3.0);
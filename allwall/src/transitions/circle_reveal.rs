@@ -3,7 +3,7 @@ use std::{iter::once, time::Duration};
 use rand::Rng;
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     engine::{Context, Texture},
@@ -12,6 +12,9 @@ use crate::{
     transitions::Transition,
 };
 
+/// Narrowest edge the shader is given, since `smoothstep` is undefined for an empty band
+const MIN_FEATHER: f32 = 1e-4;
+
 fn default_feather() -> f32 {
     0.05
 }
@@ -19,7 +22,7 @@ fn default_feather() -> f32 {
 /// Origin point for circle reveal transition
 ///
 /// Defines where the circle animation starts when revealing the new wallpaper.
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
 #[serde(rename_all = "kebab-case")]
 #[derive(Default)]
@@ -43,12 +46,34 @@ pub enum CircleOrigin {
 
     /// Start from a random position each time
     Random,
+
+    /// Start from the mouse pointer, or the center when its position isn't known
+    Mouse,
+}
+
+/// Normalized circle center for `origin`, with `explicit` taking precedence when given
+fn circle_center(origin: CircleOrigin, explicit: Option<[f32; 2]>) -> [f32; 2] {
+    if let Some(center) = explicit {
+        return center;
+    }
+
+    match origin {
+        CircleOrigin::Random => {
+            let mut rng = rand::rng();
+            [rng.random::<f32>(), rng.random::<f32>()]
+        },
+        CircleOrigin::TopLeft => [0.0, 0.0],
+        CircleOrigin::TopRight => [1.0, 0.0],
+        CircleOrigin::BottomLeft => [0.0, 1.0],
+        CircleOrigin::BottomRight => [1.0, 1.0],
+        CircleOrigin::Center | CircleOrigin::Mouse => [0.5, 0.5],
+    }
 }
 
 /// Options for circle reveal transition
 ///
 /// Configures the appearance of the circle reveal animation.
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
 pub struct CircleOptions {
     /// Edge softness (feathering) for the circle reveal
@@ -94,6 +119,21 @@ impl CircleOptions {
     }
 }
 
+/// Matches `Uniforms` in `circle_reveal.wgsl`, whose vec2 member rounds the struct up to 8-byte alignment
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct CircleRevealUniforms {
+    progress: f32,
+    aspect_ratio: f32,
+    center: [f32; 2],
+    surface_to_from_arr: f32,
+    surface_to_to_arr: f32,
+    feather: f32,
+    _padding: f32,
+}
+
+const UNIFORM_SIZE: u64 = std::mem::size_of::<CircleRevealUniforms>() as u64;
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -207,6 +247,34 @@ mod tests {
         assert_eq!(config.origin, CircleOrigin::Random);
     }
 
+    #[test]
+    fn test_circle_origin_deserialize_mouse() {
+        let config: CircleOriginConfig = toml::from_str(r#"origin = "mouse""#).unwrap();
+        assert_eq!(config.origin, CircleOrigin::Mouse);
+    }
+
+    #[test]
+    fn test_circle_center_explicit_overrides_origin() {
+        assert_eq!(circle_center(CircleOrigin::TopLeft, Some([0.2, 0.7])), [0.2, 0.7]);
+        assert_eq!(circle_center(CircleOrigin::Random, Some([0.2, 0.7])), [0.2, 0.7]);
+        assert_eq!(circle_center(CircleOrigin::Mouse, Some([0.9, 0.1])), [0.9, 0.1]);
+    }
+
+    #[test]
+    fn test_circle_center_mouse_falls_back_to_center() {
+        assert_eq!(circle_center(CircleOrigin::Mouse, None), [0.5, 0.5]);
+        assert_eq!(circle_center(CircleOrigin::BottomRight, None), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_circle_reveal_uniforms_match_shader_layout() {
+        assert_eq!(UNIFORM_SIZE, 32);
+        assert_eq!(std::mem::offset_of!(CircleRevealUniforms, center), 8);
+        assert_eq!(std::mem::offset_of!(CircleRevealUniforms, surface_to_from_arr), 16);
+        assert_eq!(std::mem::offset_of!(CircleRevealUniforms, surface_to_to_arr), 20);
+        assert_eq!(std::mem::offset_of!(CircleRevealUniforms, feather), 24);
+    }
+
     #[test]
     fn test_circle_origin_default() {
         assert_eq!(CircleOrigin::default(), CircleOrigin::Center);
@@ -239,7 +307,8 @@ pub struct CircleRevealTransition {
     elapsed: Duration,
     duration: Duration,
     origin: CircleOrigin,
-    center: Option<[f32; 2]>,
+    center: [f32; 2],
+    feather: f32,
 
     render_pipeline: wgpu::RenderPipeline,
     texture_bind_group_layout: wgpu::BindGroupLayout,
@@ -251,22 +320,21 @@ pub struct CircleRevealTransition {
 }
 
 impl CircleRevealTransition {
-    pub fn new(previous_texture: Option<Texture>, duration: Duration, origin: CircleOrigin, ctx: &Context) -> Self {
+    /// Creates the transition, centering the circle on `center` when given and on `options.origin` otherwise
+    pub fn new(
+        previous_texture: Option<Texture>,
+        duration: Duration,
+        options: CircleOptions,
+        center: Option<[f32; 2]>,
+        ctx: &Context,
+    ) -> Result<Self> {
+        let CircleOptions { origin, feather } = options;
         debug!(
-            "Creating CircleRevealTransition with origin {:?} and duration {:?}",
+            "Creating CircleRevealTransition with origin {:?}, feather {feather} and duration {:?}",
             origin, duration
         );
 
-        let mut rng = rand::rng();
-
-        let center = match origin {
-            CircleOrigin::Random => Some([rng.random::<f32>(), rng.random::<f32>()]),
-            CircleOrigin::TopLeft => Some([0.0, 0.0]),
-            CircleOrigin::TopRight => Some([1.0, 0.0]),
-            CircleOrigin::BottomLeft => Some([0.0, 1.0]),
-            CircleOrigin::BottomRight => Some([1.0, 1.0]),
-            CircleOrigin::Center => Some([0.5, 0.5]),
-        };
+        let center = circle_center(origin, center);
 
         let vertex_buffer = create_vertex_buffer(ctx);
         let index_buffer = create_index_buffer(ctx);
@@ -310,24 +378,6 @@ impl CircleRevealTransition {
             ],
         });
 
-        // WGSL struct alignment: vec2<f32> requires 8-byte alignment
-        // progress (f32) at offset 0, aspect_ratio at offset 4
-        // center (vec2<f32>) at offset 8
-        // surface_to_from_arr (f32) at offset 16
-        // surface_to_to_arr (f32) at offset 20
-        // Total: 24 bytes
-        const UNIFORM_SIZE: u64 = 24;
-
-        #[repr(C)]
-        #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-        struct CircleRevealUniforms {
-            progress: f32,
-            aspect_ratio: f32,
-            center: [f32; 2],
-            surface_to_from_arr: f32,
-            surface_to_to_arr: f32,
-        }
-
         let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("circle_reveal_uniform_layout"),
             entries: &[wgpu::BindGroupLayoutEntry {
@@ -349,14 +399,6 @@ impl CircleRevealTransition {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        let _uniforms = CircleRevealUniforms {
-            progress: 0.0,
-            aspect_ratio: ctx.surface_aspect_ratio(),
-            center: [0.5, 0.5],
-            surface_to_from_arr: 1.0,
-            surface_to_to_arr: 1.0,
-        };
-
         let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &uniform_bind_group_layout,
             entries: &[wgpu::BindGroupEntry {
@@ -370,30 +412,29 @@ impl CircleRevealTransition {
             label: Some("circle_reveal_uniform_bind_group"),
         });
 
-        let shader = ctx
-            .device()
-            .create_shader_module(wgpu::include_wgsl!("./shaders/circle_reveal.wgsl"));
+        let shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/circle_reveal.wgsl"));
 
         let render_pipeline = create_pipeline(
             ctx,
             &[&texture_bind_group_layout, &uniform_bind_group_layout],
             &shader,
             ctx.config(),
-        );
+        )?;
 
-        Self {
+        Ok(Self {
             previous_texture,
             elapsed: Duration::ZERO,
             duration,
             origin,
             center,
+            feather,
             render_pipeline,
             texture_bind_group_layout,
             vertex_buffer,
             index_buffer,
             uniform_buffer,
             uniform_bind_group,
-        }
+        })
     }
 }
 
@@ -412,16 +453,11 @@ impl Transition for CircleRevealTransition {
     fn render(&self, ctx: &Context, current_texture: &Texture) {
         let queue = ctx.queue();
         let device = ctx.device();
-        let surface = ctx.surface();
 
-        let output = match surface.get_current_texture() {
-            Ok(output) => output,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
         };
-        let view = output.texture.create_view(&Default::default());
+        let view = output.view();
 
         let from_texture: &Texture = self.previous_texture.as_ref().unwrap_or(current_texture);
 
@@ -453,24 +489,14 @@ impl Transition for CircleRevealTransition {
         let to_aspect = current_texture.aspect_ratio();
         let progress = self.progress();
 
-        let center = self.center.unwrap_or([0.5, 0.5]);
-
-        #[repr(C)]
-        #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-        struct CircleRevealUniforms {
-            progress: f32,
-            aspect_ratio: f32,
-            center: [f32; 2],
-            surface_to_from_arr: f32,
-            surface_to_to_arr: f32,
-        }
-
         let uniforms = CircleRevealUniforms {
             progress,
             aspect_ratio: surface_aspect,
-            center,
+            center: self.center,
             surface_to_from_arr: surface_aspect / from_aspect,
             surface_to_to_arr: surface_aspect / to_aspect,
+            feather: self.feather.max(MIN_FEATHER),
+            _padding: 0.0,
         };
 
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
@@ -483,7 +509,7 @@ impl Transition for CircleRevealTransition {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: ctx.clear_op(wgpu::Color::BLACK),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -1,10 +1,12 @@
-use std::time::Duration;
+use std::{borrow::Cow, collections::BTreeMap, time::Duration};
 
+use rand::{Rng, seq::IndexedRandom};
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{CircleOptions, TransitionType};
+use super::{CircleOptions, DipOptions, FadeOptions, TransitionType, WipeOptions};
+use crate::{config::error::ConfigError, sources::SourceKind};
 
 fn default_duration() -> u64 {
     1
@@ -18,6 +20,16 @@ fn default_transition_type() -> TransitionType {
     TransitionType::Fade
 }
 
+fn default_random_types() -> Vec<TransitionType> {
+    TransitionType::CONCRETE.to_vec()
+}
+
+/// Jitter never pulls a rotation closer than this, so the timer can't spin
+const MIN_JITTERED_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Jitter never shortens a transition below this, so it can't collapse into a cut
+const MIN_JITTERED_DURATION: Duration = Duration::from_millis(100);
+
 /// Transition configuration for wallpaper changes
 ///
 /// Controls how wallpapers transition between each other, including
@@ -34,17 +46,30 @@ fn default_transition_type() -> TransitionType {
 /// [transition.circle]
 /// feather = 0.1
 /// origin = "center"
+///
+/// [transition.fade]
+/// mode = "overlay-out"
+///
+/// [transition.dip]
+/// color = [1.0, 1.0, 1.0]
+///
+/// [transition.wipe]
+/// angle = 45
+/// feather = 0.03
+///
+/// [transition.per_source]
+/// media = "circle-center"
+/// smoke = "fade"
 /// ```
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
 pub struct TransitionConfig {
     /// Transition animation type
     ///
     /// Determines the visual effect used when switching between wallpapers.
-    #[serde(default = "default_transition_type")]
-    #[cfg_attr(feature = "generate", schemars(default = "default_transition_type"))]
-    #[cfg_attr(feature = "generate", nixos(default = "\"fade\""))]
-    pub r#type: TransitionType,
+    /// Left out of a scene's table, the global type is kept, which fades by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<TransitionType>,
 
     /// Duration of the transition animation in seconds
     ///
@@ -64,25 +89,87 @@ pub struct TransitionConfig {
     #[cfg_attr(feature = "generate", nixos(default = "10"))]
     pub interval: u64,
 
+    /// Random offset applied to each rotation in seconds
+    ///
+    /// Each rotation happens somewhere within `interval ± interval_jitter`,
+    /// so monitors with independent scenes don't all change at once.
+    /// Set to 0 (the default) for a fixed interval.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "0"))]
+    pub interval_jitter: u64,
+
+    /// Random offset applied to each transition's duration in milliseconds
+    ///
+    /// Each transition lasts somewhere within `duration ± duration_jitter_ms`,
+    /// never shorter than a tenth of a second. Set to 0 (the default) for a
+    /// fixed duration.
+    #[serde(default)]
+    #[cfg_attr(feature = "generate", nixos(default = "0"))]
+    pub duration_jitter_ms: u64,
+
     /// Options specific to circle reveal transitions
     ///
     /// Only used when `type` is set to a circle variant.
     #[serde(default)]
     pub circle: CircleOptions,
+
+    /// Options specific to the fade transition
+    ///
+    /// Only used when `type` is set to `fade`.
+    #[serde(default)]
+    pub fade: FadeOptions,
+
+    /// Options specific to the dip transition
+    ///
+    /// Only used when `type` is set to `dip`.
+    #[serde(default)]
+    pub dip: DipOptions,
+
+    /// Options specific to the wipe transition
+    ///
+    /// Only used when `type` is set to `wipe`.
+    #[serde(default)]
+    pub wipe: WipeOptions,
+
+    /// Transition type for particular source kinds
+    ///
+    /// Overrides `type` from the global transition table, but a `type` set in a
+    /// scene's own transition table still wins. Smoke supports `fade`, `dip`, `wipe`
+    /// and `pixelate`, while grass, color and gradient only `fade`.
+    #[serde(default)]
+    pub per_source: BTreeMap<SourceKind, TransitionType>,
+
+    /// Types `type = "random"` picks from, one per rotation
+    ///
+    /// Defaults to every transition type. Must not be empty while `random` is in use.
+    #[serde(default = "default_random_types")]
+    pub random_types: Vec<TransitionType>,
 }
 
 impl Default for TransitionConfig {
     fn default() -> Self {
         Self {
-            r#type: default_transition_type(),
+            r#type: None,
             duration: default_duration(),
             interval: default_interval(),
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         }
     }
 }
 
 impl TransitionConfig {
+    /// Transition type to draw, `fade` when none was configured
+    pub fn transition_type(&self) -> TransitionType {
+        self.r#type.unwrap_or_else(default_transition_type)
+    }
+
     pub fn duration(&self) -> Duration {
         Duration::from_secs(self.duration)
     }
@@ -91,10 +178,66 @@ impl TransitionConfig {
         Duration::from_secs(self.interval)
     }
 
+    pub fn interval_jitter(&self) -> Duration {
+        Duration::from_secs(self.interval_jitter)
+    }
+
+    pub fn duration_jitter(&self) -> Duration {
+        Duration::from_millis(self.duration_jitter_ms)
+    }
+
+    /// Duration for the next transition, with `duration_jitter_ms` applied
+    pub fn sampled_duration(&self) -> Duration {
+        jittered_duration(self.duration(), self.duration_jitter(), &mut rand::rng())
+    }
+
+    /// This config with a `random` type settled on one of `random_types`
+    pub fn sampled(&self) -> Cow<'_, Self> {
+        if self.transition_type() != TransitionType::Random {
+            return Cow::Borrowed(self);
+        }
+
+        Cow::Owned(Self {
+            r#type: Some(random_transition(&self.random_types, &mut rand::rng())),
+            ..self.clone()
+        })
+    }
+
+    /// Transition type for an incoming source of `kind`
+    pub fn type_for(&self, kind: SourceKind) -> TransitionType {
+        self.per_source.get(&kind).copied().unwrap_or_else(|| self.transition_type())
+    }
+
+    /// This config with `type` resolved for sources of `kind`
+    pub fn for_kind(&self, kind: SourceKind) -> Self {
+        Self {
+            r#type: Some(self.type_for(kind)),
+            ..self.clone()
+        }
+    }
+
+    /// Rejects per-source overrides the source can't render, and `random` with nothing to pick from
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let uses_random = self.transition_type() == TransitionType::Random
+            || self.per_source.values().any(|t| *t == TransitionType::Random);
+        if uses_random && !self.random_types.iter().any(|t| *t != TransitionType::Random) {
+            return Err(ConfigError::EmptyRandomTransitions);
+        }
+
+        match self
+            .per_source
+            .iter()
+            .find(|(kind, transition)| !kind.supports_transition(**transition))
+        {
+            Some((&kind, &transition)) => Err(ConfigError::UnsupportedSourceTransition { kind, transition }),
+            None => Ok(()),
+        }
+    }
+
     pub fn merge(&self, other: Option<&Self>) -> Self {
         match other {
             Some(other) => Self {
-                r#type: other.r#type,
+                r#type: other.r#type.or(self.r#type),
                 duration: if other.duration != default_duration() {
                     other.duration
                 } else {
@@ -105,13 +248,73 @@ impl TransitionConfig {
                 } else {
                     self.interval
                 },
+                interval_jitter: if other.interval_jitter != 0 {
+                    other.interval_jitter
+                } else {
+                    self.interval_jitter
+                },
+                duration_jitter_ms: if other.duration_jitter_ms != 0 {
+                    other.duration_jitter_ms
+                } else {
+                    self.duration_jitter_ms
+                },
                 circle: self.circle.merge(&other.circle),
+                fade: self.fade.merge(&other.fade),
+                dip: self.dip.merge(&other.dip),
+                wipe: self.wipe.merge(&other.wipe),
+                per_source: self.merge_per_source(other),
+                random_types: if other.random_types != default_random_types() {
+                    other.random_types.clone()
+                } else {
+                    self.random_types.clone()
+                },
             },
             None => self.clone(),
         }
     }
 }
 
+impl TransitionConfig {
+    /// Kind overrides from the scene on top of global ones, dropping the global ones when
+    /// the scene picks its own `type`, since a scene's choice outranks kind defaults
+    fn merge_per_source(&self, scene: &Self) -> BTreeMap<SourceKind, TransitionType> {
+        let mut per_source = if scene.r#type.is_some() {
+            BTreeMap::new()
+        } else {
+            self.per_source.clone()
+        };
+        per_source.extend(&scene.per_source);
+        per_source
+    }
+}
+
+/// Picks one of `allowed` at random, skipping `random` itself, or fades when nothing is left
+pub fn random_transition(allowed: &[TransitionType], rng: &mut impl Rng) -> TransitionType {
+    let concrete: Vec<_> = allowed.iter().filter(|t| **t != TransitionType::Random).collect();
+    concrete.choose(rng).map_or(TransitionType::Fade, |t| **t)
+}
+
+/// Samples a rotation interval uniformly from `base ± jitter`
+pub fn jittered_interval(base: Duration, jitter: Duration, rng: &mut impl Rng) -> Duration {
+    jittered(base, jitter, MIN_JITTERED_INTERVAL, rng)
+}
+
+/// Samples a transition duration uniformly from `base ± jitter`
+pub fn jittered_duration(base: Duration, jitter: Duration, rng: &mut impl Rng) -> Duration {
+    jittered(base, jitter, MIN_JITTERED_DURATION, rng)
+}
+
+/// Samples uniformly from `base ± jitter`, but never below `floor`, or `base` as-is without jitter
+fn jittered(base: Duration, jitter: Duration, floor: Duration, rng: &mut impl Rng) -> Duration {
+    if jitter.is_zero() {
+        return base;
+    }
+
+    let min = base.saturating_sub(jitter).max(floor);
+    let max = (base + jitter).max(min);
+    rng.random_range(min..=max)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -120,7 +323,7 @@ mod tests {
     #[test]
     fn test_transition_config_defaults() {
         let config = TransitionConfig::default();
-        assert_eq!(config.r#type, TransitionType::Fade);
+        assert_eq!(config.transition_type(), TransitionType::Fade);
         assert_eq!(config.duration, 1);
         assert_eq!(config.interval, 10);
     }
@@ -128,10 +331,17 @@ mod tests {
     #[test]
     fn test_transition_config_duration_method() {
         let config = TransitionConfig {
-            r#type: TransitionType::Fade,
+            r#type: Some(TransitionType::Fade),
             duration: 5,
             interval: 10,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
         assert_eq!(config.duration(), Duration::from_secs(5));
     }
@@ -139,10 +349,17 @@ mod tests {
     #[test]
     fn test_transition_config_interval_method() {
         let config = TransitionConfig {
-            r#type: TransitionType::Fade,
+            r#type: Some(TransitionType::Fade),
             duration: 1,
             interval: 30,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
         assert_eq!(config.interval(), Duration::from_secs(30));
     }
@@ -159,21 +376,35 @@ mod tests {
     #[test]
     fn test_transition_config_merge_partial() {
         let global = TransitionConfig {
-            r#type: TransitionType::Fade,
+            r#type: Some(TransitionType::Fade),
             duration: 5,
             interval: 30,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
         let scene = TransitionConfig {
-            r#type: TransitionType::CircleCenter,
+            r#type: Some(TransitionType::CircleCenter),
             duration: 1,
             interval: 10,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
 
         let merged = global.merge(Some(&scene));
 
-        assert_eq!(merged.r#type, TransitionType::CircleCenter);
+        assert_eq!(merged.transition_type(), TransitionType::CircleCenter);
         assert_eq!(merged.duration, 5);
         assert_eq!(merged.interval, 30);
     }
@@ -181,25 +412,52 @@ mod tests {
     #[test]
     fn test_transition_config_merge_full() {
         let global = TransitionConfig {
-            r#type: TransitionType::Fade,
+            r#type: Some(TransitionType::Fade),
             duration: 1,
             interval: 10,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
         let scene = TransitionConfig {
-            r#type: TransitionType::CircleTopLeft,
+            r#type: Some(TransitionType::CircleTopLeft),
             duration: 3,
             interval: 20,
+            interval_jitter: 0,
+            duration_jitter_ms: 0,
             circle: CircleOptions::default(),
+            fade: FadeOptions::default(),
+            dip: DipOptions::default(),
+            wipe: WipeOptions::default(),
+            per_source: BTreeMap::new(),
+            random_types: default_random_types(),
         };
 
         let merged = global.merge(Some(&scene));
 
-        assert_eq!(merged.r#type, TransitionType::CircleTopLeft);
+        assert_eq!(merged.transition_type(), TransitionType::CircleTopLeft);
         assert_eq!(merged.duration, 3);
         assert_eq!(merged.interval, 20);
     }
 
+    #[test]
+    fn test_transition_config_merge_scene_picks_fade() {
+        let global = TransitionConfig {
+            r#type: Some(TransitionType::Wipe),
+            ..Default::default()
+        };
+        let scene: TransitionConfig = toml::from_str(r#"type = "fade""#).unwrap();
+        assert_eq!(global.merge(Some(&scene)).transition_type(), TransitionType::Fade);
+
+        let scene: TransitionConfig = toml::from_str("duration = 3").unwrap();
+        assert_eq!(global.merge(Some(&scene)).transition_type(), TransitionType::Wipe);
+    }
+
     #[test]
     fn test_transition_config_deserialize() {
         let config: TransitionConfig = toml::from_str(
@@ -211,7 +469,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(config.r#type, TransitionType::CircleCenter);
+        assert_eq!(config.transition_type(), TransitionType::CircleCenter);
         assert_eq!(config.duration, 2);
         assert_eq!(config.interval, 30);
     }
@@ -219,8 +477,332 @@ mod tests {
     #[test]
     fn test_transition_config_deserialize_defaults() {
         let config: TransitionConfig = toml::from_str("").unwrap();
-        assert_eq!(config.r#type, TransitionType::Fade);
+        assert_eq!(config.transition_type(), TransitionType::Fade);
         assert_eq!(config.duration, 1);
         assert_eq!(config.interval, 10);
     }
+
+    #[test]
+    fn test_transition_config_deserialize_wipe() {
+        let config: TransitionConfig = toml::from_str(
+            r#"
+            type = "wipe"
+
+            [wipe]
+            angle = 45
+            feather = 0.03
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.transition_type(), TransitionType::Wipe);
+        assert_eq!(config.wipe.angle, 45.0);
+    }
+
+    #[test]
+    fn test_transition_config_deserialize_interval_jitter() {
+        let config: TransitionConfig = toml::from_str("interval_jitter = 5").unwrap();
+        assert_eq!(config.interval_jitter(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_transition_config_merge_interval_jitter() {
+        let global = TransitionConfig {
+            interval_jitter: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(global.merge(Some(&TransitionConfig::default())).interval_jitter, 4);
+
+        let scene = TransitionConfig {
+            interval_jitter: 8,
+            ..Default::default()
+        };
+        assert_eq!(global.merge(Some(&scene)).interval_jitter, 8);
+    }
+
+    #[test]
+    fn test_jittered_interval_without_jitter() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(30);
+        assert_eq!(jittered_interval(base, Duration::ZERO, &mut rng), base);
+    }
+
+    #[test]
+    fn test_jittered_interval_bounds() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(30);
+        let jitter = Duration::from_secs(5);
+
+        for _ in 0..1000 {
+            let interval = jittered_interval(base, jitter, &mut rng);
+            assert!(interval >= base - jitter);
+            assert!(interval <= base + jitter);
+        }
+    }
+
+    #[test]
+    fn test_jittered_interval_never_below_minimum() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(3);
+        let jitter = Duration::from_secs(10);
+
+        for _ in 0..1000 {
+            let interval = jittered_interval(base, jitter, &mut rng);
+            assert!(interval >= MIN_JITTERED_INTERVAL);
+            assert!(interval <= base + jitter);
+        }
+    }
+
+    #[test]
+    fn test_transition_config_merge_duration_jitter() {
+        let global = TransitionConfig {
+            duration_jitter_ms: 250,
+            ..Default::default()
+        };
+
+        assert_eq!(global.merge(Some(&TransitionConfig::default())).duration_jitter_ms, 250);
+
+        let scene = TransitionConfig {
+            duration_jitter_ms: 500,
+            ..Default::default()
+        };
+        assert_eq!(global.merge(Some(&scene)).duration_jitter(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_jittered_duration_without_jitter() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(2);
+        assert_eq!(jittered_duration(base, Duration::ZERO, &mut rng), base);
+    }
+
+    #[test]
+    fn test_jittered_duration_bounds() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(2);
+        let jitter = Duration::from_millis(500);
+
+        for _ in 0..1000 {
+            let duration = jittered_duration(base, jitter, &mut rng);
+            assert!(duration >= base - jitter);
+            assert!(duration <= base + jitter);
+        }
+    }
+
+    #[test]
+    fn test_jittered_duration_stays_positive() {
+        let mut rng = rand::rng();
+        let base = Duration::from_secs(1);
+        let jitter = Duration::from_secs(3);
+
+        for _ in 0..1000 {
+            let duration = jittered_duration(base, jitter, &mut rng);
+            assert!(duration >= MIN_JITTERED_DURATION);
+            assert!(duration <= base + jitter);
+        }
+    }
+
+    #[test]
+    fn test_jittered_duration_never_zero() {
+        let mut rng = rand::rng();
+
+        for jitter in [Duration::from_millis(50), Duration::from_millis(500)] {
+            for _ in 0..1000 {
+                let duration = jittered_duration(Duration::ZERO, jitter, &mut rng);
+                assert!(duration >= MIN_JITTERED_DURATION);
+                assert!(duration <= MIN_JITTERED_DURATION.max(jitter));
+            }
+        }
+    }
+
+    fn with_per_source(r#type: TransitionType, per_source: &[(SourceKind, TransitionType)]) -> TransitionConfig {
+        TransitionConfig {
+            r#type: Some(r#type),
+            per_source: per_source.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transition_config_deserialize_per_source() {
+        let config: TransitionConfig = toml::from_str(
+            r#"
+            type = "wipe"
+
+            [per_source]
+            media = "circle-center"
+            smoke = "fade"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.type_for(SourceKind::Media), TransitionType::CircleCenter);
+        assert_eq!(config.type_for(SourceKind::Smoke), TransitionType::Fade);
+        assert_eq!(config.type_for(SourceKind::Color), TransitionType::Wipe);
+    }
+
+    #[test]
+    fn test_transition_precedence_kind_default_over_global() {
+        let global = with_per_source(
+            TransitionType::Wipe,
+            &[(SourceKind::Media, TransitionType::CircleCenter)],
+        );
+
+        let merged = global.merge(None);
+        assert_eq!(merged.type_for(SourceKind::Media), TransitionType::CircleCenter);
+        assert_eq!(merged.type_for(SourceKind::Gradient), TransitionType::Wipe);
+
+        // A scene table that leaves `type` alone keeps the kind default
+        let merged = global.merge(Some(&TransitionConfig::default()));
+        assert_eq!(merged.type_for(SourceKind::Media), TransitionType::CircleCenter);
+        assert_eq!(merged.type_for(SourceKind::Gradient), TransitionType::Wipe);
+    }
+
+    #[test]
+    fn test_transition_precedence_scene_over_kind_default() {
+        let global = with_per_source(
+            TransitionType::Fade,
+            &[(SourceKind::Media, TransitionType::CircleCenter)],
+        );
+        let scene = with_per_source(TransitionType::Dip, &[]);
+
+        let merged = global.merge(Some(&scene));
+        assert_eq!(merged.type_for(SourceKind::Media), TransitionType::Dip);
+    }
+
+    #[test]
+    fn test_transition_precedence_scene_kind_override_wins() {
+        let global = with_per_source(
+            TransitionType::Fade,
+            &[(SourceKind::Media, TransitionType::CircleCenter)],
+        );
+        let scene = with_per_source(TransitionType::Dip, &[(SourceKind::Media, TransitionType::Wipe)]);
+
+        let merged = global.merge(Some(&scene));
+        assert_eq!(merged.type_for(SourceKind::Media), TransitionType::Wipe);
+    }
+
+    #[test]
+    fn test_transition_for_kind_resolves_type() {
+        let config = with_per_source(TransitionType::Fade, &[(SourceKind::Media, TransitionType::Wipe)]);
+        assert_eq!(
+            config.for_kind(SourceKind::Media).transition_type(),
+            TransitionType::Wipe
+        );
+        assert_eq!(
+            config.for_kind(SourceKind::Smoke).transition_type(),
+            TransitionType::Fade
+        );
+    }
+
+    #[test]
+    fn test_transition_validate_rejects_unsupported_source_transitions() {
+        for (kind, transition) in [
+            (SourceKind::Smoke, TransitionType::CircleCenter),
+            (SourceKind::Smoke, TransitionType::Random),
+            (SourceKind::Grass, TransitionType::Wipe),
+            (SourceKind::Color, TransitionType::CircleCenter),
+            (SourceKind::Gradient, TransitionType::Dip),
+        ] {
+            let config = with_per_source(TransitionType::Fade, &[(kind, transition)]);
+            assert!(matches!(
+                config.validate(),
+                Err(ConfigError::UnsupportedSourceTransition { kind: k, .. }) if k == kind
+            ));
+        }
+
+        for transition in [
+            TransitionType::Fade,
+            TransitionType::Dip,
+            TransitionType::Wipe,
+            TransitionType::Pixelate,
+        ] {
+            let config = with_per_source(
+                TransitionType::Fade,
+                &[(SourceKind::Smoke, transition), (SourceKind::Media, TransitionType::Wipe)],
+            );
+            assert!(config.validate().is_ok(), "smoke should accept {transition}");
+        }
+
+        let config = with_per_source(
+            TransitionType::Wipe,
+            &[
+                (SourceKind::Color, TransitionType::Fade),
+                (SourceKind::Gradient, TransitionType::Fade),
+            ],
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_random_transition_honours_allowed_types() {
+        let mut rng = rand::rng();
+        let allowed = [TransitionType::Wipe, TransitionType::Random, TransitionType::Dip];
+
+        for _ in 0..100 {
+            let picked = random_transition(&allowed, &mut rng);
+            assert!(matches!(picked, TransitionType::Wipe | TransitionType::Dip));
+        }
+    }
+
+    #[test]
+    fn test_random_transition_falls_back_to_fade() {
+        let mut rng = rand::rng();
+        assert_eq!(random_transition(&[], &mut rng), TransitionType::Fade);
+        assert_eq!(
+            random_transition(&[TransitionType::Random], &mut rng),
+            TransitionType::Fade
+        );
+    }
+
+    #[test]
+    fn test_transition_sampled_resolves_random() {
+        let config = TransitionConfig {
+            r#type: Some(TransitionType::Random),
+            random_types: vec![TransitionType::Pixelate],
+            ..Default::default()
+        };
+        assert_eq!(config.sampled().transition_type(), TransitionType::Pixelate);
+
+        let config = TransitionConfig::default();
+        assert!(matches!(config.sampled(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_transition_config_deserialize_random() {
+        let config: TransitionConfig = toml::from_str(
+            r#"
+            type = "random"
+            random_types = ["wipe", "circle-center"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.transition_type(), TransitionType::Random);
+        assert_eq!(
+            config.random_types,
+            vec![TransitionType::Wipe, TransitionType::CircleCenter]
+        );
+        assert_eq!(
+            TransitionConfig::default().random_types,
+            TransitionType::CONCRETE.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_transition_validate_rejects_random_without_types() {
+        let config = TransitionConfig {
+            r#type: Some(TransitionType::Random),
+            random_types: vec![TransitionType::Random],
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::EmptyRandomTransitions)));
+
+        let config = TransitionConfig {
+            random_types: Vec::new(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
 }
@@ -0,0 +1,254 @@
+use std::{iter::once, time::Duration};
+
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "generate")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        INDICES, create_index_buffer, create_pipeline, create_texture_bind_group, create_texture_bind_group_layout,
+        create_uniform_binds, create_vertex_buffer, types::surface_rgb,
+    },
+    transitions::Transition,
+};
+
+/// Color a dip transition passes through when none is given
+pub const DEFAULT_DIP_COLOR: [f32; 3] = [0.0, 0.0, 0.0];
+
+fn default_dip_color() -> [f32; 3] {
+    DEFAULT_DIP_COLOR
+}
+
+/// Options for dip transition
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+pub struct DipOptions {
+    /// Color the old wallpaper fades out to and the new one fades in from, as sRGB (0.0 - 1.0)
+    #[serde(default = "default_dip_color")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_dip_color"))]
+    pub color: [f32; 3],
+}
+
+impl Default for DipOptions {
+    fn default() -> Self {
+        Self {
+            color: default_dip_color(),
+        }
+    }
+}
+
+impl DipOptions {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            color: if other.color != default_dip_color() {
+                other.color
+            } else {
+                self.color
+            },
+        }
+    }
+}
+
+/// Which half of a dip transition is playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DipPhase {
+    /// The previous wallpaper fading out to the dip color
+    Out = 0,
+    /// The dip color fading up into the current wallpaper
+    In = 1,
+}
+
+/// Splits overall transition progress into a phase and the progress within that phase
+fn dip_phase(progress: f32) -> (DipPhase, f32) {
+    let progress = progress.clamp(0.0, 1.0);
+    if progress < 0.5 {
+        (DipPhase::Out, progress * 2.0)
+    } else {
+        (DipPhase::In, (progress - 0.5) * 2.0)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct DipUniforms {
+    dip_color: [f32; 4],
+    local_progress: f32,
+    phase: u32,
+    surface_to_from_arr: f32,
+    surface_to_to_arr: f32,
+}
+
+/// Fades the previous wallpaper to a solid color, then fades the current one up from it
+#[derive(Debug)]
+pub struct DipTransition {
+    previous_texture: Option<Texture>,
+    elapsed: Duration,
+    duration: Duration,
+    progress: f32,
+    color: [f32; 3],
+    render_pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+impl DipTransition {
+    pub fn new(previous_texture: Option<Texture>, duration: Duration, color: [f32; 3], ctx: &Context) -> Result<Self> {
+        debug!("Creating DipTransition to {:?} with duration {:?}", color, duration);
+
+        let texture_bind_group_layout = create_texture_bind_group_layout(2, ctx);
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<DipUniforms>() as u64, ctx);
+
+        let shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/dip.wgsl"));
+
+        let render_pipeline = create_pipeline(
+            ctx,
+            &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            &shader,
+            ctx.config(),
+        )?;
+
+        Ok(Self {
+            previous_texture,
+            elapsed: Duration::ZERO,
+            duration,
+            progress: 0.0,
+            color,
+            render_pipeline,
+            texture_bind_group_layout,
+            vertex_buffer: create_vertex_buffer(ctx),
+            index_buffer: create_index_buffer(ctx),
+            uniform_buffer,
+            uniform_bind_group,
+        })
+    }
+}
+
+impl Transition for DipTransition {
+    fn update(&mut self, dt: Duration) -> bool {
+        self.elapsed += dt;
+        self.progress = (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        debug!("DipTransition progress: {:.2}", self.progress);
+        self.progress >= 1.0
+    }
+
+    fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    fn render(&self, ctx: &Context, current_texture: &Texture) {
+        let queue = ctx.queue();
+
+        let Some(output) = ctx.acquire_frame() else {
+            return;
+        };
+        let view = output.view();
+
+        let from_texture: &Texture = self.previous_texture.as_ref().unwrap_or(current_texture);
+        let texture_bind_group =
+            create_texture_bind_group(&self.texture_bind_group_layout, &[from_texture, current_texture], ctx);
+
+        let surface_aspect = ctx.surface_aspect_ratio();
+        let (phase, local_progress) = dip_phase(self.progress);
+        let [r, g, b] = surface_rgb(self.color, ctx.config().format);
+
+        let uniforms = DipUniforms {
+            dip_color: [r, g, b, 1.0],
+            local_progress,
+            phase: phase as u32,
+            surface_to_from_arr: surface_aspect / from_texture.aspect_ratio(),
+            surface_to_to_arr: surface_aspect / current_texture.aspect_ratio(),
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = ctx.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("dip_transition"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: ctx.clear_op(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.set_bind_group(0, &texture_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        queue.submit(once(encoder.finish()));
+        output.present();
+    }
+
+    fn previous_texture(&self) -> Option<&Texture> {
+        self.previous_texture.as_ref()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dip_phase_first_half_fades_out() {
+        assert_eq!(dip_phase(0.0), (DipPhase::Out, 0.0));
+        assert_eq!(dip_phase(0.25), (DipPhase::Out, 0.5));
+        assert!((dip_phase(0.4999).1 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dip_phase_second_half_fades_in() {
+        assert_eq!(dip_phase(0.5), (DipPhase::In, 0.0));
+        assert_eq!(dip_phase(0.75), (DipPhase::In, 0.5));
+        assert_eq!(dip_phase(1.0), (DipPhase::In, 1.0));
+    }
+
+    #[test]
+    fn test_dip_phase_clamps_out_of_range() {
+        assert_eq!(dip_phase(-0.5), (DipPhase::Out, 0.0));
+        assert_eq!(dip_phase(1.5), (DipPhase::In, 1.0));
+    }
+
+    #[test]
+    fn test_dip_options_merge() {
+        let global = DipOptions { color: [1.0, 1.0, 1.0] };
+
+        assert_eq!(global.merge(&DipOptions::default()).color, [1.0, 1.0, 1.0]);
+
+        let scene = DipOptions { color: [0.2, 0.0, 0.4] };
+        assert_eq!(global.merge(&scene).color, [0.2, 0.0, 0.4]);
+    }
+
+    #[test]
+    fn test_dip_options_deserialize() {
+        let options: DipOptions = toml::from_str("color = [1.0, 0.5, 0.0]").unwrap();
+        assert_eq!(options.color, [1.0, 0.5, 0.0]);
+
+        let options: DipOptions = toml::from_str("").unwrap();
+        assert_eq!(options.color, DEFAULT_DIP_COLOR);
+    }
+
+    #[test]
+    fn test_dip_uniforms_layout_matches_shader() {
+        // vec4 dip_color followed by four 4-byte scalars
+        assert_eq!(std::mem::size_of::<DipUniforms>(), 32);
+    }
+}
@@ -1,5 +1,9 @@
 use std::{iter::once, time::Duration};
 
+#[cfg(feature = "generate")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
 use crate::{
     engine::{Context, Texture},
     prelude::*,
@@ -7,6 +11,59 @@ use crate::{
     transitions::Transition,
 };
 
+/// How the two wallpapers are layered while fading
+///
+/// The modes only look different where a wallpaper is translucent; opaque
+/// wallpapers crossfade the same way in all three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+#[serde(rename_all = "kebab-case")]
+#[derive(Default)]
+pub enum FadeMode {
+    /// Crossfade both wallpapers at once
+    #[cfg_attr(feature = "generate", nixos(default = "true"))]
+    #[default]
+    Blend,
+
+    /// Draw the new wallpaper fully, with the old one fading out on top of it
+    OverlayOut,
+
+    /// Keep the old wallpaper fully drawn and fade the new one in on top of it
+    OverlayIn,
+}
+
+impl FadeMode {
+    /// Value of the shader's `mode` uniform
+    fn as_uniform(self) -> u32 {
+        match self {
+            FadeMode::Blend => 0,
+            FadeMode::OverlayOut => 1,
+            FadeMode::OverlayIn => 2,
+        }
+    }
+}
+
+/// Options for fade transition
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+pub struct FadeOptions {
+    /// Which wallpaper is drawn on top while fading
+    #[serde(default)]
+    pub mode: FadeMode,
+}
+
+impl FadeOptions {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            mode: if other.mode != FadeMode::default() {
+                other.mode
+            } else {
+                self.mode
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FadeTransition {
     previous_texture: Option<Texture>,
@@ -24,8 +81,16 @@ pub struct FadeTransition {
 }
 
 impl FadeTransition {
-    pub fn new(previous_texture: Option<Texture>, duration: Duration, ctx: &Context) -> Self {
-        debug!("Creating FadeTransition with duration {:?}", duration);
+    pub fn new(
+        previous_texture: Option<Texture>,
+        duration: Duration,
+        options: FadeOptions,
+        ctx: &Context,
+    ) -> Result<Self> {
+        debug!(
+            "Creating {:?} FadeTransition with duration {:?}",
+            options.mode, duration
+        );
 
         let vertex_buffer = create_vertex_buffer(ctx);
         let index_buffer = create_index_buffer(ctx);
@@ -103,6 +168,16 @@ impl FadeTransition {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -124,6 +199,15 @@ impl FadeTransition {
             mapped_at_creation: false,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
+        // The mode never changes during a transition, so it's written once here
+        let mode_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fade_mode_uniform"),
+            size: 4,
+            mapped_at_creation: false,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        ctx.queue()
+            .write_buffer(&mode_buffer, 0, bytemuck::cast_slice(&[options.mode.as_uniform()]));
 
         let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &uniform_bind_group_layout,
@@ -140,20 +224,24 @@ impl FadeTransition {
                     binding: 2,
                     resource: to_aspect_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: mode_buffer.as_entire_binding(),
+                },
             ],
             label: Some("fade_uniform_bind_group"),
         });
 
-        let shader = ctx.device().create_shader_module(wgpu::include_wgsl!("./shaders/fade.wgsl"));
+        let shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/fade.wgsl"));
 
         let render_pipeline = create_pipeline(
             ctx,
             &[&texture_bind_group_layout, &uniform_bind_group_layout],
             &shader,
             ctx.config(),
-        );
+        )?;
 
-        Self {
+        Ok(Self {
             previous_texture,
             elapsed: Duration::ZERO,
             duration,
@@ -166,7 +254,7 @@ impl FadeTransition {
             from_aspect_buffer,
             to_aspect_buffer,
             uniform_bind_group,
-        }
+        })
     }
 }
 
@@ -185,16 +273,11 @@ impl Transition for FadeTransition {
     fn render(&self, ctx: &Context, current_texture: &Texture) {
         let queue = ctx.queue();
         let device = ctx.device();
-        let surface = ctx.surface();
 
-        let output = match surface.get_current_texture() {
-            Ok(output) => output,
-            Err(e) => {
-                error!("Could not get texture from surface: {e}");
-                return;
-            },
+        let Some(output) = ctx.acquire_frame() else {
+            return;
         };
-        let view = output.texture.create_view(&Default::default());
+        let view = output.view();
 
         let from_texture: &Texture = self.previous_texture.as_ref().unwrap_or(current_texture);
 
@@ -245,7 +328,7 @@ impl Transition for FadeTransition {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: ctx.clear_op(wgpu::Color::BLACK),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -270,3 +353,52 @@ impl Transition for FadeTransition {
         self.previous_texture.as_ref()
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fade_mode_defaults_to_blend() {
+        let options: FadeOptions = toml::from_str("").unwrap();
+        assert_eq!(options.mode, FadeMode::Blend);
+    }
+
+    #[test]
+    fn test_fade_mode_deserializes_each_mode() {
+        for (name, mode) in [
+            ("blend", FadeMode::Blend),
+            ("overlay-out", FadeMode::OverlayOut),
+            ("overlay-in", FadeMode::OverlayIn),
+        ] {
+            let options: FadeOptions = toml::from_str(&f!("mode = \"{name}\"")).unwrap();
+            assert_eq!(options.mode, mode, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_fade_mode_rejects_unknown_mode() {
+        assert!(toml::from_str::<FadeOptions>("mode = \"dissolve\"").is_err());
+    }
+
+    #[test]
+    fn test_fade_mode_uniform_values_are_distinct() {
+        assert_eq!(FadeMode::Blend.as_uniform(), 0);
+        assert_eq!(FadeMode::OverlayOut.as_uniform(), 1);
+        assert_eq!(FadeMode::OverlayIn.as_uniform(), 2);
+    }
+
+    #[test]
+    fn test_fade_options_merge_prefers_scene_mode() {
+        let global = FadeOptions {
+            mode: FadeMode::OverlayOut,
+        };
+        assert_eq!(global.merge(&FadeOptions::default()).mode, FadeMode::OverlayOut);
+
+        let scene = FadeOptions {
+            mode: FadeMode::OverlayIn,
+        };
+        assert_eq!(global.merge(&scene).mode, FadeMode::OverlayIn);
+    }
+}
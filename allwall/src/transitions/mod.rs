@@ -4,7 +4,7 @@ use clap::ValueEnum;
 use derive_more::Display;
 #[cfg(feature = "generate")]
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     engine::{Context, Texture},
@@ -13,17 +13,23 @@ use crate::{
 
 pub mod circle_reveal;
 pub mod config;
+pub mod dip;
 pub mod error;
 pub mod fade;
+pub mod pixelate;
+pub mod wipe;
 
 pub use circle_reveal::{CircleOptions, CircleOrigin, CircleRevealTransition};
 pub use config::TransitionConfig;
-pub use fade::FadeTransition;
+pub use dip::{DipOptions, DipTransition};
+pub use fade::{FadeMode, FadeOptions, FadeTransition};
+pub use pixelate::PixelateTransition;
+pub use wipe::{WipeOptions, WipeTransition};
 
 /// Transition animation type
 ///
 /// Defines the visual effect used when transitioning between wallpapers.
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Display, Deserialize)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Display, Deserialize, Serialize)]
 #[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
 #[clap(rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
@@ -31,6 +37,9 @@ pub enum TransitionType {
     /// Simple crossfade between wallpapers
     Fade,
 
+    /// Fade out to a solid color, then fade the new wallpaper in from it
+    Dip,
+
     /// Circle reveal from top-left corner
     CircleTopLeft,
 
@@ -48,18 +57,50 @@ pub enum TransitionType {
 
     /// Circle reveal from random position
     CircleRandom,
+
+    /// Circle reveal from the mouse pointer, or the center when it isn't over the wallpaper
+    CircleMouse,
+
+    /// Straight edge sweeping across at an angle
+    Wipe,
+
+    /// Old wallpaper breaks into growing blocks, new one sharpens out of them
+    Pixelate,
+
+    /// A different type each rotation, drawn from `random_types`
+    Random,
 }
 
 impl TransitionType {
+    /// Every type that draws a transition itself, so all but [`TransitionType::Random`]
+    pub const CONCRETE: [Self; 11] = [
+        Self::Fade,
+        Self::Dip,
+        Self::CircleTopLeft,
+        Self::CircleTopRight,
+        Self::CircleBottomLeft,
+        Self::CircleBottomRight,
+        Self::CircleCenter,
+        Self::CircleRandom,
+        Self::CircleMouse,
+        Self::Wipe,
+        Self::Pixelate,
+    ];
+
     pub fn as_kebab_case_str(&self) -> &'static str {
         match self {
             TransitionType::Fade => "fade",
+            TransitionType::Dip => "dip",
             TransitionType::CircleTopLeft => "circle-top-left",
             TransitionType::CircleTopRight => "circle-top-right",
             TransitionType::CircleBottomLeft => "circle-bottom-left",
             TransitionType::CircleBottomRight => "circle-bottom-right",
             TransitionType::CircleCenter => "circle-center",
             TransitionType::CircleRandom => "circle-random",
+            TransitionType::CircleMouse => "circle-mouse",
+            TransitionType::Wipe => "wipe",
+            TransitionType::Pixelate => "pixelate",
+            TransitionType::Random => "random",
         }
     }
 }
@@ -71,18 +112,28 @@ pub trait Transition: std::fmt::Debug {
     fn previous_texture(&self) -> Option<&Texture>;
 }
 
+/// Boxes a built transition, for sources picking one by [`TransitionType`]
+pub fn boxed<T: Transition + 'static>(transition: T) -> Box<dyn Transition> {
+    Box::new(transition)
+}
+
 impl TryFrom<&str> for TransitionType {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self> {
         match value.to_lowercase().as_str() {
             "fade" => Ok(TransitionType::Fade),
+            "dip" => Ok(TransitionType::Dip),
             "circle-top-left" => Ok(TransitionType::CircleTopLeft),
             "circle-top-right" => Ok(TransitionType::CircleTopRight),
             "circle-bottom-left" => Ok(TransitionType::CircleBottomLeft),
             "circle-bottom-right" => Ok(TransitionType::CircleBottomRight),
             "circle-center" => Ok(TransitionType::CircleCenter),
             "circle-random" => Ok(TransitionType::CircleRandom),
+            "circle-mouse" => Ok(TransitionType::CircleMouse),
+            "wipe" => Ok(TransitionType::Wipe),
+            "pixelate" => Ok(TransitionType::Pixelate),
+            "random" => Ok(TransitionType::Random),
             _ => Err(Error::Generic(f!("Invalid transition type: {}", value))),
         }
     }
@@ -99,6 +150,12 @@ mod tests {
         assert_eq!(t, TransitionType::Fade);
     }
 
+    #[test]
+    fn test_transition_type_try_from_dip() {
+        let t: TransitionType = "dip".try_into().unwrap();
+        assert_eq!(t, TransitionType::Dip);
+    }
+
     #[test]
     fn test_transition_type_try_from_circle_top_left() {
         let t: TransitionType = "circle-top-left".try_into().unwrap();
@@ -135,6 +192,31 @@ mod tests {
         assert_eq!(t, TransitionType::CircleRandom);
     }
 
+    #[test]
+    fn test_transition_type_try_from_wipe() {
+        let t: TransitionType = "wipe".try_into().unwrap();
+        assert_eq!(t, TransitionType::Wipe);
+    }
+
+    #[test]
+    fn test_transition_type_try_from_pixelate() {
+        let t: TransitionType = "pixelate".try_into().unwrap();
+        assert_eq!(t, TransitionType::Pixelate);
+    }
+
+    #[test]
+    fn test_transition_type_kebab_roundtrip_random() {
+        let t = TransitionType::Random;
+        let t2: TransitionType = t.as_kebab_case_str().try_into().unwrap();
+        assert_eq!(t, t2);
+    }
+
+    #[test]
+    fn test_transition_type_concrete_excludes_random() {
+        assert!(!TransitionType::CONCRETE.contains(&TransitionType::Random));
+        assert!(TransitionType::CONCRETE.contains(&TransitionType::Pixelate));
+    }
+
     #[test]
     fn test_transition_type_try_from_invalid() {
         let result: std::result::Result<TransitionType, _> = "invalid-type".try_into();
@@ -158,6 +240,27 @@ mod tests {
         assert_eq!(t, t2);
     }
 
+    #[test]
+    fn test_transition_type_kebab_roundtrip_dip() {
+        let t = TransitionType::Dip;
+        let t2: TransitionType = t.as_kebab_case_str().try_into().unwrap();
+        assert_eq!(t, t2);
+    }
+
+    #[test]
+    fn test_transition_type_kebab_roundtrip_wipe() {
+        let t = TransitionType::Wipe;
+        let t2: TransitionType = t.as_kebab_case_str().try_into().unwrap();
+        assert_eq!(t, t2);
+    }
+
+    #[test]
+    fn test_transition_type_kebab_roundtrip_pixelate() {
+        let t = TransitionType::Pixelate;
+        let t2: TransitionType = t.as_kebab_case_str().try_into().unwrap();
+        assert_eq!(t, t2);
+    }
+
     #[test]
     fn test_transition_type_kebab_roundtrip_all_circle() {
         for t in [
@@ -167,6 +270,7 @@ mod tests {
             TransitionType::CircleBottomRight,
             TransitionType::CircleCenter,
             TransitionType::CircleRandom,
+            TransitionType::CircleMouse,
         ] {
             let s = t.as_kebab_case_str();
             let t2: TransitionType = s.try_into().unwrap();
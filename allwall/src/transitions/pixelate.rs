@@ -0,0 +1,182 @@
+use std::{f32::consts::PI, iter::once, time::Duration};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        INDICES, create_index_buffer, create_pipeline, create_texture_bind_group, create_texture_bind_group_layout,
+        create_uniform_binds, create_vertex_buffer,
+    },
+    transitions::Transition,
+};
+
+/// Largest block, in pixels, reached halfway through the transition
+const MAX_BLOCK_SIZE: f32 = 64.0;
+
+/// Block edge in pixels at `progress`, growing from single pixels to `max` halfway and shrinking back
+fn block_size(progress: f32, max: f32) -> f32 {
+    let progress = progress.clamp(0.0, 1.0);
+    1.0 + (max - 1.0).max(0.0) * (progress * PI).sin()
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct PixelateUniforms {
+    progress: f32,
+    block_size: f32,
+    surface_size: [f32; 2],
+    surface_to_from_arr: f32,
+    surface_to_to_arr: f32,
+    _padding: [f32; 2],
+}
+
+/// Breaks the old wallpaper into growing blocks, then resolves the new one out of them
+#[derive(Debug)]
+pub struct PixelateTransition {
+    previous_texture: Option<Texture>,
+    elapsed: Duration,
+    duration: Duration,
+
+    render_pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+impl PixelateTransition {
+    pub fn new(previous_texture: Option<Texture>, duration: Duration, ctx: &Context) -> Result<Self> {
+        debug!("Creating PixelateTransition with duration {:?}", duration);
+
+        let texture_bind_group_layout = create_texture_bind_group_layout(2, ctx);
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<PixelateUniforms>() as u64, ctx);
+
+        let shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/pixelate.wgsl"));
+
+        let render_pipeline = create_pipeline(
+            ctx,
+            &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            &shader,
+            ctx.config(),
+        )?;
+
+        Ok(Self {
+            previous_texture,
+            elapsed: Duration::ZERO,
+            duration,
+            render_pipeline,
+            texture_bind_group_layout,
+            vertex_buffer: create_vertex_buffer(ctx),
+            index_buffer: create_index_buffer(ctx),
+            uniform_buffer,
+            uniform_bind_group,
+        })
+    }
+}
+
+impl Transition for PixelateTransition {
+    fn update(&mut self, dt: Duration) -> bool {
+        self.elapsed += dt;
+        let progress = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        debug!("PixelateTransition progress: {:.2}", progress.min(1.0));
+        progress >= 1.0
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    fn render(&self, ctx: &Context, current_texture: &Texture) {
+        let queue = ctx.queue();
+
+        let Some(output) = ctx.acquire_frame() else {
+            return;
+        };
+        let view = output.view();
+
+        let from_texture: &Texture = self.previous_texture.as_ref().unwrap_or(current_texture);
+        let texture_bind_group =
+            create_texture_bind_group(&self.texture_bind_group_layout, &[from_texture, current_texture], ctx);
+
+        let config = ctx.config();
+        let surface_aspect = ctx.surface_aspect_ratio();
+        let progress = self.progress();
+        let uniforms = PixelateUniforms {
+            progress,
+            block_size: block_size(progress, MAX_BLOCK_SIZE * ctx.scale_factor() as f32),
+            surface_size: [config.width as f32, config.height as f32],
+            surface_to_from_arr: surface_aspect / from_texture.aspect_ratio(),
+            surface_to_to_arr: surface_aspect / current_texture.aspect_ratio(),
+            _padding: [0.0; 2],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = ctx.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("pixelate_transition"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: ctx.clear_op(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.set_bind_group(0, &texture_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        queue.submit(once(encoder.finish()));
+        output.present();
+    }
+
+    fn previous_texture(&self) -> Option<&Texture> {
+        self.previous_texture.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_size_sharp_at_both_ends() {
+        assert!((block_size(0.0, MAX_BLOCK_SIZE) - 1.0).abs() < 1e-5);
+        assert!((block_size(1.0, MAX_BLOCK_SIZE) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_block_size_peaks_mid_transition() {
+        assert!((block_size(0.5, MAX_BLOCK_SIZE) - MAX_BLOCK_SIZE).abs() < 1e-3);
+
+        let samples: Vec<f32> = (0..=10).map(|i| block_size(i as f32 / 10.0, MAX_BLOCK_SIZE)).collect();
+        assert!(samples[..=5].windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(samples[5..].windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn test_block_size_clamps_progress() {
+        assert_eq!(block_size(-0.5, MAX_BLOCK_SIZE), block_size(0.0, MAX_BLOCK_SIZE));
+        assert_eq!(block_size(1.5, MAX_BLOCK_SIZE), block_size(1.0, MAX_BLOCK_SIZE));
+    }
+
+    #[test]
+    fn test_pixelate_uniforms_size() {
+        assert_eq!(std::mem::size_of::<PixelateUniforms>(), 32);
+    }
+}
@@ -34,6 +34,8 @@ struct Uniforms {
 	center: vec2<f32>,
 	surface_to_from_arr: f32,
 	surface_to_to_arr: f32,
+	// Half-width of the softened edge, in screen heights
+	feather: f32,
 }
 
 @group(1) @binding(0)
@@ -58,8 +60,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 	
 	// Max distance is diagonal of normalized space, adjusted for aspect ratio
 	let max_dist = sqrt(uniforms.aspect_ratio * uniforms.aspect_ratio + 1.0);
-	let radius = uniforms.progress * max_dist;
-	let mask = step(radius, dist);
+	// Start and end with the whole feathered band off screen
+	let radius = mix(-uniforms.feather, max_dist + uniforms.feather, uniforms.progress);
+	let mask = smoothstep(radius - uniforms.feather, radius + uniforms.feather, dist);
 
 	let from_color = sample_texture(t_from, s_from, in.tex_coords, uniforms.surface_to_from_arr);
 	let to_color = sample_texture(t_to, s_to, in.tex_coords, uniforms.surface_to_to_arr);
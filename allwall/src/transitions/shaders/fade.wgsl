@@ -37,6 +37,10 @@ var<uniform> surface_to_from_arr: f32;
 @group(1) @binding(2)
 var<uniform> surface_to_to_arr: f32;
 
+// 0 = blend, 1 = overlay-out, 2 = overlay-in, matching FadeMode::as_uniform
+@group(1) @binding(3)
+var<uniform> mode: u32;
+
 fn sample_texture(tex: texture_2d<f32>, samp: sampler, coords: vec2<f32>, aspect_ratio: f32) -> vec4<f32> {
 	let scale = select(
 		vec2<f32>(aspect_ratio, 1.0),
@@ -46,9 +50,24 @@ fn sample_texture(tex: texture_2d<f32>, samp: sampler, coords: vec2<f32>, aspect
 	return textureSample(tex, samp, coords * scale + 0.5 * (vec2<f32>(1.0) - scale));
 }
 
+// Premultiplied `top` composited over `bottom`
+fn over(top: vec4<f32>, bottom: vec4<f32>) -> vec4<f32> {
+	return top + bottom * (1.0 - top.a);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 	let from_color = sample_texture(t_from, s_from, in.tex_coords, surface_to_from_arr);
 	let to_color = sample_texture(t_to, s_to, in.tex_coords, surface_to_to_arr);
-	return mix(from_color, to_color, progress);
+	switch mode {
+		case 1u: {
+			return over(from_color * (1.0 - progress), to_color);
+		}
+		case 2u: {
+			return over(to_color * progress, from_color);
+		}
+		default: {
+			return mix(from_color, to_color, progress);
+		}
+	}
 }
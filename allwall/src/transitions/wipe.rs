@@ -0,0 +1,303 @@
+use std::{iter::once, time::Duration};
+
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "generate")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::{Context, Texture},
+    prelude::*,
+    sources::{
+        INDICES, create_index_buffer, create_pipeline, create_texture_bind_group, create_texture_bind_group_layout,
+        create_uniform_binds, create_vertex_buffer,
+    },
+    transitions::Transition,
+};
+
+/// Narrowest edge the shader is given, since `smoothstep` is undefined for an empty band
+const MIN_FEATHER: f32 = 1e-4;
+
+fn default_angle() -> f32 {
+    0.0
+}
+
+fn default_feather() -> f32 {
+    0.03
+}
+
+/// Options for wipe transition
+///
+/// Configures the direction and edge of the wipe animation.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "generate", derive(JsonSchema, serde_nixos::NixosType))]
+pub struct WipeOptions {
+    /// Direction the edge travels in degrees
+    ///
+    /// - 0: Left to right (default)
+    /// - 90: Bottom to top
+    /// - 180: Right to left
+    /// - 45: Bottom-left to top-right
+    #[serde(default = "default_angle")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_angle"))]
+    #[cfg_attr(feature = "generate", nixos(default = "0"))]
+    pub angle: f32,
+
+    /// Edge softness (feathering) for the wipe
+    ///
+    /// Width of the blended band either side of the edge, as a fraction of
+    /// the screen height.
+    /// - 0.0: Hard edge
+    /// - 0.03: Slight softness (default)
+    #[serde(default = "default_feather")]
+    #[cfg_attr(feature = "generate", schemars(default = "default_feather"))]
+    #[cfg_attr(feature = "generate", nixos(default = "0.03"))]
+    pub feather: f32,
+}
+
+impl Default for WipeOptions {
+    fn default() -> Self {
+        Self {
+            angle: default_angle(),
+            feather: default_feather(),
+        }
+    }
+}
+
+impl WipeOptions {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            angle: if other.angle != default_angle() {
+                other.angle
+            } else {
+                self.angle
+            },
+            feather: if other.feather != default_feather() {
+                other.feather
+            } else {
+                self.feather
+            },
+        }
+    }
+}
+
+/// Unit vector the edge moves along for `angle` degrees, in texture space where Y points down
+fn wipe_direction(angle: f32) -> [f32; 2] {
+    let radians = angle.to_radians();
+    [radians.cos(), -radians.sin()]
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct WipeUniforms {
+    progress: f32,
+    feather: f32,
+    direction: [f32; 2],
+    aspect_ratio: f32,
+    surface_to_from_arr: f32,
+    surface_to_to_arr: f32,
+    _padding: f32,
+}
+
+/// Sweeps a straight, softened edge across the screen to reveal the new wallpaper
+#[derive(Debug)]
+pub struct WipeTransition {
+    previous_texture: Option<Texture>,
+    elapsed: Duration,
+    duration: Duration,
+    options: WipeOptions,
+
+    render_pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+impl WipeTransition {
+    pub fn new(
+        previous_texture: Option<Texture>,
+        duration: Duration,
+        options: WipeOptions,
+        ctx: &Context,
+    ) -> Result<Self> {
+        debug!(
+            "Creating WipeTransition at {} degrees with duration {:?}",
+            options.angle, duration
+        );
+
+        let texture_bind_group_layout = create_texture_bind_group_layout(2, ctx);
+        let (uniform_buffer, uniform_bind_group_layout, uniform_bind_group) =
+            create_uniform_binds(std::mem::size_of::<WipeUniforms>() as u64, ctx);
+
+        let shader = ctx.device().create_shader_module(crate::wgsl!("./shaders/wipe.wgsl"));
+
+        let render_pipeline = create_pipeline(
+            ctx,
+            &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            &shader,
+            ctx.config(),
+        )?;
+
+        Ok(Self {
+            previous_texture,
+            elapsed: Duration::ZERO,
+            duration,
+            options,
+            render_pipeline,
+            texture_bind_group_layout,
+            vertex_buffer: create_vertex_buffer(ctx),
+            index_buffer: create_index_buffer(ctx),
+            uniform_buffer,
+            uniform_bind_group,
+        })
+    }
+}
+
+impl Transition for WipeTransition {
+    fn update(&mut self, dt: Duration) -> bool {
+        self.elapsed += dt;
+        let progress = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        debug!("WipeTransition progress: {:.2}", progress.min(1.0));
+        progress >= 1.0
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    fn render(&self, ctx: &Context, current_texture: &Texture) {
+        let queue = ctx.queue();
+
+        let Some(output) = ctx.acquire_frame() else {
+            return;
+        };
+        let view = output.view();
+
+        let from_texture: &Texture = self.previous_texture.as_ref().unwrap_or(current_texture);
+        let texture_bind_group =
+            create_texture_bind_group(&self.texture_bind_group_layout, &[from_texture, current_texture], ctx);
+
+        let surface_aspect = ctx.surface_aspect_ratio();
+        let uniforms = WipeUniforms {
+            progress: self.progress(),
+            feather: self.options.feather.max(MIN_FEATHER),
+            direction: wipe_direction(self.options.angle),
+            aspect_ratio: surface_aspect,
+            surface_to_from_arr: surface_aspect / from_texture.aspect_ratio(),
+            surface_to_to_arr: surface_aspect / current_texture.aspect_ratio(),
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = ctx.device().create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("wipe_transition"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: ctx.clear_op(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.set_bind_group(0, &texture_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+            render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        }
+
+        queue.submit(once(encoder.finish()));
+        output.present();
+    }
+
+    fn previous_texture(&self) -> Option<&Texture> {
+        self.previous_texture.as_ref()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn assert_direction(angle: f32, expected: [f32; 2]) {
+        let [x, y] = wipe_direction(angle);
+        assert!((x - expected[0]).abs() < 1e-5, "x for {angle}: {x}");
+        assert!((y - expected[1]).abs() < 1e-5, "y for {angle}: {y}");
+    }
+
+    #[test]
+    fn test_wipe_direction_axes() {
+        assert_direction(0.0, [1.0, 0.0]);
+        assert_direction(90.0, [0.0, -1.0]);
+        assert_direction(180.0, [-1.0, 0.0]);
+        assert_direction(270.0, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_wipe_direction_is_unit_length() {
+        let [x, y] = wipe_direction(45.0);
+        assert!((x * x + y * y - 1.0).abs() < 1e-5);
+        assert!(x > 0.0 && y < 0.0);
+    }
+
+    #[test]
+    fn test_wipe_options_defaults() {
+        let options = WipeOptions::default();
+        assert_eq!(options.angle, 0.0);
+        assert!((options.feather - 0.03).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_wipe_options_merge() {
+        let global = WipeOptions {
+            angle: 45.0,
+            feather: 0.1,
+        };
+        let scene = WipeOptions {
+            angle: 90.0,
+            feather: default_feather(),
+        };
+
+        let merged = global.merge(&scene);
+        assert_eq!(merged.angle, 90.0);
+        assert!((merged.feather - 0.1).abs() < f32::EPSILON);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct WipeConfigTest {
+        wipe: WipeOptions,
+    }
+
+    #[test]
+    fn test_wipe_options_deserialize() {
+        let config: WipeConfigTest = toml::from_str(
+            r#"
+            [wipe]
+            angle = 45
+            feather = 0.05
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.wipe.angle, 45.0);
+        assert!((config.wipe.feather - 0.05).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_wipe_uniforms_layout_matches_shader() {
+        // vec2 direction aligned to 8 bytes, struct padded to a multiple of 8
+        assert_eq!(std::mem::size_of::<WipeUniforms>(), 32);
+    }
+}
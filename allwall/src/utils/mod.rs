@@ -1 +1,3 @@
-
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+pub(crate) mod scratch;
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use allwall::config::Config;
+use anyhow::Result;
+use tracing::info;
+
+pub fn generate(output_dir: &Path) -> Result<()> {
+    info!("Generating example config...");
+
+    let example = Config::example_toml().map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    let example_path = output_dir.join("example.toml");
+    std::fs::write(&example_path, example)?;
+
+    info!("\tWritten to: {}", example_path.display());
+    Ok(())
+}
@@ -1,3 +1,4 @@
+mod example;
 mod nix;
 mod schema;
 
@@ -13,6 +14,8 @@ pub enum GenerateCmd {
 
     Nix,
 
+    Example,
+
     All,
 }
 
@@ -32,9 +35,13 @@ pub fn run(cmd: GenerateCmd) -> Result<()> {
         GenerateCmd::Nix => {
             nix::generate(&output)?;
         },
+        GenerateCmd::Example => {
+            example::generate(&output)?;
+        },
         GenerateCmd::All => {
             schema::generate(&output)?;
             nix::generate(&output)?;
+            example::generate(&output)?;
         },
     }
 
@@ -82,6 +82,9 @@ in
     transitionType = lib.mkOption {
       type = lib.types.nullOr (lib.types.enum [
         "fade"
+        "dip"
+        "wipe"
+        "pixelate"
         "circle-top-left"
         "circle-top-right"
         "circle-bottom-left"